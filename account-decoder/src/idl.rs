@@ -0,0 +1,586 @@
+//! Anchor-style IDL definitions, and decoding of the Borsh-serialized data they describe into
+//! JSON. Both the legacy (`publicKey`, `defined: "Name"`) and current (`pubkey`,
+//! `defined: {"name": "Name"}`, explicit discriminators) IDL formats are accepted.
+
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    inflector::Inflector,
+    serde_json::{Map, Value},
+    solana_sdk::{hash::hashv, pubkey::Pubkey},
+    std::{fs, io, path::Path, str::FromStr},
+    thiserror::Error,
+};
+
+/// Length of the discriminators Anchor derives from instruction and account names
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// How deeply decoded values may nest. Recursive types would otherwise let the data overflow the
+/// stack.
+pub const MAX_DECODE_DEPTH: usize = 64;
+
+/// How many `vec` and `array` elements one value may decode to. Elements that take no bytes, such
+/// as empty structs, would otherwise let a short length prefix decode to billions of values.
+pub const MAX_DECODED_ELEMENTS: usize = 1 << 20;
+
+#[derive(Error, Debug)]
+pub enum IdlError {
+    #[error("failed to read IDL: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid IDL: {0}")]
+    InvalidIdl(#[from] serde_json::Error),
+
+    #[error("IDL does not specify a program address")]
+    MissingAddress,
+
+    #[error("invalid program address {0}")]
+    InvalidAddress(String),
+
+    #[error("unknown type {0}")]
+    UnknownType(String),
+
+    #[error("unexpected end of data")]
+    UnexpectedEof,
+
+    #[error("invalid {0}")]
+    InvalidData(&'static str),
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Idl {
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub metadata: IdlMetadata,
+    #[serde(default)]
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccount>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    #[serde(default)]
+    pub accounts: Vec<IdlInstructionAccount>,
+    #[serde(default)]
+    pub args: Vec<IdlField>,
+}
+
+/// An instruction account, or a group of accounts in the legacy format
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlInstructionAccount {
+    pub name: String,
+    #[serde(default)]
+    pub accounts: Vec<IdlInstructionAccount>,
+}
+
+/// An account type. In the legacy format the layout is given inline, in the current format it is
+/// the type definition of the same name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlAccount {
+    pub name: String,
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    #[serde(default, rename = "type")]
+    pub ty: Option<IdlTypeDefTy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: IdlDefinedFields,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum IdlDefinedFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+impl Default for IdlDefinedFields {
+    fn default() -> Self {
+        Self::Named(vec![])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlEnumVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlDefinedFields>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum IdlType {
+    /// `bool`, `u8` to `u128`, `i8` to `i128`, `f32`, `f64`, `string`, `bytes` or `pubkey`
+    Primitive(String),
+    Option {
+        option: Box<IdlType>,
+    },
+    Vec {
+        vec: Box<IdlType>,
+    },
+    Array {
+        array: (Box<IdlType>, usize),
+    },
+    Defined {
+        defined: IdlDefinedName,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum IdlDefinedName {
+    Legacy(String),
+    Named { name: String },
+}
+
+impl IdlDefinedName {
+    fn name(&self) -> &str {
+        match self {
+            Self::Legacy(name) | Self::Named { name } => name,
+        }
+    }
+}
+
+impl Idl {
+    pub fn from_file(path: &Path) -> Result<Self, IdlError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// The program the IDL describes
+    pub fn program_id(&self) -> Result<Pubkey, IdlError> {
+        let address = self
+            .address
+            .as_ref()
+            .or(self.metadata.address.as_ref())
+            .ok_or(IdlError::MissingAddress)?;
+        Pubkey::from_str(address).map_err(|_| IdlError::InvalidAddress(address.clone()))
+    }
+
+    /// The program name, in kebab case
+    pub fn program_name(&self) -> Option<String> {
+        self.metadata
+            .name
+            .as_ref()
+            .or(self.name.as_ref())
+            .map(|name| name.to_kebab_case())
+    }
+
+    /// Decode a value of type `ty` from the start of `data`, advancing it past the value
+    pub fn decode(&self, ty: &IdlType, data: &mut &[u8]) -> Result<Value, IdlError> {
+        self.decode_nested(ty, data, 0, &mut 0)
+    }
+
+    pub fn decode_type_def(&self, ty: &IdlTypeDefTy, data: &mut &[u8]) -> Result<Value, IdlError> {
+        self.decode_type_def_nested(ty, data, 0, &mut 0)
+    }
+
+    /// Decode named fields into an object with camel case keys, and tuple fields into an array
+    pub fn decode_fields(
+        &self,
+        fields: &IdlDefinedFields,
+        data: &mut &[u8],
+    ) -> Result<Value, IdlError> {
+        self.decode_fields_nested(fields, data, 0, &mut 0)
+    }
+
+    /// `num_elements` counts the `vec` and `array` elements decoded so far
+    fn decode_nested(
+        &self,
+        ty: &IdlType,
+        data: &mut &[u8],
+        depth: usize,
+        num_elements: &mut usize,
+    ) -> Result<Value, IdlError> {
+        if depth >= MAX_DECODE_DEPTH {
+            return Err(IdlError::InvalidData("nesting depth"));
+        }
+        let depth = depth + 1;
+        Ok(match ty {
+            IdlType::Primitive(name) => match name.as_str() {
+                "bool" => match take::<1>(data)? {
+                    [0] => Value::Bool(false),
+                    [1] => Value::Bool(true),
+                    _ => return Err(IdlError::InvalidData("bool")),
+                },
+                "u8" => u8::from_le_bytes(take(data)?).into(),
+                "i8" => i8::from_le_bytes(take(data)?).into(),
+                "u16" => u16::from_le_bytes(take(data)?).into(),
+                "i16" => i16::from_le_bytes(take(data)?).into(),
+                "u32" => u32::from_le_bytes(take(data)?).into(),
+                "i32" => i32::from_le_bytes(take(data)?).into(),
+                "u64" => u64::from_le_bytes(take(data)?).into(),
+                "i64" => i64::from_le_bytes(take(data)?).into(),
+                // JSON numbers can't hold every 128-bit integer
+                "u128" => u128::from_le_bytes(take(data)?).to_string().into(),
+                "i128" => i128::from_le_bytes(take(data)?).to_string().into(),
+                "f32" => f32::from_le_bytes(take(data)?).into(),
+                "f64" => f64::from_le_bytes(take(data)?).into(),
+                "string" => String::from_utf8(take_len_prefixed(data)?.to_vec())
+                    .map_err(|_| IdlError::InvalidData("string"))?
+                    .into(),
+                "bytes" => BASE64_STANDARD.encode(take_len_prefixed(data)?).into(),
+                "pubkey" | "publicKey" => Pubkey::new_from_array(take(data)?).to_string().into(),
+                _ => return Err(IdlError::UnknownType(name.clone())),
+            },
+            IdlType::Option { option } => match take::<1>(data)? {
+                [0] => Value::Null,
+                [1] => self.decode_nested(option, data, depth, num_elements)?,
+                _ => return Err(IdlError::InvalidData("option")),
+            },
+            IdlType::Vec { vec } => {
+                let len = u32::from_le_bytes(take(data)?) as usize;
+                // Each element takes at least a byte, unless the type has no data
+                if len > data.len() && !self.is_zero_sized(vec, depth) {
+                    return Err(IdlError::InvalidData("vec length"));
+                }
+                count_elements(num_elements, len)?;
+                (0..len)
+                    .map(|_| self.decode_nested(vec, data, depth, num_elements))
+                    .collect::<Result<_, _>>()?
+            }
+            IdlType::Array { array: (ty, len) } => {
+                count_elements(num_elements, *len)?;
+                (0..*len)
+                    .map(|_| self.decode_nested(ty, data, depth, num_elements))
+                    .collect::<Result<_, _>>()?
+            }
+            IdlType::Defined { defined } => {
+                let type_def = self
+                    .types
+                    .iter()
+                    .find(|type_def| type_def.name == defined.name())
+                    .ok_or_else(|| IdlError::UnknownType(defined.name().to_string()))?;
+                self.decode_type_def_nested(&type_def.ty, data, depth, num_elements)?
+            }
+        })
+    }
+
+    fn decode_type_def_nested(
+        &self,
+        ty: &IdlTypeDefTy,
+        data: &mut &[u8],
+        depth: usize,
+        num_elements: &mut usize,
+    ) -> Result<Value, IdlError> {
+        match ty {
+            IdlTypeDefTy::Struct { fields } => {
+                self.decode_fields_nested(fields, data, depth, num_elements)
+            }
+            IdlTypeDefTy::Enum { variants } => {
+                let [index] = take::<1>(data)?;
+                let variant = variants
+                    .get(index as usize)
+                    .ok_or(IdlError::InvalidData("enum variant"))?;
+                let name = variant.name.to_camel_case();
+                Ok(match &variant.fields {
+                    None => Value::String(name),
+                    Some(fields) => Value::Object(Map::from_iter([(
+                        name,
+                        self.decode_fields_nested(fields, data, depth, num_elements)?,
+                    )])),
+                })
+            }
+        }
+    }
+
+    fn decode_fields_nested(
+        &self,
+        fields: &IdlDefinedFields,
+        data: &mut &[u8],
+        depth: usize,
+        num_elements: &mut usize,
+    ) -> Result<Value, IdlError> {
+        match fields {
+            IdlDefinedFields::Named(fields) => fields
+                .iter()
+                .map(|field| {
+                    Ok((
+                        field.name.to_camel_case(),
+                        self.decode_nested(&field.ty, data, depth, num_elements)?,
+                    ))
+                })
+                .collect::<Result<Map<_, _>, _>>()
+                .map(Value::Object),
+            IdlDefinedFields::Tuple(types) => types
+                .iter()
+                .map(|ty| self.decode_nested(ty, data, depth, num_elements))
+                .collect::<Result<_, _>>(),
+        }
+    }
+
+    /// Whether values of type `ty` take no bytes, such as structs without fields. Types nested
+    /// too deeply to decode, or unknown, are not.
+    fn is_zero_sized(&self, ty: &IdlType, depth: usize) -> bool {
+        if depth >= MAX_DECODE_DEPTH {
+            return false;
+        }
+        let depth = depth + 1;
+        match ty {
+            IdlType::Primitive(_) | IdlType::Option { .. } | IdlType::Vec { .. } => false,
+            IdlType::Array { array: (ty, len) } => *len == 0 || self.is_zero_sized(ty, depth),
+            IdlType::Defined { defined } => self
+                .types
+                .iter()
+                .find(|type_def| type_def.name == defined.name())
+                .is_some_and(|type_def| match &type_def.ty {
+                    IdlTypeDefTy::Struct {
+                        fields: IdlDefinedFields::Named(fields),
+                    } => fields
+                        .iter()
+                        .all(|field| self.is_zero_sized(&field.ty, depth)),
+                    IdlTypeDefTy::Struct {
+                        fields: IdlDefinedFields::Tuple(types),
+                    } => types.iter().all(|ty| self.is_zero_sized(ty, depth)),
+                    IdlTypeDefTy::Enum { .. } => false,
+                }),
+        }
+    }
+}
+
+fn count_elements(num_elements: &mut usize, len: usize) -> Result<(), IdlError> {
+    *num_elements = num_elements.saturating_add(len);
+    if *num_elements > MAX_DECODED_ELEMENTS {
+        return Err(IdlError::InvalidData("number of elements"));
+    }
+    Ok(())
+}
+
+/// The discriminator Anchor derives for `name` in `namespace`, e.g. `global` for instructions
+pub fn sighash(namespace: &str, name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    hash.to_bytes()[..DISCRIMINATOR_LEN].try_into().unwrap()
+}
+
+fn take_slice<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], IdlError> {
+    if data.len() < len {
+        return Err(IdlError::UnexpectedEof);
+    }
+    let (bytes, rest) = data.split_at(len);
+    *data = rest;
+    Ok(bytes)
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], IdlError> {
+    Ok(take_slice(data, N)?.try_into().unwrap())
+}
+
+fn take_len_prefixed<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], IdlError> {
+    let len = u32::from_le_bytes(take(data)?) as usize;
+    take_slice(data, len)
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn test_decode() {
+        let idl: Idl = serde_json::from_value(json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "my_program" },
+            "types": [
+                {
+                    "name": "Side",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [
+                            { "name": "Bid" },
+                            { "name": "Ask", "fields": [{ "name": "limit", "type": "u64" }] }
+                        ]
+                    }
+                },
+                {
+                    "name": "Order",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "owner", "type": "pubkey" },
+                            { "name": "side", "type": { "defined": { "name": "Side" } } },
+                            { "name": "price_lots", "type": "u128" },
+                            { "name": "memo", "type": { "option": "string" } },
+                            { "name": "fills", "type": { "vec": "i16" } },
+                            { "name": "flags", "type": { "array": ["bool", 2] } }
+                        ]
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(idl.program_id().unwrap(), Pubkey::default());
+        assert_eq!(idl.program_name().unwrap(), "my-program");
+
+        let owner = Pubkey::new_unique();
+        let mut data = owner.to_bytes().to_vec();
+        data.extend([1]);
+        data.extend(7u64.to_le_bytes());
+        data.extend(u128::MAX.to_le_bytes());
+        data.extend([0]);
+        data.extend(2u32.to_le_bytes());
+        data.extend((-1i16).to_le_bytes());
+        data.extend(5i16.to_le_bytes());
+        data.extend([1, 0]);
+        data.extend([9, 9]);
+
+        let order = IdlType::Defined {
+            defined: IdlDefinedName::Legacy("Order".to_string()),
+        };
+        let mut remaining = &data[..];
+        assert_eq!(
+            idl.decode(&order, &mut remaining).unwrap(),
+            json!({
+                "owner": owner.to_string(),
+                "side": { "ask": { "limit": 7 } },
+                "priceLots": u128::MAX.to_string(),
+                "memo": null,
+                "fills": [-1, 5],
+                "flags": [true, false],
+            })
+        );
+        assert_eq!(remaining, [9, 9]);
+
+        let mut truncated = &data[..40];
+        assert!(matches!(
+            idl.decode(&order, &mut truncated),
+            Err(IdlError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            idl.decode(&IdlType::Primitive("u256".to_string()), &mut &data[..]),
+            Err(IdlError::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_max_depth() {
+        let idl: Idl = serde_json::from_value(json!({
+            "types": [{
+                "name": "Node",
+                "type": {
+                    "kind": "struct",
+                    "fields": [{ "name": "next", "type": { "option": { "defined": "Node" } } }]
+                }
+            }]
+        }))
+        .unwrap();
+        let node = IdlType::Defined {
+            defined: IdlDefinedName::Legacy("Node".to_string()),
+        };
+
+        // Each node nests two levels deep, its struct and the option
+        let data = [[1].repeat(MAX_DECODE_DEPTH / 2 - 1), vec![0]].concat();
+        assert!(idl.decode(&node, &mut &data[..]).is_ok());
+
+        let data = [[1].repeat(MAX_DECODE_DEPTH / 2), vec![0]].concat();
+        assert!(matches!(
+            idl.decode(&node, &mut &data[..]),
+            Err(IdlError::InvalidData("nesting depth"))
+        ));
+        let data = vec![1; 1 << 20];
+        assert!(matches!(
+            idl.decode(&node, &mut &data[..]),
+            Err(IdlError::InvalidData("nesting depth"))
+        ));
+    }
+
+    #[test]
+    fn test_decode_zero_sized_elements() {
+        let idl: Idl = serde_json::from_value(json!({
+            "types": [
+                { "name": "Empty", "type": { "kind": "struct", "fields": [] } },
+                {
+                    "name": "Wrapper",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [{ "name": "inner", "type": { "defined": "Empty" } }]
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        let empty_vec = |name: &str| IdlType::Vec {
+            vec: Box::new(IdlType::Defined {
+                defined: IdlDefinedName::Legacy(name.to_string()),
+            }),
+        };
+
+        let data = 3u32.to_le_bytes();
+        assert_eq!(
+            idl.decode(&empty_vec("Empty"), &mut &data[..]).unwrap(),
+            json!([{}, {}, {}])
+        );
+
+        let data = u32::MAX.to_le_bytes();
+        for name in ["Empty", "Wrapper"] {
+            assert!(matches!(
+                idl.decode(&empty_vec(name), &mut &data[..]),
+                Err(IdlError::InvalidData("number of elements"))
+            ));
+        }
+        let empty_arrays = IdlType::Vec {
+            vec: Box::new(IdlType::Array {
+                array: (Box::new(IdlType::Primitive("u64".to_string())), 0),
+            }),
+        };
+        assert!(matches!(
+            idl.decode(&empty_arrays, &mut &data[..]),
+            Err(IdlError::InvalidData("number of elements"))
+        ));
+
+        // Elements that take bytes can't outnumber them
+        let u8_vec = IdlType::Vec {
+            vec: Box::new(IdlType::Primitive("u8".to_string())),
+        };
+        let data = [u32::MAX.to_le_bytes().as_slice(), &[1, 2]].concat();
+        assert!(matches!(
+            idl.decode(&u8_vec, &mut &data[..]),
+            Err(IdlError::InvalidData("vec length"))
+        ));
+    }
+
+    #[test]
+    fn test_sighash() {
+        // Discriminator of Anchor's `initialize` instruction
+        assert_eq!(
+            sighash("global", "initialize"),
+            [175, 175, 109, 31, 13, 152, 155, 237]
+        );
+    }
+}
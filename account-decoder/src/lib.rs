@@ -4,11 +4,13 @@ extern crate lazy_static;
 #[macro_use]
 extern crate serde_derive;
 
+pub mod idl;
 pub mod parse_account_data;
 pub mod parse_address_lookup_table;
 pub mod parse_bpf_loader;
 #[allow(deprecated)]
 pub mod parse_config;
+pub mod parse_idl;
 pub mod parse_nonce;
 pub mod parse_stake;
 pub mod parse_sysvar;
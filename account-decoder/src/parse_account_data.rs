@@ -11,7 +11,10 @@ use {
         address_lookup_table, instruction::InstructionError, pubkey::Pubkey, stake, system_program,
         sysvar, vote,
     },
-    std::collections::HashMap,
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
     thiserror::Error,
 };
 
@@ -42,6 +45,42 @@ lazy_static! {
         m.insert(*VOTE_PROGRAM_ID, ParsableAccount::Vote);
         m
     };
+    static ref REGISTERED_PARSERS: RwLock<HashMap<Pubkey, Arc<dyn AccountParser>>> =
+        RwLock::default();
+}
+
+/// Parses the accounts owned by a program without a built-in parser
+pub trait AccountParser: Send + Sync {
+    /// Reported as the `program` of parsed accounts
+    fn program_name(&self) -> &str;
+
+    fn parse(&self, pubkey: &Pubkey, data: &[u8]) -> Result<Value, ParseAccountError>;
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegisterAccountParserError {
+    #[error("{0} has a built-in account parser")]
+    BuiltinParser(Pubkey),
+
+    #[error("{0} already has a registered account parser")]
+    AlreadyRegistered(Pubkey),
+}
+
+/// Register `parser` for the accounts owned by `program_id`, typically at startup so that
+/// `jsonParsed` accounts include them
+pub fn register_account_parser(
+    program_id: Pubkey,
+    parser: Arc<dyn AccountParser>,
+) -> Result<(), RegisterAccountParserError> {
+    if PARSABLE_PROGRAM_IDS.contains_key(&program_id) {
+        return Err(RegisterAccountParserError::BuiltinParser(program_id));
+    }
+    let mut registered_parsers = REGISTERED_PARSERS.write().unwrap();
+    if registered_parsers.contains_key(&program_id) {
+        return Err(RegisterAccountParserError::AlreadyRegistered(program_id));
+    }
+    registered_parsers.insert(program_id, parser);
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -52,6 +91,9 @@ pub enum ParseAccountError {
     #[error("Program not parsable")]
     ProgramNotParsable,
 
+    #[error("{0} account not parsable")]
+    RegisteredAccountNotParsable(String),
+
     #[error("Additional data required to parse: {0}")]
     AdditionalDataMissing(String),
 
@@ -95,9 +137,19 @@ pub fn parse_account_data(
     data: &[u8],
     additional_data: Option<AccountAdditionalData>,
 ) -> Result<ParsedAccount, ParseAccountError> {
-    let program_name = PARSABLE_PROGRAM_IDS
-        .get(program_id)
-        .ok_or(ParseAccountError::ProgramNotParsable)?;
+    let Some(program_name) = PARSABLE_PROGRAM_IDS.get(program_id) else {
+        let parser = REGISTERED_PARSERS
+            .read()
+            .unwrap()
+            .get(program_id)
+            .cloned()
+            .ok_or(ParseAccountError::ProgramNotParsable)?;
+        return Ok(ParsedAccount {
+            program: parser.program_name().to_string(),
+            parsed: parser.parse(pubkey, data)?,
+            space: data.len() as u64,
+        });
+    };
     let additional_data = additional_data.unwrap_or_default();
     let parsed_json = match program_name {
         ParsableAccount::AddressLookupTable => {
@@ -126,6 +178,7 @@ pub fn parse_account_data(
 mod test {
     use {
         super::*,
+        serde_json::json,
         solana_sdk::{
             nonce::{
                 state::{Data, Versions},
@@ -171,4 +224,49 @@ mod test {
         assert_eq!(parsed.program, "nonce".to_string());
         assert_eq!(parsed.space, State::size() as u64);
     }
+
+    struct LengthParser;
+
+    impl AccountParser for LengthParser {
+        fn program_name(&self) -> &str {
+            "length"
+        }
+
+        fn parse(&self, _pubkey: &Pubkey, data: &[u8]) -> Result<Value, ParseAccountError> {
+            if data.is_empty() {
+                return Err(ParseAccountError::RegisteredAccountNotParsable(
+                    self.program_name().to_string(),
+                ));
+            }
+            Ok(json!({ "len": data.len() }))
+        }
+    }
+
+    #[test]
+    fn test_register_account_parser() {
+        let account_pubkey = solana_sdk::pubkey::new_rand();
+        let program_id = Pubkey::new_unique();
+        let data = vec![0; 4];
+        assert!(parse_account_data(&account_pubkey, &program_id, &data, None).is_err());
+
+        register_account_parser(program_id, Arc::new(LengthParser)).unwrap();
+        assert_eq!(
+            register_account_parser(program_id, Arc::new(LengthParser)),
+            Err(RegisterAccountParserError::AlreadyRegistered(program_id))
+        );
+        assert_eq!(
+            register_account_parser(vote_program_id(), Arc::new(LengthParser)),
+            Err(RegisterAccountParserError::BuiltinParser(vote_program_id()))
+        );
+
+        assert_eq!(
+            parse_account_data(&account_pubkey, &program_id, &data, None).unwrap(),
+            ParsedAccount {
+                program: "length".to_string(),
+                parsed: json!({ "len": 4 }),
+                space: 4,
+            }
+        );
+        assert!(parse_account_data(&account_pubkey, &program_id, &[], None).is_err());
+    }
 }
@@ -0,0 +1,174 @@
+use {
+    crate::{
+        idl::{sighash, Idl, IdlError, IdlTypeDefTy},
+        parse_account_data::{AccountParser, ParseAccountError},
+    },
+    inflector::Inflector,
+    serde_json::{json, Value},
+    solana_sdk::pubkey::Pubkey,
+    std::path::Path,
+};
+
+struct IdlAccountLayout {
+    name: String,
+    discriminator: Vec<u8>,
+    ty: IdlTypeDefTy,
+}
+
+/// Parses the accounts owned by a program described by an Anchor-style IDL. Accounts are matched
+/// by discriminator, and their data after it decoded with the account's Borsh layout; trailing
+/// bytes are ignored, since accounts are often allocated with room to grow.
+pub struct IdlAccountParser {
+    program_id: Pubkey,
+    program_name: String,
+    idl: Idl,
+    layouts: Vec<IdlAccountLayout>,
+}
+
+impl IdlAccountParser {
+    pub fn new(idl: Idl) -> Result<Self, IdlError> {
+        let program_id = idl.program_id()?;
+        let program_name = idl.program_name().unwrap_or_else(|| program_id.to_string());
+        // Newer IDLs describe accounts in `types`, older ones inline
+        let layouts = idl
+            .accounts
+            .iter()
+            .map(|account| {
+                let ty = account
+                    .ty
+                    .clone()
+                    .or_else(|| {
+                        idl.types
+                            .iter()
+                            .find(|type_def| type_def.name == account.name)
+                            .map(|type_def| type_def.ty.clone())
+                    })
+                    .ok_or_else(|| IdlError::UnknownType(account.name.clone()))?;
+                Ok(IdlAccountLayout {
+                    name: account.name.to_camel_case(),
+                    discriminator: account
+                        .discriminator
+                        .clone()
+                        .unwrap_or_else(|| sighash("account", &account.name).to_vec()),
+                    ty,
+                })
+            })
+            .collect::<Result<_, IdlError>>()?;
+        Ok(Self {
+            program_id,
+            program_name,
+            idl,
+            layouts,
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, IdlError> {
+        Self::new(Idl::from_file(path)?)
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    fn not_parsable(&self) -> ParseAccountError {
+        ParseAccountError::RegisteredAccountNotParsable(self.program_name.clone())
+    }
+}
+
+impl AccountParser for IdlAccountParser {
+    fn program_name(&self) -> &str {
+        &self.program_name
+    }
+
+    fn parse(&self, _pubkey: &Pubkey, data: &[u8]) -> Result<Value, ParseAccountError> {
+        let layout = self
+            .layouts
+            .iter()
+            .find(|layout| data.starts_with(&layout.discriminator))
+            .ok_or_else(|| self.not_parsable())?;
+        let mut data = &data[layout.discriminator.len()..];
+        let info = self
+            .idl
+            .decode_type_def(&layout.ty, &mut data)
+            .map_err(|_| self.not_parsable())?;
+        Ok(json!({
+            "type": layout.name,
+            "info": info,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_idl_account() {
+        let program_id = Pubkey::new_unique();
+        let idl: Idl = serde_json::from_value(json!({
+            "address": program_id.to_string(),
+            "metadata": { "name": "counterProgram" },
+            "accounts": [
+                { "name": "Counter" },
+                {
+                    "name": "Config",
+                    "discriminator": [1, 2],
+                    "type": {
+                        "kind": "struct",
+                        "fields": [{ "name": "admin", "type": "pubkey" }]
+                    }
+                }
+            ],
+            "types": [{
+                "name": "Counter",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "count", "type": "u64" },
+                        { "name": "last_caller", "type": { "option": "pubkey" } }
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+        let parser = IdlAccountParser::new(idl).unwrap();
+        assert_eq!(parser.program_id(), &program_id);
+        assert_eq!(parser.program_name(), "counter-program");
+        let pubkey = Pubkey::new_unique();
+
+        let mut data = sighash("account", "Counter").to_vec();
+        data.extend(3u64.to_le_bytes());
+        data.push(0);
+        data.extend([0; 16]);
+        assert_eq!(
+            parser.parse(&pubkey, &data).unwrap(),
+            json!({
+                "type": "counter",
+                "info": { "count": 3, "lastCaller": null },
+            })
+        );
+
+        let admin = Pubkey::new_unique();
+        let mut data = vec![1, 2];
+        data.extend(admin.to_bytes());
+        assert_eq!(
+            parser.parse(&pubkey, &data).unwrap(),
+            json!({
+                "type": "config",
+                "info": { "admin": admin.to_string() },
+            })
+        );
+
+        // Truncated data and unknown discriminator
+        assert!(parser.parse(&pubkey, &data[..10]).is_err());
+        assert!(parser.parse(&pubkey, &[2, 1]).is_err());
+
+        // An account without a layout
+        let idl: Idl = serde_json::from_value(json!({
+            "address": program_id.to_string(),
+            "accounts": [{ "name": "Counter" }],
+        }))
+        .unwrap();
+        assert!(IdlAccountParser::new(idl).is_err());
+    }
+}
@@ -865,6 +865,7 @@ mod tests {
                 durable_nonce_fee: nonce.map(DurableNonceFee::from),
                 return_data: None,
                 executed_units: 0,
+                instruction_compute_units: None,
                 accounts_data_len_delta: 0,
             },
             programs_modified_by_tx: Box::<ProgramCacheForTxBatch>::default(),
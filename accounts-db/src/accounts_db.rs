@@ -60,6 +60,7 @@ use {
         },
         contains::Contains,
         epoch_accounts_hash::EpochAccountsHashManager,
+        lazy_accounts_source::LazyAccounts,
         partitioned_rewards::{PartitionedEpochRewardsConfig, TestPartitionedEpochRewards},
         pubkey_bins::PubkeyBinCalculator24,
         read_only_accounts_cache::ReadOnlyAccountsCache,
@@ -1402,6 +1403,11 @@ pub struct AccountsDb {
     /// GeyserPlugin accounts update notifier
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
 
+    /// Accounts loaded on demand when they are not in the index
+    lazy_accounts_source: Option<LazyAccounts>,
+    /// Total lamports of the accounts in `lazy_accounts_source`
+    lazy_accounts_capitalization: u64,
+
     pub(crate) active_stats: ActiveStats,
 
     pub verify_accounts_hash_in_bg: VerifyAccountsHashInBackground,
@@ -2392,6 +2398,8 @@ impl AccountsDb {
             dirty_stores: DashMap::default(),
             zero_lamport_accounts_to_purge_after_full_snapshot: DashSet::default(),
             accounts_update_notifier: None,
+            lazy_accounts_source: None,
+            lazy_accounts_capitalization: 0,
             log_dead_slots: AtomicBool::new(true),
             exhaustively_verify_refcounts: false,
             accounts_file_provider: AccountsFileProvider::default(),
@@ -4916,6 +4924,24 @@ impl AccountsDb {
         bank_hash_stats.insert(slot, BankHashStats::default());
     }
 
+    /// Load accounts missing from the index from `lazy_accounts_source`, see `LazyAccountsSource`.
+    /// `capitalization` is the total lamports of its accounts.
+    pub fn set_lazy_accounts_source(
+        &mut self,
+        lazy_accounts_source: LazyAccounts,
+        capitalization: u64,
+    ) {
+        self.lazy_accounts_source = Some(lazy_accounts_source);
+        self.lazy_accounts_capitalization = capitalization;
+    }
+
+    fn load_from_lazy_accounts_source(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        let lazy_accounts_source = self.lazy_accounts_source.as_ref()?;
+        lazy_accounts_source
+            .load_account(pubkey)
+            .map(|account| (account, lazy_accounts_source.slot()))
+    }
+
     pub fn load(
         &self,
         ancestors: &Ancestors,
@@ -4935,9 +4961,17 @@ impl AccountsDb {
         account: &Pubkey,
         owners: &[Pubkey],
     ) -> Result<usize, MatchAccountOwnerError> {
-        let (slot, storage_location, _maybe_account_accesor) = self
-            .read_index_for_accessor_or_load_slow(ancestors, account, None, false)
-            .ok_or(MatchAccountOwnerError::UnableToLoad)?;
+        let Some((slot, storage_location, _maybe_account_accesor)) =
+            self.read_index_for_accessor_or_load_slow(ancestors, account, None, false)
+        else {
+            let (account, _slot) = self
+                .load_from_lazy_accounts_source(account)
+                .ok_or(MatchAccountOwnerError::UnableToLoad)?;
+            return owners
+                .iter()
+                .position(|entry| account.owner() == entry)
+                .ok_or(MatchAccountOwnerError::NoMatch);
+        };
 
         if !storage_location.is_cached() {
             let result = self.read_only_accounts_cache.load(*account, slot);
@@ -5328,9 +5362,16 @@ impl AccountsDb {
         #[cfg(not(test))]
         assert!(max_root.is_none());
 
-        let (slot, storage_location, _maybe_account_accesor) =
-            self.read_index_for_accessor_or_load_slow(ancestors, pubkey, max_root, false)?;
-        // Notice the subtle `?` at previous line, we bail out pretty early if missing.
+        let Some((slot, storage_location, _maybe_account_accesor)) =
+            self.read_index_for_accessor_or_load_slow(ancestors, pubkey, max_root, false)
+        else {
+            // Missing from the index, only the lazy accounts source can have it
+            return if load_into_read_cache_only {
+                None
+            } else {
+                self.load_from_lazy_accounts_source(pubkey)
+            };
+        };
 
         let in_write_cache = storage_location.is_cached();
         if !load_into_read_cache_only {
@@ -6550,11 +6591,14 @@ impl AccountsDb {
         // We'll also accumulate the lamports within each chunk and fewer chunks results in less contention to accumulate the sum.
         let chunks = crate::accounts_hash::MERKLE_FANOUT.pow(4);
         let total_lamports = Mutex::<u64>::new(0);
+        // Lamports of the lazily loaded accounts which were stored since
+        let shadowed_lazy_lamports = Mutex::<u128>::new(0);
 
         let get_account_hashes = || {
             keys.par_chunks(chunks)
                 .map(|pubkeys| {
                     let mut sum = 0u128;
+                    let mut shadowed_sum = 0u128;
                     let account_hashes: Vec<Hash> = pubkeys
                         .iter()
                         .filter_map(|pubkey| {
@@ -6565,6 +6609,14 @@ impl AccountsDb {
                                     config.ancestors,
                                     Some(max_slot),
                                     |(slot, account_info)| {
+                                        if let Some(lazy_accounts_source) =
+                                            &self.lazy_accounts_source
+                                        {
+                                            shadowed_sum += lazy_accounts_source
+                                                .load_account(pubkey)
+                                                .map_or(0, |account| account.lamports())
+                                                as u128;
+                                        }
                                         if account_info.is_zero_lamport() {
                                             return None;
                                         }
@@ -6602,6 +6654,7 @@ impl AccountsDb {
                         .collect();
                     let mut total = total_lamports.lock().unwrap();
                     *total = AccountsHasher::checked_cast_for_capitalization(*total as u128 + sum);
+                    *shadowed_lazy_lamports.lock().unwrap() += shadowed_sum;
                     account_hashes
                 })
                 .collect()
@@ -6611,7 +6664,16 @@ impl AccountsDb {
         let account_hashes: Vec<Vec<Hash>> = self.thread_pool_clean.install(get_account_hashes);
         scan.stop();
 
-        let total_lamports = *total_lamports.lock().unwrap();
+        let mut total_lamports = *total_lamports.lock().unwrap();
+        if self.lazy_accounts_source.is_some() {
+            // The accounts which were never loaded are not in the index, but are still part of the
+            // capitalization. They are not part of the hash though.
+            let shadowed_lazy_lamports = *shadowed_lazy_lamports.lock().unwrap();
+            total_lamports = AccountsHasher::checked_cast_for_capitalization(
+                (total_lamports as u128 + self.lazy_accounts_capitalization as u128)
+                    .saturating_sub(shadowed_lazy_lamports),
+            );
+        }
 
         let mut hash_time = Measure::start("hash");
         let (accumulated_hash, hash_total) = AccountsHasher::calculate_hash(account_hashes);
@@ -9480,6 +9542,7 @@ pub mod tests {
             ancient_append_vecs,
             append_vec::{test_utils::TempFile, AppendVec, AppendVecStoredAccountMeta},
             cache_hash_data::CacheHashDataFile,
+            lazy_accounts_source::LazyAccountsSource,
             storable_accounts::AccountForStorage,
         },
         assert_matches::assert_matches,
@@ -13453,6 +13516,78 @@ pub mod tests {
         assert_eq!(db.read_only_accounts_cache.cache_len(), 1);
     }
 
+    #[derive(Debug)]
+    struct TestLazyAccountsSource {
+        slot: Slot,
+        accounts: HashMap<Pubkey, AccountSharedData>,
+    }
+
+    impl LazyAccountsSource for TestLazyAccountsSource {
+        fn slot(&self) -> Slot {
+            self.slot
+        }
+
+        fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+            self.accounts.get(pubkey).cloned()
+        }
+    }
+
+    #[test]
+    fn test_load_from_lazy_accounts_source() {
+        let owner = Pubkey::new_unique();
+        let lazy_key = Pubkey::new_unique();
+        let lazy_account = AccountSharedData::new(42, 1, &owner);
+        let mut db = AccountsDb::new_single_for_tests();
+        db.set_lazy_accounts_source(
+            Arc::new(TestLazyAccountsSource {
+                slot: 0,
+                accounts: HashMap::from([(lazy_key, lazy_account.clone())]),
+            }),
+            42,
+        );
+        let ancestors = Ancestors::from(vec![0, 1]);
+
+        assert_eq!(
+            db.load_without_fixed_root(&ancestors, &lazy_key),
+            Some((lazy_account, 0))
+        );
+        assert_eq!(
+            db.account_matches_owners(&ancestors, &lazy_key, &[owner]),
+            Ok(0)
+        );
+        assert_eq!(
+            db.account_matches_owners(&ancestors, &lazy_key, &[Pubkey::new_unique()]),
+            Err(MatchAccountOwnerError::NoMatch)
+        );
+        assert_eq!(
+            db.load_without_fixed_root(&ancestors, &Pubkey::new_unique()),
+            None
+        );
+        assert_eq!(
+            db.update_accounts_hash_for_tests(1, &ancestors, false, true)
+                .1,
+            42
+        );
+
+        // Once stored, the account is loaded from the accounts db
+        let stored_account = AccountSharedData::new(7, 0, &owner);
+        db.store_cached((1, &[(&lazy_key, &stored_account)][..]), None);
+        assert_eq!(
+            db.load_without_fixed_root(&ancestors, &lazy_key),
+            Some((stored_account, 1))
+        );
+        // and its lazily loaded lamports are no longer part of the capitalization
+        assert_eq!(
+            db.update_accounts_hash_for_tests(1, &ancestors, false, true)
+                .1,
+            7
+        );
+
+        // Including when it is closed
+        db.store_cached((1, &[(&lazy_key, &AccountSharedData::default())][..]), None);
+        assert_eq!(db.load_without_fixed_root(&ancestors, &lazy_key), None);
+    }
+
     #[test]
     fn test_account_matches_owners() {
         let db = Arc::new(AccountsDb::new_single_for_tests());
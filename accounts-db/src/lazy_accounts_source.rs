@@ -0,0 +1,26 @@
+use {
+    solana_sdk::{account::AccountSharedData, clock::Slot, pubkey::Pubkey},
+    std::sync::Arc,
+};
+
+/// Accounts of a root which are read on demand instead of being stored in the accounts db, such
+/// as those of a snapshot archive that is not unpacked.
+///
+/// When an account is not in the accounts index, `AccountsDb` loads it from the source, as of
+/// `slot()`. Once the account is stored, it is loaded from the accounts db as usual.
+///
+/// Index scans, such as `getProgramAccounts` or eager rent collection, only see the accounts that
+/// were stored. The accounts hash calculated from the index adds the lamports of the accounts which
+/// were never stored to its total, so it still matches the capitalization, but its hash only
+/// covers the stored accounts. Accounts must not be cleaned from the index while a source is set,
+/// or the version in the source would be loaded again.
+pub trait LazyAccountsSource: std::fmt::Debug {
+    /// The root slot the accounts of the source are from
+    fn slot(&self) -> Slot;
+
+    /// Load the account at `pubkey`. Returns `None` if the source does not have it, or if it has
+    /// zero lamports.
+    fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+}
+
+pub type LazyAccounts = Arc<dyn LazyAccountsSource + Sync + Send>;
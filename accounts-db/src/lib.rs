@@ -27,6 +27,7 @@ mod cache_hash_data_stats;
 pub mod contains;
 pub mod epoch_accounts_hash;
 pub mod hardened_unpack;
+pub mod lazy_accounts_source;
 pub mod partitioned_rewards;
 mod pubkey_bins;
 mod read_only_accounts_cache;
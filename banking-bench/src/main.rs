@@ -7,7 +7,9 @@ use {
     rayon::prelude::*,
     solana_client::connection_cache::ConnectionCache,
     solana_core::{
-        banking_stage::BankingStage,
+        banking_stage::{
+            transaction_scheduler::scheduler::PrioGraphSchedulerFactory, BankingStage,
+        },
         banking_trace::{BankingPacketBatch, BankingTracer, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
         validator::BlockProductionMethod,
     },
@@ -462,6 +464,7 @@ fn main() {
     };
     let banking_stage = BankingStage::new_num_threads(
         block_production_method,
+        Arc::new(PrioGraphSchedulerFactory),
         &cluster_info,
         &poh_recorder,
         non_vote_receiver,
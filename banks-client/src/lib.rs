@@ -164,6 +164,18 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub fn simulate_bundle_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        transactions: Vec<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Vec<BanksTransactionResultWithSimulation>, BanksClientError>> + '_
+    {
+        self.inner
+            .simulate_bundle_with_commitment_and_context(ctx, transactions, commitment)
+            .map_err(Into::into)
+    }
+
     pub fn get_account_with_commitment_and_context(
         &mut self,
         ctx: Context,
@@ -357,6 +369,31 @@ impl BanksClient {
         self.simulate_transaction_with_commitment(transaction, CommitmentLevel::default())
     }
 
+    /// Simulate a bundle of transactions, in order, at the given commitment level. Each
+    /// transaction observes the account state left behind by the transactions before it, and
+    /// simulation stops at the first transaction that fails.
+    pub fn simulate_bundle_with_commitment(
+        &mut self,
+        transactions: Vec<impl Into<VersionedTransaction>>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Vec<BanksTransactionResultWithSimulation>, BanksClientError>> + '_
+    {
+        self.simulate_bundle_with_commitment_and_context(
+            context::current(),
+            transactions.into_iter().map(Into::into).collect(),
+            commitment,
+        )
+    }
+
+    /// Simulate a bundle of transactions, in order, at the default commitment level
+    pub fn simulate_bundle(
+        &mut self,
+        transactions: Vec<impl Into<VersionedTransaction>>,
+    ) -> impl Future<Output = Result<Vec<BanksTransactionResultWithSimulation>, BanksClientError>> + '_
+    {
+        self.simulate_bundle_with_commitment(transactions, CommitmentLevel::default())
+    }
+
     /// Return the most recent rooted slot. All transactions at or below this slot
     /// are said to be finalized. The cluster will not fork to a higher slot.
     pub fn get_root_slot(&mut self) -> impl Future<Output = Result<Slot, BanksClientError>> + '_ {
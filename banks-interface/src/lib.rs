@@ -90,6 +90,10 @@ pub trait Banks {
         transaction: VersionedTransaction,
         commitment: CommitmentLevel,
     ) -> BanksTransactionResultWithSimulation;
+    async fn simulate_bundle_with_commitment_and_context(
+        transactions: Vec<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> Vec<BanksTransactionResultWithSimulation>;
     async fn get_account_with_commitment_and_context(
         address: Pubkey,
         commitment: CommitmentLevel,
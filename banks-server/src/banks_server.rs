@@ -195,6 +195,7 @@ fn simulate_transaction(
         units_consumed,
         return_data,
         inner_instructions,
+        instruction_compute_units: _,
     } = bank.simulate_transaction_unchecked(&sanitized_transaction, false);
 
     let simulation_details = TransactionSimulationDetails {
@@ -209,6 +210,65 @@ fn simulate_transaction(
     }
 }
 
+fn simulate_bundle(
+    bank: &Bank,
+    transactions: Vec<VersionedTransaction>,
+) -> Vec<BanksTransactionResultWithSimulation> {
+    let mut sanitized_transactions = Vec::with_capacity(transactions.len());
+    let mut sanitize_error = None;
+    for transaction in transactions {
+        match SanitizedTransaction::try_create(
+            transaction,
+            MessageHash::Compute,
+            Some(false), // is_simple_vote_tx
+            bank,
+            bank.get_reserved_account_keys(),
+        ) {
+            Ok(tx) => sanitized_transactions.push(tx),
+            Err(err) => {
+                sanitize_error = Some(err);
+                break;
+            }
+        }
+    }
+
+    let simulation_results =
+        bank.simulate_transaction_bundle_unchecked(&sanitized_transactions, false);
+    let all_succeeded = simulation_results
+        .iter()
+        .all(|result| result.result.is_ok());
+    let mut results = simulation_results
+        .into_iter()
+        .map(|simulation_result| {
+            let TransactionSimulationResult {
+                result,
+                logs,
+                post_simulation_accounts: _,
+                units_consumed,
+                return_data,
+                inner_instructions,
+                instruction_compute_units: _,
+            } = simulation_result;
+            BanksTransactionResultWithSimulation {
+                result: Some(result),
+                simulation_details: Some(TransactionSimulationDetails {
+                    logs,
+                    units_consumed,
+                    return_data,
+                    inner_instructions,
+                }),
+            }
+        })
+        .collect::<Vec<_>>();
+    if let Some(err) = sanitize_error.filter(|_| all_succeeded) {
+        results.push(BanksTransactionResultWithSimulation {
+            result: Some(Err(err)),
+            simulation_details: None,
+        });
+    }
+    results
+}
+
 #[tarpc::server]
 impl Banks for BanksServer {
     async fn send_transaction_with_context(self, _: Context, transaction: VersionedTransaction) {
@@ -321,6 +381,15 @@ impl Banks for BanksServer {
         simulate_transaction(&self.bank(commitment), transaction)
     }
 
+    async fn simulate_bundle_with_commitment_and_context(
+        self,
+        _: Context,
+        transactions: Vec<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> Vec<BanksTransactionResultWithSimulation> {
+        simulate_bundle(&self.bank(commitment), transactions)
+    }
+
     async fn process_transaction_with_commitment_and_context(
         self,
         _: Context,
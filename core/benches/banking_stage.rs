@@ -20,6 +20,7 @@ use {
             consumer::Consumer,
             leader_slot_metrics::LeaderSlotMetricsTracker,
             qos_service::QosService,
+            transaction_scheduler::scheduler::PrioGraphSchedulerFactory,
             unprocessed_packet_batches::*,
             unprocessed_transaction_storage::{ThreadType, UnprocessedTransactionStorage},
             BankingStage, BankingStageStats,
@@ -292,6 +293,7 @@ fn bench_banking(bencher: &mut Bencher, tx_type: TransactionType) {
     let (s, _r) = unbounded();
     let _banking_stage = BankingStage::new(
         BlockProductionMethod::ThreadLocalMultiIterator,
+        Arc::new(PrioGraphSchedulerFactory),
         &cluster_info,
         &poh_recorder,
         non_vote_receiver,
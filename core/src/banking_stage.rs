@@ -20,8 +20,8 @@ use {
             consume_worker::ConsumeWorker,
             packet_deserializer::PacketDeserializer,
             transaction_scheduler::{
-                prio_graph_scheduler::PrioGraphScheduler,
-                scheduler_controller::SchedulerController, scheduler_error::SchedulerError,
+                scheduler::SchedulerFactory, scheduler_controller::SchedulerController,
+                scheduler_error::SchedulerError,
             },
         },
         banking_trace::BankingPacketReceiver,
@@ -53,8 +53,12 @@ use {
 // Below modules are pub to allow use by banking_stage bench
 pub mod committer;
 pub mod consumer;
+pub mod immutable_deserialized_packet;
 pub mod leader_slot_metrics;
 pub mod qos_service;
+pub mod scheduler_messages;
+pub mod trace_replay;
+pub mod transaction_scheduler;
 pub mod unprocessed_packet_batches;
 pub mod unprocessed_transaction_storage;
 
@@ -63,16 +67,12 @@ mod decision_maker;
 mod forward_packet_batches_by_accounts;
 mod forward_worker;
 mod forwarder;
-mod immutable_deserialized_packet;
 mod latest_unprocessed_votes;
 mod leader_slot_timing_metrics;
 mod multi_iterator_scanner;
 mod packet_deserializer;
 mod packet_receiver;
 mod read_write_account_set;
-#[allow(dead_code)]
-mod scheduler_messages;
-mod transaction_scheduler;
 
 // Fixed thread size seems to be fastest on GCP setup
 pub const NUM_THREADS: u32 = 6;
@@ -324,6 +324,7 @@ impl BankingStage {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         block_production_method: BlockProductionMethod,
+        transaction_scheduler_factory: Arc<dyn SchedulerFactory>,
         cluster_info: &Arc<ClusterInfo>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -338,6 +339,7 @@ impl BankingStage {
     ) -> Self {
         Self::new_num_threads(
             block_production_method,
+            transaction_scheduler_factory,
             cluster_info,
             poh_recorder,
             non_vote_receiver,
@@ -356,6 +358,7 @@ impl BankingStage {
     #[allow(clippy::too_many_arguments)]
     pub fn new_num_threads(
         block_production_method: BlockProductionMethod,
+        transaction_scheduler_factory: Arc<dyn SchedulerFactory>,
         cluster_info: &Arc<ClusterInfo>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -387,6 +390,7 @@ impl BankingStage {
                 )
             }
             BlockProductionMethod::CentralScheduler => Self::new_central_scheduler(
+                transaction_scheduler_factory,
                 cluster_info,
                 poh_recorder,
                 non_vote_receiver,
@@ -489,6 +493,7 @@ impl BankingStage {
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_central_scheduler(
+        transaction_scheduler_factory: Arc<dyn SchedulerFactory>,
         cluster_info: &Arc<ClusterInfo>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -586,7 +591,8 @@ impl BankingStage {
         bank_thread_hdls.push({
             let packet_deserializer =
                 PacketDeserializer::new(non_vote_receiver, bank_forks.clone());
-            let scheduler = PrioGraphScheduler::new(work_senders, finished_work_receiver);
+            let scheduler = transaction_scheduler_factory
+                .create_scheduler(work_senders, finished_work_receiver);
             let scheduler_controller = SchedulerController::new(
                 decision_maker.clone(),
                 packet_deserializer,
@@ -783,7 +789,10 @@ impl BankingStage {
 mod tests {
     use {
         super::*,
-        crate::banking_trace::{BankingPacketBatch, BankingTracer},
+        crate::{
+            banking_stage::transaction_scheduler::scheduler::PrioGraphSchedulerFactory,
+            banking_trace::{BankingPacketBatch, BankingTracer},
+        },
         crossbeam_channel::{unbounded, Receiver},
         itertools::Itertools,
         solana_entry::entry::{self, Entry, EntrySlice},
@@ -859,6 +868,7 @@ mod tests {
 
             let banking_stage = BankingStage::new(
                 BlockProductionMethod::ThreadLocalMultiIterator,
+                Arc::new(PrioGraphSchedulerFactory),
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -914,6 +924,7 @@ mod tests {
 
             let banking_stage = BankingStage::new(
                 BlockProductionMethod::ThreadLocalMultiIterator,
+                Arc::new(PrioGraphSchedulerFactory),
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -993,6 +1004,7 @@ mod tests {
 
             let banking_stage = BankingStage::new(
                 block_production_method,
+                Arc::new(PrioGraphSchedulerFactory),
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -1354,6 +1366,7 @@ mod tests {
 
             let banking_stage = BankingStage::new(
                 BlockProductionMethod::ThreadLocalMultiIterator,
+                Arc::new(PrioGraphSchedulerFactory),
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -35,7 +35,9 @@ use {
         account_loader::{validate_fee_payer, TransactionCheckResult},
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_processor::ExecutionRecordingConfig,
+        transaction_results::TransactionExecutionResult,
     },
+    solana_transaction_metrics_tracker::lifecycle_tracing::{self, TransactionStage},
     std::{
         sync::{atomic::Ordering, Arc},
         time::Instant,
@@ -471,6 +473,12 @@ impl Consumer {
         chunk_offset: usize,
         pre_results: impl Iterator<Item = Result<(), TransactionError>>,
     ) -> ProcessTransactionBatchOutput {
+        if lifecycle_tracing::is_enabled() {
+            for tx in txs {
+                lifecycle_tracing::record(tx.signature(), TransactionStage::Consuming);
+            }
+        }
+
         let (
             (transaction_qos_cost_results, cost_model_throttled_transactions_count),
             cost_model_us,
@@ -614,6 +622,14 @@ impl Consumer {
             ..
         } = load_and_execute_transactions_output;
 
+        if lifecycle_tracing::is_enabled() {
+            Self::trace_execution_results(
+                batch.sanitized_transactions(),
+                &execution_results,
+                &retryable_transaction_indexes,
+            );
+        }
+
         let transactions_attempted_execution_count = execution_results.len();
         let (executed_transactions, execution_results_to_transactions_us) =
             measure_us!(execution_results
@@ -657,6 +673,26 @@ impl Consumer {
             ..record_transactions_timings
         };
 
+        if lifecycle_tracing::is_enabled() {
+            for (tx, _) in batch
+                .sanitized_transactions()
+                .iter()
+                .zip(&execution_results)
+                .filter(|(_, execution_result)| execution_result.was_executed())
+            {
+                match &record_transactions_result {
+                    Ok(()) => {
+                        lifecycle_tracing::record(tx.signature(), TransactionStage::PohRecorded)
+                    }
+                    Err(recorder_err) => lifecycle_tracing::record_retry(
+                        tx.signature(),
+                        TransactionStage::PohRecorded.as_str(),
+                        format!("{recorder_err:?}"),
+                    ),
+                }
+            }
+        }
+
         if let Err(recorder_err) = record_transactions_result {
             retryable_transaction_indexes.extend(execution_results.iter().enumerate().filter_map(
                 |(index, execution_result)| execution_result.was_executed().then_some(index),
@@ -700,6 +736,18 @@ impl Consumer {
 
         drop(freeze_lock);
 
+        if lifecycle_tracing::is_enabled() {
+            for (tx, commit_details) in batch
+                .sanitized_transactions()
+                .iter()
+                .zip(&commit_transaction_statuses)
+            {
+                if let CommitTransactionDetails::Committed { .. } = commit_details {
+                    lifecycle_tracing::record(tx.signature(), TransactionStage::Committed);
+                }
+            }
+        }
+
         debug!(
             "bank: {} process_and_record_locked: {}us record: {}us commit: {}us txs_len: {}",
             bank.slot(),
@@ -732,6 +780,35 @@ impl Consumer {
         }
     }
 
+    /// Record the execution outcome of sampled transactions for lifecycle tracing
+    fn trace_execution_results(
+        txs: &[SanitizedTransaction],
+        execution_results: &[TransactionExecutionResult],
+        retryable_transaction_indexes: &[usize],
+    ) {
+        for (index, (tx, execution_result)) in txs.iter().zip(execution_results).enumerate() {
+            match execution_result {
+                TransactionExecutionResult::Executed { .. } => {
+                    lifecycle_tracing::record(tx.signature(), TransactionStage::Executed)
+                }
+                TransactionExecutionResult::NotExecuted(err)
+                    if retryable_transaction_indexes.contains(&index) =>
+                {
+                    lifecycle_tracing::record_retry(
+                        tx.signature(),
+                        TransactionStage::Executed.as_str(),
+                        err,
+                    )
+                }
+                TransactionExecutionResult::NotExecuted(err) => lifecycle_tracing::record_dropped(
+                    tx.signature(),
+                    TransactionStage::Executed.as_str(),
+                    err,
+                ),
+            }
+        }
+    }
+
     pub fn check_fee_payer_unlocked(
         bank: &Bank,
         message: &SanitizedMessage,
@@ -0,0 +1,680 @@
+//! Deterministic replay of a banking trace recorded by `BankingTracer`.
+//!
+//! The non-vote packets of the trace are grouped into the leader blocks they were
+//! received for, and fed block by block into a `Scheduler` on top of a parent bank,
+//! typically loaded from a snapshot. Packets go through the scheduler's intake hooks,
+//! `Scheduler::filter_received_packet` and `Scheduler::buffer_transaction`, as they do
+//! in the `SchedulerController`. Batches picked by the scheduler are executed in
+//! order on the calling thread instead of by consume worker threads, so the outcome
+//! only depends on the trace, the parent bank and the scheduler.
+use {
+    super::{
+        immutable_deserialized_packet::ImmutableDeserializedPacket,
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
+        transaction_scheduler::{
+            scheduler::{Scheduler, SchedulerFactory},
+            scheduler_controller::SchedulerController,
+            scheduler_error::SchedulerError,
+            transaction_id_generator::TransactionIdGenerator,
+            transaction_state::SanitizedTransactionTTL,
+            transaction_state_container::TransactionStateContainer,
+        },
+        TOTAL_BUFFERED_PACKETS,
+    },
+    crate::banking_trace::{BankingPacketBatch, ChannelLabel, TimedTracedEvent, TracedEvent},
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    solana_cost_model::{block_cost_limits::MAX_BLOCK_UNITS, cost_model::CostModel},
+    solana_program_runtime::{
+        compute_budget_processor::process_compute_budget_instructions, timings::ExecuteTimings,
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, installed_scheduler_pool::BankWithScheduler,
+    },
+    solana_sdk::{
+        clock::{Slot, MAX_PROCESSING_AGE},
+        fee::FeeBudgetLimits,
+        hash::{hashv, Hash},
+        pubkey::Pubkey,
+        saturating_add_assign,
+        signature::Signature,
+        transaction::{SanitizedTransaction, TransactionError},
+    },
+    solana_svm::{
+        transaction_error_metrics::TransactionErrorMetrics,
+        transaction_processor::ExecutionRecordingConfig,
+    },
+    std::{
+        sync::RwLock,
+        time::{Duration, SystemTime},
+    },
+};
+
+/// Packets received for one leader block of a banking trace.
+#[derive(Debug, Default)]
+pub struct TraceBlock {
+    /// Parent slot and its last blockhash, as recorded when the block was started.
+    /// `None` if the trace has no record of the node's leader slots.
+    pub parent: Option<(Slot, Hash)>,
+    /// Non-vote packets available to the block: those buffered since the previous
+    /// block, followed by those received while the block was being produced.
+    pub packet_batches: Vec<BankingPacketBatch>,
+}
+
+impl TraceBlock {
+    /// Groups the non-vote packets of `events` by leader block.
+    ///
+    /// Leader blocks start at the recorded `BlockAndBankHash` events and last
+    /// `slot_duration`. Traces without such events, such as those recorded on a node
+    /// that was never leader, are split into consecutive blocks of `slot_duration`.
+    pub fn from_trace_events(events: Vec<TimedTracedEvent>, slot_duration: Duration) -> Vec<Self> {
+        let has_leader_blocks = events
+            .iter()
+            .any(|TimedTracedEvent(_, event)| matches!(event, TracedEvent::BlockAndBankHash(..)));
+
+        let mut blocks = vec![];
+        let mut current: Option<(SystemTime, TraceBlock)> = None;
+        let mut pending = vec![];
+        for TimedTracedEvent(time, event) in events {
+            match event {
+                TracedEvent::BlockAndBankHash(slot, blockhash, _bank_hash) => {
+                    blocks.extend(current.take().map(|(_, block)| block));
+                    let block = TraceBlock {
+                        parent: Some((slot, blockhash)),
+                        packet_batches: std::mem::take(&mut pending),
+                    };
+                    current = Some((time, block));
+                }
+                TracedEvent::PacketBatch(ChannelLabel::NonVote, batch) => {
+                    if !has_leader_blocks {
+                        let starts_new_block = current.as_ref().map_or(true, |(start, _)| {
+                            time.duration_since(*start).unwrap_or_default() >= slot_duration
+                        });
+                        if starts_new_block {
+                            blocks.extend(current.take().map(|(_, block)| block));
+                            current = Some((time, TraceBlock::default()));
+                        }
+                    }
+                    match current.as_mut() {
+                        Some((start, block))
+                            if time.duration_since(*start).unwrap_or_default() < slot_duration =>
+                        {
+                            block.packet_batches.push(batch)
+                        }
+                        _ => pending.push(batch),
+                    }
+                }
+                TracedEvent::PacketBatch(..) => {}
+            }
+        }
+        blocks.extend(current.map(|(_, block)| block));
+        blocks
+    }
+}
+
+/// Outcome of replaying one block.
+#[derive(Debug, Default)]
+pub struct ReplayedBlock {
+    pub slot: Slot,
+    /// Signatures of the transactions committed to the block, in execution order.
+    pub landed: Vec<Signature>,
+    /// Committed transactions whose execution failed, they still paid fees.
+    pub num_failed: usize,
+    /// Block cost as accounted by the block's `CostTracker`.
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    /// Compute units consumed by the committed transactions.
+    pub executed_units: u64,
+    /// Fees, including priority fees, collected by the leader.
+    pub fees: u64,
+    /// Transactions from the block's packets buffered into the scheduler.
+    pub num_buffered: usize,
+    /// Packets rejected by `Scheduler::filter_received_packet`, or dropped by
+    /// sanitization, account lock validation or transaction checks.
+    pub num_dropped_on_receive: usize,
+    /// Transactions which passed the checks but were dropped because their compute
+    /// budget instructions are invalid.
+    pub num_dropped_on_compute_budget: usize,
+    /// Buffered transactions dropped because the buffer was full.
+    pub num_dropped_on_capacity: usize,
+    /// Transactions handed to the workers.
+    pub num_scheduled: usize,
+    /// Transactions the scheduler could not schedule due to account lock conflicts.
+    pub num_unschedulable: usize,
+    /// Transactions the scheduler dropped before scheduling them.
+    pub num_filtered_out: usize,
+    /// Transactions that failed to take their account locks when executed.
+    pub num_account_in_use: usize,
+    /// Transactions sent back to the scheduler because they did not fit the block limits.
+    pub num_cost_limited: usize,
+}
+
+/// Drives a `Scheduler` through the blocks of a banking trace.
+pub struct BankingTraceReplayer {
+    scheduler: Box<dyn Scheduler>,
+    consume_work_receivers: Vec<Receiver<ConsumeWork>>,
+    finished_consume_work_sender: Sender<FinishedConsumeWork>,
+    container: TransactionStateContainer,
+    transaction_id_generator: TransactionIdGenerator,
+}
+
+impl BankingTraceReplayer {
+    pub fn new(scheduler_factory: &dyn SchedulerFactory, num_workers: usize) -> Self {
+        let (consume_work_senders, consume_work_receivers) =
+            (0..num_workers).map(|_| unbounded()).unzip();
+        let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
+        Self {
+            scheduler: scheduler_factory
+                .create_scheduler(consume_work_senders, finished_consume_work_receiver),
+            consume_work_receivers,
+            finished_consume_work_sender,
+            container: TransactionStateContainer::with_capacity(TOTAL_BUFFERED_PACKETS),
+            transaction_id_generator: TransactionIdGenerator::default(),
+        }
+    }
+
+    /// Produces one block per entry of `blocks`, each a child of the previous one,
+    /// starting from the bank at `parent_slot`. The new banks are inserted into
+    /// `bank_forks`. Transactions not scheduled by the end of a block stay
+    /// buffered for the next ones.
+    ///
+    /// At the end of each block, the recorded parent blockhash of the following block
+    /// is registered, so that transactions referencing it remain valid in the replay.
+    pub fn replay(
+        &mut self,
+        bank_forks: &RwLock<BankForks>,
+        parent_slot: Slot,
+        collector_id: &Pubkey,
+        blocks: &[TraceBlock],
+    ) -> Result<Vec<ReplayedBlock>, SchedulerError> {
+        let mut parent = bank_forks
+            .read()
+            .unwrap()
+            .get(parent_slot)
+            .expect("parent bank is in bank forks");
+        let mut replayed_blocks = Vec::with_capacity(blocks.len());
+        for (index, block) in blocks.iter().enumerate() {
+            parent.freeze();
+            let slot = parent.slot() + 1;
+            let bank = bank_forks
+                .write()
+                .unwrap()
+                .insert(Bank::new_from_parent(parent, collector_id, slot))
+                .clone_without_scheduler();
+            replayed_blocks.push(self.replay_block(&bank, block)?);
+
+            let blockhash = blocks
+                .get(index + 1)
+                .and_then(|next_block| next_block.parent)
+                .map(|(_parent_slot, blockhash)| blockhash)
+                .unwrap_or_else(|| hashv(&[bank.last_blockhash().as_ref(), &slot.to_le_bytes()]));
+            Self::register_ticks(&bank, &blockhash);
+            parent = bank;
+        }
+        parent.freeze();
+        Ok(replayed_blocks)
+    }
+
+    /// Fills `bank` with ticks, the last of which registers `blockhash`.
+    fn register_ticks(bank: &Bank, blockhash: &Hash) {
+        let scheduler = BankWithScheduler::no_scheduler_available();
+        while bank.tick_height() < bank.max_tick_height() {
+            let hash = if bank.tick_height() + 1 == bank.max_tick_height() {
+                *blockhash
+            } else {
+                Hash::default()
+            };
+            bank.register_tick(&hash, &scheduler);
+        }
+    }
+
+    fn replay_block(
+        &mut self,
+        bank: &Bank,
+        block: &TraceBlock,
+    ) -> Result<ReplayedBlock, SchedulerError> {
+        let mut replayed_block = ReplayedBlock {
+            slot: bank.slot(),
+            block_cost_limit: MAX_BLOCK_UNITS,
+            ..ReplayedBlock::default()
+        };
+        self.buffer_packets(bank, &block.packet_batches, &mut replayed_block);
+
+        // Schedule until a round of scheduling commits nothing, either because the
+        // buffer is drained or because what is left does not fit the block.
+        loop {
+            let summary = self.scheduler.schedule(
+                &mut self.container,
+                &|txs, results| SchedulerController::pre_graph_filter(txs, results, bank),
+                &|_| true,
+            )?;
+            saturating_add_assign!(replayed_block.num_scheduled, summary.num_scheduled);
+            saturating_add_assign!(replayed_block.num_unschedulable, summary.num_unschedulable);
+            saturating_add_assign!(replayed_block.num_filtered_out, summary.num_filtered_out);
+
+            let num_landed = replayed_block.landed.len();
+            for receiver in &self.consume_work_receivers {
+                while let Ok(work) = receiver.try_recv() {
+                    let finished_work = Self::execute(bank, work, &mut replayed_block);
+                    self.finished_consume_work_sender
+                        .send(finished_work)
+                        .expect("scheduler holds the receiver");
+                }
+            }
+            self.scheduler.receive_completed(&mut self.container)?;
+            if replayed_block.landed.len() == num_landed {
+                break;
+            }
+        }
+
+        replayed_block.block_cost = bank.read_cost_tracker().unwrap().block_cost();
+        Ok(replayed_block)
+    }
+
+    /// Keeps the packets accepted by the scheduler, sanitizes and checks them against
+    /// `bank`, then hands them to the scheduler with the priority it gives them, as the
+    /// `SchedulerController` does.
+    fn buffer_packets(
+        &mut self,
+        bank: &Bank,
+        packet_batches: &[BankingPacketBatch],
+        replayed_block: &mut ReplayedBlock,
+    ) {
+        let last_slot_in_epoch = bank.epoch_schedule().get_last_slot_in_epoch(bank.epoch());
+        let transaction_account_lock_limit = bank.get_transaction_account_lock_limit();
+        let mut num_packets: usize = 0;
+        let transactions: Vec<_> = packet_batches
+            .iter()
+            .flat_map(|banking_packet_batch| banking_packet_batch.0.iter())
+            .flat_map(|packet_batch| packet_batch.iter())
+            .filter(|packet| !packet.meta().discard())
+            .inspect(|_| saturating_add_assign!(num_packets, 1))
+            .filter_map(|packet| ImmutableDeserializedPacket::new(packet.clone()).ok())
+            .filter(|packet| self.scheduler.filter_received_packet(packet))
+            .filter_map(|packet| {
+                packet.build_sanitized_transaction(
+                    &bank.feature_set,
+                    bank.vote_only_bank(),
+                    bank,
+                    bank.get_reserved_account_keys(),
+                )
+            })
+            .filter(|tx| {
+                SanitizedTransaction::validate_account_locks(
+                    tx.message(),
+                    transaction_account_lock_limit,
+                )
+                .is_ok()
+            })
+            .collect();
+
+        let lock_results = vec![Ok(()); transactions.len()];
+        let mut error_counts = TransactionErrorMetrics::default();
+        let check_results = bank.check_transactions(
+            &transactions,
+            &lock_results,
+            MAX_PROCESSING_AGE,
+            &mut error_counts,
+        );
+        for (transaction, _) in transactions
+            .into_iter()
+            .zip(check_results)
+            .filter(|(_, check_result)| check_result.0.is_ok())
+        {
+            let Some(fee_budget_limits) = Self::fee_budget_limits(&transaction) else {
+                saturating_add_assign!(replayed_block.num_dropped_on_compute_budget, 1);
+                continue;
+            };
+            let cost = CostModel::calculate_cost(&transaction, &bank.feature_set).sum();
+            let reward = bank.calculate_reward_for_transaction(&transaction, &fee_budget_limits);
+            let priority = self
+                .scheduler
+                .calculate_priority(&transaction, reward, cost);
+            let transaction_ttl = SanitizedTransactionTTL {
+                transaction,
+                max_age_slot: last_slot_in_epoch,
+            };
+            if self.scheduler.buffer_transaction(
+                &mut self.container,
+                self.transaction_id_generator.next(),
+                transaction_ttl,
+                priority,
+                cost,
+            ) {
+                saturating_add_assign!(replayed_block.num_dropped_on_capacity, 1);
+            }
+            saturating_add_assign!(replayed_block.num_buffered, 1);
+        }
+        replayed_block.num_dropped_on_receive = num_packets
+            .saturating_sub(replayed_block.num_buffered)
+            .saturating_sub(replayed_block.num_dropped_on_compute_budget);
+    }
+
+    fn fee_budget_limits(transaction: &SanitizedTransaction) -> Option<FeeBudgetLimits> {
+        process_compute_budget_instructions(transaction.message().program_instructions_iter())
+            .map(FeeBudgetLimits::from)
+            .ok()
+    }
+
+    /// Executes and commits a batch the way a consume worker would, reserving block
+    /// space with the bank's `CostTracker` first.
+    fn execute(
+        bank: &Bank,
+        work: ConsumeWork,
+        replayed_block: &mut ReplayedBlock,
+    ) -> FinishedConsumeWork {
+        let transaction_costs: Vec<_> = work
+            .transactions
+            .iter()
+            .map(|tx| CostModel::calculate_cost(tx, &bank.feature_set))
+            .collect();
+        let cost_results: Vec<_> = {
+            let mut cost_tracker = bank.write_cost_tracker().unwrap();
+            transaction_costs
+                .iter()
+                .map(|tx_cost| cost_tracker.try_add(tx_cost).map(|_| ()))
+                .collect()
+        };
+
+        let batch = bank.prepare_sanitized_batch_with_results(
+            &work.transactions,
+            cost_results.iter().map(|cost_result| match cost_result {
+                Ok(()) => Ok(()),
+                Err(_) => Err(TransactionError::WouldExceedMaxBlockCostLimit),
+            }),
+        );
+        let (transaction_results, _) = bank.load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            ExecutionRecordingConfig::new_single_setting(false),
+            &mut ExecuteTimings::default(),
+            None,
+        );
+
+        let mut retryable_indexes = vec![];
+        let mut cost_tracker = bank.write_cost_tracker().unwrap();
+        for (index, (((transaction, execution_result), lock_result), tx_cost)) in work
+            .transactions
+            .iter()
+            .zip(&transaction_results.execution_results)
+            .zip(batch.lock_results())
+            .zip(&transaction_costs)
+            .enumerate()
+        {
+            match (
+                execution_result.details(),
+                &cost_results[index],
+                lock_result,
+            ) {
+                (Some(details), _, _) => {
+                    cost_tracker.update_execution_cost(tx_cost, details.executed_units);
+                    replayed_block.landed.push(*transaction.signature());
+                    if details.status.is_err() {
+                        saturating_add_assign!(replayed_block.num_failed, 1);
+                    }
+                    saturating_add_assign!(replayed_block.executed_units, details.executed_units);
+                    if let Some(fee_budget_limits) = Self::fee_budget_limits(transaction) {
+                        saturating_add_assign!(
+                            replayed_block.fees,
+                            bank.calculate_reward_for_transaction(transaction, &fee_budget_limits)
+                        );
+                    }
+                }
+                (None, Err(_), _) => {
+                    saturating_add_assign!(replayed_block.num_cost_limited, 1);
+                    retryable_indexes.push(index);
+                }
+                (None, Ok(()), lock_result) => {
+                    cost_tracker.remove(tx_cost);
+                    if matches!(lock_result, Err(TransactionError::AccountInUse)) {
+                        saturating_add_assign!(replayed_block.num_account_in_use, 1);
+                        retryable_indexes.push(index);
+                    }
+                }
+            }
+        }
+        drop(cost_tracker);
+        drop(batch);
+
+        FinishedConsumeWork {
+            work,
+            retryable_indexes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_stage::{
+            tests::create_slow_genesis_config,
+            transaction_scheduler::{
+                prio_graph_scheduler::PrioGraphScheduler,
+                scheduler::{PrioGraphSchedulerFactory, SchedulingSummary},
+            },
+        },
+        solana_ledger::genesis_utils::GenesisConfigInfo,
+        solana_perf::packet::to_packet_batches,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction, message::Message, signature::Keypair,
+            signer::Signer, system_instruction, system_transaction, transaction::Transaction,
+        },
+        std::sync::Arc,
+    };
+
+    /// Prio-graph scheduling, but rejecting every received packet.
+    struct RejectingScheduler(PrioGraphScheduler);
+
+    impl Scheduler for RejectingScheduler {
+        fn filter_received_packet(&self, _packet: &ImmutableDeserializedPacket) -> bool {
+            false
+        }
+
+        fn schedule(
+            &mut self,
+            container: &mut TransactionStateContainer,
+            pre_graph_filter: &dyn Fn(&[&SanitizedTransaction], &mut [bool]),
+            pre_lock_filter: &dyn Fn(&SanitizedTransaction) -> bool,
+        ) -> Result<SchedulingSummary, SchedulerError> {
+            self.0
+                .schedule(container, pre_graph_filter, pre_lock_filter)
+        }
+
+        fn receive_completed(
+            &mut self,
+            container: &mut TransactionStateContainer,
+        ) -> Result<(usize, usize), SchedulerError> {
+            self.0.receive_completed(container)
+        }
+    }
+
+    struct RejectingSchedulerFactory;
+
+    impl SchedulerFactory for RejectingSchedulerFactory {
+        fn create_scheduler(
+            &self,
+            consume_work_senders: Vec<Sender<ConsumeWork>>,
+            finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+        ) -> Box<dyn Scheduler> {
+            Box::new(RejectingScheduler(PrioGraphScheduler::new(
+                consume_work_senders,
+                finished_consume_work_receiver,
+            )))
+        }
+    }
+
+    fn packet_batch_event(time: SystemTime, txs: &[Transaction]) -> TimedTracedEvent {
+        TimedTracedEvent(
+            time,
+            TracedEvent::PacketBatch(
+                ChannelLabel::NonVote,
+                Arc::new((to_packet_batches(txs, 64), None)),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_trace_blocks_from_leader_slots() {
+        let start = SystemTime::UNIX_EPOCH;
+        let slot_duration = Duration::from_millis(400);
+        let tx = system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            Hash::default(),
+        );
+        let events = vec![
+            packet_batch_event(start, &[tx.clone()]),
+            TimedTracedEvent(
+                start + Duration::from_millis(100),
+                TracedEvent::BlockAndBankHash(9, Hash::default(), Hash::default()),
+            ),
+            packet_batch_event(start + Duration::from_millis(200), &[tx.clone()]),
+            // after the end of the first block, buffered for the next one
+            packet_batch_event(start + Duration::from_millis(600), &[tx.clone()]),
+            TimedTracedEvent(
+                start + Duration::from_millis(900),
+                TracedEvent::BlockAndBankHash(10, Hash::default(), Hash::default()),
+            ),
+            TimedTracedEvent(
+                start + Duration::from_millis(950),
+                TracedEvent::PacketBatch(ChannelLabel::TpuVote, Arc::default()),
+            ),
+        ];
+
+        let blocks = TraceBlock::from_trace_events(events, slot_duration);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].parent, Some((9, Hash::default())));
+        assert_eq!(blocks[0].packet_batches.len(), 2);
+        assert_eq!(blocks[1].parent, Some((10, Hash::default())));
+        assert_eq!(blocks[1].packet_batches.len(), 1);
+    }
+
+    #[test]
+    fn test_trace_blocks_without_leader_slots() {
+        let start = SystemTime::UNIX_EPOCH;
+        let tx = system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            Hash::default(),
+        );
+        let events = [0, 100, 450, 1000]
+            .into_iter()
+            .map(|ms| packet_batch_event(start + Duration::from_millis(ms), &[tx.clone()]))
+            .collect();
+
+        let blocks = TraceBlock::from_trace_events(events, Duration::from_millis(400));
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| (block.parent, block.packet_batches.len()))
+                .collect::<Vec<_>>(),
+            vec![(None, 2), (None, 1), (None, 1)]
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_slow_genesis_config(u64::MAX);
+        let (bank, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+
+        let prioritized_transfer = |compute_unit_price: u64| {
+            let from_keypair = Keypair::new();
+            bank.process_transaction(&system_transaction::transfer(
+                &mint_keypair,
+                &from_keypair.pubkey(),
+                500_000,
+                bank.last_blockhash(),
+            ))
+            .unwrap();
+            let message = Message::new(
+                &[
+                    system_instruction::transfer(&from_keypair.pubkey(), &Pubkey::new_unique(), 1),
+                    ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+                ],
+                Some(&from_keypair.pubkey()),
+            );
+            Transaction::new(&[&from_keypair], message, bank.last_blockhash())
+        };
+        let tx1 = prioritized_transfer(1);
+        let tx2 = prioritized_transfer(1_000);
+        // fee payer has no funds, dropped by the scheduler
+        let tx3 = system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            bank.last_blockhash(),
+        );
+        let blocks = TraceBlock::from_trace_events(
+            vec![packet_batch_event(
+                SystemTime::UNIX_EPOCH,
+                &[tx1.clone(), tx2.clone(), tx3],
+            )],
+            Duration::from_millis(400),
+        );
+
+        let mut replayer = BankingTraceReplayer::new(&PrioGraphSchedulerFactory, 1);
+        let replayed_blocks = replayer
+            .replay(&bank_forks, bank.slot(), &Pubkey::new_unique(), &blocks)
+            .unwrap();
+        assert_eq!(replayed_blocks.len(), 1);
+        let replayed_block = &replayed_blocks[0];
+        assert_eq!(replayed_block.slot, bank.slot() + 1);
+        assert_eq!(
+            replayed_block.landed,
+            vec![tx2.signatures[0], tx1.signatures[0]]
+        );
+        assert_eq!(replayed_block.num_failed, 0);
+        assert_eq!(replayed_block.num_buffered, 3);
+        assert_eq!(replayed_block.num_dropped_on_receive, 0);
+        assert_eq!(replayed_block.num_dropped_on_compute_budget, 0);
+        assert_eq!(replayed_block.num_filtered_out, 1);
+        assert_eq!(replayed_block.num_scheduled, 2);
+        assert!(replayed_block.fees > 0);
+        assert!(replayed_block.executed_units > 0);
+        assert!(replayed_block.block_cost > 0);
+        assert!(bank_forks
+            .read()
+            .unwrap()
+            .get(replayed_block.slot)
+            .unwrap()
+            .is_frozen());
+    }
+
+    #[test]
+    fn test_replay_filter_received_packet() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_slow_genesis_config(u64::MAX);
+        let (bank, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+        let tx = system_transaction::transfer(
+            &mint_keypair,
+            &Pubkey::new_unique(),
+            1,
+            bank.last_blockhash(),
+        );
+        let blocks = TraceBlock::from_trace_events(
+            vec![packet_batch_event(SystemTime::UNIX_EPOCH, &[tx])],
+            Duration::from_millis(400),
+        );
+
+        let mut replayer = BankingTraceReplayer::new(&RejectingSchedulerFactory, 1);
+        let replayed_blocks = replayer
+            .replay(&bank_forks, bank.slot(), &Pubkey::new_unique(), &blocks)
+            .unwrap();
+        assert_eq!(replayed_blocks.len(), 1);
+        let replayed_block = &replayed_blocks[0];
+        assert!(replayed_block.landed.is_empty());
+        assert_eq!(replayed_block.num_buffered, 0);
+        assert_eq!(replayed_block.num_dropped_on_receive, 1);
+        assert_eq!(replayed_block.num_scheduled, 0);
+    }
+}
@@ -1,11 +1,12 @@
 mod batch_id_generator;
 mod in_flight_tracker;
-pub(crate) mod prio_graph_scheduler;
+pub mod prio_graph_scheduler;
+pub mod scheduler;
 pub(crate) mod scheduler_controller;
-pub(crate) mod scheduler_error;
+pub mod scheduler_error;
 mod scheduler_metrics;
 mod thread_aware_account_locks;
-mod transaction_id_generator;
-mod transaction_priority_id;
-mod transaction_state;
-mod transaction_state_container;
+pub(crate) mod transaction_id_generator;
+pub mod transaction_priority_id;
+pub mod transaction_state;
+pub mod transaction_state_container;
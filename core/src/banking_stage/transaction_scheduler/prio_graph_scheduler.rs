@@ -1,6 +1,7 @@
 use {
     super::{
         in_flight_tracker::InFlightTracker,
+        scheduler::{Scheduler, SchedulingSummary},
         scheduler_error::SchedulerError,
         thread_aware_account_locks::{ThreadAwareAccountLocks, ThreadId, ThreadSet},
         transaction_state::SanitizedTransactionTTL,
@@ -22,7 +23,7 @@ use {
     },
 };
 
-pub(crate) struct PrioGraphScheduler {
+pub struct PrioGraphScheduler {
     in_flight_tracker: InFlightTracker,
     account_locks: ThreadAwareAccountLocks,
     consume_work_senders: Vec<Sender<ConsumeWork>>,
@@ -31,7 +32,7 @@ pub(crate) struct PrioGraphScheduler {
 }
 
 impl PrioGraphScheduler {
-    pub(crate) fn new(
+    pub fn new(
         consume_work_senders: Vec<Sender<ConsumeWork>>,
         finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
     ) -> Self {
@@ -61,7 +62,7 @@ impl PrioGraphScheduler {
     /// This, combined with internal tracking of threads' in-flight transactions, allows
     /// for load-balancing while prioritizing scheduling transactions onto threads that will
     /// not cause conflicts in the near future.
-    pub(crate) fn schedule(
+    pub fn schedule(
         &mut self,
         container: &mut TransactionStateContainer,
         pre_graph_filter: impl Fn(&[&SanitizedTransaction], &mut [bool]),
@@ -427,17 +428,22 @@ impl PrioGraphScheduler {
     }
 }
 
-/// Metrics from scheduling transactions.
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct SchedulingSummary {
-    /// Number of transactions scheduled.
-    pub num_scheduled: usize,
-    /// Number of transactions that were not scheduled due to conflicts.
-    pub num_unschedulable: usize,
-    /// Number of transactions that were dropped due to filter.
-    pub num_filtered_out: usize,
-    /// Time spent filtering transactions
-    pub filter_time_us: u64,
+impl Scheduler for PrioGraphScheduler {
+    fn schedule(
+        &mut self,
+        container: &mut TransactionStateContainer,
+        pre_graph_filter: &dyn Fn(&[&SanitizedTransaction], &mut [bool]),
+        pre_lock_filter: &dyn Fn(&SanitizedTransaction) -> bool,
+    ) -> Result<SchedulingSummary, SchedulerError> {
+        PrioGraphScheduler::schedule(self, container, pre_graph_filter, pre_lock_filter)
+    }
+
+    fn receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError> {
+        PrioGraphScheduler::receive_completed(self, container)
+    }
 }
 
 struct Batches {
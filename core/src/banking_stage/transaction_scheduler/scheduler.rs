@@ -0,0 +1,141 @@
+use {
+    super::{
+        prio_graph_scheduler::PrioGraphScheduler, scheduler_error::SchedulerError,
+        transaction_state::SanitizedTransactionTTL,
+        transaction_state_container::TransactionStateContainer,
+    },
+    crate::banking_stage::{
+        immutable_deserialized_packet::ImmutableDeserializedPacket,
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionId},
+    },
+    crossbeam_channel::{Receiver, Sender},
+    solana_sdk::transaction::SanitizedTransaction,
+};
+
+/// Scheduling policy run by the central scheduler thread of `BankingStage`.
+///
+/// The `SchedulerController` receives packets, keeping those accepted by
+/// [`Scheduler::filter_received_packet`], sanitizes them and hands the
+/// resulting transactions, with the priority given by
+/// [`Scheduler::calculate_priority`], to [`Scheduler::buffer_transaction`].
+/// While the node is leader, it calls [`Scheduler::schedule`] to pick batches
+/// from the container and send them to the consume workers, and
+/// [`Scheduler::receive_completed`] to collect the finished batches.
+pub trait Scheduler: Send {
+    /// Whether to keep a newly received packet, before it is sanitized.
+    /// Packets for which this returns `false` are dropped on receive.
+    /// The default keeps every packet.
+    #[allow(unused_variables)]
+    fn filter_received_packet(&self, packet: &ImmutableDeserializedPacket) -> bool {
+        true
+    }
+
+    /// Buffer a newly received transaction, which passed sanitization and
+    /// age, status and fee-payer checks. Returns `true` if a transaction was
+    /// dropped because the container is full.
+    /// The default inserts it by priority, dropping the lowest priority
+    /// transaction when the container is full.
+    fn buffer_transaction(
+        &mut self,
+        container: &mut TransactionStateContainer,
+        transaction_id: TransactionId,
+        transaction_ttl: SanitizedTransactionTTL,
+        priority: u64,
+        cost: u64,
+    ) -> bool {
+        container.insert_new_transaction(transaction_id, transaction_ttl, priority, cost)
+    }
+
+    /// Priority of a newly received transaction. Transactions are popped from
+    /// the container highest priority first, and the lowest priority
+    /// transaction is dropped when the container is full.
+    /// `reward` is the fee collected by the leader, and `cost` is the cost
+    /// towards block limits as calculated by the `CostModel`.
+    ///
+    /// The default sells blockspace to the highest bidder:
+    /// P = R / (1 + C)
+    /// where P is the priority, R is the reward, and C is the cost.
+    #[allow(unused_variables)]
+    fn calculate_priority(
+        &self,
+        transaction: &SanitizedTransaction,
+        reward: u64,
+        cost: u64,
+    ) -> u64 {
+        // We need a multiplier here to avoid rounding down too aggressively.
+        // For many transactions, the cost will be greater than the fees in terms of raw lamports.
+        // For the purposes of calculating prioritization, we multiply the fees by a large number so that
+        // the cost is a small fraction.
+        // An offset of 1 is used in the denominator to explicitly avoid division by zero.
+        const MULTIPLIER: u64 = 1_000_000;
+        reward
+            .saturating_mul(MULTIPLIER)
+            .saturating_div(cost.saturating_add(1))
+    }
+
+    /// Schedule transactions from the given `TransactionStateContainer` to be
+    /// consumed by the worker threads. Returns summary of scheduling, or an
+    /// error.
+    /// `pre_graph_filter` is used to filter out transactions that should be
+    /// skipped and dropped before they are considered for scheduling. This fn
+    /// sets `false` for transactions that should be dropped, and `true`
+    /// otherwise.
+    /// `pre_lock_filter` is used to filter out transactions immediately before
+    /// locks are checked and taken. This fn returns `true` for transactions
+    /// that should be scheduled, and `false` otherwise.
+    fn schedule(
+        &mut self,
+        container: &mut TransactionStateContainer,
+        pre_graph_filter: &dyn Fn(&[&SanitizedTransaction], &mut [bool]),
+        pre_lock_filter: &dyn Fn(&SanitizedTransaction) -> bool,
+    ) -> Result<SchedulingSummary, SchedulerError>;
+
+    /// Receive completed batches of transactions from the workers without
+    /// blocking, retrying or removing them from the container as appropriate.
+    /// Returns (num_transactions, num_retryable_transactions) on success.
+    fn receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError>;
+}
+
+/// Creates the `Scheduler` for the central scheduler thread, given the
+/// channels to and from its consume workers. Selected through
+/// `ValidatorConfig::transaction_scheduler_factory`.
+pub trait SchedulerFactory: Send + Sync {
+    fn create_scheduler(
+        &self,
+        consume_work_senders: Vec<Sender<ConsumeWork>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+    ) -> Box<dyn Scheduler>;
+}
+
+/// Creates the default `PrioGraphScheduler`.
+#[derive(Debug, Default)]
+pub struct PrioGraphSchedulerFactory;
+
+impl SchedulerFactory for PrioGraphSchedulerFactory {
+    fn create_scheduler(
+        &self,
+        consume_work_senders: Vec<Sender<ConsumeWork>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+    ) -> Box<dyn Scheduler> {
+        Box::new(PrioGraphScheduler::new(
+            consume_work_senders,
+            finished_consume_work_receiver,
+        ))
+    }
+}
+
+/// Metrics from scheduling transactions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchedulingSummary {
+    /// Number of transactions scheduled.
+    pub num_scheduled: usize,
+    /// Number of transactions that were not scheduled due to conflicts.
+    pub num_unschedulable: usize,
+    /// Number of transactions that were dropped due to filter.
+    pub num_filtered_out: usize,
+    /// Time spent filtering transactions
+    pub filter_time_us: u64,
+}
@@ -3,7 +3,7 @@
 
 use {
     super::{
-        prio_graph_scheduler::PrioGraphScheduler,
+        scheduler::Scheduler,
         scheduler_error::SchedulerError,
         scheduler_metrics::{
             SchedulerCountMetrics, SchedulerLeaderDetectionMetrics, SchedulerTimingMetrics,
@@ -30,6 +30,7 @@ use {
         transaction::SanitizedTransaction,
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
+    solana_transaction_metrics_tracker::lifecycle_tracing::{self, TransactionStage},
     std::{
         sync::{Arc, RwLock},
         time::Duration,
@@ -49,7 +50,7 @@ pub(crate) struct SchedulerController {
     /// Shared resource between `packet_receiver` and `scheduler`.
     container: TransactionStateContainer,
     /// State for scheduling and communicating with worker threads.
-    scheduler: PrioGraphScheduler,
+    scheduler: Box<dyn Scheduler>,
     /// Metrics tracking time for leader bank detection.
     leader_detection_metrics: SchedulerLeaderDetectionMetrics,
     /// Metrics tracking counts on transactions in different states
@@ -67,7 +68,7 @@ impl SchedulerController {
         decision_maker: DecisionMaker,
         packet_deserializer: PacketDeserializer,
         bank_forks: Arc<RwLock<BankForks>>,
-        scheduler: PrioGraphScheduler,
+        scheduler: Box<dyn Scheduler>,
         worker_metrics: Vec<Arc<ConsumeWorkerMetrics>>,
     ) -> Self {
         Self {
@@ -142,10 +143,10 @@ impl SchedulerController {
             BufferedPacketsDecision::Consume(bank_start) => {
                 let (scheduling_summary, schedule_time_us) = measure_us!(self.scheduler.schedule(
                     &mut self.container,
-                    |txs, results| {
+                    &|txs, results| {
                         Self::pre_graph_filter(txs, results, &bank_start.working_bank)
                     },
-                    |_| true // no pre-lock filter for now
+                    &|_| true // no pre-lock filter for now
                 )?);
 
                 self.count_metrics.update(|count_metrics| {
@@ -189,7 +190,11 @@ impl SchedulerController {
         Ok(())
     }
 
-    fn pre_graph_filter(transactions: &[&SanitizedTransaction], results: &mut [bool], bank: &Bank) {
+    pub(crate) fn pre_graph_filter(
+        transactions: &[&SanitizedTransaction],
+        results: &mut [bool],
+        bank: &Bank,
+    ) {
         let lock_results = vec![Ok(()); transactions.len()];
         let mut error_counters = TransactionErrorMetrics::default();
         let check_results = bank.check_transactions(
@@ -322,7 +327,9 @@ impl SchedulerController {
 
         let (received_packet_results, receive_time_us) = measure_us!(self
             .packet_receiver
-            .receive_packets(recv_timeout, remaining_queue_capacity, |_| true));
+            .receive_packets(recv_timeout, remaining_queue_capacity, |packet| {
+                self.scheduler.filter_received_packet(packet)
+            }));
 
         self.timing_metrics.update(|timing_metrics| {
             saturating_add_assign!(timing_metrics.receive_time_us, receive_time_us);
@@ -405,6 +412,22 @@ impl SchedulerController {
             );
             let post_lock_validation_count = transactions.len();
 
+            if lifecycle_tracing::is_enabled() {
+                for (transaction, (check_result, ..)) in transactions.iter().zip(&check_results) {
+                    match check_result {
+                        Ok(()) => lifecycle_tracing::record(
+                            transaction.signature(),
+                            TransactionStage::BankingBuffered,
+                        ),
+                        Err(err) => lifecycle_tracing::record_dropped(
+                            transaction.signature(),
+                            TransactionStage::BankingBuffered.as_str(),
+                            err,
+                        ),
+                    }
+                }
+            }
+
             let mut post_transaction_check_count: usize = 0;
             let mut num_dropped_on_capacity: usize = 0;
             let mut num_buffered: usize = 0;
@@ -418,13 +441,14 @@ impl SchedulerController {
                 let transaction_id = self.transaction_id_generator.next();
 
                 let (priority, cost) =
-                    Self::calculate_priority_and_cost(&transaction, &fee_budget_limits, &bank);
+                    self.calculate_priority_and_cost(&transaction, &fee_budget_limits, &bank);
                 let transaction_ttl = SanitizedTransactionTTL {
                     transaction,
                     max_age_slot: last_slot_in_epoch,
                 };
 
-                if self.container.insert_new_transaction(
+                if self.scheduler.buffer_transaction(
+                    &mut self.container,
                     transaction_id,
                     transaction_ttl,
                     priority,
@@ -467,39 +491,18 @@ impl SchedulerController {
     /// Calculate priority and cost for a transaction:
     ///
     /// Cost is calculated through the `CostModel`,
-    /// and priority is calculated through a formula here that attempts to sell
-    /// blockspace to the highest bidder.
-    ///
-    /// The priority is calculated as:
-    /// P = R / (1 + C)
-    /// where P is the priority, R is the reward,
-    /// and C is the cost towards block-limits.
-    ///
-    /// Current minimum costs are on the order of several hundred,
-    /// so the denominator is effectively C, and the +1 is simply
-    /// to avoid any division by zero due to a bug - these costs
-    /// are calculated by the cost-model and are not direct
-    /// from user input. They should never be zero.
-    /// Any difference in the prioritization is negligible for
-    /// the current transaction costs.
+    /// and priority is left to the scheduler, see
+    /// `Scheduler::calculate_priority`.
     fn calculate_priority_and_cost(
+        &self,
         transaction: &SanitizedTransaction,
         fee_budget_limits: &FeeBudgetLimits,
         bank: &Bank,
     ) -> (u64, u64) {
         let cost = CostModel::calculate_cost(transaction, &bank.feature_set).sum();
         let reward = bank.calculate_reward_for_transaction(transaction, fee_budget_limits);
-
-        // We need a multiplier here to avoid rounding down too aggressively.
-        // For many transactions, the cost will be greater than the fees in terms of raw lamports.
-        // For the purposes of calculating prioritization, we multiply the fees by a large number so that
-        // the cost is a small fraction.
-        // An offset of 1 is used in the denominator to explicitly avoid division by zero.
-        const MULTIPLIER: u64 = 1_000_000;
         (
-            reward
-                .saturating_mul(MULTIPLIER)
-                .saturating_div(cost.saturating_add(1)),
+            self.scheduler.calculate_priority(transaction, reward, cost),
             cost,
         )
     }
@@ -514,6 +517,10 @@ mod tests {
                 consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
                 scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId},
                 tests::create_slow_genesis_config,
+                transaction_scheduler::{
+                    prio_graph_scheduler::PrioGraphScheduler,
+                    scheduler::{PrioGraphSchedulerFactory, SchedulerFactory, SchedulingSummary},
+                },
             },
             banking_trace::BankingPacketBatch,
             sigverify::SigverifyTracerPacketStats,
@@ -556,6 +563,13 @@ mod tests {
     }
 
     fn create_test_frame(num_threads: usize) -> (TestFrame, SchedulerController) {
+        create_test_frame_with_scheduler_factory(num_threads, &PrioGraphSchedulerFactory)
+    }
+
+    fn create_test_frame_with_scheduler_factory(
+        num_threads: usize,
+        scheduler_factory: &dyn SchedulerFactory,
+    ) -> (TestFrame, SchedulerController) {
         let GenesisConfigInfo {
             genesis_config,
             mint_keypair,
@@ -602,7 +616,8 @@ mod tests {
             decision_maker,
             packet_deserializer,
             bank_forks,
-            PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver),
+            scheduler_factory
+                .create_scheduler(consume_work_senders, finished_consume_work_receiver),
             vec![], // no actual workers with metrics to report, this can be empty
         );
 
@@ -737,6 +752,122 @@ mod tests {
         assert_eq!(message_hashes, vec![&tx2_hash, &tx1_hash]);
     }
 
+    /// Prio-graph scheduling, but cheapest transactions first, and only for
+    /// transactions setting a compute unit price.
+    struct LowestRewardFirstScheduler(PrioGraphScheduler);
+
+    impl Scheduler for LowestRewardFirstScheduler {
+        fn filter_received_packet(&self, packet: &ImmutableDeserializedPacket) -> bool {
+            packet.compute_unit_price() > 0
+        }
+
+        fn calculate_priority(
+            &self,
+            _transaction: &SanitizedTransaction,
+            reward: u64,
+            _cost: u64,
+        ) -> u64 {
+            u64::MAX - reward
+        }
+
+        fn schedule(
+            &mut self,
+            container: &mut TransactionStateContainer,
+            pre_graph_filter: &dyn Fn(&[&SanitizedTransaction], &mut [bool]),
+            pre_lock_filter: &dyn Fn(&SanitizedTransaction) -> bool,
+        ) -> Result<SchedulingSummary, SchedulerError> {
+            self.0
+                .schedule(container, pre_graph_filter, pre_lock_filter)
+        }
+
+        fn receive_completed(
+            &mut self,
+            container: &mut TransactionStateContainer,
+        ) -> Result<(usize, usize), SchedulerError> {
+            self.0.receive_completed(container)
+        }
+    }
+
+    struct LowestRewardFirstSchedulerFactory;
+
+    impl SchedulerFactory for LowestRewardFirstSchedulerFactory {
+        fn create_scheduler(
+            &self,
+            consume_work_senders: Vec<Sender<ConsumeWork>>,
+            finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+        ) -> Box<dyn Scheduler> {
+            Box::new(LowestRewardFirstScheduler(PrioGraphScheduler::new(
+                consume_work_senders,
+                finished_consume_work_receiver,
+            )))
+        }
+    }
+
+    #[test]
+    fn test_schedule_consume_custom_scheduler() {
+        let (test_frame, mut scheduler_controller) =
+            create_test_frame_with_scheduler_factory(1, &LowestRewardFirstSchedulerFactory);
+        let TestFrame {
+            bank,
+            mint_keypair,
+            poh_recorder,
+            banking_packet_sender,
+            consume_work_receivers,
+            ..
+        } = &test_frame;
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        let tx1 = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            1,
+            bank.last_blockhash(),
+        );
+        let tx2 = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            1_000,
+            bank.last_blockhash(),
+        );
+        // Dropped on receive, for not setting a compute unit price
+        let tx3 = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            0,
+            bank.last_blockhash(),
+        );
+        let tx1_hash = tx1.message().hash();
+        let tx2_hash = tx2.message().hash();
+
+        let txs = vec![tx1, tx2, tx3];
+        banking_packet_sender
+            .send(to_banking_packet_batch(&txs))
+            .unwrap();
+
+        // The custom priority schedules the lower paying transaction first.
+        test_receive_then_schedule(&mut scheduler_controller);
+        let consume_work = consume_work_receivers[0].try_recv().unwrap();
+        let message_hashes = consume_work
+            .transactions
+            .iter()
+            .map(|tx| tx.message_hash())
+            .collect_vec();
+        assert_eq!(message_hashes, vec![&tx1_hash, &tx2_hash]);
+    }
+
     #[test]
     fn test_schedule_consume_single_threaded_conflict() {
         let (test_frame, mut scheduler_controller) = create_test_frame(1);
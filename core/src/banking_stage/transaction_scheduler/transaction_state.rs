@@ -1,9 +1,9 @@
 use solana_sdk::{clock::Slot, transaction::SanitizedTransaction};
 
 /// Simple wrapper type to tie a sanitized transaction to max age slot.
-pub(crate) struct SanitizedTransactionTTL {
-    pub(crate) transaction: SanitizedTransaction,
-    pub(crate) max_age_slot: Slot,
+pub struct SanitizedTransactionTTL {
+    pub transaction: SanitizedTransaction,
+    pub max_age_slot: Slot,
 }
 
 /// TransactionState is used to track the state of a transaction in the transaction scheduler
@@ -26,7 +26,7 @@ pub(crate) struct SanitizedTransactionTTL {
 ///   to the appropriate thread for processing. This is done to avoid cloning the
 ///  `SanitizedTransaction`.
 #[allow(clippy::large_enum_variant)]
-pub(crate) enum TransactionState {
+pub enum TransactionState {
     /// The transaction is available for scheduling.
     Unprocessed {
         transaction_ttl: SanitizedTransactionTTL,
@@ -39,7 +39,7 @@ pub(crate) enum TransactionState {
 
 impl TransactionState {
     /// Creates a new `TransactionState` in the `Unprocessed` state.
-    pub(crate) fn new(transaction_ttl: SanitizedTransactionTTL, priority: u64, cost: u64) -> Self {
+    pub fn new(transaction_ttl: SanitizedTransactionTTL, priority: u64, cost: u64) -> Self {
         Self::Unprocessed {
             transaction_ttl,
             priority,
@@ -50,7 +50,7 @@ impl TransactionState {
     /// Return the priority of the transaction.
     /// This is *not* the same as the `compute_unit_price` of the transaction.
     /// The priority is used to order transactions for processing.
-    pub(crate) fn priority(&self) -> u64 {
+    pub fn priority(&self) -> u64 {
         match self {
             Self::Unprocessed { priority, .. } => *priority,
             Self::Pending { priority, .. } => *priority,
@@ -58,7 +58,7 @@ impl TransactionState {
     }
 
     /// Return the cost of the transaction.
-    pub(crate) fn cost(&self) -> u64 {
+    pub fn cost(&self) -> u64 {
         match self {
             Self::Unprocessed { cost, .. } => *cost,
             Self::Pending { cost, .. } => *cost,
@@ -72,7 +72,7 @@ impl TransactionState {
     /// # Panics
     /// This method will panic if the transaction is already in the `Pending` state,
     ///   as this is an invalid state transition.
-    pub(crate) fn transition_to_pending(&mut self) -> SanitizedTransactionTTL {
+    pub fn transition_to_pending(&mut self) -> SanitizedTransactionTTL {
         match self.take() {
             TransactionState::Unprocessed {
                 transaction_ttl,
@@ -94,7 +94,7 @@ impl TransactionState {
     /// # Panics
     /// This method will panic if the transaction is already in the `Unprocessed`
     ///   state, as this is an invalid state transition.
-    pub(crate) fn transition_to_unprocessed(&mut self, transaction_ttl: SanitizedTransactionTTL) {
+    pub fn transition_to_unprocessed(&mut self, transaction_ttl: SanitizedTransactionTTL) {
         match self.take() {
             TransactionState::Unprocessed { .. } => panic!("already unprocessed"),
             TransactionState::Pending { priority, cost } => {
@@ -111,7 +111,7 @@ impl TransactionState {
     ///
     /// # Panics
     /// This method will panic if the transaction is in the `Pending` state.
-    pub(crate) fn transaction_ttl(&self) -> &SanitizedTransactionTTL {
+    pub fn transaction_ttl(&self) -> &SanitizedTransactionTTL {
         match self {
             Self::Unprocessed {
                 transaction_ttl, ..
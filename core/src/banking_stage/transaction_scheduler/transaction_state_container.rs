@@ -34,13 +34,13 @@ use {
 ///
 /// The container maintains a fixed capacity. If the queue is full when pushing
 /// a new transaction, the lowest priority transaction will be dropped.
-pub(crate) struct TransactionStateContainer {
+pub struct TransactionStateContainer {
     priority_queue: MinMaxHeap<TransactionPriorityId>,
     id_to_transaction_state: HashMap<TransactionId, TransactionState>,
 }
 
 impl TransactionStateContainer {
-    pub(crate) fn with_capacity(capacity: usize) -> Self {
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
             priority_queue: MinMaxHeap::with_capacity(capacity),
             id_to_transaction_state: HashMap::with_capacity(capacity),
@@ -48,22 +48,22 @@ impl TransactionStateContainer {
     }
 
     /// Returns true if the queue is empty.
-    pub(crate) fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.priority_queue.is_empty()
     }
 
     /// Returns the remaining capacity of the queue
-    pub(crate) fn remaining_queue_capacity(&self) -> usize {
+    pub fn remaining_queue_capacity(&self) -> usize {
         self.priority_queue.capacity() - self.priority_queue.len()
     }
 
     /// Get the top transaction id in the priority queue.
-    pub(crate) fn pop(&mut self) -> Option<TransactionPriorityId> {
+    pub fn pop(&mut self) -> Option<TransactionPriorityId> {
         self.priority_queue.pop_max()
     }
 
     /// Get mutable transaction state by id.
-    pub(crate) fn get_mut_transaction_state(
+    pub fn get_mut_transaction_state(
         &mut self,
         id: &TransactionId,
     ) -> Option<&mut TransactionState> {
@@ -72,10 +72,7 @@ impl TransactionStateContainer {
 
     /// Get reference to `SanitizedTransactionTTL` by id.
     /// Panics if the transaction does not exist.
-    pub(crate) fn get_transaction_ttl(
-        &self,
-        id: &TransactionId,
-    ) -> Option<&SanitizedTransactionTTL> {
+    pub fn get_transaction_ttl(&self, id: &TransactionId) -> Option<&SanitizedTransactionTTL> {
         self.id_to_transaction_state
             .get(id)
             .map(|state| state.transaction_ttl())
@@ -83,7 +80,7 @@ impl TransactionStateContainer {
 
     /// Insert a new transaction into the container's queues and maps.
     /// Returns `true` if a packet was dropped due to capacity limits.
-    pub(crate) fn insert_new_transaction(
+    pub fn insert_new_transaction(
         &mut self,
         transaction_id: TransactionId,
         transaction_ttl: SanitizedTransactionTTL,
@@ -100,7 +97,7 @@ impl TransactionStateContainer {
 
     /// Retries a transaction - inserts transaction back into map (but not packet).
     /// This transitions the transaction to `Unprocessed` state.
-    pub(crate) fn retry_transaction(
+    pub fn retry_transaction(
         &mut self,
         transaction_id: TransactionId,
         transaction_ttl: SanitizedTransactionTTL,
@@ -116,7 +113,7 @@ impl TransactionStateContainer {
     /// Pushes a transaction id into the priority queue. If the queue is full, the lowest priority
     /// transaction will be dropped (removed from the queue and map).
     /// Returns `true` if a packet was dropped due to capacity limits.
-    pub(crate) fn push_id_into_queue(&mut self, priority_id: TransactionPriorityId) -> bool {
+    pub fn push_id_into_queue(&mut self, priority_id: TransactionPriorityId) -> bool {
         if self.remaining_queue_capacity() == 0 {
             let popped_id = self.priority_queue.push_pop_min(priority_id);
             self.remove_by_id(&popped_id.id);
@@ -128,13 +125,13 @@ impl TransactionStateContainer {
     }
 
     /// Remove transaction by id.
-    pub(crate) fn remove_by_id(&mut self, id: &TransactionId) {
+    pub fn remove_by_id(&mut self, id: &TransactionId) {
         self.id_to_transaction_state
             .remove(id)
             .expect("transaction must exist");
     }
 
-    pub(crate) fn get_min_max_priority(&self) -> MinMaxResult<u64> {
+    pub fn get_min_max_priority(&self) -> MinMaxResult<u64> {
         match self.priority_queue.peek_min() {
             Some(min) => match self.priority_queue.peek_max() {
                 Some(max) => MinMaxResult::MinMax(min.priority, max.priority),
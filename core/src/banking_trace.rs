@@ -1,15 +1,15 @@
 use {
     crate::sigverify::SigverifyTracerPacketStats,
-    bincode::serialize_into,
+    bincode::{deserialize_from, serialize_into},
     chrono::{DateTime, Local},
     crossbeam_channel::{unbounded, Receiver, SendError, Sender, TryRecvError},
     rolling_file::{RollingCondition, RollingConditionBasic, RollingFileAppender},
     solana_perf::packet::PacketBatch,
     solana_sdk::{hash::Hash, slot_history::Slot},
     std::{
-        fs::{create_dir_all, remove_dir_all},
-        io::{self, Write},
-        path::PathBuf,
+        fs::{create_dir_all, remove_dir_all, File},
+        io::{self, BufReader, Write},
+        path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
@@ -271,6 +271,34 @@ impl BankingTracer {
         })
     }
 
+    /// Reads back all events recorded under `path`, oldest first. Rotated files are read
+    /// before the current one, and an incomplete event at the end of a file is ignored.
+    pub fn read_events(path: &Path) -> Result<Vec<TimedTracedEvent>, TraceError> {
+        let mut events = vec![];
+        for file_path in (1..TRACE_FILE_ROTATE_COUNT)
+            .rev()
+            .map(|index| path.join(format!("{BASENAME}.{index}")))
+            .chain(Some(path.join(BASENAME)))
+            .filter(|file_path| file_path.exists())
+        {
+            let mut stream = BufReader::new(File::open(file_path)?);
+            loop {
+                match deserialize_from::<_, TimedTracedEvent>(&mut stream) {
+                    Ok(event) => events.push(event),
+                    Err(err) => match *err {
+                        bincode::ErrorKind::Io(ref error)
+                            if error.kind() == io::ErrorKind::UnexpectedEof =>
+                        {
+                            break
+                        }
+                        _ => return Err(err.into()),
+                    },
+                }
+            }
+        }
+        Ok(events)
+    }
+
     fn create_file_appender(
         path: &PathBuf,
         rotate_threshold_size: u64,
@@ -397,11 +425,7 @@ mod tests {
     use {
         super::*,
         bincode::ErrorKind::Io as BincodeIoError,
-        std::{
-            fs::File,
-            io::{BufReader, ErrorKind::UnexpectedEof},
-            str::FromStr,
-        },
+        std::{io::ErrorKind::UnexpectedEof, str::FromStr},
         tempfile::TempDir,
     };
 
@@ -532,6 +556,67 @@ mod tests {
         for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
     }
 
+    #[test]
+    fn test_read_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, TraceError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        tracer.hash_event(4, &Hash::default(), &Hash::default());
+
+        for_test::terminate_tracer(
+            tracer,
+            tracer_thread,
+            dummy_main_thread,
+            non_vote_sender,
+            None,
+        );
+
+        // an older, rotated file with a torn write at its end
+        let mut rotated = File::create(path.join("events.1")).unwrap();
+        serialize_into(
+            &mut rotated,
+            &TimedTracedEvent(
+                SystemTime::now(),
+                TracedEvent::BlockAndBankHash(3, Hash::default(), Hash::default()),
+            ),
+        )
+        .unwrap();
+        rotated.write_all(&[1]).unwrap();
+        drop(rotated);
+
+        let events = BankingTracer::read_events(&path).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_matches!(
+            events[0],
+            TimedTracedEvent(_, TracedEvent::BlockAndBankHash(3, _, _))
+        );
+        assert_matches!(
+            events[1],
+            TimedTracedEvent(_, TracedEvent::PacketBatch(ChannelLabel::NonVote, _))
+        );
+        assert_matches!(
+            events[2],
+            TimedTracedEvent(_, TracedEvent::BlockAndBankHash(4, _, _))
+        );
+
+        for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
+    }
+
     #[test]
     fn test_spill_over_at_rotation() {
         let temp_dir = TempDir::new().unwrap();
@@ -18,11 +18,15 @@ use {
             count_discarded_packets, count_packets_in_batches, count_valid_packets, shrink_batches,
         },
     },
-    solana_sdk::timing,
+    solana_sdk::{signature::Signature, timing},
     solana_streamer::streamer::{self, StreamerError},
+    solana_transaction_metrics_tracker::{
+        get_signature_from_packet,
+        lifecycle_tracing::{self, TransactionStage},
+    },
     std::{
         thread::{self, Builder, JoinHandle},
-        time::Instant,
+        time::{Instant, SystemTime},
     },
     thiserror::Error,
 };
@@ -292,6 +296,31 @@ impl SigVerifyStage {
         (shrink_time.as_us(), shrink_total)
     }
 
+    /// Record the sampled transactions that passed signature verification, as received when
+    /// they arrived over QUIC, or at `received_time` if the streamer did not record their
+    /// arrival. Nothing is recorded for packets that were discarded or failed verification,
+    /// whose signatures may be forged.
+    fn trace_verified_packets(batches: &[PacketBatch], received_time: SystemTime) {
+        if !lifecycle_tracing::is_enabled() {
+            return;
+        }
+        let now = SystemTime::now();
+        for packet in batches
+            .iter()
+            .flat_map(|batch| batch.iter())
+            .filter(|packet| !packet.meta().discard())
+        {
+            let Ok(signature) = get_signature_from_packet(packet) else {
+                continue;
+            };
+            let signature = Signature::from(*signature);
+            let received_time =
+                lifecycle_tracing::take_arrival(&signature).unwrap_or(received_time);
+            lifecycle_tracing::record_at(&signature, TransactionStage::Received, received_time);
+            lifecycle_tracing::record_at(&signature, TransactionStage::SigVerified, now);
+        }
+    }
+
     fn verifier<const K: usize, T: SigVerifier>(
         deduper: &Deduper<K, [u8]>,
         recvr: &Receiver<PacketBatch>,
@@ -299,6 +328,7 @@ impl SigVerifyStage {
         stats: &mut SigVerifierStats,
     ) -> Result<(), T::SendType> {
         let (mut batches, num_packets, recv_duration) = streamer::recv_packet_batches(recvr)?;
+        let received_time = SystemTime::now();
 
         let batches_len = batches.len();
         debug!(
@@ -358,6 +388,8 @@ impl SigVerifyStage {
         );
         verify_time.stop();
 
+        Self::trace_verified_packets(&batches, received_time);
+
         // Post-shrink packet batches if many packets are discarded from sigverify
         let (post_shrink_time_us, post_shrink_total) = Self::maybe_shrink_batches(&mut batches);
 
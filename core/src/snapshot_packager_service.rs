@@ -9,9 +9,10 @@ use {
         snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_config::SnapshotConfig,
         snapshot_hash::StartingSnapshotHashes,
-        snapshot_package::{self, SnapshotPackage},
+        snapshot_package::{self, SnapshotKind, SnapshotPackage},
         snapshot_utils,
     },
+    solana_sdk::clock::Slot,
     std::{
         sync::{
             atomic::{AtomicBool, Ordering},
@@ -53,7 +54,7 @@ impl SnapshotPackagerService {
                     }
 
                     let Some((
-                        snapshot_package,
+                        mut snapshot_package,
                         num_outstanding_snapshot_packages,
                         num_re_enqueued_snapshot_packages,
                     )) = Self::get_next_snapshot_package(
@@ -64,6 +65,15 @@ impl SnapshotPackagerService {
                         std::thread::sleep(Self::LOOP_LIMITER);
                         continue;
                     };
+                    if let Some(incremental_snapshot_chain_base_slot) =
+                        Self::get_incremental_snapshot_chain_base_slot(
+                            &snapshot_package,
+                            &snapshot_config,
+                        )
+                    {
+                        snapshot_package
+                            .rebase_on_incremental_snapshot(incremental_snapshot_chain_base_slot);
+                    }
                     info!("handling snapshot package: {snapshot_package:?}");
                     let enqueued_time = snapshot_package.enqueued.elapsed();
 
@@ -136,6 +146,43 @@ impl SnapshotPackagerService {
         self.t_snapshot_packager.join()
     }
 
+    /// Get the slot of the incremental snapshot archive to base this snapshot package on, if it
+    /// should extend a chain of incremental snapshot archives
+    ///
+    /// The chain ending at the highest incremental snapshot archive for the same full snapshot is
+    /// extended, unless it is already as long as allowed.  In that case, or if there is no such
+    /// archive yet, the snapshot package stays based on the full snapshot, which starts a new
+    /// chain.
+    fn get_incremental_snapshot_chain_base_slot(
+        snapshot_package: &SnapshotPackage,
+        snapshot_config: &SnapshotConfig,
+    ) -> Option<Slot> {
+        let SnapshotKind::IncrementalSnapshot(full_snapshot_slot) = snapshot_package.snapshot_kind
+        else {
+            return None;
+        };
+        let maximum_chain_length = snapshot_config
+            .maximum_incremental_snapshot_chain_length
+            .get();
+        if maximum_chain_length <= 1 {
+            return None;
+        }
+
+        let incremental_snapshot_archive_info =
+            snapshot_utils::get_highest_incremental_snapshot_archive_info_at_or_below(
+                &snapshot_config.incremental_snapshot_archives_dir,
+                full_snapshot_slot,
+                snapshot_package.slot().checked_sub(1)?,
+            )?;
+        let chain_length = snapshot_utils::get_incremental_snapshot_archive_chain(
+            &snapshot_config.incremental_snapshot_archives_dir,
+            full_snapshot_slot,
+            &incremental_snapshot_archive_info,
+        )
+        .len();
+        (chain_length < maximum_chain_length).then_some(incremental_snapshot_archive_info.slot())
+    }
+
     /// Get the next snapshot package to handle
     ///
     /// Look through the snapshot package channel to find the highest priority one to handle next.
@@ -4,7 +4,7 @@
 pub use solana_sdk::net::DEFAULT_TPU_COALESCE;
 use {
     crate::{
-        banking_stage::BankingStage,
+        banking_stage::{transaction_scheduler::scheduler::SchedulerFactory, BankingStage},
         banking_trace::{BankingTracer, TracerThread},
         cluster_info_vote_listener::{
             ClusterInfoVoteListener, DuplicateConfirmedSlotsSender, GossipVerifiedVoteHashSender,
@@ -110,6 +110,7 @@ impl Tpu {
         tpu_enable_udp: bool,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
         block_production_method: BlockProductionMethod,
+        transaction_scheduler_factory: Arc<dyn SchedulerFactory>,
         _generator_config: Option<GeneratorConfig>, /* vestigial code for replay invalidator */
     ) -> (Self, Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>>) {
         let TpuSockets {
@@ -227,6 +228,7 @@ impl Tpu {
 
         let banking_stage = BankingStage::new(
             block_production_method,
+            transaction_scheduler_factory,
             cluster_info,
             poh_recorder,
             non_vote_receiver,
@@ -5,6 +5,9 @@ use {
     crate::{
         accounts_hash_verifier::AccountsHashVerifier,
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
+        banking_stage::transaction_scheduler::scheduler::{
+            PrioGraphSchedulerFactory, SchedulerFactory,
+        },
         banking_trace::{self, BankingTracer},
         cache_block_meta_service::{CacheBlockMetaSender, CacheBlockMetaService},
         cluster_info_vote_listener::VoteTracker,
@@ -94,6 +97,7 @@ use {
             PrunedBanksRequestHandler, SnapshotRequestHandler,
         },
         bank::Bank,
+        bank_event_notifier_interface::BankEventNotifierArc,
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
         prioritization_fee_cache::PrioritizationFeeCache,
@@ -214,6 +218,8 @@ pub struct ValidatorConfig {
     pub rpc_config: JsonRpcConfig,
     /// Specifies which plugins to start up with
     pub on_start_geyser_plugin_config_files: Option<Vec<PathBuf>>,
+    /// Number of recent notifications buffered for Geyser plugins catching up after a reload
+    pub geyser_plugin_replay_buffer_size: usize,
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
@@ -265,6 +271,8 @@ pub struct ValidatorConfig {
     pub banking_trace_dir_byte_limit: banking_trace::DirByteLimit,
     pub block_verification_method: BlockVerificationMethod,
     pub block_production_method: BlockProductionMethod,
+    /// Creates the transaction scheduler used by `BlockProductionMethod::CentralScheduler`
+    pub transaction_scheduler_factory: Arc<dyn SchedulerFactory>,
     pub generator_config: Option<GeneratorConfig>,
     pub use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup,
     pub wen_restart_proto_path: Option<PathBuf>,
@@ -288,6 +296,7 @@ impl Default for ValidatorConfig {
             account_snapshot_paths: Vec::new(),
             rpc_config: JsonRpcConfig::default(),
             on_start_geyser_plugin_config_files: None,
+            geyser_plugin_replay_buffer_size: 0,
             rpc_addrs: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: SnapshotConfig::new_load_only(),
@@ -336,6 +345,7 @@ impl Default for ValidatorConfig {
             banking_trace_dir_byte_limit: 0,
             block_verification_method: BlockVerificationMethod::default(),
             block_production_method: BlockProductionMethod::default(),
+            transaction_scheduler_factory: Arc::new(PrioGraphSchedulerFactory),
             generator_config: None,
             use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup::default(),
             wen_restart_proto_path: None,
@@ -538,6 +548,7 @@ impl Validator {
                         confirmed_bank_receiver,
                         geyser_plugin_config_files,
                         rpc_to_plugin_manager_receiver_and_exit,
+                        config.geyser_plugin_replay_buffer_size,
                     )
                     .map_err(|err| format!("Failed to load the Geyser plugin: {err:?}"))?,
                 )
@@ -669,13 +680,19 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let bank_event_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_bank_event_notifier());
+
         info!(
             "Geyser plugin: accounts_update_notifier: {}, \
             transaction_notifier: {}, \
-            entry_notifier: {}",
+            entry_notifier: {}, \
+            bank_event_notifier: {}",
             accounts_update_notifier.is_some(),
             transaction_notifier.is_some(),
-            entry_notifier.is_some()
+            entry_notifier.is_some(),
+            bank_event_notifier.is_some()
         );
 
         let system_monitor_service = Some(SystemMonitorService::new(
@@ -723,6 +740,7 @@ impl Validator {
             accounts_update_notifier,
             transaction_notifier,
             entry_notifier,
+            bank_event_notifier,
             Some(poh_timing_point_sender.clone()),
         )?;
         let hard_forks = bank_forks.read().unwrap().root_bank().hard_forks();
@@ -1395,6 +1413,7 @@ impl Validator {
             tpu_enable_udp,
             &prioritization_fee_cache,
             config.block_production_method.clone(),
+            config.transaction_scheduler_factory.clone(),
             config.generator_config.clone(),
         );
 
@@ -1769,6 +1788,7 @@ fn load_blockstore(
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierArc>,
     entry_notifier: Option<EntryNotifierArc>,
+    bank_event_notifier: Option<BankEventNotifierArc>,
     poh_timing_point_sender: Option<PohTimingSender>,
 ) -> Result<
     (
@@ -1895,6 +1915,13 @@ fn load_blockstore(
     let pruned_banks_receiver =
         AccountsBackgroundService::setup_bank_drop_callback(bank_forks.clone());
 
+    // Likewise, banks created during replay inherit the bank event notifier from the root bank
+    bank_forks
+        .read()
+        .unwrap()
+        .root_bank()
+        .set_bank_event_notifier(bank_event_notifier);
+
     leader_schedule_cache.set_fixed_leader_schedule(config.fixed_leader_schedule.clone());
     {
         let mut bank_forks = bank_forks.write().unwrap();
@@ -4,6 +4,7 @@ use {
     indicatif::{ProgressBar, ProgressStyle},
     log::*,
     solana_runtime::{
+        snapshot_archive_info::{IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter},
         snapshot_hash::SnapshotHash,
         snapshot_package::SnapshotKind,
         snapshot_utils::{self, ArchiveFormat},
@@ -257,7 +258,8 @@ pub fn download_genesis_if_missing(
 }
 
 /// Download a snapshot archive from `rpc_addr`.  Use `snapshot_kind` to specify downloading either
-/// a full snapshot or an incremental snapshot.
+/// a full snapshot or an incremental snapshot.  If the incremental snapshot is the last of a chain
+/// of incremental snapshot archives, the whole chain is downloaded.
 pub fn download_snapshot_archive(
     rpc_addr: &SocketAddr,
     full_snapshot_archives_dir: &Path,
@@ -285,6 +287,7 @@ pub fn download_snapshot_archive(
 
     for archive_format in [
         ArchiveFormat::TarZstd,
+        ArchiveFormat::TarZstdIndexed,
         ArchiveFormat::TarGzip,
         ArchiveFormat::TarBzip2,
         ArchiveFormat::TarLz4,
@@ -326,8 +329,111 @@ pub fn download_snapshot_archive(
             Err(err) => info!("{}", err),
         }
     }
+
+    // The incremental snapshot archive may be the last of a chain, each based on the previous one
+    // rather than on the full snapshot, so its name is not known up front
+    if let SnapshotKind::IncrementalSnapshot(base_slot) = snapshot_kind {
+        match download_incremental_snapshot_archive_chain(
+            rpc_addr,
+            &snapshot_archives_remote_dir,
+            base_slot,
+            desired_snapshot_hash,
+            use_progress_bar,
+            progress_notify_callback,
+        ) {
+            Ok(()) => return Ok(()),
+            Err(err) => info!("{}", err),
+        }
+    }
     Err(format!(
         "Failed to download a snapshot archive for slot {} from {}",
         desired_snapshot_hash.0, rpc_addr
     ))
 }
+
+/// Download the chain of incremental snapshot archives that ends at `desired_snapshot_hash`, and
+/// leads back to the full snapshot at `full_snapshot_slot`, from `rpc_addr`
+///
+/// Archives of the chain that were already downloaded are not downloaded again.
+fn download_incremental_snapshot_archive_chain(
+    rpc_addr: &SocketAddr,
+    snapshot_archives_remote_dir: &Path,
+    full_snapshot_slot: Slot,
+    desired_snapshot_hash: (Slot, SnapshotHash),
+    use_progress_bar: bool,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+) -> Result<(), String> {
+    let url = format!(
+        "http://{}{}",
+        rpc_addr,
+        snapshot_utils::INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_DOWNLOAD_PATH
+    );
+    let file_names = reqwest::blocking::Client::new()
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|err| format!("Unable to get incremental snapshot archive chain: {err}"))?;
+
+    let incremental_snapshot_archive_chain = file_names
+        .lines()
+        .map(|file_name| {
+            let file_name = file_name.trim();
+            // Only bare file names, the remote must not pick where the archives are written
+            if Path::new(file_name).file_name() != Some(file_name.as_ref()) {
+                return Err(format!(
+                    "Invalid incremental snapshot archive file name from {url}: {file_name}"
+                ));
+            }
+            IncrementalSnapshotArchiveInfo::new_from_path(
+                snapshot_archives_remote_dir.join(file_name),
+            )
+            .map_err(|err| format!("Invalid incremental snapshot archive chain from {url}: {err}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(last_incremental_snapshot_archive_info) = incremental_snapshot_archive_chain.last()
+    else {
+        return Err(format!(
+            "Empty incremental snapshot archive chain from {url}"
+        ));
+    };
+    if (
+        last_incremental_snapshot_archive_info.slot(),
+        *last_incremental_snapshot_archive_info.hash(),
+    ) != desired_snapshot_hash
+    {
+        return Err(format!(
+            "Incremental snapshot archive chain from {url} ends at slot {}, not at the desired slot {}",
+            last_incremental_snapshot_archive_info.slot(),
+            desired_snapshot_hash.0,
+        ));
+    }
+    let mut base_slot = full_snapshot_slot;
+    for incremental_snapshot_archive_info in &incremental_snapshot_archive_chain {
+        if incremental_snapshot_archive_info.base_slot() != base_slot {
+            return Err(format!(
+                "Incremental snapshot archive chain from {url} is broken at slot {base_slot}"
+            ));
+        }
+        base_slot = incremental_snapshot_archive_info.slot();
+    }
+
+    for incremental_snapshot_archive_info in &incremental_snapshot_archive_chain {
+        let destination_path = incremental_snapshot_archive_info.path();
+        if destination_path.is_file() {
+            continue;
+        }
+        download_file(
+            &format!(
+                "http://{}/{}",
+                rpc_addr,
+                destination_path.file_name().unwrap().to_str().unwrap()
+            ),
+            destination_path,
+            use_progress_bar,
+            progress_notify_callback,
+        )?;
+    }
+    Ok(())
+}
@@ -1,20 +1,29 @@
 use {
     clap::{crate_description, crate_name, values_t, App, Arg},
     log::*,
-    solana_clap_utils::input_parsers::{lamports_of_sol, value_of},
+    solana_clap_utils::{
+        input_parsers::{lamports_of_sol, value_of},
+        input_validators::{is_port, is_pubkey},
+    },
     solana_faucet::{
+        daily_limits::DailyLimits,
         faucet::{run_faucet, Faucet, FAUCET_PORT},
+        http_api::run_faucet_http,
         socketaddr,
     },
-    solana_sdk::signature::read_keypair_file,
+    solana_sdk::{pubkey::Pubkey, signature::read_keypair_file},
     std::{
         collections::HashSet,
         net::{IpAddr, Ipv4Addr, SocketAddr},
+        process::exit,
         sync::{Arc, Mutex},
         thread,
+        time::Duration,
     },
 };
 
+const PERSIST_LIMITS_INTERVAL: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() {
     let default_keypair = solana_cli_config::Config::default().keypair_path;
@@ -67,6 +76,51 @@ async fn main() {
                     recipient address will be used to check request limits instead",
                 ),
         )
+        .arg(
+            Arg::with_name("allowed_pubkey")
+                .long("allow-pubkey")
+                .value_name("PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help(
+                    "Allow airdrops to a particular recipient address without request limits, \
+                    other than the per-request cap. Requests are not authenticated, so anyone \
+                    can request unlimited airdrops to this address",
+                ),
+        )
+        .arg(
+            Arg::with_name("per_recipient_daily_cap")
+                .long("per-recipient-daily-cap")
+                .value_name("NUM")
+                .takes_value(true)
+                .help("Request limit per recipient address per UTC day, in SOL"),
+        )
+        .arg(
+            Arg::with_name("per_ip_daily_cap")
+                .long("per-ip-daily-cap")
+                .value_name("NUM")
+                .takes_value(true)
+                .help("Request limit per IP address per UTC day, in SOL"),
+        )
+        .arg(
+            Arg::with_name("limits_state_file")
+                .long("limits-state-file")
+                .value_name("PATH")
+                .takes_value(true)
+                .help(
+                    "File in which to persist daily request limits across restarts, written \
+                    every few seconds [default: limits are kept in memory only]",
+                ),
+        )
+        .arg(
+            Arg::with_name("http_port")
+                .long("http-port")
+                .value_name("PORT")
+                .takes_value(true)
+                .validator(is_port)
+                .help("Also serve airdrops over HTTP/JSON on this port"),
+        )
         .get_matches();
 
     let faucet_keypair = read_keypair_file(matches.value_of("keypair").unwrap())
@@ -80,16 +134,46 @@ async fn main() {
         .unwrap_or_default()
         .into_iter()
         .collect();
+    let allowed_pubkeys: HashSet<_> = values_t!(matches.values_of("allowed_pubkey"), Pubkey)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let per_recipient_daily_cap = lamports_of_sol(&matches, "per_recipient_daily_cap");
+    let per_ip_daily_cap = lamports_of_sol(&matches, "per_ip_daily_cap");
+    let limits_state_file = matches.value_of("limits_state_file");
 
     let faucet_addr = socketaddr!(Ipv4Addr::UNSPECIFIED, FAUCET_PORT);
 
-    let faucet = Arc::new(Mutex::new(Faucet::new_with_allowed_ips(
+    let mut faucet = Faucet::new_with_allowed_ips(
         faucet_keypair,
         time_slice,
         per_time_cap,
         per_request_cap,
         allowed_ips,
-    )));
+    )
+    .with_allowed_pubkeys(allowed_pubkeys);
+    if per_recipient_daily_cap.is_some() || per_ip_daily_cap.is_some() {
+        let daily_limits = match limits_state_file {
+            Some(limits_state_file) => DailyLimits::new_with_state_file(
+                per_recipient_daily_cap,
+                per_ip_daily_cap,
+                limits_state_file,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Unable to load faucet limits from {limits_state_file}: {err}");
+                exit(1);
+            }),
+            None => DailyLimits::new(per_recipient_daily_cap, per_ip_daily_cap),
+        };
+        faucet = faucet.with_daily_limits(daily_limits);
+    }
+    let faucet = Arc::new(Mutex::new(faucet));
+
+    if let Some(http_port) = value_of::<u16>(&matches, "http_port") {
+        let http_addr = socketaddr!(Ipv4Addr::UNSPECIFIED, http_port);
+        tokio::spawn(run_faucet_http(faucet.clone(), http_addr, None));
+    }
 
     let faucet1 = faucet.clone();
     thread::spawn(move || loop {
@@ -99,5 +183,18 @@ async fn main() {
         faucet1.lock().unwrap().clear_caches();
     });
 
+    if limits_state_file.is_some() {
+        let faucet2 = faucet.clone();
+        thread::spawn(move || loop {
+            thread::sleep(PERSIST_LIMITS_INTERVAL);
+            let snapshot = faucet2.lock().unwrap().daily_limits_snapshot();
+            if let Some(snapshot) = snapshot {
+                if let Err(err) = snapshot.persist() {
+                    warn!("failed to persist faucet daily limits: {}", err);
+                }
+            }
+        });
+    }
+
     run_faucet(faucet, faucet_addr, None).await;
 }
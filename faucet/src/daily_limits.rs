@@ -0,0 +1,263 @@
+//! Per-recipient and per-IP daily airdrop limits, optionally persisted to a file so they survive
+//! faucet restarts.
+//!
+//! Usage is only kept in memory while requests are processed. `DailyLimits::take_snapshot` copies
+//! it so the caller can write the state file without holding up requests.
+
+use {
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        fs,
+        hash::Hash,
+        io,
+        net::IpAddr,
+        path::{Path, PathBuf},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Lamports airdropped since the start of `day`, in days since the Unix epoch (UTC)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+struct DailyUsage {
+    day: u64,
+    by_ip: HashMap<IpAddr, u64>,
+    by_recipient: HashMap<Pubkey, u64>,
+}
+
+#[derive(Debug)]
+pub struct DailyLimits {
+    per_recipient_cap: Option<u64>,
+    per_ip_cap: Option<u64>,
+    state_path: Option<PathBuf>,
+    usage: DailyUsage,
+    // Whether usage changed since the last snapshot
+    dirty: bool,
+}
+
+/// The daily cap a request would exceed
+#[derive(Debug, PartialEq, Eq)]
+pub enum DailyCapExceeded {
+    Recipient { current: u64, cap: u64 },
+    Ip { current: u64, cap: u64 },
+}
+
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_PER_DAY
+}
+
+impl DailyLimits {
+    /// Limits kept in memory only
+    pub fn new(per_recipient_cap: Option<u64>, per_ip_cap: Option<u64>) -> Self {
+        Self {
+            per_recipient_cap,
+            per_ip_cap,
+            state_path: None,
+            usage: DailyUsage {
+                day: today(),
+                ..DailyUsage::default()
+            },
+            dirty: false,
+        }
+    }
+
+    /// Limits persisted to `state_path`, resuming today's usage if the file already holds it
+    pub fn new_with_state_file(
+        per_recipient_cap: Option<u64>,
+        per_ip_cap: Option<u64>,
+        state_path: impl Into<PathBuf>,
+    ) -> io::Result<Self> {
+        let state_path = state_path.into();
+        let mut limits = Self::new(per_recipient_cap, per_ip_cap);
+        match fs::read(&state_path) {
+            Ok(bytes) => {
+                let usage: DailyUsage = bincode::deserialize(&bytes)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                if usage.day == limits.usage.day {
+                    limits.usage = usage;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        limits.state_path = Some(state_path);
+        Ok(limits)
+    }
+
+    pub fn state_path(&self) -> Option<&Path> {
+        self.state_path.as_deref()
+    }
+
+    fn roll_over(&mut self) {
+        let day = today();
+        if self.usage.day != day {
+            self.usage = DailyUsage {
+                day,
+                ..DailyUsage::default()
+            };
+        }
+    }
+
+    /// Check that airdropping `lamports` to `recipient` stays within today's caps, without
+    /// recording it. `ip` is `None` for requests exempt from the per-IP cap.
+    pub fn check(
+        &mut self,
+        lamports: u64,
+        recipient: &Pubkey,
+        ip: Option<IpAddr>,
+    ) -> Result<(), DailyCapExceeded> {
+        self.roll_over();
+
+        if let Some(cap) = self.per_recipient_cap {
+            let current = total(&self.usage.by_recipient, recipient, lamports);
+            if current > cap {
+                return Err(DailyCapExceeded::Recipient { current, cap });
+            }
+        }
+        if let (Some(ip), Some(cap)) = (ip, self.per_ip_cap) {
+            let current = total(&self.usage.by_ip, &ip, lamports);
+            if current > cap {
+                return Err(DailyCapExceeded::Ip { current, cap });
+            }
+        }
+        Ok(())
+    }
+
+    /// Record airdropping `lamports` to `recipient` against today's caps
+    pub fn record(&mut self, lamports: u64, recipient: &Pubkey, ip: Option<IpAddr>) {
+        self.roll_over();
+
+        let recipient_total = total(&self.usage.by_recipient, recipient, lamports);
+        self.usage.by_recipient.insert(*recipient, recipient_total);
+        if let Some(ip) = ip {
+            let ip_total = total(&self.usage.by_ip, &ip, lamports);
+            self.usage.by_ip.insert(ip, ip_total);
+        }
+        self.dirty = true;
+    }
+
+    /// Check that airdropping `lamports` to `recipient` stays within today's caps, and if so
+    /// record it
+    pub fn check_and_record(
+        &mut self,
+        lamports: u64,
+        recipient: &Pubkey,
+        ip: Option<IpAddr>,
+    ) -> Result<(), DailyCapExceeded> {
+        self.check(lamports, recipient, ip)?;
+        self.record(lamports, recipient, ip);
+        Ok(())
+    }
+
+    /// A copy of the usage to write to the state file, if there is one and usage changed since
+    /// the last snapshot
+    pub fn take_snapshot(&mut self) -> Option<DailyLimitsSnapshot> {
+        let state_path = self.state_path.clone()?;
+        std::mem::take(&mut self.dirty).then(|| DailyLimitsSnapshot {
+            state_path,
+            usage: self.usage.clone(),
+        })
+    }
+}
+
+fn total<K: Eq + Hash>(usage: &HashMap<K, u64>, key: &K, lamports: u64) -> u64 {
+    usage
+        .get(key)
+        .copied()
+        .unwrap_or_default()
+        .saturating_add(lamports)
+}
+
+/// Daily usage taken from `DailyLimits`, to be persisted without holding it
+pub struct DailyLimitsSnapshot {
+    state_path: PathBuf,
+    usage: DailyUsage,
+}
+
+impl DailyLimitsSnapshot {
+    /// Atomically replace the state file with the snapshot
+    pub fn persist(&self) -> io::Result<()> {
+        let bytes = bincode::serialize(&self.usage)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let tmp_path = self.state_path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, &self.state_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::net::Ipv4Addr};
+
+    fn persist(limits: &mut DailyLimits) {
+        limits.take_snapshot().unwrap().persist().unwrap();
+    }
+
+    #[test]
+    fn test_check_and_record() {
+        let mut limits = DailyLimits::new(Some(3), Some(4));
+        let recipient = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)));
+
+        assert_eq!(limits.check_and_record(2, &recipient, ip), Ok(()));
+        assert_eq!(
+            limits.check_and_record(2, &recipient, ip),
+            Err(DailyCapExceeded::Recipient { current: 4, cap: 3 })
+        );
+        assert_eq!(limits.check_and_record(2, &other, ip), Ok(()));
+        assert_eq!(
+            limits.check_and_record(1, &Pubkey::new_unique(), ip),
+            Err(DailyCapExceeded::Ip { current: 5, cap: 4 })
+        );
+        // Exempt from the per-IP cap
+        assert_eq!(limits.check_and_record(1, &recipient, None), Ok(()));
+
+        // A new day resets usage
+        limits.usage.day -= 1;
+        assert_eq!(limits.check_and_record(3, &recipient, ip), Ok(()));
+    }
+
+    #[test]
+    fn test_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("faucet-limits.bin");
+        let recipient = Pubkey::new_unique();
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)));
+
+        let mut limits = DailyLimits::new_with_state_file(Some(3), None, &state_path).unwrap();
+        assert!(limits.take_snapshot().is_none());
+        assert_eq!(limits.check_and_record(2, &recipient, ip), Ok(()));
+        persist(&mut limits);
+        assert!(limits.take_snapshot().is_none());
+
+        // Usage survives a restart
+        let mut limits = DailyLimits::new_with_state_file(Some(3), None, &state_path).unwrap();
+        assert_eq!(limits.usage.by_ip[&ip.unwrap()], 2);
+        assert_eq!(
+            limits.check_and_record(2, &recipient, ip),
+            Err(DailyCapExceeded::Recipient { current: 4, cap: 3 })
+        );
+
+        // Rejected requests are not recorded
+        assert!(limits.take_snapshot().is_none());
+
+        // Usage from a previous day is discarded
+        limits.usage.day -= 1;
+        limits.dirty = true;
+        persist(&mut limits);
+        let limits = DailyLimits::new_with_state_file(Some(3), None, &state_path).unwrap();
+        assert!(limits.usage.by_recipient.is_empty());
+
+        fs::write(&state_path, b"garbage").unwrap();
+        assert!(DailyLimits::new_with_state_file(Some(3), None, &state_path).is_err());
+    }
+}
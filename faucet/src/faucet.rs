@@ -1,10 +1,11 @@
 //! The `faucet` module provides an object for launching a Solana Faucet,
 //! which is the custodian of any remaining lamports in a mint.
 //! The Solana Faucet builds and sends airdrop transactions,
-//! checking requests against a single-request cap and a per-IP limit
-//! for a given time time_slice.
+//! checking requests against a single-request cap, a per-IP limit
+//! for a given time time_slice, and optional per-recipient and per-IP daily limits.
 
 use {
+    crate::daily_limits::{DailyCapExceeded, DailyLimits, DailyLimitsSnapshot},
     bincode::{deserialize, serialize, serialized_size},
     byteorder::{ByteOrder, LittleEndian},
     crossbeam_channel::{unbounded, Sender},
@@ -73,6 +74,9 @@ pub enum FaucetError {
 
     #[error("limit reached; req: ◎{0}, to: {1}, current: ◎{2}, cap: ◎{3}")]
     PerTimeCapExceeded(f64, String, f64, f64),
+
+    #[error("daily limit reached; req: ◎{0}, to: {1}, current: ◎{2}, cap: ◎{3}")]
+    DailyCapExceeded(f64, String, f64, f64),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -97,6 +101,8 @@ pub struct Faucet {
     per_time_cap: Option<u64>,
     per_request_cap: Option<u64>,
     allowed_ips: HashSet<IpAddr>,
+    allowed_pubkeys: HashSet<Pubkey>,
+    daily_limits: Option<DailyLimits>,
 }
 
 impl Faucet {
@@ -141,9 +147,32 @@ impl Faucet {
             per_time_cap,
             per_request_cap,
             allowed_ips,
+            allowed_pubkeys: HashSet::new(),
+            daily_limits: None,
         }
     }
 
+    /// Recipients exempt from all request limits except the per-request cap
+    /// Exempt airdrops to `allowed_pubkeys` from every limit but the per-request cap. Requests
+    /// are not authenticated, so anyone can request airdrops to these recipients.
+    pub fn with_allowed_pubkeys(mut self, allowed_pubkeys: HashSet<Pubkey>) -> Self {
+        self.allowed_pubkeys = allowed_pubkeys;
+        self
+    }
+
+    pub fn with_daily_limits(mut self, daily_limits: DailyLimits) -> Self {
+        self.daily_limits = Some(daily_limits);
+        self
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.faucet_keypair.pubkey()
+    }
+
+    pub fn per_request_cap(&self) -> Option<u64> {
+        self.per_request_cap
+    }
+
     pub fn check_time_request_limit<T: LimitByTime + std::fmt::Display>(
         &mut self,
         request_amount: u64,
@@ -151,6 +180,25 @@ impl Faucet {
     ) -> Result<(), FaucetError> {
         let new_total = to.check_cache(self, request_amount);
         to.datapoint_info(request_amount, new_total);
+        self.check_time_cap(request_amount, &to, new_total)
+    }
+
+    /// Checks the per-time limit of `to` without recording the request
+    fn check_time_request_limit_unrecorded<T: LimitByTime + std::fmt::Display>(
+        &self,
+        request_amount: u64,
+        to: &T,
+    ) -> Result<(), FaucetError> {
+        let new_total = to.cached_total(self).saturating_add(request_amount);
+        self.check_time_cap(request_amount, to, new_total)
+    }
+
+    fn check_time_cap(
+        &self,
+        request_amount: u64,
+        to: &impl std::fmt::Display,
+        new_total: u64,
+    ) -> Result<(), FaucetError> {
         if let Some(cap) = self.per_time_cap {
             if new_total > cap {
                 return Err(FaucetError::PerTimeCapExceeded(
@@ -164,13 +212,51 @@ impl Faucet {
         Ok(())
     }
 
+    /// Checks the daily limits, if any, without recording the request. `ip` is `None` for
+    /// requests exempt from the per-IP limit.
+    pub fn check_daily_limits(
+        &mut self,
+        request_amount: u64,
+        to: &Pubkey,
+        ip: Option<IpAddr>,
+    ) -> Result<(), FaucetError> {
+        let Some(daily_limits) = self.daily_limits.as_mut() else {
+            return Ok(());
+        };
+        daily_limits.check(request_amount, to, ip).map_err(|err| {
+            let (limited, current, cap) = match err {
+                DailyCapExceeded::Recipient { current, cap } => (to.to_string(), current, cap),
+                DailyCapExceeded::Ip { current, cap } => (ip.unwrap().to_string(), current, cap),
+            };
+            FaucetError::DailyCapExceeded(
+                lamports_to_sol(request_amount),
+                limited,
+                lamports_to_sol(current),
+                lamports_to_sol(cap),
+            )
+        })
+    }
+
+    /// Records the request against the daily limits, if any
+    pub fn record_daily_limits(&mut self, request_amount: u64, to: &Pubkey, ip: Option<IpAddr>) {
+        if let Some(daily_limits) = self.daily_limits.as_mut() {
+            daily_limits.record(request_amount, to, ip);
+        }
+    }
+
+    /// Daily usage to persist, if it is persisted and changed since the last snapshot
+    pub fn daily_limits_snapshot(&mut self) -> Option<DailyLimitsSnapshot> {
+        self.daily_limits.as_mut()?.take_snapshot()
+    }
+
     pub fn clear_caches(&mut self) {
         self.ip_cache.clear();
         self.address_cache.clear();
     }
 
-    /// Checks per-request and per-time-ip limits; if both pass, this method returns a signed
-    /// SystemProgram::Transfer transaction from the faucet keypair to the requested recipient. If
+    /// Checks per-request, per-time and daily limits; if all pass, this method returns a signed
+    /// SystemProgram::Transfer transaction from the faucet keypair to the requested recipient.
+    /// Recipients in the pubkey allowlist are only checked against the per-request limit. If
     /// the request exceeds this per-request limit, this method returns a signed SPL Memo
     /// transaction with the memo: `"request too large; req: <REQUEST> SOL cap: <CAP> SOL"`
     pub fn build_airdrop_transaction(
@@ -213,10 +299,22 @@ impl Faucet {
                         )));
                     }
                 }
-                if !ip.is_loopback() && !self.allowed_ips.contains(&ip) {
-                    self.check_time_request_limit(lamports, ip)?;
+                if !self.allowed_pubkeys.contains(&to) {
+                    let limit_ip = !ip.is_loopback() && !self.allowed_ips.contains(&ip);
+                    // Check every limit before recording the request against any of them, so a
+                    // request rejected by one limit doesn't use up the others
+                    if limit_ip {
+                        self.check_time_request_limit_unrecorded(lamports, &ip)?;
+                    }
+                    self.check_time_request_limit_unrecorded(lamports, &to)?;
+                    self.check_daily_limits(lamports, &to, limit_ip.then_some(ip))?;
+
+                    if limit_ip {
+                        self.check_time_request_limit(lamports, ip)?;
+                    }
+                    self.check_time_request_limit(lamports, to)?;
+                    self.record_daily_limits(lamports, &to, limit_ip.then_some(ip));
                 }
-                self.check_time_request_limit(lamports, to)?;
 
                 let transfer_instruction =
                     system_instruction::transfer(&mint_pubkey, &to, lamports);
@@ -448,11 +546,16 @@ async fn process(
 }
 
 pub trait LimitByTime {
+    fn cached_total(&self, faucet: &Faucet) -> u64;
     fn check_cache(&self, faucet: &mut Faucet, request_amount: u64) -> u64;
     fn datapoint_info(&self, request_amount: u64, new_total: u64);
 }
 
 impl LimitByTime for IpAddr {
+    fn cached_total(&self, faucet: &Faucet) -> u64 {
+        faucet.ip_cache.get(self).copied().unwrap_or_default()
+    }
+
     fn check_cache(&self, faucet: &mut Faucet, request_amount: u64) -> u64 {
         *faucet
             .ip_cache
@@ -472,6 +575,10 @@ impl LimitByTime for IpAddr {
 }
 
 impl LimitByTime for Pubkey {
+    fn cached_total(&self, faucet: &Faucet) -> u64 {
+        faucet.address_cache.get(self).copied().unwrap_or_default()
+    }
+
     fn check_cache(&self, faucet: &mut Faucet, request_amount: u64) -> u64 {
         *faucet
             .address_cache
@@ -647,6 +754,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_faucet_daily_limits_and_allowed_pubkeys() {
+        let to = Pubkey::new_unique();
+        let allowed = Pubkey::new_unique();
+        let blockhash = Hash::default();
+        let request = |to| FaucetRequest::GetAirdrop {
+            lamports: 2,
+            to,
+            blockhash,
+        };
+        let ip = socketaddr!([203, 0, 113, 1], 1234).ip();
+
+        let mut faucet = Faucet::new(Keypair::new(), None, None, Some(2))
+            .with_allowed_pubkeys(HashSet::from([allowed]))
+            .with_daily_limits(DailyLimits::new(Some(3), Some(5)));
+        assert!(faucet.build_airdrop_transaction(request(to), ip).is_ok());
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(to), ip),
+            Err(FaucetError::DailyCapExceeded(..))
+        ));
+        assert!(faucet
+            .build_airdrop_transaction(request(Pubkey::new_unique()), ip)
+            .is_ok());
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(Pubkey::new_unique()), ip),
+            Err(FaucetError::DailyCapExceeded(..))
+        ));
+
+        // Allowed pubkeys skip the per-time and daily limits, but not the per-request cap
+        for _ in 0..3 {
+            assert!(matches!(
+                faucet.build_airdrop_transaction(request(allowed), ip),
+                Ok(FaucetTransaction::Airdrop(_))
+            ));
+        }
+        let too_large = FaucetRequest::GetAirdrop {
+            lamports: 3,
+            to: allowed,
+            blockhash,
+        };
+        assert!(matches!(
+            faucet.build_airdrop_transaction(too_large, ip),
+            Ok(FaucetTransaction::Memo(_))
+        ));
+    }
+
+    #[test]
+    fn test_faucet_rejected_request_not_recorded() {
+        let to = Pubkey::new_unique();
+        let ip = socketaddr!([203, 0, 113, 1], 1234).ip();
+        let request = |to| FaucetRequest::GetAirdrop {
+            lamports: 2,
+            to,
+            blockhash: Hash::default(),
+        };
+
+        let mut faucet = Faucet::new(Keypair::new(), None, Some(4), None)
+            .with_daily_limits(DailyLimits::new(Some(2), None));
+        assert!(faucet.build_airdrop_transaction(request(to), ip).is_ok());
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(to), ip),
+            Err(FaucetError::DailyCapExceeded(..))
+        ));
+        assert_eq!(faucet.ip_cache[&ip], 2);
+        assert_eq!(faucet.address_cache[&to], 2);
+
+        // The rejected request didn't use up the per-time limit of the IP
+        assert!(faucet
+            .build_airdrop_transaction(request(Pubkey::new_unique()), ip)
+            .is_ok());
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(Pubkey::new_unique()), ip),
+            Err(FaucetError::PerTimeCapExceeded(..))
+        ));
+        assert_eq!(faucet.ip_cache[&ip], 4);
+    }
+
     #[test]
     fn test_process_faucet_request() {
         let to = solana_sdk::pubkey::new_rand();
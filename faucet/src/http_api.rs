@@ -0,0 +1,352 @@
+//! The `http_api` module serves airdrops over HTTP/JSON next to the binary faucet protocol, so
+//! browser front-ends can request airdrops without an RPC proxy.
+//!
+//! `POST /airdrop` takes `{"recipient": "<PUBKEY>", "lamports": <NUM>, "blockhash": "<HASH>"}` and
+//! returns `{"transaction": "<BASE64>", "signature": "<SIGNATURE>"}`, where `transaction` is the
+//! bincode-serialized airdrop transaction for the client to submit. `GET /` returns the faucet
+//! address and its per-request cap. Failed requests return `{"error": "<MESSAGE>"}`.
+
+use {
+    crate::faucet::{Faucet, FaucetError, FaucetRequest, FaucetTransaction},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    crossbeam_channel::Sender,
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    serde_json::json,
+    solana_sdk::{hash::Hash, pubkey::Pubkey},
+    std::{
+        io,
+        net::{IpAddr, SocketAddr},
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        time::timeout,
+    },
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REQUEST_LEN: usize = 8192;
+
+const BAD_REQUEST: &str = "400 Bad Request";
+const TOO_MANY_REQUESTS: &str = "429 Too Many Requests";
+
+#[derive(Deserialize, Debug)]
+struct AirdropRequest {
+    recipient: String,
+    lamports: u64,
+    blockhash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AirdropResponse {
+    transaction: String,
+    signature: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+pub async fn run_faucet_http(
+    faucet: Arc<Mutex<Faucet>>,
+    http_addr: SocketAddr,
+    sender: Option<Sender<Result<SocketAddr, String>>>,
+) {
+    let listener = TcpListener::bind(&http_addr).await;
+    if let Some(sender) = sender {
+        sender
+            .send(
+                listener
+                    .as_ref()
+                    .map(|listener| listener.local_addr().unwrap())
+                    .map_err(|err| {
+                        format!(
+                            "Unable to bind faucet HTTP API to {http_addr:?}, check the address \
+                             is not already in use: {err}"
+                        )
+                    }),
+            )
+            .unwrap();
+    }
+
+    let listener = match listener {
+        Err(err) => {
+            error!("Faucet HTTP API failed to start: {}", err);
+            return;
+        }
+        Ok(listener) => listener,
+    };
+    info!("Faucet HTTP API started. Listening on: {}", http_addr);
+
+    loop {
+        let faucet = faucet.clone();
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = process(stream, faucet).await {
+                        info!("failed to process HTTP request; error = {:?}", e);
+                    }
+                });
+            }
+            Err(e) => debug!("failed to accept socket; error = {:?}", e),
+        }
+    }
+}
+
+async fn process(mut stream: TcpStream, faucet: Arc<Mutex<Faucet>>) -> io::Result<()> {
+    let ip = stream.peer_addr()?.ip();
+    let request = timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+    let (status, body) = match request {
+        Some(request) => handle_request(&faucet, &request, ip),
+        None => error_response(BAD_REQUEST, "malformed HTTP request"),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Reads a single request, returning `None` if it is malformed or too large
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<HttpRequest>> {
+    let mut bytes = vec![];
+    let mut buf = [0u8; 1024];
+    loop {
+        if let Some(request) = parse_request(&bytes) {
+            return Ok(Some(request));
+        }
+        let len = stream.read(&mut buf).await?;
+        if len == 0 || bytes.len() + len > MAX_REQUEST_LEN {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&buf[..len]);
+    }
+}
+
+/// Parses a request, returning `None` until its headers and body have been received in full
+fn parse_request(bytes: &[u8]) -> Option<HttpRequest> {
+    let header_len = bytes.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let headers = String::from_utf8_lossy(&bytes[..header_len]);
+    let mut lines = headers.lines();
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?;
+    let path = path.split('?').next().unwrap_or(path).to_string();
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>().unwrap_or(usize::MAX))
+        .unwrap_or_default();
+    let body = bytes.get(header_len..header_len.checked_add(content_length)?)?;
+    Some(HttpRequest {
+        method,
+        path,
+        body: body.to_vec(),
+    })
+}
+
+fn error_response(status: &'static str, message: &str) -> (&'static str, String) {
+    (status, json!({ "error": message }).to_string())
+}
+
+fn handle_request(
+    faucet: &Mutex<Faucet>,
+    request: &HttpRequest,
+    ip: IpAddr,
+) -> (&'static str, String) {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", _) => ("204 No Content", String::new()),
+        ("GET", "/") => {
+            let faucet = faucet.lock().unwrap();
+            (
+                "200 OK",
+                json!({
+                    "faucet": faucet.pubkey().to_string(),
+                    "perRequestCap": faucet.per_request_cap(),
+                })
+                .to_string(),
+            )
+        }
+        ("POST", "/airdrop") => match airdrop(faucet, &request.body, ip) {
+            Ok(response) => ("200 OK", serde_json::to_string(&response).unwrap()),
+            Err((status, message)) => error_response(status, &message),
+        },
+        ("GET" | "POST", _) => error_response("404 Not Found", "not found"),
+        _ => error_response("405 Method Not Allowed", "method not allowed"),
+    }
+}
+
+fn airdrop(
+    faucet: &Mutex<Faucet>,
+    body: &[u8],
+    ip: IpAddr,
+) -> Result<AirdropResponse, (&'static str, String)> {
+    let request: AirdropRequest = serde_json::from_slice(body)
+        .map_err(|err| (BAD_REQUEST, format!("invalid request: {err}")))?;
+    let to = Pubkey::from_str(&request.recipient)
+        .map_err(|err| (BAD_REQUEST, format!("invalid recipient: {err}")))?;
+    let blockhash = Hash::from_str(&request.blockhash)
+        .map_err(|err| (BAD_REQUEST, format!("invalid blockhash: {err}")))?;
+    let request = FaucetRequest::GetAirdrop {
+        lamports: request.lamports,
+        to,
+        blockhash,
+    };
+
+    info!("HTTP airdrop transaction requested...{:?}", request);
+    let internal_error = |err: FaucetError| ("500 Internal Server Error", err.to_string());
+    match faucet
+        .lock()
+        .unwrap()
+        .build_airdrop_transaction(request, ip)
+    {
+        Ok(FaucetTransaction::Airdrop(tx)) => {
+            info!("Airdrop transaction granted");
+            let transaction = bincode::serialize(&tx).map_err(|err| internal_error(err.into()))?;
+            Ok(AirdropResponse {
+                transaction: BASE64_STANDARD.encode(transaction),
+                signature: tx.signatures[0].to_string(),
+            })
+        }
+        // A memo transaction is of no use to an HTTP client, so report the memo as the error
+        Ok(FaucetTransaction::Memo((_, memo))) => {
+            warn!("Memo transaction returned: {}", memo);
+            Err((BAD_REQUEST, memo))
+        }
+        Err(err @ (FaucetError::PerTimeCapExceeded(..) | FaucetError::DailyCapExceeded(..))) => {
+            warn!("Airdrop transaction failed: {}", err);
+            Err((TOO_MANY_REQUESTS, err.to_string()))
+        }
+        Err(err) => {
+            warn!("Airdrop transaction failed: {}", err);
+            Err(internal_error(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            signature::{Keypair, Signer},
+            system_instruction::SystemInstruction,
+            transaction::Transaction,
+        },
+        std::net::Ipv4Addr,
+    };
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(b"POST /airdrop HTTP/1.1\r\nHost: x\r\n"),
+            None
+        );
+        assert_eq!(
+            parse_request(b"POST /airdrop HTTP/1.1\r\ncontent-length: 4\r\n\r\n{}"),
+            None
+        );
+        assert_eq!(
+            parse_request(b"POST /airdrop?x=1 HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}"),
+            Some(HttpRequest {
+                method: "POST".to_string(),
+                path: "/airdrop".to_string(),
+                body: b"{}".to_vec(),
+            })
+        );
+        assert_eq!(
+            parse_request(b"GET / HTTP/1.1\r\n\r\n"),
+            Some(HttpRequest {
+                method: "GET".to_string(),
+                path: "/".to_string(),
+                body: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let mint = Keypair::new();
+        let mint_pubkey = mint.pubkey();
+        let faucet = Mutex::new(Faucet::new(mint, None, Some(3), Some(2)));
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+        let to = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let request = |method: &str, path: &str, body: serde_json::Value| HttpRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string().into_bytes(),
+        };
+        let airdrop = |lamports: u64| {
+            request(
+                "POST",
+                "/airdrop",
+                json!({
+                    "recipient": to.to_string(),
+                    "lamports": lamports,
+                    "blockhash": blockhash.to_string(),
+                }),
+            )
+        };
+
+        let (status, body) = handle_request(&faucet, &request("GET", "/", json!(null)), ip);
+        assert_eq!(status, "200 OK");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({ "faucet": mint_pubkey.to_string(), "perRequestCap": 2 })
+        );
+
+        let (status, body) = handle_request(&faucet, &airdrop(2), ip);
+        assert_eq!(status, "200 OK");
+        let response: AirdropResponse = serde_json::from_str(&body).unwrap();
+        let tx: Transaction =
+            bincode::deserialize(&BASE64_STANDARD.decode(response.transaction).unwrap()).unwrap();
+        assert_eq!(response.signature, tx.signatures[0].to_string());
+        assert_eq!(tx.message.account_keys[..2], [mint_pubkey, to]);
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+        let instruction: SystemInstruction =
+            bincode::deserialize(&tx.message.instructions[0].data).unwrap();
+        assert_eq!(instruction, SystemInstruction::Transfer { lamports: 2 });
+
+        // Per-request cap
+        assert_eq!(handle_request(&faucet, &airdrop(3), ip).0, BAD_REQUEST);
+        // Per-time cap
+        assert_eq!(
+            handle_request(&faucet, &airdrop(2), ip).0,
+            TOO_MANY_REQUESTS
+        );
+
+        let (status, body) = handle_request(
+            &faucet,
+            &request(
+                "POST",
+                "/airdrop",
+                json!({ "recipient": "nope", "lamports": 1, "blockhash": blockhash.to_string() }),
+            ),
+            ip,
+        );
+        assert_eq!(status, BAD_REQUEST);
+        assert!(body.contains("invalid recipient"));
+
+        assert_eq!(
+            handle_request(&faucet, &request("OPTIONS", "/airdrop", json!(null)), ip).0,
+            "204 No Content"
+        );
+        assert_eq!(
+            handle_request(&faucet, &request("GET", "/airdrops", json!(null)), ip).0,
+            "404 Not Found"
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod daily_limits;
 pub mod faucet;
 pub mod faucet_mock;
+pub mod http_api;
@@ -4,12 +4,13 @@
 /// creates the implementation of the plugin.
 use {
     solana_sdk::{
-        clock::{Slot, UnixTimestamp},
+        clock::{Epoch, Slot, UnixTimestamp},
+        pubkey::Pubkey,
         signature::Signature,
         transaction::SanitizedTransaction,
     },
     solana_transaction_status::{Reward, TransactionStatusMeta},
-    std::{any::Any, error, io},
+    std::{any::Any, collections::HashSet, error, io, ops::Range},
     thiserror::Error,
 };
 
@@ -122,6 +123,52 @@ pub enum ReplicaAccountInfoVersions<'a> {
     V0_0_3(&'a ReplicaAccountInfoV3<'a>),
 }
 
+/// Compares `bytes` against the account data starting at `offset`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDataMemcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl AccountDataMemcmp {
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.offset
+            .checked_add(self.bytes.len())
+            .and_then(|end| data.get(self.offset..end))
+            .map(|slice| slice == self.bytes.as_slice())
+            .unwrap_or(false)
+    }
+}
+
+/// Account updates a plugin wants to receive, declared once at load time.
+/// An update matches when:
+/// * its pubkey is in `pubkeys` or its owner is in `owners`; if both are
+///   empty, any account matches
+/// * its data length falls in one of `data_size_ranges`, if any are given
+/// * every entry in `memcmp` matches its data
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountUpdateFilter {
+    pub owners: HashSet<Pubkey>,
+    pub pubkeys: HashSet<Pubkey>,
+    pub data_size_ranges: Vec<Range<usize>>,
+    pub memcmp: Vec<AccountDataMemcmp>,
+}
+
+impl AccountUpdateFilter {
+    pub fn matches(&self, pubkey: &Pubkey, owner: &Pubkey, data: &[u8]) -> bool {
+        let account_matches = (self.owners.is_empty() && self.pubkeys.is_empty())
+            || self.pubkeys.contains(pubkey)
+            || self.owners.contains(owner);
+        account_matches
+            && (self.data_size_ranges.is_empty()
+                || self
+                    .data_size_ranges
+                    .iter()
+                    .any(|range| range.contains(&data.len())))
+            && self.memcmp.iter().all(|memcmp| memcmp.matches(data))
+    }
+}
+
 /// Information about a transaction
 #[derive(Clone, Debug)]
 #[repr(C)]
@@ -258,6 +305,39 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_3(&'a ReplicaBlockInfoV3<'a>),
 }
 
+/// A bank-level state transition that is otherwise only visible through the
+/// sysvar and feature account writes it causes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum ReplicaBankEvent<'a> {
+    /// The first bank of a new epoch has been created and the epoch
+    /// transition has been processed.
+    EpochBoundary {
+        slot: Slot,
+        parent_slot: Slot,
+        parent_epoch: Epoch,
+        epoch: Epoch,
+    },
+    /// One partition of the epoch's stake rewards has been credited in `slot`.
+    PartitionedRewardsDistributed {
+        slot: Slot,
+        epoch: Epoch,
+        partition_index: u64,
+        num_partitions: u64,
+        num_stake_accounts: u64,
+        lamports: u64,
+    },
+    /// The feature `feature_id` has been activated in `slot`.
+    FeatureActivated { slot: Slot, feature_id: &'a [u8] },
+}
+
+/// A wrapper to future-proof ReplicaBankEvent handling. Events are notified while the
+/// bank is being built, so the slot may still be on a fork that is later abandoned.
+#[repr(u32)]
+pub enum ReplicaBankEventVersions<'a> {
+    V0_0_1(&'a ReplicaBankEvent<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -366,6 +446,24 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     /// Used for doing cleanup before unload.
     fn on_unload(&mut self) {}
 
+    /// Called right after `on_load` succeeds. A plugin that returns a filter
+    /// is only called with account updates matching it, both at startup and
+    /// during transaction processing. Default is None -- all account updates
+    /// are delivered.
+    fn account_update_filter(&self) -> Option<AccountUpdateFilter> {
+        None
+    }
+
+    /// Called right after `on_load` succeeds. A plugin that returns a slot is
+    /// first sent the notifications the validator has buffered for that slot
+    /// and later, before any new notifications, so it can be reloaded without
+    /// missing data. The buffer is bounded and disabled by default; see the
+    /// validator's `--geyser-plugin-replay-buffer-size`. Replayed account
+    /// updates have no `txn`. Default is None -- no catch-up replay.
+    fn catch_up_from_slot(&self) -> Option<Slot> {
+        None
+    }
+
     /// Called when an account is updated at a slot.
     /// When `is_startup` is true, it indicates the account is loaded from
     /// snapshots when the validator starts up. When `is_startup` is false,
@@ -418,6 +516,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called on bank-level events: epoch boundaries, partitioned rewards
+    /// distribution and feature activations.
+    #[allow(unused_variables)]
+    fn notify_bank_event(&self, event: ReplicaBankEventVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -438,4 +543,11 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in bank events
+    /// Default is false -- if the plugin is interested in
+    /// bank events, return true.
+    fn bank_event_notifications_enabled(&self) -> bool {
+        false
+    }
 }
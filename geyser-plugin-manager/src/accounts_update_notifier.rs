@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
     },
@@ -33,18 +35,42 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         pubkey: &Pubkey,
         write_version: u64,
     ) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if !plugin_manager.account_update_matches(pubkey, account.owner(), account.data()) {
+            return;
+        }
+        plugin_manager.buffer_notification(|| BufferedNotification::Account {
+            slot,
+            pubkey: *pubkey,
+            account: account.clone(),
+            write_version,
+        });
+
         if let Some(account_info) =
             self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version)
         {
-            self.notify_plugins_of_account_update(account_info, slot, false);
+            self.notify_plugins_of_account_update(
+                &plugin_manager,
+                pubkey,
+                account.owner(),
+                account_info,
+                slot,
+                false,
+            );
         }
     }
 
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if !plugin_manager.account_update_matches(account.pubkey(), account.owner(), account.data())
+        {
+            return;
+        }
+
         let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
         let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
 
-        let account = self.accountinfo_from_stored_account_meta(account);
+        let account_info = self.accountinfo_from_stored_account_meta(account);
         measure_copy.stop();
 
         inc_new_counter_debug!(
@@ -54,8 +80,15 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
             100000
         );
 
-        if let Some(account_info) = account {
-            self.notify_plugins_of_account_update(account_info, slot, true);
+        if let Some(account_info) = account_info {
+            self.notify_plugins_of_account_update(
+                &plugin_manager,
+                account.pubkey(),
+                account.owner(),
+                account_info,
+                slot,
+                true,
+            );
         }
         measure_all.stop();
 
@@ -141,17 +174,22 @@ impl AccountsUpdateNotifierImpl {
 
     fn notify_plugins_of_account_update(
         &self,
+        plugin_manager: &GeyserPluginManager,
+        pubkey: &Pubkey,
+        owner: &Pubkey,
         account: ReplicaAccountInfoV3,
         slot: Slot,
         is_startup: bool,
     ) {
         let mut measure2 = Measure::start("geyser-plugin-notify_plugins_of_account_update");
-        let plugin_manager = self.plugin_manager.read().unwrap();
 
         if plugin_manager.plugins.is_empty() {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
+            if !plugin.account_update_matches(pubkey, owner, account.data) {
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
             match plugin.update_account(
                 ReplicaAccountInfoVersions::V0_0_3(&account),
@@ -0,0 +1,103 @@
+/// Module responsible for notifying plugins of bank-level events
+use {
+    crate::geyser_plugin_manager::GeyserPluginManager,
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaBankEvent, ReplicaBankEventVersions,
+    },
+    log::*,
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_runtime::bank_event_notifier_interface::{BankEvent, BankEventNotifier},
+    std::sync::{Arc, RwLock},
+};
+
+#[derive(Debug)]
+pub(crate) struct BankEventNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl BankEventNotifier for BankEventNotifierImpl {
+    fn notify_bank_event(&self, event: &BankEvent) {
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_bank_event");
+
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let replica_event = Self::build_replica_bank_event(event);
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.bank_event_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_bank_event(ReplicaBankEventVersions::V0_0_1(&replica_event)) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify bank event {:?}, error: ({}) to plugin {}",
+                        event,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified bank event {:?} to plugin {}",
+                        event,
+                        plugin.name()
+                    );
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_bank_event-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
+}
+
+impl BankEventNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+
+    fn build_replica_bank_event<'a>(event: &BankEvent<'a>) -> ReplicaBankEvent<'a> {
+        match *event {
+            BankEvent::EpochBoundary {
+                slot,
+                parent_slot,
+                parent_epoch,
+                epoch,
+            } => ReplicaBankEvent::EpochBoundary {
+                slot,
+                parent_slot,
+                parent_epoch,
+                epoch,
+            },
+            BankEvent::PartitionedRewardsDistributed {
+                slot,
+                epoch,
+                partition_index,
+                num_partitions,
+                num_stake_accounts,
+                lamports,
+            } => ReplicaBankEvent::PartitionedRewardsDistributed {
+                slot,
+                epoch,
+                partition_index,
+                num_partitions,
+                num_stake_accounts,
+                lamports,
+            },
+            BankEvent::FeatureActivated { slot, feature_id } => {
+                ReplicaBankEvent::FeatureActivated {
+                    slot,
+                    feature_id: feature_id.as_ref(),
+                }
+            }
+        }
+    }
+}
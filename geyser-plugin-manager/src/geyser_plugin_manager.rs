@@ -1,8 +1,10 @@
 use {
-    agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+    crate::notification_buffer::{BufferedNotification, NotificationBuffer},
+    agave_geyser_plugin_interface::geyser_plugin_interface::{AccountUpdateFilter, GeyserPlugin},
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
+    solana_sdk::pubkey::Pubkey,
     std::{
         ops::{Deref, DerefMut},
         path::Path,
@@ -14,6 +16,7 @@ use {
 pub struct LoadedGeyserPlugin {
     name: String,
     plugin: Box<dyn GeyserPlugin>,
+    account_update_filter: Option<AccountUpdateFilter>,
 }
 
 impl LoadedGeyserPlugin {
@@ -21,12 +24,40 @@ impl LoadedGeyserPlugin {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
             plugin,
+            account_update_filter: None,
         }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Fetch and cache the plugin's account update filter. Must be called
+    /// after `on_load`, since plugins usually build it from their config.
+    pub(crate) fn load_account_update_filter(&mut self) {
+        self.account_update_filter = self.plugin.account_update_filter();
+        if let Some(filter) = &self.account_update_filter {
+            info!(
+                "Plugin {} filters account updates: {} owners, {} pubkeys, {} data size ranges, \
+                 {} memcmps",
+                self.name,
+                filter.owners.len(),
+                filter.pubkeys.len(),
+                filter.data_size_ranges.len(),
+                filter.memcmp.len(),
+            );
+        }
+    }
+
+    /// Check if the plugin is interested in account data and its account update filter, if any,
+    /// matches the account
+    pub fn account_update_matches(&self, pubkey: &Pubkey, owner: &Pubkey, data: &[u8]) -> bool {
+        self.plugin.account_data_notifications_enabled()
+            && self
+                .account_update_filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(pubkey, owner, data))
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
@@ -47,6 +78,8 @@ impl DerefMut for LoadedGeyserPlugin {
 pub struct GeyserPluginManager {
     pub plugins: Vec<LoadedGeyserPlugin>,
     libs: Vec<Library>,
+    /// Recent notifications kept for plugins asking to catch up after a (re)load
+    notification_buffer: Option<NotificationBuffer>,
 }
 
 impl GeyserPluginManager {
@@ -54,9 +87,78 @@ impl GeyserPluginManager {
         GeyserPluginManager {
             plugins: Vec::default(),
             libs: Vec::default(),
+            notification_buffer: None,
+        }
+    }
+
+    /// Create a manager which keeps the last `capacity` account, transaction and slot status
+    /// notifications, to be replayed to plugins returning a slot from `catch_up_from_slot()`.
+    ///
+    /// Account and transaction notifications are filtered before being buffered: only those
+    /// wanted by a plugin loaded at the time are kept. A plugin reloaded with a wider account
+    /// filter, or loaded while no other plugin wanted transactions, cannot replay what was not
+    /// buffered.
+    pub fn new_with_notification_buffer(capacity: usize) -> Self {
+        GeyserPluginManager {
+            notification_buffer: (capacity > 0).then(|| NotificationBuffer::new(capacity)),
+            ..Self::new()
         }
     }
 
+    /// Buffer a notification for later catch-up replay. `notification` is only
+    /// evaluated if buffering is enabled. Callers only buffer notifications that a
+    /// loaded plugin is interested in.
+    pub(crate) fn buffer_notification(&self, notification: impl FnOnce() -> BufferedNotification) {
+        if let Some(notification_buffer) = &self.notification_buffer {
+            notification_buffer.push(notification());
+        }
+    }
+
+    /// Replay buffered notifications to a newly loaded plugin that asks for it.
+    /// This runs under the manager's write lock, so no live notification can be
+    /// interleaved with or overtake the replayed ones.
+    fn catch_up_plugin(&self, plugin: &LoadedGeyserPlugin) {
+        let Some(from_slot) = plugin.catch_up_from_slot() else {
+            return;
+        };
+        let Some(notification_buffer) = &self.notification_buffer else {
+            warn!(
+                "Plugin {} asked to catch up from slot {from_slot}, but notification buffering \
+                 is disabled",
+                plugin.name()
+            );
+            return;
+        };
+
+        if let Some(oldest_slot) = notification_buffer.oldest_slot() {
+            if from_slot < oldest_slot {
+                warn!(
+                    "Plugin {} asked to catch up from slot {from_slot}, but the oldest buffered \
+                     notification is for slot {oldest_slot}",
+                    plugin.name()
+                );
+            }
+        }
+
+        let mut replayed = 0;
+        notification_buffer.for_each_from_slot(from_slot, |notification| {
+            if let Err(err) = notification.notify(plugin) {
+                error!(
+                    "Failed to replay notification for slot {}, error: {} to plugin {}",
+                    notification.slot(),
+                    err,
+                    plugin.name()
+                );
+            }
+            replayed += 1;
+        });
+        info!(
+            "Replayed {replayed} of {} buffered notifications to plugin {} from slot {from_slot}",
+            notification_buffer.len(),
+            plugin.name()
+        );
+    }
+
     /// Unload all plugins and loaded plugin libraries, making sure to fire
     /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
     pub fn unload(&mut self) {
@@ -80,6 +182,13 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin whose account update filter matches the account
+    pub fn account_update_matches(&self, pubkey: &Pubkey, owner: &Pubkey, data: &[u8]) -> bool {
+        self.plugins
+            .iter()
+            .any(|plugin| plugin.account_update_matches(pubkey, owner, data))
+    }
+
     /// Check if there is any plugin interested in transaction data
     pub fn transaction_notifications_enabled(&self) -> bool {
         for plugin in &self.plugins {
@@ -100,6 +209,16 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in bank events
+    pub fn bank_event_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.bank_event_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
@@ -156,6 +275,8 @@ impl GeyserPluginManager {
                 ),
                 data: None,
             })?;
+        new_plugin.load_account_update_filter();
+        self.catch_up_plugin(&new_plugin);
         let name = new_plugin.name().to_string();
         self.plugins.push(new_plugin);
         self.libs.push(new_lib);
@@ -237,6 +358,8 @@ impl GeyserPluginManager {
         match new_plugin.on_load(new_parsed_config_file, true) {
             // On success, push plugin and library
             Ok(()) => {
+                new_plugin.load_account_update_filter();
+                self.catch_up_plugin(&new_plugin);
                 self.plugins.push(new_plugin);
                 self.libs.push(new_lib);
             }
@@ -439,12 +562,18 @@ pub(crate) fn load_plugin_from_config(
 #[cfg(test)]
 mod tests {
     use {
-        crate::geyser_plugin_manager::{
-            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+        crate::{
+            geyser_plugin_manager::{
+                GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            },
+            notification_buffer::BufferedNotification,
+        },
+        agave_geyser_plugin_interface::geyser_plugin_interface::{
+            AccountDataMemcmp, AccountUpdateFilter, GeyserPlugin, Result, SlotStatus,
         },
-        agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
         libloading::Library,
-        std::sync::{Arc, RwLock},
+        solana_sdk::{clock::Slot, pubkey::Pubkey},
+        std::sync::{Arc, Mutex, RwLock},
     };
 
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
@@ -484,6 +613,147 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Copy, Debug)]
+    struct FilteredTestPlugin {
+        owner: Pubkey,
+    }
+
+    impl GeyserPlugin for FilteredTestPlugin {
+        fn name(&self) -> &'static str {
+            "filtered_dummy"
+        }
+
+        fn account_update_filter(&self) -> Option<AccountUpdateFilter> {
+            Some(AccountUpdateFilter {
+                owners: [self.owner].into(),
+                data_size_ranges: vec![0..10],
+                memcmp: vec![AccountDataMemcmp {
+                    offset: 1,
+                    bytes: vec![2, 3],
+                }],
+                ..AccountUpdateFilter::default()
+            })
+        }
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct NoAccountsTestPlugin;
+
+    impl GeyserPlugin for NoAccountsTestPlugin {
+        fn name(&self) -> &'static str {
+            "no_accounts_dummy"
+        }
+
+        fn account_data_notifications_enabled(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_account_update_filter() {
+        let mut plugin_manager = GeyserPluginManager::new();
+        let owner = Pubkey::new_unique();
+        let other_owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+
+        // Nothing matches without plugins, or with plugins not interested in account data
+        assert!(!plugin_manager.account_update_matches(&pubkey, &owner, &[1, 2, 3]));
+        let (mut plugin, lib, config) =
+            dummy_plugin_and_library(NoAccountsTestPlugin, DUMMY_CONFIG);
+        plugin.on_load(config, false).unwrap();
+        plugin.load_account_update_filter();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+        assert!(!plugin_manager.account_update_matches(&pubkey, &owner, &[1, 2, 3]));
+
+        let (mut plugin, lib, config) =
+            dummy_plugin_and_library(FilteredTestPlugin { owner }, DUMMY_CONFIG);
+        plugin.on_load(config, false).unwrap();
+        plugin.load_account_update_filter();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+
+        assert!(plugin_manager.account_update_matches(&pubkey, &owner, &[1, 2, 3]));
+        // Wrong owner
+        assert!(!plugin_manager.account_update_matches(&pubkey, &other_owner, &[1, 2, 3]));
+        // Data size out of range
+        assert!(!plugin_manager.account_update_matches(
+            &pubkey,
+            &owner,
+            &[1, 2, 3, 0, 0, 0, 0, 0, 0, 0]
+        ));
+        // Memcmp mismatch, and memcmp past the end of the data
+        assert!(!plugin_manager.account_update_matches(&pubkey, &owner, &[1, 2, 4]));
+        assert!(!plugin_manager.account_update_matches(&pubkey, &owner, &[1, 2]));
+
+        // A plugin without a filter receives every account update
+        let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        plugin.on_load(config, false).unwrap();
+        plugin.load_account_update_filter();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+        assert!(plugin_manager.account_update_matches(&pubkey, &other_owner, &[]));
+        assert!(!plugin_manager.plugins[1].account_update_matches(&pubkey, &other_owner, &[]));
+    }
+
+    #[derive(Debug, Default)]
+    struct CatchUpTestPlugin {
+        slots: Arc<Mutex<Vec<Slot>>>,
+    }
+
+    impl GeyserPlugin for CatchUpTestPlugin {
+        fn name(&self) -> &'static str {
+            "catch_up_dummy"
+        }
+
+        fn catch_up_from_slot(&self) -> Option<Slot> {
+            Some(3)
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<u64>,
+            _status: SlotStatus,
+        ) -> Result<()> {
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_catch_up_plugin() {
+        let plugin_manager = GeyserPluginManager::new_with_notification_buffer(4);
+        for slot in 0..6 {
+            plugin_manager.buffer_notification(|| BufferedNotification::SlotStatus {
+                slot,
+                parent: slot.checked_sub(1),
+                status: SlotStatus::Rooted,
+            });
+        }
+
+        let slots = Arc::<Mutex<Vec<Slot>>>::default();
+        let (plugin, _lib, _config) = dummy_plugin_and_library(
+            CatchUpTestPlugin {
+                slots: slots.clone(),
+            },
+            DUMMY_CONFIG,
+        );
+        plugin_manager.catch_up_plugin(&plugin);
+        assert_eq!(*slots.lock().unwrap(), vec![3, 4, 5]);
+
+        // Without a buffer there is nothing to replay
+        let slots = Arc::<Mutex<Vec<Slot>>>::default();
+        let (plugin, _lib, _config) = dummy_plugin_and_library(
+            CatchUpTestPlugin {
+                slots: slots.clone(),
+            },
+            DUMMY_CONFIG,
+        );
+        GeyserPluginManager::new().catch_up_plugin(&plugin);
+        assert!(slots.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_geyser_reload() {
         // Initialize empty manager
@@ -1,6 +1,7 @@
 use {
     crate::{
         accounts_update_notifier::AccountsUpdateNotifierImpl,
+        bank_event_notifier::BankEventNotifierImpl,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
         entry_notifier::EntryNotifierImpl,
@@ -17,6 +18,7 @@ use {
         optimistically_confirmed_bank_tracker::SlotNotification,
         transaction_notifier_interface::TransactionNotifierArc,
     },
+    solana_runtime::bank_event_notifier_interface::BankEventNotifierArc,
     std::{
         path::{Path, PathBuf},
         sync::{
@@ -37,6 +39,7 @@ pub struct GeyserPluginService {
     transaction_notifier: Option<TransactionNotifierArc>,
     entry_notifier: Option<EntryNotifierArc>,
     block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+    bank_event_notifier: Option<BankEventNotifierArc>,
 }
 
 impl GeyserPluginService {
@@ -56,9 +59,13 @@ impl GeyserPluginService {
         confirmed_bank_receiver: Receiver<SlotNotification>,
         geyser_plugin_config_files: &[PathBuf],
     ) -> Result<Self, GeyserPluginServiceError> {
-        Self::new_with_receiver(confirmed_bank_receiver, geyser_plugin_config_files, None)
+        Self::new_with_receiver(confirmed_bank_receiver, geyser_plugin_config_files, None, 0)
     }
 
+    /// Like `new`, additionally serving plugin manager requests from the admin rpc and
+    /// buffering up to `replay_buffer_size` recent notifications for plugins which ask
+    /// to catch up after being (re)loaded. Only the notification kinds enabled by the
+    /// plugins loaded at startup are buffered.
     pub fn new_with_receiver(
         confirmed_bank_receiver: Receiver<SlotNotification>,
         geyser_plugin_config_files: &[PathBuf],
@@ -66,12 +73,14 @@ impl GeyserPluginService {
             Receiver<GeyserPluginManagerRequest>,
             Arc<AtomicBool>,
         )>,
+        replay_buffer_size: usize,
     ) -> Result<Self, GeyserPluginServiceError> {
         info!(
             "Starting GeyserPluginService from config files: {:?}",
             geyser_plugin_config_files
         );
-        let mut plugin_manager = GeyserPluginManager::new();
+        let mut plugin_manager =
+            GeyserPluginManager::new_with_notification_buffer(replay_buffer_size);
 
         for geyser_plugin_config_file in geyser_plugin_config_files {
             Self::load_plugin(&mut plugin_manager, geyser_plugin_config_file)?;
@@ -81,6 +90,7 @@ impl GeyserPluginService {
             plugin_manager.account_data_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
+        let bank_event_notifications_enabled = plugin_manager.bank_event_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
@@ -107,6 +117,14 @@ impl GeyserPluginService {
             None
         };
 
+        let bank_event_notifier: Option<BankEventNotifierArc> = if bank_event_notifications_enabled
+        {
+            let bank_event_notifier = BankEventNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(bank_event_notifier))
+        } else {
+            None
+        };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierArc>,
@@ -143,6 +161,7 @@ impl GeyserPluginService {
             transaction_notifier,
             entry_notifier,
             block_metadata_notifier,
+            bank_event_notifier,
         })
     }
 
@@ -172,6 +191,10 @@ impl GeyserPluginService {
         self.block_metadata_notifier.clone()
     }
 
+    pub fn get_bank_event_notifier(&self) -> Option<BankEventNotifierArc> {
+        self.bank_event_notifier.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
@@ -1,9 +1,11 @@
 pub mod accounts_update_notifier;
+pub mod bank_event_notifier;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
+mod notification_buffer;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
@@ -0,0 +1,254 @@
+/// Module holding a bounded buffer of recent notifications, which can be replayed to a
+/// plugin that asks to catch up after being loaded or reloaded
+use {
+    crate::{
+        geyser_plugin_manager::LoadedGeyserPlugin, transaction_notifier::TransactionNotifierImpl,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaTransactionInfoVersions, Result,
+        SlotStatus,
+    },
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::TransactionStatusMeta,
+    std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex,
+        },
+    },
+};
+
+#[derive(Debug)]
+pub(crate) enum BufferedNotification {
+    /// Account update during transaction processing. The transaction which caused the
+    /// update is not retained, so replayed account updates have no `txn`.
+    Account {
+        slot: Slot,
+        pubkey: Pubkey,
+        account: AccountSharedData,
+        write_version: u64,
+    },
+    Transaction {
+        slot: Slot,
+        index: usize,
+        signature: Signature,
+        transaction_status_meta: TransactionStatusMeta,
+        transaction: SanitizedTransaction,
+    },
+    SlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+}
+
+impl BufferedNotification {
+    pub(crate) fn slot(&self) -> Slot {
+        match self {
+            Self::Account { slot, .. }
+            | Self::Transaction { slot, .. }
+            | Self::SlotStatus { slot, .. } => *slot,
+        }
+    }
+
+    /// Send this notification to `plugin`, subject to the same checks as live notifications.
+    pub(crate) fn notify(&self, plugin: &LoadedGeyserPlugin) -> Result<()> {
+        match self {
+            Self::Account {
+                slot,
+                pubkey,
+                account,
+                write_version,
+            } => {
+                if !plugin.account_update_matches(pubkey, account.owner(), account.data()) {
+                    return Ok(());
+                }
+                let account_info = ReplicaAccountInfoV3 {
+                    pubkey: pubkey.as_ref(),
+                    lamports: account.lamports(),
+                    owner: account.owner().as_ref(),
+                    executable: account.executable(),
+                    rent_epoch: account.rent_epoch(),
+                    data: account.data(),
+                    write_version: *write_version,
+                    txn: None,
+                };
+                plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(&account_info),
+                    *slot,
+                    false,
+                )
+            }
+            Self::Transaction {
+                slot,
+                index,
+                signature,
+                transaction_status_meta,
+                transaction,
+            } => {
+                if !plugin.transaction_notifications_enabled() {
+                    return Ok(());
+                }
+                let transaction_info = TransactionNotifierImpl::build_replica_transaction_info(
+                    *index,
+                    signature,
+                    transaction_status_meta,
+                    transaction,
+                );
+                plugin.notify_transaction(
+                    ReplicaTransactionInfoVersions::V0_0_2(&transaction_info),
+                    *slot,
+                )
+            }
+            Self::SlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, *status),
+        }
+    }
+}
+
+/// Shards spread notifiers over locks, so concurrent notifications rarely wait on each other
+const MAX_SHARDS: usize = 16;
+
+/// Keeps about the most recent `capacity` notifications, dropping the oldest ones first.
+/// Notifications are assigned to shards round-robin, and numbered to restore their order on
+/// replay.
+#[derive(Debug)]
+pub(crate) struct NotificationBuffer {
+    next_sequence: AtomicU64,
+    shard_capacity: usize,
+    shards: Vec<Mutex<VecDeque<(u64, BufferedNotification)>>>,
+}
+
+impl NotificationBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let num_shards = capacity.min(MAX_SHARDS);
+        Self {
+            next_sequence: AtomicU64::default(),
+            shard_capacity: capacity.checked_div(num_shards).unwrap_or_default(),
+            shards: (0..num_shards).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    pub(crate) fn push(&self, notification: BufferedNotification) {
+        if self.shards.is_empty() {
+            return;
+        }
+        let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        let mut shard = self.shards[sequence as usize % self.shards.len()]
+            .lock()
+            .unwrap();
+        if shard.len() == self.shard_capacity {
+            shard.pop_front();
+        }
+        shard.push_back((sequence, notification));
+    }
+
+    /// The lowest slot with a buffered notification
+    pub(crate) fn oldest_slot(&self) -> Option<Slot> {
+        self.shards
+            .iter()
+            .filter_map(|shard| {
+                shard
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(_, notification)| notification.slot())
+                    .min()
+            })
+            .min()
+    }
+
+    /// Call `f` with the buffered notifications for `from_slot` and later, oldest first.
+    /// Notifications pushed meanwhile wait until it returns.
+    pub(crate) fn for_each_from_slot(
+        &self,
+        from_slot: Slot,
+        mut f: impl FnMut(&BufferedNotification),
+    ) {
+        let shards: Vec<_> = self
+            .shards
+            .iter()
+            .map(|shard| shard.lock().unwrap())
+            .collect();
+        let mut notifications: Vec<_> = shards
+            .iter()
+            .flat_map(|shard| shard.iter())
+            .filter(|(_, notification)| notification.slot() >= from_slot)
+            .collect();
+        notifications.sort_unstable_by_key(|(sequence, _)| *sequence);
+        for (_, notification) in notifications {
+            f(notification);
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_status(slot: Slot) -> BufferedNotification {
+        BufferedNotification::SlotStatus {
+            slot,
+            parent: slot.checked_sub(1),
+            status: SlotStatus::Processed,
+        }
+    }
+
+    fn slots_from(buffer: &NotificationBuffer, from_slot: Slot) -> Vec<Slot> {
+        let mut slots = vec![];
+        buffer.for_each_from_slot(from_slot, |notification| slots.push(notification.slot()));
+        slots
+    }
+
+    #[test]
+    fn test_notification_buffer_is_bounded() {
+        let buffer = NotificationBuffer::new(3);
+        assert_eq!(buffer.oldest_slot(), None);
+
+        for slot in 0..5 {
+            buffer.push(slot_status(slot));
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.oldest_slot(), Some(2));
+        assert_eq!(slots_from(&buffer, 3), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_notification_buffer_sharded() {
+        let buffer = NotificationBuffer::new(4 * MAX_SHARDS);
+        for slot in 0..(8 * MAX_SHARDS as Slot) {
+            buffer.push(slot_status(slot));
+        }
+        assert_eq!(buffer.len(), 4 * MAX_SHARDS);
+        assert_eq!(buffer.oldest_slot(), Some(4 * MAX_SHARDS as Slot));
+        // Replayed in the order they were pushed, across shards
+        assert_eq!(
+            slots_from(&buffer, 0),
+            (4 * MAX_SHARDS as Slot..8 * MAX_SHARDS as Slot).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_notification_buffer_disabled() {
+        let buffer = NotificationBuffer::new(0);
+        buffer.push(slot_status(1));
+        assert_eq!(buffer.len(), 0);
+    }
+}
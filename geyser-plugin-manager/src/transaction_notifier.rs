@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of transactions
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
     },
@@ -39,10 +41,16 @@ impl TransactionNotifier for TransactionNotifierImpl {
         );
 
         let plugin_manager = self.plugin_manager.read().unwrap();
-
-        if plugin_manager.plugins.is_empty() {
+        if !plugin_manager.transaction_notifications_enabled() {
             return;
         }
+        plugin_manager.buffer_notification(|| BufferedNotification::Transaction {
+            slot,
+            index,
+            signature: *signature,
+            transaction_status_meta: transaction_status_meta.clone(),
+            transaction: transaction.clone(),
+        });
 
         for plugin in plugin_manager.plugins.iter() {
             if !plugin.transaction_notifications_enabled() {
@@ -82,7 +90,7 @@ impl TransactionNotifierImpl {
         Self { plugin_manager }
     }
 
-    fn build_replica_transaction_info<'a>(
+    pub(crate) fn build_replica_transaction_info<'a>(
         index: usize,
         signature: &'a Signature,
         transaction_status_meta: &'a TransactionStatusMeta,
@@ -50,6 +50,7 @@ async fn upload(
     let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
+    let bigtable = Arc::new(bigtable);
 
     let config = ConfirmedBlockUploadConfig {
         force_reupload,
@@ -6,6 +6,7 @@ use {
         ledger_path::canonicalize_ledger_path,
         ledger_utils::{get_program_ids, get_shred_storage_type},
         output::{output_ledger, output_slot, SlotBounds, SlotInfo},
+        parquet_export::export_parquet,
     },
     chrono::{DateTime, Utc},
     clap::{
@@ -15,7 +16,10 @@ use {
     log::*,
     regex::Regex,
     serde_json::json,
-    solana_clap_utils::{hidden_unless_forced, input_validators::is_slot},
+    solana_clap_utils::{
+        hidden_unless_forced,
+        input_validators::{is_parsable, is_slot},
+    },
     solana_cli_output::OutputFormat,
     solana_ledger::{
         ancestor_iterator::AncestorIterator,
@@ -347,6 +351,37 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
             .about("Print all the duplicate slots in the ledger")
             .settings(&hidden)
             .arg(&starting_slot_arg),
+        SubCommand::with_name("export-parquet")
+            .about(
+                "Export rooted blocks, transactions, instructions and token balance changes to \
+                 Parquet files",
+            )
+            .settings(&hidden)
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg)
+            .arg(
+                Arg::with_name("output_dir")
+                    .long("output-dir")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .required(true)
+                    .help(
+                        "Directory to write the Parquet files to, in one subdirectory per table: \
+                         blocks, transactions, instructions and token_balance_changes",
+                    ),
+            )
+            .arg(
+                Arg::with_name("slots_per_file")
+                    .long("slots-per-file")
+                    .value_name("NUM")
+                    .takes_value(true)
+                    .default_value("10000")
+                    .validator(is_parsable::<u64>)
+                    .help(
+                        "Number of slots covered by each Parquet file. Files start at multiples \
+                         of this number",
+                    ),
+            ),
         SubCommand::with_name("json")
             .about("Print the ledger in JSON format")
             .settings(&hidden)
@@ -717,6 +752,26 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 println!("{slot}");
             }
         }
+        ("export-parquet", Some(arg_matches)) => {
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let output_dir = PathBuf::from(value_t_or_exit!(arg_matches, "output_dir", String));
+            let slots_per_file = value_t_or_exit!(arg_matches, "slots_per_file", u64);
+            if slots_per_file == 0 {
+                return Err(LedgerToolError::BadArgument(
+                    "--slots-per-file must be greater than zero".to_string(),
+                ));
+            }
+            export_parquet(
+                &blockstore,
+                starting_slot,
+                ending_slot,
+                &output_dir,
+                slots_per_file,
+            )?;
+        }
         ("json", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
@@ -34,6 +34,7 @@ use {
         },
         bank_forks::BankForks,
         prioritization_fee_cache::PrioritizationFeeCache,
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_config::SnapshotConfig,
         snapshot_hash::StartingSnapshotHashes,
         snapshot_utils::{self, clean_orphaned_account_snapshot_dirs},
@@ -140,14 +141,21 @@ pub fn load_and_process_ledger(
             snapshot_archive_path.unwrap_or_else(|| blockstore.ledger_path().to_path_buf());
         let incremental_snapshot_archives_dir =
             incremental_snapshot_archive_path.unwrap_or_else(|| full_snapshot_archives_dir.clone());
+        let max_snapshot_slot = process_options.max_snapshot_slot.unwrap_or(Slot::MAX);
         if let Some(full_snapshot_slot) =
-            snapshot_utils::get_highest_full_snapshot_archive_slot(&full_snapshot_archives_dir)
+            snapshot_utils::get_highest_full_snapshot_archive_info_at_or_below(
+                &full_snapshot_archives_dir,
+                max_snapshot_slot,
+            )
+            .map(|info| info.slot())
         {
             let incremental_snapshot_slot =
-                snapshot_utils::get_highest_incremental_snapshot_archive_slot(
+                snapshot_utils::get_highest_incremental_snapshot_archive_info_at_or_below(
                     &incremental_snapshot_archives_dir,
                     full_snapshot_slot,
+                    max_snapshot_slot,
                 )
+                .map(|info| info.slot())
                 .unwrap_or_default();
             starting_slot = std::cmp::max(full_snapshot_slot, incremental_snapshot_slot);
         }
@@ -10,12 +10,14 @@ use {
             output_account, AccountsOutputConfig, AccountsOutputMode, AccountsOutputStreamer,
         },
         program::*,
+        replay_banking_trace::{replay_banking_trace, SCHEDULERS},
+        replay_transaction::replay_transaction,
     },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
         AppSettings, Arg, ArgMatches, SubCommand,
     },
-    dashmap::DashMap,
+    dashmap::{DashMap, DashSet},
     log::*,
     serde::Serialize,
     solana_account_decoder::UiAccountEncoding,
@@ -50,9 +52,9 @@ use {
         bank_forks::BankForks,
         snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_bank_utils,
-        snapshot_minimizer::SnapshotMinimizer,
+        snapshot_minimizer::{AccountSelection, SnapshotMinimizer},
         snapshot_utils::{
-            ArchiveFormat, SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION,
+            ArchiveFormat, IndexedSnapshotArchive, SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION,
             SUPPORTED_ARCHIVE_COMPRESSION,
         },
     },
@@ -69,6 +71,7 @@ use {
         rent::Rent,
         reserved_account_keys::ReservedAccountKeys,
         shred_version::compute_shred_version,
+        signature::Signature,
         stake::{self, state::StakeStateV2},
         system_program,
         transaction::{MessageHash, SanitizedTransaction, SimpleAddressLoader},
@@ -102,7 +105,10 @@ mod error;
 mod ledger_path;
 mod ledger_utils;
 mod output;
+mod parquet_export;
 mod program;
+mod replay_banking_trace;
+mod replay_transaction;
 
 fn parse_encoding_format(matches: &ArgMatches<'_>) -> UiAccountEncoding {
     match matches.value_of("encoding") {
@@ -510,7 +516,8 @@ fn compute_slot_cost(
     Ok(())
 }
 
-/// Finds the accounts needed to replay slots `snapshot_slot` to `ending_slot`.
+/// Finds the accounts needed to replay slots `snapshot_slot` to `ending_slot`, if any, and the
+/// accounts selected by `account_selection`.
 /// Removes all other accounts from accounts_db, and updates the accounts hash
 /// and capitalization. This is used by the --minimize option in create-snapshot
 /// Returns true if the minimized snapshot may be incomplete.
@@ -518,16 +525,45 @@ fn minimize_bank_for_snapshot(
     blockstore: &Blockstore,
     bank: &Bank,
     snapshot_slot: Slot,
-    ending_slot: Slot,
+    ending_slot: Option<Slot>,
+    account_selection: &AccountSelection,
 ) -> bool {
-    let ((transaction_account_set, possibly_incomplete), transaction_accounts_measure) = measure!(
-        blockstore.get_accounts_used_in_range(bank, snapshot_slot, ending_slot),
-        "get transaction accounts"
-    );
-    let total_accounts_len = transaction_account_set.len();
-    info!("Added {total_accounts_len} accounts from transactions. {transaction_accounts_measure}");
+    let (account_set, possibly_incomplete) = if let Some(ending_slot) = ending_slot {
+        let ((transaction_account_set, possibly_incomplete), transaction_accounts_measure) = measure!(
+            blockstore.get_accounts_used_in_range(bank, snapshot_slot, ending_slot),
+            "get transaction accounts"
+        );
+        let total_accounts_len = transaction_account_set.len();
+        info!(
+            "Added {total_accounts_len} accounts from transactions. {transaction_accounts_measure}"
+        );
+        (transaction_account_set, possibly_incomplete)
+    } else {
+        (DashSet::new(), false)
+    };
+
+    if !account_selection.is_empty() {
+        let (selected_account_set, selected_accounts_measure) =
+            measure!(account_selection.resolve(bank), "get selected accounts");
+        let selected_account_set = selected_account_set.unwrap_or_else(|err| {
+            eprintln!("Unable to resolve the selected accounts: {err}");
+            exit(1);
+        });
+        info!(
+            "Added {} selected accounts. {selected_accounts_measure}",
+            selected_account_set.len()
+        );
+        selected_account_set.into_iter().for_each(|pubkey| {
+            account_set.insert(pubkey);
+        });
+    }
 
-    SnapshotMinimizer::minimize(bank, snapshot_slot, ending_slot, transaction_account_set);
+    SnapshotMinimizer::minimize(
+        bank,
+        snapshot_slot,
+        ending_slot.unwrap_or(snapshot_slot),
+        account_set,
+    );
     possibly_incomplete
 }
 
@@ -1328,10 +1364,11 @@ fn main() {
                         .help(
                             "Create a minimized snapshot instead of a full snapshot. This \
                              snapshot will only include information needed to replay the ledger \
-                             from the snapshot slot to the ending slot.",
+                             from the snapshot slot to the ending slot, and the accounts selected \
+                             with --minimized-include-account, --minimized-include-owner and \
+                             --minimized-include-pdas-of.",
                         )
-                        .conflicts_with("incremental")
-                        .requires("ending_slot"),
+                        .conflicts_with("incremental"),
                 )
                 .arg(
                     Arg::with_name("ending_slot")
@@ -1340,6 +1377,42 @@ fn main() {
                         .value_name("ENDING_SLOT")
                         .help("Ending slot for minimized snapshot creation"),
                 )
+                .arg(
+                    Arg::with_name("minimized_include_account")
+                        .long("minimized-include-account")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .requires("minimized")
+                        .help("Keep this account in the minimized snapshot"),
+                )
+                .arg(
+                    Arg::with_name("minimized_include_owner")
+                        .long("minimized-include-owner")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("PROGRAM_ID")
+                        .validator(is_pubkey)
+                        .requires("minimized")
+                        .help("Keep all accounts owned by this program in the minimized snapshot"),
+                )
+                .arg(
+                    Arg::with_name("minimized_include_pdas_of")
+                        .long("minimized-include-pdas-of")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("PROGRAM_ID")
+                        .validator(is_pubkey)
+                        .requires("minimized")
+                        .help(
+                            "Keep the program derived accounts of this program in the minimized \
+                             snapshot, and all accounts whose address they store",
+                        ),
+                )
                 .arg(
                     Arg::with_name("snapshot_archive_format")
                         .long("snapshot-archive-format")
@@ -1419,6 +1492,190 @@ fn main() {
                         .help("Limit output to accounts owned by the provided program pubkey"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("accounts-at-slot")
+                .about(
+                    "Print the contents of accounts as of a past slot, rebuilt from the nearest \
+                     snapshot at or below that slot plus ledger replay",
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .index(1)
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Slot at which to read the accounts"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .takes_value(true)
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .required(true)
+                        .help("Account to print, may be specified multiple times"),
+                )
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&hard_forks_arg)
+                .arg(&log_messages_bytes_limit_arg)
+                .arg(&accounts_data_encoding_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(
+                    Arg::with_name("no_account_data")
+                        .long("no-account-data")
+                        .takes_value(false)
+                        .help("Do not print account data when printing account contents."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("snapshot-accounts")
+                .about(
+                    "Print the contents of accounts read directly from a zstd-indexed snapshot \
+                     archive, without unpacking it",
+                )
+                .arg(
+                    Arg::with_name("snapshot_archive")
+                        .index(1)
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the indexed snapshot archive"),
+                )
+                .arg(
+                    Arg::with_name("account")
+                        .long("account")
+                        .takes_value(true)
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .required(true)
+                        .help("Account to print, may be specified multiple times"),
+                )
+                .arg(&accounts_data_encoding_arg)
+                .arg(
+                    Arg::with_name("no_account_data")
+                        .long("no-account-data")
+                        .takes_value(false)
+                        .help("Do not print account data when printing account contents."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("replay-transaction")
+                .about(
+                    "Re-execute a single transaction against the state it originally observed \
+                     and print its logs, instruction trace, compute units and account diffs",
+                )
+                .arg(
+                    Arg::with_name("signature")
+                        .index(1)
+                        .value_name("SIGNATURE")
+                        .validator(is_parsable::<Signature>)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Signature of the transaction to replay"),
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .long("slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help(
+                            "Slot containing the transaction. Required unless transaction \
+                             statuses were recorded in the ledger",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("program_override")
+                        .long("program-override")
+                        .value_names(&["PROGRAM_ID", "PROGRAM.SO"])
+                        .takes_value(true)
+                        .number_of_values(2)
+                        .multiple(true)
+                        .help(
+                            "Replace the ELF of a deployed program with a local build before \
+                             executing the transaction, may be specified multiple times",
+                        ),
+                )
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&hard_forks_arg)
+                .arg(&log_messages_bytes_limit_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(&max_genesis_archive_unpacked_size_arg),
+        )
+        .subcommand(
+            SubCommand::with_name("replay-banking-trace")
+                .about(
+                    "Feed the packets of a banking trace into a transaction scheduler on top of \
+                     a bank loaded from the ledger, and report which transactions land, block \
+                     fill, fee revenue and lock conflicts for each leader block",
+                )
+                .arg(
+                    Arg::with_name("banking_trace")
+                        .index(1)
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Banking trace directory, as recorded by the validator"),
+                )
+                .arg(
+                    Arg::with_name("parent_slot")
+                        .long("parent-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help(
+                            "Slot to produce the first block on top of [default: the parent \
+                             of the first leader slot recorded in the trace]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("scheduler")
+                        .long("scheduler")
+                        .value_name("SCHEDULER")
+                        .takes_value(true)
+                        .possible_values(SCHEDULERS)
+                        .default_value(SCHEDULERS[0])
+                        .help("Transaction scheduler to replay the trace with"),
+                )
+                .arg(
+                    Arg::with_name("num_workers")
+                        .long("num-workers")
+                        .value_name("NUMBER")
+                        .takes_value(true)
+                        .validator(is_parsable::<usize>)
+                        .default_value("4")
+                        .help("Number of consume workers the scheduler distributes batches to"),
+                )
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&hard_forks_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(&max_genesis_archive_unpacked_size_arg),
+        )
         .subcommand(
             SubCommand::with_name("capitalization")
                 .about("Print capitalization (aka, total supply) while checksumming it")
@@ -1527,6 +1784,32 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("snapshot-accounts", Some(arg_matches)) => {
+            let archive_path =
+                PathBuf::from(value_t_or_exit!(arg_matches, "snapshot_archive", String));
+            let archive = IndexedSnapshotArchive::open(&archive_path).unwrap_or_else(|err| {
+                eprintln!("Failed to open {}: {err}", archive_path.display());
+                exit(1);
+            });
+            let print_account_data = !arg_matches.is_present("no_account_data");
+            let print_encoding_format = parse_encoding_format(arg_matches);
+            for pubkey in pubkeys_of(arg_matches, "account").unwrap() {
+                match archive.get_account(&pubkey) {
+                    Ok(Some(account)) => output_account(
+                        &pubkey,
+                        &account,
+                        None,
+                        print_account_data,
+                        print_encoding_format,
+                    ),
+                    Ok(None) => println!("{pubkey}: not found"),
+                    Err(err) => {
+                        eprintln!("Failed to read account {pubkey}: {err}");
+                        exit(1);
+                    }
+                }
+            }
+        }
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -1982,7 +2265,7 @@ fn main() {
                     }
                     process_options.halt_at_slot = Some(snapshot_slot);
 
-                    let ending_slot = if is_minimized {
+                    let ending_slot = if is_minimized && arg_matches.is_present("ending_slot") {
                         let ending_slot = value_t_or_exit!(arg_matches, "ending_slot", Slot);
                         if ending_slot <= snapshot_slot {
                             eprintln!(
@@ -1996,6 +2279,31 @@ fn main() {
                     } else {
                         None
                     };
+                    let minimized_account_selection = AccountSelection {
+                        accounts: pubkeys_of(arg_matches, "minimized_include_account")
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect(),
+                        owners: pubkeys_of(arg_matches, "minimized_include_owner")
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect(),
+                        pda_owners: pubkeys_of(arg_matches, "minimized_include_pdas_of")
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect(),
+                    };
+                    if is_minimized
+                        && ending_slot.is_none()
+                        && minimized_account_selection.is_empty()
+                    {
+                        eprintln!(
+                            "Error: a minimized snapshot requires --ending-slot, or accounts \
+                             selected with --minimized-include-account, --minimized-include-owner \
+                             or --minimized-include-pdas-of"
+                        );
+                        exit(1);
+                    }
 
                     let enable_capitalization_change =
                         arg_matches.is_present("enable_capitalization_change");
@@ -2286,7 +2594,8 @@ fn main() {
                             &blockstore,
                             &bank,
                             snapshot_slot,
-                            ending_slot.unwrap(),
+                            ending_slot,
+                            &minimized_account_selection,
                         )
                     } else {
                         false
@@ -2367,10 +2676,9 @@ fn main() {
                             full_snapshot_archive_info.path().display(),
                         );
 
-                        if is_minimized {
+                        if let Some(ending_slot) = ending_slot {
                             let starting_epoch = bank.epoch_schedule().get_epoch(snapshot_slot);
-                            let ending_epoch =
-                                bank.epoch_schedule().get_epoch(ending_slot.unwrap());
+                            let ending_epoch = bank.epoch_schedule().get_epoch(ending_slot);
                             if starting_epoch != ending_epoch {
                                 warn!(
                                     "Minimized snapshot range crosses epoch boundary ({} to \
@@ -2452,6 +2760,72 @@ fn main() {
                     );
                     info!("{scan_time}");
                 }
+                ("accounts-at-slot", Some(arg_matches)) => {
+                    let slot = value_t_or_exit!(arg_matches, "slot", Slot);
+                    let mut process_options = parse_process_options(&ledger_path, arg_matches);
+                    process_options.halt_at_slot = Some(slot);
+                    process_options.max_snapshot_slot = Some(slot);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    );
+                    let (bank_forks, _) = load_and_process_ledger_or_exit(
+                        arg_matches,
+                        &genesis_config,
+                        Arc::new(blockstore),
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    );
+                    let Some(bank) = bank_forks.read().unwrap().get(slot) else {
+                        eprintln!("Unable to reach slot {slot} by replaying the ledger");
+                        exit(1);
+                    };
+
+                    let pubkeys = pubkeys_of(arg_matches, "account").unwrap();
+                    let config = AccountsOutputConfig {
+                        mode: AccountsOutputMode::Individual(pubkeys),
+                        include_sysvars: true,
+                        include_account_contents: true,
+                        include_account_data: !arg_matches.is_present("no_account_data"),
+                        account_data_encoding: parse_encoding_format(arg_matches),
+                    };
+                    let output_format =
+                        OutputFormat::from_matches(arg_matches, "output_format", false);
+
+                    AccountsOutputStreamer::new(bank, output_format, config)
+                        .output()
+                        .unwrap_or_else(|err| {
+                            eprintln!("Error while outputting accounts: {err}");
+                            exit(1);
+                        });
+                }
+                ("replay-transaction", Some(arg_matches)) => {
+                    replay_transaction(
+                        &ledger_path,
+                        arg_matches,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to replay transaction: {err}");
+                        exit(1);
+                    });
+                }
+                ("replay-banking-trace", Some(arg_matches)) => {
+                    replay_banking_trace(
+                        &ledger_path,
+                        arg_matches,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to replay banking trace: {err}");
+                        exit(1);
+                    });
+                }
                 ("capitalization", Some(arg_matches)) => {
                     let process_options = parse_process_options(&ledger_path, arg_matches);
                     let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
@@ -0,0 +1,673 @@
+//! Export of rooted blocks to Parquet files, for the `blockstore export-parquet` subcommand
+//!
+//! Each table is written to its own directory of the output directory, partitioned into files
+//! named after the range of slots they cover, e.g. `transactions/000000010000-000000019999.parquet`
+
+use {
+    crate::error::Result,
+    arrow_array::{
+        builder::{
+            BinaryBuilder, BooleanBuilder, Int64Builder, ListBuilder, StringBuilder, UInt32Builder,
+            UInt64Builder, UInt8Builder,
+        },
+        ArrayRef, RecordBatch,
+    },
+    arrow_schema::ArrowError,
+    log::*,
+    parquet::{
+        arrow::ArrowWriter,
+        basic::{Compression, ZstdLevel},
+        file::properties::WriterProperties,
+    },
+    solana_ledger::{blockstore::Blockstore, rooted_slot_iterator::RootedSlotIterator},
+    solana_sdk::{
+        clock::Slot, instruction::CompiledInstruction, message::AccountKeys, pubkey::Pubkey,
+        transaction::TransactionVersion, vote,
+    },
+    solana_transaction_status::{
+        extract_memos::extract_and_fmt_memos, parse_instruction::parse, TransactionTokenBalance,
+        VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
+    },
+    std::{
+        collections::BTreeMap,
+        fs::{self, File},
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+// Rows are handed to the Parquet writers in batches of about this many rows per table
+const ROWS_PER_BATCH: usize = 64 * 1024;
+const MAX_ROWS_PER_ROW_GROUP: usize = 1024 * 1024;
+
+trait Table: Default {
+    /// Name of the table, and of its directory in the output directory
+    const NAME: &'static str;
+
+    fn num_rows(&self) -> usize;
+
+    /// Take the rows appended since the last call
+    fn finish(&mut self) -> std::result::Result<RecordBatch, ArrowError>;
+}
+
+fn column(
+    name: &str,
+    array: impl arrow_array::Array + 'static,
+    nullable: bool,
+) -> (&str, ArrayRef, bool) {
+    (name, Arc::new(array), nullable)
+}
+
+#[derive(Default)]
+struct BlocksTable {
+    slot: UInt64Builder,
+    parent_slot: UInt64Builder,
+    blockhash: StringBuilder,
+    previous_blockhash: StringBuilder,
+    block_time: Int64Builder,
+    block_height: UInt64Builder,
+    transaction_count: UInt64Builder,
+    total_fees: UInt64Builder,
+}
+
+impl Table for BlocksTable {
+    const NAME: &'static str = "blocks";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> std::result::Result<RecordBatch, ArrowError> {
+        RecordBatch::try_from_iter_with_nullable([
+            column("slot", self.slot.finish(), false),
+            column("parent_slot", self.parent_slot.finish(), false),
+            column("blockhash", self.blockhash.finish(), false),
+            column(
+                "previous_blockhash",
+                self.previous_blockhash.finish(),
+                false,
+            ),
+            column("block_time", self.block_time.finish(), true),
+            column("block_height", self.block_height.finish(), true),
+            column("transaction_count", self.transaction_count.finish(), false),
+            column("total_fees", self.total_fees.finish(), false),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct TransactionsTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    version: StringBuilder,
+    fee_payer: StringBuilder,
+    recent_blockhash: StringBuilder,
+    account_keys: ListBuilder<StringBuilder>,
+    is_vote: BooleanBuilder,
+    success: BooleanBuilder,
+    err: StringBuilder,
+    fee: UInt64Builder,
+    compute_units_consumed: UInt64Builder,
+    memo: StringBuilder,
+}
+
+impl Table for TransactionsTable {
+    const NAME: &'static str = "transactions";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> std::result::Result<RecordBatch, ArrowError> {
+        RecordBatch::try_from_iter_with_nullable([
+            column("slot", self.slot.finish(), false),
+            column("transaction_index", self.transaction_index.finish(), false),
+            column("signature", self.signature.finish(), false),
+            column("version", self.version.finish(), false),
+            column("fee_payer", self.fee_payer.finish(), false),
+            column("recent_blockhash", self.recent_blockhash.finish(), false),
+            column("account_keys", self.account_keys.finish(), false),
+            column("is_vote", self.is_vote.finish(), false),
+            column("success", self.success.finish(), false),
+            column("err", self.err.finish(), true),
+            column("fee", self.fee.finish(), false),
+            column(
+                "compute_units_consumed",
+                self.compute_units_consumed.finish(),
+                true,
+            ),
+            column("memo", self.memo.finish(), true),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct InstructionsTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    instruction_index: UInt32Builder,
+    inner_instruction_index: UInt32Builder,
+    stack_height: UInt32Builder,
+    program_id: StringBuilder,
+    accounts: ListBuilder<StringBuilder>,
+    data: BinaryBuilder,
+    program: StringBuilder,
+    parsed: StringBuilder,
+}
+
+impl Table for InstructionsTable {
+    const NAME: &'static str = "instructions";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> std::result::Result<RecordBatch, ArrowError> {
+        RecordBatch::try_from_iter_with_nullable([
+            column("slot", self.slot.finish(), false),
+            column("transaction_index", self.transaction_index.finish(), false),
+            column("signature", self.signature.finish(), false),
+            column("instruction_index", self.instruction_index.finish(), false),
+            column(
+                "inner_instruction_index",
+                self.inner_instruction_index.finish(),
+                true,
+            ),
+            column("stack_height", self.stack_height.finish(), true),
+            column("program_id", self.program_id.finish(), false),
+            column("accounts", self.accounts.finish(), false),
+            column("data", self.data.finish(), false),
+            column("program", self.program.finish(), true),
+            column("parsed", self.parsed.finish(), true),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct TokenBalanceChangesTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    account: StringBuilder,
+    mint: StringBuilder,
+    owner: StringBuilder,
+    program_id: StringBuilder,
+    decimals: UInt8Builder,
+    pre_amount: UInt64Builder,
+    post_amount: UInt64Builder,
+}
+
+impl Table for TokenBalanceChangesTable {
+    const NAME: &'static str = "token_balance_changes";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> std::result::Result<RecordBatch, ArrowError> {
+        RecordBatch::try_from_iter_with_nullable([
+            column("slot", self.slot.finish(), false),
+            column("transaction_index", self.transaction_index.finish(), false),
+            column("signature", self.signature.finish(), false),
+            column("account", self.account.finish(), false),
+            column("mint", self.mint.finish(), false),
+            column("owner", self.owner.finish(), true),
+            column("program_id", self.program_id.finish(), true),
+            column("decimals", self.decimals.finish(), false),
+            column("pre_amount", self.pre_amount.finish(), false),
+            column("post_amount", self.post_amount.finish(), false),
+        ])
+    }
+}
+
+/// Rows of a table, and the writer of the file of the current partition once rows were written
+#[derive(Default)]
+struct TableWriter<T> {
+    table: T,
+    writer: Option<ArrowWriter<File>>,
+}
+
+impl<T: Table> TableWriter<T> {
+    fn flush(&mut self, partition_dir: &Path, file_name: &str) -> Result<()> {
+        if self.table.num_rows() == 0 {
+            return Ok(());
+        }
+        let batch = self.table.finish()?;
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let dir = partition_dir.join(T::NAME);
+                fs::create_dir_all(&dir)?;
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                    .set_max_row_group_size(MAX_ROWS_PER_ROW_GROUP)
+                    .build();
+                self.writer.insert(ArrowWriter::try_new(
+                    File::create(dir.join(file_name))?,
+                    batch.schema(),
+                    Some(properties),
+                )?)
+            }
+        };
+        writer.write(&batch)?;
+        Ok(())
+    }
+
+    fn close(&mut self, partition_dir: &Path, file_name: &str) -> Result<()> {
+        self.flush(partition_dir, file_name)?;
+        if let Some(writer) = self.writer.take() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes blocks to the Parquet files of their slot range
+pub struct ParquetExporter {
+    output_dir: PathBuf,
+    slots_per_file: u64,
+    // First slot of the partition being written
+    partition: Option<Slot>,
+    blocks: TableWriter<BlocksTable>,
+    transactions: TableWriter<TransactionsTable>,
+    instructions: TableWriter<InstructionsTable>,
+    token_balance_changes: TableWriter<TokenBalanceChangesTable>,
+}
+
+impl ParquetExporter {
+    pub fn new(output_dir: &Path, slots_per_file: u64) -> Self {
+        assert!(slots_per_file > 0);
+        Self {
+            output_dir: output_dir.to_path_buf(),
+            slots_per_file,
+            partition: None,
+            blocks: TableWriter::default(),
+            transactions: TableWriter::default(),
+            instructions: TableWriter::default(),
+            token_balance_changes: TableWriter::default(),
+        }
+    }
+
+    /// Blocks must be appended in increasing slot order
+    pub fn append_block(&mut self, slot: Slot, block: &VersionedConfirmedBlock) -> Result<()> {
+        let partition = slot - slot % self.slots_per_file;
+        if self.partition != Some(partition) {
+            self.close_partition()?;
+            self.partition = Some(partition);
+        }
+
+        let blocks = &mut self.blocks.table;
+        blocks.slot.append_value(slot);
+        blocks.parent_slot.append_value(block.parent_slot);
+        blocks.blockhash.append_value(&block.blockhash);
+        blocks
+            .previous_blockhash
+            .append_value(&block.previous_blockhash);
+        blocks.block_time.append_option(block.block_time);
+        blocks.block_height.append_option(block.block_height);
+        blocks
+            .transaction_count
+            .append_value(block.transactions.len() as u64);
+        blocks.total_fees.append_value(
+            block
+                .transactions
+                .iter()
+                .map(|tx_with_meta| tx_with_meta.meta.fee)
+                .sum(),
+        );
+
+        for (index, tx_with_meta) in block.transactions.iter().enumerate() {
+            self.append_transaction(slot, index as u32, tx_with_meta);
+        }
+
+        if self.transactions.table.num_rows() >= ROWS_PER_BATCH
+            || self.instructions.table.num_rows() >= ROWS_PER_BATCH
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn append_transaction(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        tx_with_meta: &VersionedTransactionWithStatusMeta,
+    ) {
+        let VersionedTransactionWithStatusMeta { transaction, meta } = tx_with_meta;
+        let signature = transaction.signatures[0].to_string();
+        let account_keys = tx_with_meta.account_keys();
+        let instructions = transaction.message.instructions();
+
+        let transactions = &mut self.transactions.table;
+        transactions.slot.append_value(slot);
+        transactions
+            .transaction_index
+            .append_value(transaction_index);
+        transactions.signature.append_value(&signature);
+        transactions
+            .version
+            .append_value(match transaction.version() {
+                TransactionVersion::Legacy(_) => "legacy".to_string(),
+                TransactionVersion::Number(version) => version.to_string(),
+            });
+        transactions
+            .fee_payer
+            .append_value(account_keys[0].to_string());
+        transactions
+            .recent_blockhash
+            .append_value(transaction.message.recent_blockhash().to_string());
+        for account_key in account_keys.iter() {
+            transactions
+                .account_keys
+                .values()
+                .append_value(account_key.to_string());
+        }
+        transactions.account_keys.append(true);
+        transactions
+            .is_vote
+            .append_value(instructions.iter().any(|instruction| {
+                account_keys
+                    .get(instruction.program_id_index as usize)
+                    .is_some_and(vote::program::check_id)
+            }));
+        transactions.success.append_value(meta.status.is_ok());
+        transactions
+            .err
+            .append_option(meta.status.as_ref().err().map(ToString::to_string));
+        transactions.fee.append_value(meta.fee);
+        transactions
+            .compute_units_consumed
+            .append_option(meta.compute_units_consumed);
+        transactions
+            .memo
+            .append_option(extract_and_fmt_memos(tx_with_meta));
+
+        for (instruction_index, instruction) in instructions.iter().enumerate() {
+            self.instructions.table.append(
+                slot,
+                transaction_index,
+                &signature,
+                &account_keys,
+                (instruction_index as u32, None),
+                instruction,
+                None,
+            );
+        }
+        for inner_instructions in meta.inner_instructions.iter().flatten() {
+            for (inner_instruction_index, inner_instruction) in
+                inner_instructions.instructions.iter().enumerate()
+            {
+                self.instructions.table.append(
+                    slot,
+                    transaction_index,
+                    &signature,
+                    &account_keys,
+                    (
+                        u32::from(inner_instructions.index),
+                        Some(inner_instruction_index as u32),
+                    ),
+                    &inner_instruction.instruction,
+                    inner_instruction.stack_height,
+                );
+            }
+        }
+
+        // Token balances are only recorded for accounts holding tokens before or after the
+        // transaction, keep the ones whose amount changed
+        let mut token_balances = BTreeMap::<u8, [Option<&TransactionTokenBalance>; 2]>::new();
+        for (i, balances) in [&meta.pre_token_balances, &meta.post_token_balances]
+            .into_iter()
+            .enumerate()
+        {
+            for balance in balances.iter().flatten() {
+                token_balances.entry(balance.account_index).or_default()[i] = Some(balance);
+            }
+        }
+        let amount = |balance: Option<&TransactionTokenBalance>| {
+            balance
+                .and_then(|balance| balance.ui_token_amount.amount.parse::<u64>().ok())
+                .unwrap_or_default()
+        };
+        let token_balance_changes = &mut self.token_balance_changes.table;
+        for (account_index, [pre, post]) in token_balances {
+            let (pre_amount, post_amount) = (amount(pre), amount(post));
+            let Some(balance) = post.or(pre) else {
+                continue;
+            };
+            if pre_amount == post_amount {
+                continue;
+            }
+            token_balance_changes.slot.append_value(slot);
+            token_balance_changes
+                .transaction_index
+                .append_value(transaction_index);
+            token_balance_changes.signature.append_value(&signature);
+            token_balance_changes.account.append_value(
+                account_keys
+                    .get(account_index as usize)
+                    .copied()
+                    .unwrap_or_default()
+                    .to_string(),
+            );
+            token_balance_changes.mint.append_value(&balance.mint);
+            token_balance_changes
+                .owner
+                .append_option((!balance.owner.is_empty()).then_some(&balance.owner));
+            token_balance_changes
+                .program_id
+                .append_option((!balance.program_id.is_empty()).then_some(&balance.program_id));
+            token_balance_changes
+                .decimals
+                .append_value(balance.ui_token_amount.decimals);
+            token_balance_changes.pre_amount.append_value(pre_amount);
+            token_balance_changes.post_amount.append_value(post_amount);
+        }
+    }
+
+    fn file_name(&self) -> String {
+        let partition = self.partition.unwrap_or_default();
+        format!(
+            "{:012}-{:012}.parquet",
+            partition,
+            partition + self.slots_per_file - 1
+        )
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let file_name = self.file_name();
+        self.blocks.flush(&self.output_dir, &file_name)?;
+        self.transactions.flush(&self.output_dir, &file_name)?;
+        self.instructions.flush(&self.output_dir, &file_name)?;
+        self.token_balance_changes
+            .flush(&self.output_dir, &file_name)
+    }
+
+    fn close_partition(&mut self) -> Result<()> {
+        let file_name = self.file_name();
+        self.blocks.close(&self.output_dir, &file_name)?;
+        self.transactions.close(&self.output_dir, &file_name)?;
+        self.instructions.close(&self.output_dir, &file_name)?;
+        self.token_balance_changes
+            .close(&self.output_dir, &file_name)
+    }
+
+    /// Write out the rows of the last partition
+    pub fn finish(mut self) -> Result<()> {
+        self.close_partition()
+    }
+}
+
+impl InstructionsTable {
+    #[allow(clippy::too_many_arguments)]
+    fn append(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        signature: &str,
+        account_keys: &AccountKeys,
+        (instruction_index, inner_instruction_index): (u32, Option<u32>),
+        instruction: &CompiledInstruction,
+        stack_height: Option<u32>,
+    ) {
+        let program_id = account_keys
+            .get(instruction.program_id_index as usize)
+            .copied()
+            .unwrap_or_default();
+        self.slot.append_value(slot);
+        self.transaction_index.append_value(transaction_index);
+        self.signature.append_value(signature);
+        self.instruction_index.append_value(instruction_index);
+        self.inner_instruction_index
+            .append_option(inner_instruction_index);
+        self.stack_height.append_option(stack_height);
+        self.program_id.append_value(program_id.to_string());
+        for account_index in &instruction.accounts {
+            self.accounts.values().append_value(
+                account_keys
+                    .get(*account_index as usize)
+                    .map(Pubkey::to_string)
+                    .unwrap_or_default(),
+            );
+        }
+        self.accounts.append(true);
+        self.data.append_value(&instruction.data);
+
+        // Reuse the jsonParsed encoding of RPC for the programs it knows about
+        match parse(&program_id, instruction, account_keys, stack_height) {
+            Ok(parsed_instruction) => {
+                self.program.append_value(parsed_instruction.program);
+                self.parsed
+                    .append_value(parsed_instruction.parsed.to_string());
+            }
+            Err(_) => {
+                self.program.append_null();
+                self.parsed.append_null();
+            }
+        }
+    }
+}
+
+/// Export the rooted blocks from `starting_slot` to `ending_slot` to Parquet files in
+/// `output_dir`, in files of `slots_per_file` slots
+pub fn export_parquet(
+    blockstore: &Blockstore,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    output_dir: &Path,
+    slots_per_file: u64,
+) -> Result<()> {
+    let Some(first_root) = blockstore.rooted_slot_iterator(starting_slot)?.next() else {
+        info!("No rooted slots from slot {starting_slot}");
+        return Ok(());
+    };
+
+    let mut exporter = ParquetExporter::new(output_dir, slots_per_file);
+    let mut num_blocks = 0;
+    for (slot, _slot_meta) in RootedSlotIterator::new(first_root, blockstore)?
+        .take_while(|(slot, _slot_meta)| *slot <= ending_slot)
+    {
+        match blockstore.get_rooted_block(slot, false) {
+            Ok(block) => {
+                exporter.append_block(slot, &block)?;
+                num_blocks += 1;
+            }
+            Err(err) => warn!("Skipping slot {slot}: {err}"),
+        }
+    }
+    exporter.finish()?;
+    info!(
+        "Exported {num_blocks} blocks from slot {first_root} to {}",
+        output_dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        parquet::file::reader::{FileReader, SerializedFileReader},
+        solana_sdk::{
+            hash::Hash, signature::Keypair, system_transaction, transaction::VersionedTransaction,
+        },
+        solana_transaction_status::{InnerInstruction, InnerInstructions, TransactionStatusMeta},
+        std::fs::read_dir,
+    };
+
+    fn block(parent_slot: Slot) -> VersionedConfirmedBlock {
+        let payer = Keypair::new();
+        let transaction = VersionedTransaction::from(system_transaction::transfer(
+            &payer,
+            &Pubkey::new_unique(),
+            42,
+            Hash::new_unique(),
+        ));
+        let inner_instruction = InnerInstruction {
+            instruction: transaction.message.instructions()[0].clone(),
+            stack_height: Some(2),
+        };
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction,
+                meta: TransactionStatusMeta {
+                    fee: 5000,
+                    inner_instructions: Some(vec![InnerInstructions {
+                        index: 0,
+                        instructions: vec![inner_instruction],
+                    }]),
+                    ..TransactionStatusMeta::default()
+                },
+            }],
+            rewards: vec![],
+            block_time: None,
+            block_height: Some(parent_slot + 1),
+        }
+    }
+
+    fn num_rows(path: PathBuf) -> i64 {
+        SerializedFileReader::new(File::open(path).unwrap())
+            .unwrap()
+            .metadata()
+            .file_metadata()
+            .num_rows()
+    }
+
+    #[test]
+    fn test_parquet_exporter() {
+        let output_dir = tempfile::TempDir::new().unwrap();
+        let mut exporter = ParquetExporter::new(output_dir.path(), 10);
+        for slot in [3, 5, 12] {
+            exporter.append_block(slot, &block(slot - 1)).unwrap();
+        }
+        exporter.finish().unwrap();
+
+        let blocks_dir = output_dir.path().join("blocks");
+        let mut files = read_dir(&blocks_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                "000000000000-000000000009.parquet",
+                "000000000010-000000000019.parquet",
+            ]
+        );
+        assert_eq!(num_rows(blocks_dir.join(&files[0])), 2);
+        assert_eq!(num_rows(blocks_dir.join(&files[1])), 1);
+        let transactions_dir = output_dir.path().join("transactions");
+        assert_eq!(num_rows(transactions_dir.join(&files[0])), 2);
+        // One top level and one inner instruction per transaction
+        let instructions_dir = output_dir.path().join("instructions");
+        assert_eq!(num_rows(instructions_dir.join(&files[0])), 4);
+        // No token balances were recorded
+        assert!(!output_dir.path().join("token_balance_changes").exists());
+    }
+}
@@ -0,0 +1,221 @@
+use {
+    crate::{args::*, ledger_utils::*},
+    clap::{value_t, value_t_or_exit, ArgMatches},
+    log::*,
+    serde::Serialize,
+    solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_core::{
+        banking_stage::{
+            trace_replay::{BankingTraceReplayer, ReplayedBlock, TraceBlock},
+            transaction_scheduler::scheduler::{PrioGraphSchedulerFactory, SchedulerFactory},
+        },
+        banking_trace::BankingTracer,
+    },
+    solana_ledger::leader_schedule_cache::LeaderScheduleCache,
+    solana_sdk::clock::{Slot, DEFAULT_MS_PER_SLOT},
+    std::{
+        fmt::{self, Display, Formatter},
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    },
+};
+
+pub const SCHEDULERS: &[&str] = &["prio-graph"];
+
+fn scheduler_factory(name: &str) -> Box<dyn SchedulerFactory> {
+    match name {
+        "prio-graph" => Box::new(PrioGraphSchedulerFactory),
+        _ => unreachable!("validated by clap"),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayedBlockOutput {
+    slot: Slot,
+    landed: Vec<String>,
+    num_failed: usize,
+    block_cost: u64,
+    block_cost_limit: u64,
+    executed_units: u64,
+    fees: u64,
+    num_buffered: usize,
+    num_dropped_on_receive: usize,
+    num_dropped_on_compute_budget: usize,
+    num_dropped_on_capacity: usize,
+    num_scheduled: usize,
+    num_unschedulable: usize,
+    num_filtered_out: usize,
+    num_account_in_use: usize,
+    num_cost_limited: usize,
+}
+
+impl From<ReplayedBlock> for ReplayedBlockOutput {
+    fn from(block: ReplayedBlock) -> Self {
+        Self {
+            slot: block.slot,
+            landed: block
+                .landed
+                .iter()
+                .map(|signature| signature.to_string())
+                .collect(),
+            num_failed: block.num_failed,
+            block_cost: block.block_cost,
+            block_cost_limit: block.block_cost_limit,
+            executed_units: block.executed_units,
+            fees: block.fees,
+            num_buffered: block.num_buffered,
+            num_dropped_on_receive: block.num_dropped_on_receive,
+            num_dropped_on_compute_budget: block.num_dropped_on_compute_budget,
+            num_dropped_on_capacity: block.num_dropped_on_capacity,
+            num_scheduled: block.num_scheduled,
+            num_unschedulable: block.num_unschedulable,
+            num_filtered_out: block.num_filtered_out,
+            num_account_in_use: block.num_account_in_use,
+            num_cost_limited: block.num_cost_limited,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayedBankingTrace {
+    scheduler: String,
+    parent_slot: Slot,
+    blocks: Vec<ReplayedBlockOutput>,
+}
+
+impl Display for ReplayedBankingTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Replayed {} blocks on top of slot {} with the {} scheduler",
+            self.blocks.len(),
+            self.parent_slot,
+            self.scheduler
+        )?;
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "Slot {}: {} transactions landed ({} failed), {} fees, block cost {}/{} \
+                 ({:.1}%), {} executed units",
+                block.slot,
+                block.landed.len(),
+                block.num_failed,
+                block.fees,
+                block.block_cost,
+                block.block_cost_limit,
+                100.0 * block.block_cost as f64 / block.block_cost_limit as f64,
+                block.executed_units,
+            )?;
+            writeln!(
+                f,
+                "  buffered: {}, dropped on receive: {}, invalid compute budget: {}, dropped on \
+                 capacity: {}",
+                block.num_buffered,
+                block.num_dropped_on_receive,
+                block.num_dropped_on_compute_budget,
+                block.num_dropped_on_capacity,
+            )?;
+            writeln!(
+                f,
+                "  scheduled: {}, unschedulable (lock conflicts): {}, filtered out: {}, account \
+                 in use: {}, over cost limits: {}",
+                block.num_scheduled,
+                block.num_unschedulable,
+                block.num_filtered_out,
+                block.num_account_in_use,
+                block.num_cost_limited,
+            )?;
+        }
+        let total =
+            |field: fn(&ReplayedBlockOutput) -> u64| -> u64 { self.blocks.iter().map(field).sum() };
+        writeln!(
+            f,
+            "Total: {} transactions landed, {} fees, {} executed units, {} lock conflicts",
+            total(|block| block.landed.len() as u64),
+            total(|block| block.fees),
+            total(|block| block.executed_units),
+            total(|block| (block.num_unschedulable + block.num_account_in_use) as u64),
+        )
+    }
+}
+
+impl QuietDisplay for ReplayedBankingTrace {}
+impl VerboseDisplay for ReplayedBankingTrace {}
+
+pub fn replay_banking_trace(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    snapshot_archive_path: Option<PathBuf>,
+    incremental_snapshot_archive_path: Option<PathBuf>,
+) -> Result<(), String> {
+    let trace_path = PathBuf::from(value_t_or_exit!(arg_matches, "banking_trace", String));
+    let scheduler = value_t_or_exit!(arg_matches, "scheduler", String);
+    let num_workers = value_t_or_exit!(arg_matches, "num_workers", usize);
+
+    let events = BankingTracer::read_events(&trace_path).map_err(|err| {
+        format!(
+            "Failed to read banking trace {}: {err}",
+            trace_path.display()
+        )
+    })?;
+    let blocks = TraceBlock::from_trace_events(events, Duration::from_millis(DEFAULT_MS_PER_SLOT));
+    let parent_slot = match value_t!(arg_matches, "parent_slot", Slot) {
+        Ok(parent_slot) => parent_slot,
+        Err(_) => blocks
+            .first()
+            .and_then(|block| block.parent)
+            .map(|(parent_slot, _blockhash)| parent_slot)
+            .ok_or_else(|| {
+                "The banking trace has no record of leader slots, use --parent-slot to specify \
+                 the slot to replay it on top of"
+                    .to_string()
+            })?,
+    };
+    info!(
+        "Replaying {} blocks from {} on top of slot {parent_slot}",
+        blocks.len(),
+        trace_path.display()
+    );
+
+    let mut process_options = parse_process_options(ledger_path, arg_matches);
+    process_options.halt_at_slot = Some(parent_slot);
+    process_options.max_snapshot_slot = Some(parent_slot);
+    let blockstore = open_blockstore(ledger_path, arg_matches, get_access_type(&process_options));
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    let (bank_forks, _) = load_and_process_ledger_or_exit(
+        arg_matches,
+        &genesis_config,
+        Arc::new(blockstore),
+        process_options,
+        snapshot_archive_path,
+        incremental_snapshot_archive_path,
+    );
+    let parent_bank = bank_forks
+        .read()
+        .unwrap()
+        .get(parent_slot)
+        .ok_or_else(|| format!("Unable to reach slot {parent_slot} by replaying the ledger"))?;
+    let collector_id = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(parent_slot + 1, Some(&parent_bank))
+        .unwrap_or(*parent_bank.collector_id());
+
+    let mut replayer = BankingTraceReplayer::new(&*scheduler_factory(&scheduler), num_workers);
+    let replayed_blocks = replayer
+        .replay(&bank_forks, parent_slot, &collector_id, &blocks)
+        .map_err(|err| format!("Scheduler failed: {err}"))?;
+
+    let output = ReplayedBankingTrace {
+        scheduler,
+        parent_slot,
+        blocks: replayed_blocks
+            .into_iter()
+            .map(ReplayedBlockOutput::from)
+            .collect(),
+    };
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    println!("{}", output_format.formatted_string(&output));
+    Ok(())
+}
@@ -0,0 +1,548 @@
+use {
+    crate::{args::*, ledger_utils::*},
+    clap::{value_t, value_t_or_exit, values_t, ArgMatches},
+    log::*,
+    serde::Serialize,
+    solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_rpc_client_api::response::RpcAccountDiff,
+    solana_runtime::bank::{Bank, TransactionSimulationResult},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        account_utils::StateMut,
+        bpf_loader, bpf_loader_deprecated,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Slot,
+        instruction::CompiledInstruction,
+        loader_v4::{self, LoaderV4State},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{SanitizedTransaction, VersionedTransaction},
+    },
+    std::{
+        fmt::{self, Display, Formatter},
+        fs, iter,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayedTransaction {
+    signature: String,
+    slot: Slot,
+    err: Option<String>,
+    units_consumed: u64,
+    instruction_units_consumed: Vec<u64>,
+    logs: Vec<String>,
+    instruction_trace: Vec<TracedInstruction>,
+    return_data: Option<(String, Vec<u8>)>,
+    account_diffs: Vec<RpcAccountDiff>,
+}
+
+/// One entry of the instruction trace, top-level instructions have a stack height of 1
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TracedInstruction {
+    stack_height: u8,
+    program_id: String,
+    accounts: Vec<String>,
+    data: String,
+}
+
+impl TracedInstruction {
+    fn new(instruction: &CompiledInstruction, stack_height: u8, account_keys: &[Pubkey]) -> Self {
+        Self {
+            stack_height,
+            program_id: account_keys[usize::from(instruction.program_id_index)].to_string(),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|index| account_keys[usize::from(*index)].to_string())
+                .collect(),
+            data: bs58::encode(&instruction.data).into_string(),
+        }
+    }
+}
+
+impl Display for ReplayedTransaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transaction {} in slot {}", self.signature, self.slot)?;
+        match &self.err {
+            Some(err) => writeln!(f, "Result: {err}")?,
+            None => writeln!(f, "Result: Ok")?,
+        }
+        writeln!(f, "Compute units consumed: {}", self.units_consumed)?;
+        for (index, units) in self.instruction_units_consumed.iter().enumerate() {
+            writeln!(f, "  Instruction {index}: {units}")?;
+        }
+        writeln!(f, "Instruction trace:")?;
+        for instruction in &self.instruction_trace {
+            let indent = "  ".repeat(usize::from(instruction.stack_height));
+            writeln!(
+                f,
+                "{indent}{} accounts: [{}] data: {}",
+                instruction.program_id,
+                instruction.accounts.join(", "),
+                instruction.data,
+            )?;
+        }
+        if let Some((program_id, data)) = &self.return_data {
+            writeln!(f, "Return data from {program_id}: {data:?}")?;
+        }
+        writeln!(f, "Logs:")?;
+        for log in &self.logs {
+            writeln!(f, "  {log}")?;
+        }
+        writeln!(f, "Account diffs:")?;
+        for diff in &self.account_diffs {
+            writeln!(f, "  {}", diff.pubkey)?;
+            if diff.pre_lamports != diff.post_lamports {
+                writeln!(
+                    f,
+                    "    lamports: {} -> {}",
+                    diff.pre_lamports, diff.post_lamports
+                )?;
+            }
+            if diff.pre_owner != diff.post_owner {
+                writeln!(f, "    owner: {} -> {}", diff.pre_owner, diff.post_owner)?;
+            }
+            if diff.pre_data_len != diff.post_data_len {
+                writeln!(
+                    f,
+                    "    data length: {} -> {}",
+                    diff.pre_data_len, diff.post_data_len
+                )?;
+            }
+            for change in &diff.data_changes {
+                writeln!(
+                    f,
+                    "    data at offset {}: {} -> {} (base64)",
+                    change.offset, change.pre, change.post
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl QuietDisplay for ReplayedTransaction {}
+impl VerboseDisplay for ReplayedTransaction {}
+
+/// Finds the slot and the position of `signature` within the entries of that slot
+fn find_transaction(
+    blockstore: &Blockstore,
+    signature: &Signature,
+    slot: Option<Slot>,
+) -> Result<(Slot, Vec<Vec<VersionedTransaction>>, (usize, usize)), String> {
+    let slot = match slot {
+        Some(slot) => slot,
+        None => blockstore
+            .get_rooted_transaction_status(*signature)
+            .map_err(|err| format!("Failed to read transaction status: {err}"))?
+            .map(|(slot, _)| slot)
+            .ok_or_else(|| {
+                format!(
+                    "Transaction {signature} has no status in the blockstore, use --slot to \
+                     specify the slot that contains it"
+                )
+            })?,
+    };
+    let entries = blockstore
+        .get_slot_entries(slot, 0)
+        .map_err(|err| format!("Failed to load entries for slot {slot}: {err}"))?;
+    let entry_transactions: Vec<_> = entries
+        .into_iter()
+        .map(|entry| entry.transactions)
+        .filter(|transactions| !transactions.is_empty())
+        .collect();
+    let position = entry_transactions
+        .iter()
+        .enumerate()
+        .find_map(|(entry_index, transactions)| {
+            transactions
+                .iter()
+                .position(|transaction| transaction.signatures.first() == Some(signature))
+                .map(|transaction_index| (entry_index, transaction_index))
+        })
+        .ok_or_else(|| format!("Transaction {signature} not found in slot {slot}"))?;
+    Ok((slot, entry_transactions, position))
+}
+
+/// Swaps the ELF of an already deployed program for the one in `elf`, keeping any loader
+/// metadata in place, and evicts the program from the cache so the next load picks it up
+fn override_program(bank: &Bank, program_id: &Pubkey, elf: &[u8]) -> Result<(), String> {
+    let program_account = bank
+        .get_account(program_id)
+        .ok_or_else(|| format!("Program {program_id} does not exist"))?;
+
+    let replace_data = |address: &Pubkey, mut account: AccountSharedData, offset: usize| {
+        let mut data = account.data()[..offset].to_vec();
+        data.extend_from_slice(elf);
+        account.set_data_from_slice(&data);
+        bank.store_account(address, &account);
+    };
+
+    let owner = *program_account.owner();
+    if bpf_loader_upgradeable::check_id(&owner) {
+        let Ok(UpgradeableLoaderState::Program {
+            programdata_address,
+        }) = program_account.state()
+        else {
+            return Err(format!(
+                "{program_id} is not an upgradeable program account"
+            ));
+        };
+        let programdata_account = bank
+            .get_account(&programdata_address)
+            .ok_or_else(|| format!("Program data {programdata_address} does not exist"))?;
+        replace_data(
+            &programdata_address,
+            programdata_account,
+            UpgradeableLoaderState::size_of_programdata_metadata(),
+        );
+    } else if loader_v4::check_id(&owner) {
+        replace_data(
+            program_id,
+            program_account,
+            LoaderV4State::program_data_offset(),
+        );
+    } else if bpf_loader::check_id(&owner) || bpf_loader_deprecated::check_id(&owner) {
+        replace_data(program_id, program_account, 0);
+    } else {
+        return Err(format!(
+            "{program_id} is owned by {owner}, which is not a program loader"
+        ));
+    }
+
+    bank.get_transaction_processor()
+        .program_cache
+        .write()
+        .unwrap()
+        .remove_programs([*program_id].into_iter());
+    Ok(())
+}
+
+/// Executes the transactions that precede the one at `position` within its slot, bringing `bank`
+/// up to the state that transaction observed when it was executed, and returns it
+fn replay_preceding_transactions(
+    bank: &Bank,
+    mut entry_transactions: Vec<Vec<VersionedTransaction>>,
+    (entry_index, transaction_index): (usize, usize),
+) -> Result<VersionedTransaction, String> {
+    let mut target_entry = entry_transactions.split_off(entry_index).swap_remove(0);
+    let transaction = target_entry.remove(transaction_index);
+    target_entry.truncate(transaction_index);
+    for transactions in entry_transactions
+        .into_iter()
+        .chain(Some(target_entry))
+        .filter(|transactions| !transactions.is_empty())
+    {
+        bank.try_process_entry_transactions(transactions)
+            .map_err(|err| format!("Failed to replay preceding transactions: {err}"))?;
+    }
+    Ok(transaction)
+}
+
+pub fn replay_transaction(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    snapshot_archive_path: Option<PathBuf>,
+    incremental_snapshot_archive_path: Option<PathBuf>,
+) -> Result<(), String> {
+    let signature = value_t_or_exit!(arg_matches, "signature", Signature);
+    let slot = value_t!(arg_matches, "slot", Slot).ok();
+    let program_overrides = values_t!(arg_matches, "program_override", String)
+        .unwrap_or_default()
+        .chunks(2)
+        .map(|pair| {
+            let program_id = pair[0]
+                .parse::<Pubkey>()
+                .map_err(|err| format!("Invalid program id {}: {err}", pair[0]))?;
+            let elf = fs::read(&pair[1])
+                .map_err(|err| format!("Unable to read program file {}: {err}", pair[1]))?;
+            Ok((program_id, elf))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut process_options = parse_process_options(ledger_path, arg_matches);
+    let blockstore = open_blockstore(ledger_path, arg_matches, get_access_type(&process_options));
+    let (slot, entry_transactions, (entry_index, transaction_index)) =
+        find_transaction(&blockstore, &signature, slot)?;
+    let parent_slot = blockstore
+        .meta(slot)
+        .map_err(|err| format!("Failed to read slot meta for {slot}: {err}"))?
+        .and_then(|meta| meta.parent_slot)
+        .ok_or_else(|| format!("Parent of slot {slot} is unknown"))?;
+
+    info!(
+        "Replaying {signature}, transaction {transaction_index} of entry {entry_index} in slot \
+         {slot}"
+    );
+    process_options.halt_at_slot = Some(parent_slot);
+    process_options.max_snapshot_slot = Some(parent_slot);
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    let (bank_forks, _) = load_and_process_ledger_or_exit(
+        arg_matches,
+        &genesis_config,
+        Arc::new(blockstore),
+        process_options,
+        snapshot_archive_path,
+        incremental_snapshot_archive_path,
+    );
+    let parent_bank = bank_forks.read().unwrap().get(parent_slot).ok_or_else(|| {
+        format!("Unable to reach parent slot {parent_slot} by replaying the ledger")
+    })?;
+
+    let leader = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(slot, Some(&parent_bank))
+        .ok_or_else(|| format!("Unable to determine the leader of slot {slot}"))?;
+    let bank = bank_forks
+        .write()
+        .unwrap()
+        .insert(Bank::new_from_parent(parent_bank, &leader, slot))
+        .clone_without_scheduler();
+
+    let transaction =
+        replay_preceding_transactions(&bank, entry_transactions, (entry_index, transaction_index))?;
+
+    for (program_id, elf) in &program_overrides {
+        info!("Overriding program {program_id}");
+        override_program(&bank, program_id, elf)?;
+    }
+
+    let transaction: SanitizedTransaction = bank
+        .fully_verify_transaction(transaction)
+        .map_err(|err| format!("Failed to sanitize {signature}: {err}"))?;
+    let account_keys: Vec<Pubkey> = transaction
+        .message()
+        .account_keys()
+        .iter()
+        .copied()
+        .collect();
+    let pre_accounts: Vec<_> = account_keys
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| transaction.message().is_writable(*index))
+        .map(|(_, pubkey)| (*pubkey, bank.get_account(pubkey).unwrap_or_default()))
+        .collect();
+
+    let TransactionSimulationResult {
+        result,
+        logs,
+        post_simulation_accounts,
+        units_consumed,
+        return_data,
+        inner_instructions,
+        instruction_compute_units,
+    } = bank.simulate_landed_transaction_unchecked(&transaction, true);
+
+    let account_keys = account_keys.as_slice();
+    let mut inner_instructions = inner_instructions.unwrap_or_default().into_iter();
+    let instruction_trace = transaction
+        .message()
+        .instructions()
+        .iter()
+        .flat_map(|instruction| {
+            let inner = inner_instructions.next().unwrap_or_default();
+            iter::once(TracedInstruction::new(instruction, 1, account_keys)).chain(
+                inner.into_iter().map(move |inner| {
+                    TracedInstruction::new(&inner.instruction, inner.stack_height, account_keys)
+                }),
+            )
+        })
+        .collect();
+    let account_diffs = pre_accounts
+        .iter()
+        .filter_map(|(pubkey, pre_account)| {
+            post_simulation_accounts
+                .iter()
+                .find(|(post_pubkey, _)| post_pubkey == pubkey)
+                .map(|(_, post_account)| RpcAccountDiff::new(pubkey, pre_account, post_account))
+        })
+        .collect();
+
+    let output = ReplayedTransaction {
+        signature: signature.to_string(),
+        slot,
+        err: result.err().map(|err| err.to_string()),
+        units_consumed,
+        instruction_units_consumed: instruction_compute_units.unwrap_or_default(),
+        logs,
+        instruction_trace,
+        return_data: return_data
+            .map(|return_data| (return_data.program_id.to_string(), return_data.data)),
+        account_diffs,
+    };
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    println!("{}", output_format.formatted_string(&output));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_entry::entry::next_entry,
+        solana_ledger::{blockstore::entries_to_test_shreds, get_tmp_ledger_path_auto_delete},
+        solana_runtime::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_sdk::{
+            hash::Hash,
+            native_token::LAMPORTS_PER_SOL,
+            signature::{Keypair, Signer},
+            system_transaction,
+        },
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    #[test]
+    fn test_find_transaction() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let keypair = Keypair::new();
+        let transactions: Vec<_> = (0..3)
+            .map(|lamports| {
+                system_transaction::transfer(
+                    &keypair,
+                    &Pubkey::new_unique(),
+                    lamports,
+                    Hash::default(),
+                )
+            })
+            .collect();
+        let signature = transactions[2].signatures[0];
+        let entries = vec![
+            next_entry(&Hash::default(), 1, vec![]),
+            next_entry(&Hash::default(), 1, vec![transactions[0].clone()]),
+            next_entry(&Hash::default(), 1, vec![]),
+            next_entry(&Hash::default(), 1, transactions[1..].to_vec()),
+        ];
+        let shreds = entries_to_test_shreds(&entries, 1, 0, true, 0, true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        // Entries without transactions are skipped
+        let (slot, entry_transactions, position) =
+            find_transaction(&blockstore, &signature, Some(1)).unwrap();
+        assert_eq!(slot, 1);
+        assert_eq!(entry_transactions.len(), 2);
+        assert_eq!(position, (1, 1));
+        assert_eq!(entry_transactions[1][1].signatures[0], signature);
+
+        assert!(find_transaction(&blockstore, &signature, Some(2)).is_err());
+        assert!(find_transaction(&blockstore, &Signature::new_unique(), Some(1)).is_err());
+
+        // Without a slot, the transaction's status locates it
+        assert!(find_transaction(&blockstore, &signature, None).is_err());
+        blockstore
+            .write_transaction_status(
+                1,
+                signature,
+                vec![],
+                vec![],
+                TransactionStatusMeta::default(),
+                2,
+            )
+            .unwrap();
+        blockstore.set_roots([1].iter()).unwrap();
+        let (slot, _, position) = find_transaction(&blockstore, &signature, None).unwrap();
+        assert_eq!(slot, 1);
+        assert_eq!(position, (1, 1));
+    }
+
+    #[test]
+    fn test_replay_preceding_transactions() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10 * LAMPORTS_PER_SOL);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let alice = Keypair::new();
+        let bob = Pubkey::new_unique();
+        let entry_transactions: Vec<Vec<VersionedTransaction>> = vec![
+            vec![system_transaction::transfer(
+                &mint_keypair,
+                &alice.pubkey(),
+                LAMPORTS_PER_SOL,
+                blockhash,
+            )
+            .into()],
+            vec![
+                system_transaction::transfer(&alice, &bob, 1_000_000, blockhash).into(),
+                system_transaction::transfer(&alice, &bob, 2_000_000, blockhash).into(),
+                // Follows the replayed transaction, so is never executed
+                system_transaction::transfer(&alice, &bob, 4_000_000, blockhash).into(),
+            ],
+        ];
+        let expected = entry_transactions[1][1].signatures[0];
+
+        let transaction = replay_preceding_transactions(&bank, entry_transactions, (1, 1)).unwrap();
+        assert_eq!(transaction.signatures[0], expected);
+        assert_eq!(bank.get_balance(&bob), 1_000_000);
+        assert!(bank.get_balance(&alice.pubkey()) > 0);
+    }
+
+    #[test]
+    fn test_override_program() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10 * LAMPORTS_PER_SOL);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let elf = [7; 16];
+
+        // Upgradeable programs keep their program data metadata
+        let program_id = Pubkey::new_unique();
+        let programdata_address = Pubkey::new_unique();
+        let program_account = AccountSharedData::new_data(
+            LAMPORTS_PER_SOL,
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let mut programdata_account = AccountSharedData::new(
+            LAMPORTS_PER_SOL,
+            metadata_len + 4,
+            &bpf_loader_upgradeable::id(),
+        );
+        programdata_account
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(Pubkey::new_unique()),
+            })
+            .unwrap();
+        bank.store_account(&program_id, &program_account);
+        bank.store_account(&programdata_address, &programdata_account);
+
+        override_program(&bank, &program_id, &elf).unwrap();
+        let data = bank
+            .get_account(&programdata_address)
+            .unwrap()
+            .data()
+            .to_vec();
+        assert_eq!(
+            &data[..metadata_len],
+            &programdata_account.data()[..metadata_len]
+        );
+        assert_eq!(&data[metadata_len..], &elf);
+        assert_eq!(bank.get_account(&program_id).unwrap(), program_account);
+
+        // Non-upgradeable programs are replaced entirely
+        let program_id = Pubkey::new_unique();
+        let program_account = AccountSharedData::new(LAMPORTS_PER_SOL, 4, &bpf_loader::id());
+        bank.store_account(&program_id, &program_account);
+        override_program(&bank, &program_id, &elf).unwrap();
+        assert_eq!(bank.get_account(&program_id).unwrap().data(), &elf);
+
+        // Accounts that are not programs are rejected
+        let account_id = Pubkey::new_unique();
+        bank.store_account(
+            &account_id,
+            &AccountSharedData::new(LAMPORTS_PER_SOL, 4, &Pubkey::new_unique()),
+        );
+        assert!(override_program(&bank, &account_id, &elf).is_err());
+        assert!(override_program(&bank, &Pubkey::new_unique(), &elf).is_err());
+    }
+}
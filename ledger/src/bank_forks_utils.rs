@@ -13,6 +13,7 @@ use {
     solana_accounts_db::accounts_update_notifier_interface::AccountsUpdateNotifier,
     solana_runtime::{
         accounts_background_service::AbsRequestSender,
+        bank::Bank,
         bank_forks::BankForks,
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
@@ -22,7 +23,7 @@ use {
         snapshot_hash::{FullSnapshotHash, IncrementalSnapshotHash, StartingSnapshotHashes},
         snapshot_utils,
     },
-    solana_sdk::genesis_config::GenesisConfig,
+    solana_sdk::{clock::Slot, genesis_config::GenesisConfig},
     std::{
         path::PathBuf,
         result,
@@ -60,6 +61,9 @@ pub enum BankForksUtilsError {
 
     #[error("failed to process blockstore from root: {0}")]
     ProcessBlockstoreFromRoot(#[source] BlockstoreProcessorError),
+
+    #[error("slot {0} could not be reached by replaying the blockstore")]
+    SlotNotReplayed(Slot),
 }
 
 pub type LoadResult = result::Result<
@@ -114,6 +118,43 @@ pub fn load(
     Ok((bank_forks, leader_schedule_cache, starting_snapshot_hashes))
 }
 
+/// Rebuild the bank at `slot` from the highest snapshot at or below `slot`, then replay the
+/// blockstore up to and including `slot`
+pub fn load_bank_at_slot(
+    genesis_config: &GenesisConfig,
+    blockstore: &Blockstore,
+    account_paths: Vec<PathBuf>,
+    snapshot_config: Option<&SnapshotConfig>,
+    slot: Slot,
+    process_options: ProcessOptions,
+    exit: Arc<AtomicBool>,
+) -> result::Result<Arc<Bank>, BankForksUtilsError> {
+    let process_options = ProcessOptions {
+        halt_at_slot: Some(slot),
+        max_snapshot_slot: Some(slot),
+        ..process_options
+    };
+    let (bank_forks, ..) = load(
+        genesis_config,
+        blockstore,
+        account_paths,
+        snapshot_config,
+        process_options,
+        None,
+        None,
+        None,
+        None,
+        exit,
+    )?;
+    let bank = bank_forks
+        .read()
+        .unwrap()
+        .get(slot)
+        .filter(|bank| bank.is_frozen())
+        .ok_or(BankForksUtilsError::SlotNotReplayed(slot))?;
+    Ok(bank)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn load_bank_forks(
     genesis_config: &GenesisConfig,
@@ -128,6 +169,7 @@ pub fn load_bank_forks(
 ) -> LoadResult {
     fn get_snapshots_to_load(
         snapshot_config: Option<&SnapshotConfig>,
+        max_snapshot_slot: Slot,
     ) -> Option<(
         FullSnapshotArchiveInfo,
         Option<IncrementalSnapshotArchiveInfo>,
@@ -138,8 +180,9 @@ pub fn load_bank_forks(
         };
 
         let Some(full_snapshot_archive_info) =
-            snapshot_utils::get_highest_full_snapshot_archive_info(
+            snapshot_utils::get_highest_full_snapshot_archive_info_at_or_below(
                 &snapshot_config.full_snapshot_archives_dir,
+                max_snapshot_slot,
             )
         else {
             warn!(
@@ -150,9 +193,10 @@ pub fn load_bank_forks(
         };
 
         let incremental_snapshot_archive_info =
-            snapshot_utils::get_highest_incremental_snapshot_archive_info(
+            snapshot_utils::get_highest_incremental_snapshot_archive_info_at_or_below(
                 &snapshot_config.incremental_snapshot_archives_dir,
                 full_snapshot_archive_info.slot(),
+                max_snapshot_slot,
             );
 
         Some((
@@ -163,7 +207,10 @@ pub fn load_bank_forks(
 
     let (bank_forks, starting_snapshot_hashes) =
         if let Some((full_snapshot_archive_info, incremental_snapshot_archive_info)) =
-            get_snapshots_to_load(snapshot_config)
+            get_snapshots_to_load(
+                snapshot_config,
+                process_options.max_snapshot_slot.unwrap_or(Slot::MAX),
+            )
         {
             // SAFETY: Having snapshots to load ensures a snapshot config
             let snapshot_config = snapshot_config.unwrap();
@@ -245,11 +292,13 @@ fn bank_forks_from_snapshot(
             .unwrap_or(0),
     );
 
+    let max_snapshot_slot = process_options.max_snapshot_slot.unwrap_or(Slot::MAX);
     let fastboot_snapshot = match process_options.use_snapshot_archives_at_startup {
         UseSnapshotArchivesAtStartup::Always => None,
         UseSnapshotArchivesAtStartup::Never => {
             let Some(bank_snapshot) =
                 snapshot_utils::get_highest_loadable_bank_snapshot(snapshot_config)
+                    .filter(|bank_snapshot| bank_snapshot.slot <= max_snapshot_slot)
             else {
                 return Err(BankForksUtilsError::NoBankSnapshotDirectory {
                     flag: use_snapshot_archives_at_startup::cli::LONG_ARG.to_string(),
@@ -272,8 +321,12 @@ fn bank_forks_from_snapshot(
             Some(bank_snapshot)
         }
         UseSnapshotArchivesAtStartup::WhenNewest => {
-            snapshot_utils::get_highest_loadable_bank_snapshot(snapshot_config)
-                .filter(|bank_snapshot| bank_snapshot.slot >= latest_snapshot_archive_slot)
+            snapshot_utils::get_highest_loadable_bank_snapshot(snapshot_config).filter(
+                |bank_snapshot| {
+                    bank_snapshot.slot >= latest_snapshot_archive_slot
+                        && bank_snapshot.slot <= max_snapshot_slot
+                },
+            )
         }
     };
 
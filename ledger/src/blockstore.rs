@@ -280,6 +280,11 @@ impl Blockstore {
         &self.ledger_path
     }
 
+    /// The column options this blockstore was opened with
+    pub fn column_options(&self) -> &LedgerColumnOptions {
+        &self.meta_cf.column_options
+    }
+
     pub fn banking_trace_path(&self) -> PathBuf {
         self.ledger_path.join("banking_trace")
     }
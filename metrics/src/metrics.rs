@@ -1,7 +1,7 @@
 //! The `metrics` module enables sending measurements to an `InfluxDB` instance
 
 use {
-    crate::{counter::CounterPoint, datapoint::DataPoint},
+    crate::{counter::CounterPoint, datapoint::DataPoint, prometheus},
     crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     gethostname::gethostname,
     lazy_static::lazy_static,
@@ -322,10 +322,16 @@ impl MetricsAgent {
                     }
                     MetricsCommand::Submit(point, level) => {
                         log!(level, "{}", point);
+                        if prometheus::is_enabled() {
+                            prometheus::record_point(&point);
+                        }
                         points.push(point);
                     }
                     MetricsCommand::SubmitCounter(counter, _level, bucket) => {
                         debug!("{:?}", counter);
+                        if prometheus::is_enabled() {
+                            prometheus::record_counter(&counter);
+                        }
                         let key = (counter.name, bucket);
                         if let Some(value) = counters.get_mut(&key) {
                             value.count += counter.count;
@@ -0,0 +1,384 @@
+//! The `prometheus` module serves submitted datapoints and counters over HTTP in the Prometheus
+//! text exposition format.
+//!
+//! Recording is disabled until a `PrometheusExporter` is started, and never affects what the
+//! `MetricsAgent` sends to InfluxDB. Points are recorded by the `MetricsAgent` thread as it
+//! receives them, so submitting threads never wait on the exporter. Each numeric field of a
+//! datapoint becomes a gauge named `<namespace>_<datapoint>_<field>` holding the most recently
+//! submitted value, with the datapoint tags as labels. A gauge keeps at most
+//! `MAX_SERIES_PER_GAUGE` label sets; values with new label sets past that are dropped and counted
+//! in `<namespace>_prometheus_dropped_series_total`. Each counter becomes a
+//! `<namespace>_<counter>_total` counter.
+
+use {
+    crate::{counter::CounterPoint, datapoint::DataPoint},
+    lazy_static::lazy_static,
+    log::*,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt::Write as _,
+        io::{self, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_HEADER_LEN: usize = 8192;
+/// Bounds the memory and scrape size of a gauge whose tags take unbounded values
+pub const MAX_SERIES_PER_GAUGE: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub struct PrometheusConfig {
+    /// Prefix of every exported metric name
+    pub namespace: String,
+    /// Renames datapoints and counters before they are exported. Mapping a name to the empty
+    /// string stops it from being exported.
+    pub name_map: HashMap<String, String>,
+    /// Renames datapoint tags before they are exported as labels. Mapping a tag to the empty
+    /// string drops it.
+    pub tag_map: HashMap<String, String>,
+}
+
+impl Default for PrometheusConfig {
+    fn default() -> Self {
+        Self {
+            namespace: "solana".to_string(),
+            name_map: HashMap::new(),
+            tag_map: HashMap::new(),
+        }
+    }
+}
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Default)]
+struct Registry {
+    config: PrometheusConfig,
+    gauges: BTreeMap<String, BTreeMap<Labels, f64>>,
+    counters: BTreeMap<String, f64>,
+    dropped_series: u64,
+}
+
+impl Registry {
+    fn new(config: PrometheusConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    fn metric_name(&self, name: &str) -> Option<String> {
+        let name = self
+            .config
+            .name_map
+            .get(name)
+            .map(String::as_str)
+            .unwrap_or(name);
+        if name.is_empty() {
+            return None;
+        }
+        Some(sanitize_name(&if self.config.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{}", self.config.namespace, name)
+        }))
+    }
+
+    fn record_point(&mut self, point: &DataPoint) {
+        let Some(metric_name) = self.metric_name(point.name) else {
+            return;
+        };
+        let mut labels: Labels = point
+            .tags
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = self
+                    .config
+                    .tag_map
+                    .get(*name)
+                    .map(String::as_str)
+                    .unwrap_or(*name);
+                (!name.is_empty()).then(|| (sanitize_name(name), value.clone()))
+            })
+            .collect();
+        labels.sort();
+
+        for (field, value) in &point.fields {
+            let Some(value) = parse_field_value(value) else {
+                continue;
+            };
+            let series = self
+                .gauges
+                .entry(format!("{metric_name}_{}", sanitize_name(field)))
+                .or_default();
+            if let Some(series_value) = series.get_mut(&labels) {
+                *series_value = value;
+            } else if series.len() < MAX_SERIES_PER_GAUGE {
+                series.insert(labels.clone(), value);
+            } else {
+                self.dropped_series += 1;
+            }
+        }
+    }
+
+    fn record_counter(&mut self, counter: &CounterPoint) {
+        let Some(metric_name) = self.metric_name(counter.name) else {
+            return;
+        };
+        *self
+            .counters
+            .entry(format!("{metric_name}_total"))
+            .or_default() += counter.count as f64;
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, series) in &self.gauges {
+            let _ = writeln!(text, "# TYPE {name} gauge");
+            for (labels, value) in series {
+                let _ = write!(text, "{name}");
+                if !labels.is_empty() {
+                    let labels: Vec<_> = labels
+                        .iter()
+                        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+                        .collect();
+                    let _ = write!(text, "{{{}}}", labels.join(","));
+                }
+                let _ = writeln!(text, " {value}");
+            }
+        }
+        for (name, value) in &self.counters {
+            let _ = writeln!(text, "# TYPE {name} counter");
+            let _ = writeln!(text, "{name} {value}");
+        }
+        if let Some(name) = self
+            .metric_name("prometheus_dropped_series")
+            .filter(|_| self.dropped_series > 0)
+        {
+            let _ = writeln!(text, "# TYPE {name}_total counter");
+            let _ = writeln!(text, "{name}_total {}", self.dropped_series);
+        }
+        text
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::default());
+}
+
+fn enable(config: PrometheusConfig) {
+    *REGISTRY.write().unwrap() = Registry::new(config);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn record_point(point: &DataPoint) {
+    REGISTRY.write().unwrap().record_point(point);
+}
+
+pub(crate) fn record_counter(counter: &CounterPoint) {
+    REGISTRY.write().unwrap().record_counter(counter);
+}
+
+/// Render all recorded metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    REGISTRY.read().unwrap().render()
+}
+
+/// Metric and label names may only contain `[a-zA-Z0-9_]` and must not start with a digit
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Parse a field serialized by `DataPoint`, skipping string fields
+fn parse_field_value(value: &str) -> Option<f64> {
+    match value {
+        "true" => Some(1.),
+        "false" => Some(0.),
+        _ if value.starts_with('"') => None,
+        _ => value.strip_suffix('i').unwrap_or(value).parse::<f64>().ok(),
+    }
+}
+
+/// Serves `/metrics` until `exit` is set
+pub struct PrometheusExporter {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl PrometheusExporter {
+    pub fn new(
+        bind_address: SocketAddr,
+        config: PrometheusConfig,
+        exit: Arc<AtomicBool>,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind_address)?;
+        listener.set_nonblocking(true)?;
+        enable(config);
+        info!("Serving Prometheus metrics on http://{bind_address}/metrics");
+
+        let thread_hdl = Builder::new()
+            .name("solPromExporter".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            if let Err(err) = Self::handle_connection(stream) {
+                                debug!("Prometheus request failed: {}", err);
+                            }
+                        }
+                        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                            sleep(ACCEPT_POLL_INTERVAL)
+                        }
+                        Err(err) => warn!("Prometheus accept failed: {}", err),
+                    }
+                }
+            })?;
+        Ok(Self { thread_hdl })
+    }
+
+    fn handle_connection(mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+        let mut request = vec![];
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let len = stream.read(&mut buf)?;
+            if len == 0 || request.len() + len > MAX_REQUEST_HEADER_LEN {
+                break;
+            }
+            request.extend_from_slice(&buf[..len]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+        let method = request_line.next().unwrap_or_default();
+        let path = request_line.next().unwrap_or_default();
+
+        let (status, body) = match (method, path) {
+            ("GET", "/metrics") => ("200 OK", render()),
+            ("GET", _) => ("404 Not Found", String::new()),
+            _ => ("405 Method Not Allowed", String::new()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut registry = Registry::new(PrometheusConfig {
+            name_map: HashMap::from([
+                ("replay-slot-stats".to_string(), "replay".to_string()),
+                ("dropped".to_string(), String::new()),
+            ]),
+            tag_map: HashMap::from([("host-name".to_string(), String::new())]),
+            ..PrometheusConfig::default()
+        });
+
+        registry.record_point(
+            DataPoint::new("replay-slot-stats")
+                .add_tag("host-name", "a")
+                .add_tag("stage", "\"replay\"")
+                .add_field_i64("slot", 7)
+                .add_field_f64("load.time", 1.5)
+                .add_field_bool("ok", true)
+                .add_field_str("error", "none"),
+        );
+        registry.record_point(DataPoint::new("replay-slot-stats").add_field_i64("slot", 8));
+        registry.record_point(DataPoint::new("dropped").add_field_i64("slot", 9));
+        for _ in 0..2 {
+            registry.record_counter(&CounterPoint {
+                name: "sigverify-recv",
+                count: 5,
+                timestamp: std::time::UNIX_EPOCH,
+            });
+        }
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE solana_replay_load_time gauge\n\
+             solana_replay_load_time{stage=\"\\\"replay\\\"\"} 1.5\n\
+             # TYPE solana_replay_ok gauge\n\
+             solana_replay_ok{stage=\"\\\"replay\\\"\"} 1\n\
+             # TYPE solana_replay_slot gauge\n\
+             solana_replay_slot 8\n\
+             solana_replay_slot{stage=\"\\\"replay\\\"\"} 7\n\
+             # TYPE solana_sigverify_recv_total counter\n\
+             solana_sigverify_recv_total 10\n"
+        );
+    }
+
+    #[test]
+    fn test_max_series_per_gauge() {
+        let mut registry = Registry::new(PrometheusConfig::default());
+        for i in 0..MAX_SERIES_PER_GAUGE + 2 {
+            registry.record_point(
+                DataPoint::new("peer")
+                    .add_tag("address", &i.to_string())
+                    .add_field_i64("rtt", 1),
+            );
+        }
+        registry.record_point(
+            DataPoint::new("peer")
+                .add_tag("address", "0")
+                .add_field_i64("rtt", 2),
+        );
+
+        let series = &registry.gauges["solana_peer_rtt"];
+        assert_eq!(series.len(), MAX_SERIES_PER_GAUGE);
+        assert_eq!(series[&vec![("address".to_string(), "0".to_string())]], 2.);
+        assert!(registry.render().ends_with(
+            "# TYPE solana_prometheus_dropped_series_total counter\n\
+             solana_prometheus_dropped_series_total 2\n"
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(
+            sanitize_name("bank-process_transactions.us"),
+            "bank_process_transactions_us"
+        );
+        assert_eq!(sanitize_name("5m"), "_5m");
+    }
+}
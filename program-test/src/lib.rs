@@ -9,7 +9,8 @@ use {
     chrono_humanize::{Accuracy, HumanTime, Tense},
     log::*,
     solana_accounts_db::{
-        accounts_db::AccountShrinkThreshold, accounts_index::AccountSecondaryIndexes,
+        accounts_db::{AccountShrinkThreshold, AccountsDbConfig},
+        accounts_index::AccountSecondaryIndexes,
         epoch_accounts_hash::EpochAccountsHash,
     },
     solana_banks_client::start_client,
@@ -26,6 +27,9 @@ use {
         commitment::BlockCommitmentCache,
         genesis_utils::{create_genesis_config_with_leader_ex, GenesisConfigInfo},
         runtime_config::RuntimeConfig,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, SnapshotArchiveInfoGetter},
+        snapshot_bank_utils,
+        snapshot_utils::ArchiveFormat,
     },
     solana_sdk::{
         account::{create_account_shared_data_for_test, Account, AccountSharedData},
@@ -44,6 +48,7 @@ use {
         rent::Rent,
         signature::{Keypair, Signer},
         stable_layout::stable_instruction::StableInstruction,
+        system_program,
         sysvar::{Sysvar, SysvarId},
     },
     solana_vote_program::vote_state::{self, VoteState, VoteStateVersions},
@@ -61,6 +66,7 @@ use {
         },
         time::{Duration, Instant},
     },
+    tempfile::TempDir,
     thiserror::Error,
     tokio::task::JoinHandle,
 };
@@ -462,6 +468,7 @@ pub fn read_file<P: AsRef<Path>>(path: P) -> Vec<u8> {
 }
 
 pub struct ProgramTest {
+    snapshot_archive: Option<PathBuf>,
     accounts: Vec<(Pubkey, AccountSharedData)>,
     builtin_programs: Vec<(Pubkey, &'static str, ProgramCacheEntry)>,
     compute_max_units: Option<u64>,
@@ -494,6 +501,7 @@ impl Default for ProgramTest {
             std::env::var("BPF_OUT_DIR").is_ok() || std::env::var("SBF_OUT_DIR").is_ok();
 
         Self {
+            snapshot_archive: None,
             accounts: vec![],
             builtin_programs: vec![],
             compute_max_units: None,
@@ -522,6 +530,32 @@ impl ProgramTest {
         me
     }
 
+    /// Create a `ProgramTest` that starts from the state of a full snapshot archive, such as
+    /// one downloaded from a mainnet-beta validator, instead of a new genesis.
+    ///
+    /// The genesis config of the snapshot's cluster is read from the `genesis.bin` file in the
+    /// directory of the archive, which is where validators keep both. The accounts of an archive
+    /// in the `TarZstdIndexed` format are read from it when they are first loaded, so only the
+    /// stake and vote accounts are read when the test starts. Index scans, such as
+    /// `getProgramAccounts`, only see the accounts stored during the test. Archives in other
+    /// formats are unpacked to a temporary directory and their accounts indexed when the test
+    /// starts, so startup time and disk usage grow with the size of the snapshot.
+    ///
+    /// Programs and accounts added to the test, as well as `ProgramTestContext::set_account`
+    /// and `ProgramTestContext::warp_to_slot`, override the snapshot state as usual. The
+    /// payer is a new account funded with 1,000,000 SOL, and the commonly used SPL programs
+    /// bundled with `ProgramTest` are not added since the snapshot has its own.
+    ///
+    /// To keep tests fast, minimize the snapshot to the accounts the test needs with
+    /// `ledger-tool create-snapshot --minimized`, or with
+    /// `solana_runtime::snapshot_minimizer::SnapshotMinimizer::minimize_to_selection`.
+    pub fn from_snapshot<P: Into<PathBuf>>(snapshot_archive: P) -> Self {
+        Self {
+            snapshot_archive: Some(snapshot_archive.into()),
+            ..Self::default()
+        }
+    }
+
     /// Override default SBF program selection
     pub fn prefer_bpf(&mut self, prefer_bpf: bool) {
         self.prefer_bpf = prefer_bpf;
@@ -746,6 +780,7 @@ impl ProgramTest {
         Arc<RwLock<BlockCommitmentCache>>,
         Hash,
         GenesisConfigInfo,
+        Option<SnapshotDirs>,
     ) {
         {
             use std::sync::Once;
@@ -756,6 +791,45 @@ impl ProgramTest {
             });
         }
 
+        let runtime_config = RuntimeConfig {
+            compute_budget: self.compute_max_units.map(|max_units| ComputeBudget {
+                compute_unit_limit: max_units,
+                ..ComputeBudget::default()
+            }),
+            transaction_account_lock_limit: self.transaction_account_lock_limit,
+            ..RuntimeConfig::default()
+        };
+        let (bank, genesis_config_info, snapshot_dirs) = match self.snapshot_archive.take() {
+            Some(snapshot_archive) => {
+                let (bank, genesis_config_info, snapshot_dirs) =
+                    self.setup_bank_from_snapshot(&snapshot_archive, runtime_config);
+                (bank, genesis_config_info, Some(snapshot_dirs))
+            }
+            None => {
+                let (bank, genesis_config_info) = self.setup_bank_from_genesis(runtime_config);
+                (bank, genesis_config_info, None)
+            }
+        };
+        let slot = bank.slot();
+        let last_blockhash = bank.last_blockhash();
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+
+        (
+            bank_forks,
+            block_commitment_cache,
+            last_blockhash,
+            genesis_config_info,
+            snapshot_dirs,
+        )
+    }
+
+    fn setup_bank_from_genesis(
+        &mut self,
+        runtime_config: RuntimeConfig,
+    ) -> (Bank, GenesisConfigInfo) {
         let rent = Rent::default();
         let fee_rate_governor = FeeRateGovernor {
             // Initialize with a non-zero fee
@@ -808,14 +882,7 @@ impl ProgramTest {
 
         let bank = Bank::new_with_paths(
             &genesis_config,
-            Arc::new(RuntimeConfig {
-                compute_budget: self.compute_max_units.map(|max_units| ComputeBudget {
-                    compute_unit_limit: max_units,
-                    ..ComputeBudget::default()
-                }),
-                transaction_account_lock_limit: self.transaction_account_lock_limit,
-                ..RuntimeConfig::default()
-            }),
+            Arc::new(runtime_config),
             Vec::default(),
             None,
             None,
@@ -833,20 +900,7 @@ impl ProgramTest {
             bank.store_account(program_id, account);
         }
 
-        // User-supplied additional builtins
-        let mut builtin_programs = Vec::new();
-        std::mem::swap(&mut self.builtin_programs, &mut builtin_programs);
-        for (program_id, name, builtin) in builtin_programs.into_iter() {
-            bank.get_transaction_processor()
-                .add_builtin(&bank, program_id, name, builtin);
-        }
-
-        for (address, account) in self.accounts.iter() {
-            if bank.get_account(address).is_some() {
-                info!("Overriding account at {}", address);
-            }
-            bank.store_account(address, account);
-        }
+        self.apply_overrides(&bank);
         bank.set_capitalization();
         // Advance beyond slot 0 for a slightly more realistic test environment
         let bank = {
@@ -856,17 +910,9 @@ impl ProgramTest {
             debug!("Bank slot: {}", bank.slot());
             bank
         };
-        let slot = bank.slot();
-        let last_blockhash = bank.last_blockhash();
-        let bank_forks = BankForks::new_rw_arc(bank);
-        let block_commitment_cache = Arc::new(RwLock::new(
-            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
-        ));
 
         (
-            bank_forks,
-            block_commitment_cache,
-            last_blockhash,
+            bank,
             GenesisConfigInfo {
                 genesis_config,
                 mint_keypair,
@@ -876,8 +922,71 @@ impl ProgramTest {
         )
     }
 
+    fn setup_bank_from_snapshot(
+        &mut self,
+        snapshot_archive: &Path,
+        runtime_config: RuntimeConfig,
+    ) -> (Bank, GenesisConfigInfo, SnapshotDirs) {
+        if !self.deactivate_feature_set.is_empty() {
+            warn!("Features cannot be deactivated when starting from a snapshot, ignored.");
+        }
+        let (snapshot_bank, genesis_config, snapshot_dirs) =
+            load_snapshot_bank(snapshot_archive, &runtime_config, true)
+                .unwrap_or_else(|err| panic!("{err}"));
+
+        // The snapshot bank is frozen, continue from its child
+        let snapshot_bank = Arc::new(snapshot_bank);
+        let bank = Bank::new_from_parent(
+            snapshot_bank.clone(),
+            snapshot_bank.collector_id(),
+            snapshot_bank.slot() + 1,
+        );
+        debug!("Bank slot: {}", bank.slot());
+
+        let payer = Keypair::new();
+        debug!("Payer address: {}", payer.pubkey());
+        bank.store_account(
+            &payer.pubkey(),
+            &AccountSharedData::new(sol_to_lamports(1_000_000.0), 0, &system_program::id()),
+        );
+        self.apply_overrides(&bank);
+        // Warping checks the capitalization. This scans the accounts stored so far, and every
+        // account of an unpacked snapshot.
+        bank.set_capitalization();
+
+        (
+            bank,
+            GenesisConfigInfo {
+                genesis_config,
+                mint_keypair: payer,
+                voting_keypair: Keypair::new(),
+                validator_pubkey: *snapshot_bank.collector_id(),
+            },
+            snapshot_dirs,
+        )
+    }
+
+    /// Adds the builtins and accounts of the test to `bank`
+    fn apply_overrides(&mut self, bank: &Bank) {
+        // User-supplied additional builtins
+        let mut builtin_programs = Vec::new();
+        std::mem::swap(&mut self.builtin_programs, &mut builtin_programs);
+        for (program_id, name, builtin) in builtin_programs.into_iter() {
+            bank.get_transaction_processor()
+                .add_builtin(bank, program_id, name, builtin);
+        }
+
+        for (address, account) in self.accounts.iter() {
+            if bank.get_account(address).is_some() {
+                info!("Overriding account at {}", address);
+            }
+            bank.store_account(address, account);
+        }
+    }
+
     pub async fn start(mut self) -> (BanksClient, Keypair, Hash) {
-        let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let (bank_forks, block_commitment_cache, last_blockhash, gci, snapshot_dirs) =
+            self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let target_slot_duration = target_tick_duration * gci.genesis_config.ticks_per_slot as u32;
         let transport = start_local_server(
@@ -894,6 +1003,8 @@ impl ProgramTest {
         // are required when sending multiple otherwise identical transactions in series from a
         // test
         tokio::spawn(async move {
+            // The bank reads accounts from the snapshot's directories for as long as it runs
+            let _snapshot_dirs = snapshot_dirs;
             loop {
                 tokio::time::sleep(target_slot_duration).await;
                 bank_forks
@@ -912,7 +1023,8 @@ impl ProgramTest {
     /// Returns a `BanksClient` interface into the test environment as well as a payer `Keypair`
     /// with SOL for sending transactions
     pub async fn start_with_context(mut self) -> ProgramTestContext {
-        let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let (bank_forks, block_commitment_cache, last_blockhash, gci, snapshot_dirs) =
+            self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let transport = start_local_server(
             bank_forks.clone(),
@@ -930,6 +1042,7 @@ impl ProgramTest {
             banks_client,
             last_blockhash,
             gci,
+            snapshot_dirs,
         )
     }
 }
@@ -1011,6 +1124,122 @@ impl<T> Drop for DroppableTask<T> {
     }
 }
 
+/// Temporary directories holding the accounts of a snapshot, removed once the test is done with it
+struct SnapshotDirs {
+    /// `None` when the archive is not unpacked
+    _bank_snapshots: Option<TempDir>,
+    _accounts: TempDir,
+}
+
+/// Loads the bank of the full snapshot archive at `snapshot_archive`, with the genesis config of
+/// its cluster from the `genesis.bin` file in the directory of the archive
+///
+/// If `load_accounts_lazily` is set and the archive is in the `TarZstdIndexed` format, its
+/// accounts are read from it when they are first loaded instead of unpacking it.
+fn load_snapshot_bank(
+    snapshot_archive: &Path,
+    runtime_config: &RuntimeConfig,
+    load_accounts_lazily: bool,
+) -> Result<(Bank, GenesisConfig, SnapshotDirs), String> {
+    let ledger_path = snapshot_archive.parent().unwrap_or_else(|| Path::new("."));
+    let genesis_config = GenesisConfig::load(ledger_path).map_err(|err| {
+        format!(
+            "Failed to load the genesis config from {}: {err}",
+            ledger_path.display()
+        )
+    })?;
+    let full_snapshot_archive_info =
+        FullSnapshotArchiveInfo::new_from_path(snapshot_archive.to_path_buf()).map_err(|err| {
+            format!(
+                "Invalid full snapshot archive {}: {err}",
+                snapshot_archive.display()
+            )
+        })?;
+
+    let map_bank_err = |err| {
+        format!(
+            "Failed to load bank from snapshot archive {}: {err}",
+            snapshot_archive.display()
+        )
+    };
+    let accounts_dir = TempDir::new().map_err(|err| err.to_string())?;
+
+    if load_accounts_lazily
+        && full_snapshot_archive_info.archive_format() == ArchiveFormat::TarZstdIndexed
+    {
+        let bank = snapshot_bank_utils::bank_from_indexed_snapshot_archive(
+            &[accounts_dir.path().to_path_buf()],
+            &full_snapshot_archive_info,
+            &genesis_config,
+            runtime_config,
+            None,
+            Arc::default(),
+        )
+        .map_err(map_bank_err)?;
+        let snapshot_dirs = SnapshotDirs {
+            _bank_snapshots: None,
+            _accounts: accounts_dir,
+        };
+        return Ok((bank, genesis_config, snapshot_dirs));
+    }
+
+    let bank_snapshots_dir = TempDir::new().map_err(|err| err.to_string())?;
+    let accounts_db_config = AccountsDbConfig {
+        // Trust the snapshot, hashing all of mainnet-beta's accounts would take minutes
+        skip_initial_hash_calc: true,
+        ..AccountsDbConfig::default()
+    };
+    let (bank, _) = snapshot_bank_utils::bank_from_snapshot_archives(
+        &[accounts_dir.path().to_path_buf()],
+        bank_snapshots_dir.path(),
+        &full_snapshot_archive_info,
+        None,
+        &genesis_config,
+        runtime_config,
+        None,
+        None,
+        AccountSecondaryIndexes::default(),
+        None,
+        AccountShrinkThreshold::default(),
+        false,
+        true,
+        false,
+        false,
+        Some(accounts_db_config),
+        None,
+        Arc::default(),
+    )
+    .map_err(map_bank_err)?;
+    let snapshot_dirs = SnapshotDirs {
+        _bank_snapshots: Some(bank_snapshots_dir),
+        _accounts: accounts_dir,
+    };
+    Ok((bank, genesis_config, snapshot_dirs))
+}
+
+/// Reads every account of the full snapshot archive at `snapshot_archive`, such as one minimized
+/// with `SnapshotMinimizer::minimize_to_selection`, to preload them in other test environments
+///
+/// As with `ProgramTest::from_snapshot`, the genesis config is read from the `genesis.bin` file in
+/// the directory of the archive. The archive is unpacked to a temporary directory, whatever its
+/// format.
+pub fn accounts_from_snapshot_archive(
+    snapshot_archive: &Path,
+) -> Result<Vec<(Pubkey, AccountSharedData)>, String> {
+    let (bank, _, _snapshot_dirs) =
+        load_snapshot_bank(snapshot_archive, &RuntimeConfig::default(), false)?;
+    let accounts = bank.get_all_accounts().map_err(|err| {
+        format!(
+            "Failed to read the accounts of snapshot archive {}: {err}",
+            snapshot_archive.display()
+        )
+    })?;
+    Ok(accounts
+        .into_iter()
+        .map(|(pubkey, account, _slot)| (pubkey, account))
+        .collect())
+}
+
 pub struct ProgramTestContext {
     pub banks_client: BanksClient,
     pub last_blockhash: Hash,
@@ -1019,6 +1248,7 @@ pub struct ProgramTestContext {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     _bank_task: DroppableTask<()>,
+    _snapshot_dirs: Option<SnapshotDirs>,
 }
 
 impl ProgramTestContext {
@@ -1028,6 +1258,7 @@ impl ProgramTestContext {
         banks_client: BanksClient,
         last_blockhash: Hash,
         genesis_config_info: GenesisConfigInfo,
+        snapshot_dirs: Option<SnapshotDirs>,
     ) -> Self {
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
@@ -1065,6 +1296,7 @@ impl ProgramTestContext {
             bank_forks,
             block_commitment_cache,
             _bank_task: bank_task,
+            _snapshot_dirs: snapshot_dirs,
         }
     }
 
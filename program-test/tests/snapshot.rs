@@ -0,0 +1,129 @@
+use {
+    solana_program_test::ProgramTest,
+    solana_runtime::{
+        bank::Bank,
+        genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_bank_utils,
+        snapshot_utils::ArchiveFormat,
+    },
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        pubkey::Pubkey,
+        signature::Signer,
+        system_instruction,
+        transaction::Transaction,
+    },
+    std::num::NonZeroUsize,
+    tempfile::TempDir,
+    test_case::test_case,
+};
+
+#[test_case(ArchiveFormat::Tar; "unpacked")]
+#[test_case(ArchiveFormat::TarZstdIndexed; "loaded lazily")]
+#[tokio::test]
+async fn start_from_snapshot(archive_format: ArchiveFormat) {
+    let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let owner = Pubkey::new_unique();
+    let snapshot_account = Pubkey::new_unique();
+    bank.store_account(
+        &snapshot_account,
+        &AccountSharedData::from(Account {
+            lamports: 42_000_000,
+            data: vec![1, 2, 3],
+            owner,
+            ..Account::default()
+        }),
+    );
+    let untouched_account = Pubkey::new_unique();
+    bank.store_account(
+        &untouched_account,
+        &AccountSharedData::new(5_000_000, 0, &owner),
+    );
+    bank.fill_bank_with_ticks_for_tests();
+
+    // Validators keep the genesis config next to the snapshot archives
+    let bank_snapshots_dir = TempDir::new().unwrap();
+    let ledger_dir = TempDir::new().unwrap();
+    genesis_config.write(ledger_dir.path()).unwrap();
+    let archive_info = snapshot_bank_utils::bank_to_full_snapshot_archive(
+        bank_snapshots_dir.path(),
+        &bank,
+        None,
+        ledger_dir.path(),
+        ledger_dir.path(),
+        archive_format,
+        NonZeroUsize::new(1).unwrap(),
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .unwrap();
+
+    let mut context = ProgramTest::from_snapshot(archive_info.path())
+        .start_with_context()
+        .await;
+    let account = context
+        .banks_client
+        .get_account(snapshot_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.lamports, 42_000_000);
+    assert_eq!(account.data, vec![1, 2, 3]);
+    assert_eq!(account.owner, owner);
+
+    // Transactions run on top of the snapshot state
+    let recipient = Pubkey::new_unique();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &recipient,
+            1_000_000,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        1_000_000
+    );
+
+    // Snapshot accounts can be overridden like any other
+    context.set_account(
+        &snapshot_account,
+        &AccountSharedData::new(7_000_000, 0, &owner),
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(snapshot_account)
+            .await
+            .unwrap(),
+        7_000_000
+    );
+
+    // Warping keeps both the snapshot state and the overrides
+    context.warp_to_slot(20).unwrap();
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(snapshot_account)
+            .await
+            .unwrap(),
+        7_000_000
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(untouched_account)
+            .await
+            .unwrap(),
+        5_000_000
+    );
+}
@@ -110,6 +110,7 @@ fn process_transaction_and_record_inner(
                 enable_cpi_recording: true,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_instruction_compute_units_recording: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -24,6 +24,8 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READS_NOT_AVAILABLE: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READ_IN_PROGRESS: i64 = -32018;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -65,6 +67,10 @@ pub enum RpcCustomError {
     UnsupportedTransactionVersion(u8),
     #[error("MinContextSlotNotReached")]
     MinContextSlotNotReached { context_slot: Slot },
+    #[error("HistoricalAccountReadsNotAvailable")]
+    HistoricalAccountReadsNotAvailable,
+    #[error("HistoricalAccountReadInProgress")]
+    HistoricalAccountReadInProgress,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -206,6 +212,21 @@ impl From<RpcCustomError> for Error {
                     context_slot,
                 })),
             },
+            RpcCustomError::HistoricalAccountReadsNotAvailable => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READS_NOT_AVAILABLE,
+                ),
+                message: "Historical account reads are not available from this node".to_string(),
+                data: None,
+            },
+            RpcCustomError::HistoricalAccountReadInProgress => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READ_IN_PROGRESS,
+                ),
+                message: "Another historical account read is in progress, try again later"
+                    .to_string(),
+                data: None,
+            },
         }
     }
 }
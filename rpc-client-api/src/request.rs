@@ -61,6 +61,7 @@ pub enum RpcRequest {
     GetFirstAvailableBlock,
     GetGenesisHash,
     GetHealth,
+    GetHistoricalAccountInfo,
     GetIdentity,
     GetInflationGovernor,
     GetInflationRate,
@@ -112,6 +113,7 @@ pub enum RpcRequest {
     RegisterNode,
     RequestAirdrop,
     SendTransaction,
+    SimulateBundle,
     SimulateTransaction,
     SignVote,
 }
@@ -145,6 +147,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetFirstAvailableBlock => "getFirstAvailableBlock",
             RpcRequest::GetGenesisHash => "getGenesisHash",
             RpcRequest::GetHealth => "getHealth",
+            RpcRequest::GetHistoricalAccountInfo => "getHistoricalAccountInfo",
             RpcRequest::GetIdentity => "getIdentity",
             RpcRequest::GetInflationGovernor => "getInflationGovernor",
             RpcRequest::GetInflationRate => "getInflationRate",
@@ -188,6 +191,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::RegisterNode => "registerNode",
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
+            RpcRequest::SimulateBundle => "simulateBundle",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SignVote => "signVote",
         };
@@ -205,6 +209,7 @@ pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
+pub const MAX_SIMULATE_BUNDLE_TRANSACTIONS: usize = 16;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
 // response
@@ -1,12 +1,15 @@
 use {
     crate::client_error,
+    base64::{prelude::BASE64_STANDARD, Engine},
     serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_account_decoder::{parse_token::UiTokenAmount, UiAccount},
     solana_sdk::{
+        account::ReadableAccount,
         clock::{Epoch, Slot, UnixTimestamp},
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        pubkey::Pubkey,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
@@ -425,6 +428,71 @@ pub struct RpcSimulateTransactionResult {
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
     pub replacement_blockhash: Option<RpcBlockhash>,
+    pub account_diffs: Option<Vec<RpcAccountDiff>>,
+    pub instruction_units_consumed: Option<Vec<u64>>,
+}
+
+/// Change in state of a writable account over the course of a simulated transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDiff {
+    pub pubkey: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: String,
+    pub post_owner: String,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    /// Contiguous byte ranges of account data that differ, in ascending offset order
+    pub data_changes: Vec<RpcAccountDataChange>,
+}
+
+impl RpcAccountDiff {
+    /// Compares the pre- and post-execution state of an account, reporting every contiguous
+    /// run of differing data bytes
+    pub fn new<T: ReadableAccount>(pubkey: &Pubkey, pre_account: &T, post_account: &T) -> Self {
+        let pre_data = pre_account.data();
+        let post_data = post_account.data();
+        let mut data_changes = vec![];
+        let mut offset = 0;
+        let max_data_len = pre_data.len().max(post_data.len());
+        while offset < max_data_len {
+            if pre_data.get(offset) == post_data.get(offset) {
+                offset += 1;
+                continue;
+            }
+            let start = offset;
+            while offset < max_data_len && pre_data.get(offset) != post_data.get(offset) {
+                offset += 1;
+            }
+            let encode_range = |data: &[u8]| {
+                BASE64_STANDARD.encode(&data[start.min(data.len())..offset.min(data.len())])
+            };
+            data_changes.push(RpcAccountDataChange {
+                offset: start,
+                pre: encode_range(pre_data),
+                post: encode_range(post_data),
+            });
+        }
+        Self {
+            pubkey: pubkey.to_string(),
+            pre_lamports: pre_account.lamports(),
+            post_lamports: post_account.lamports(),
+            pre_owner: pre_account.owner().to_string(),
+            post_owner: post_account.owner().to_string(),
+            pre_data_len: pre_data.len(),
+            post_data_len: post_data.len(),
+            data_changes,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDataChange {
+    pub offset: usize,
+    pub pre: String, // base-64 encoded bytes, empty if past the end of the pre-simulation data
+    pub post: String, // base-64 encoded bytes, empty if past the end of the post-simulation data
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -559,7 +627,11 @@ pub struct RpcPrioritizationFee {
 #[cfg(test)]
 pub mod tests {
 
-    use {super::*, serde_json::json};
+    use {
+        super::*,
+        serde_json::json,
+        solana_sdk::account::{Account, AccountSharedData},
+    };
 
     // Make sure that `RpcPerfSample` can read previous version JSON, one without the
     // `num_non_vote_transactions` field.
@@ -619,4 +691,46 @@ pub mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn rpc_account_diff_new() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let pre_account = AccountSharedData::from(Account {
+            lamports: 10,
+            data: vec![0, 1, 2, 3, 4, 5],
+            owner,
+            ..Account::default()
+        });
+        let post_account = AccountSharedData::from(Account {
+            lamports: 7,
+            data: vec![0, 9, 9, 3, 4, 5, 6, 7],
+            owner,
+            ..Account::default()
+        });
+
+        let diff = RpcAccountDiff::new(&pubkey, &pre_account, &post_account);
+        assert_eq!(diff.pre_lamports, 10);
+        assert_eq!(diff.post_lamports, 7);
+        assert_eq!(diff.pre_data_len, 6);
+        assert_eq!(diff.post_data_len, 8);
+        assert_eq!(
+            diff.data_changes,
+            vec![
+                RpcAccountDataChange {
+                    offset: 1,
+                    pre: BASE64_STANDARD.encode([1u8, 2]),
+                    post: BASE64_STANDARD.encode([9u8, 9]),
+                },
+                RpcAccountDataChange {
+                    offset: 6,
+                    pre: String::new(),
+                    post: BASE64_STANDARD.encode([6u8, 7]),
+                },
+            ]
+        );
+
+        let diff = RpcAccountDiff::new(&pubkey, &pre_account, &pre_account);
+        assert!(diff.data_changes.is_empty());
+    }
 }
@@ -0,0 +1,621 @@
+//! Nonblocking [`RpcSender`] that spreads requests across several RPC nodes.
+//!
+//! Every endpoint is polled with `getSlot` and requests go to a pinned endpoint, the most
+//! up-to-date one when it was chosen. The pin only moves when that endpoint falls more than
+//! [`FailoverConfig::max_slot_lag`] slots behind or fails, so consecutive reads see a consistent
+//! view of the chain. Requests that fail with a transport error or an unhealthy node are retried
+//! on the next endpoint, and endpoints that keep failing are skipped until a cooldown expires.
+//!
+//! Polls run alongside requests rather than ahead of them: a request never waits for a poll, and
+//! polls still outstanding when it completes are abandoned until the next interval. A timed out
+//! `requestAirdrop` may still have been processed, so it is not retried.
+//!
+//! Like any [`RpcSender`], it can back both the blocking and the nonblocking `RpcClient`:
+//!
+//! ```no_run
+//! use solana_rpc_client::{failover_sender::FailoverSender, rpc_client::RpcClient};
+//! use solana_rpc_client_api::client_error::Result;
+//! # fn run() -> Result<()> {
+//! let sender = FailoverSender::new([
+//!     "https://rpc-a.example.com",
+//!     "https://rpc-b.example.com",
+//! ]);
+//! let client = RpcClient::new_sender(sender, Default::default());
+//! let slot = client.get_slot()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use {
+    crate::{http_sender::HttpSender, rpc_sender::*},
+    async_trait::async_trait,
+    futures::future::{join_all, select, Either},
+    log::*,
+    serde_json::json,
+    solana_rpc_client_api::{
+        client_error::{ErrorKind, Result},
+        custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        request::{RpcError, RpcRequest},
+    },
+    solana_sdk::clock::Slot,
+    std::{
+        cmp::Reverse,
+        future::Future,
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+    tokio::time::{sleep, timeout},
+};
+
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    /// How often to poll every endpoint's slot with `getSlot`
+    pub health_check_interval: Duration,
+
+    /// How long to wait for an endpoint's slot before counting the poll as a failure
+    pub health_check_timeout: Duration,
+
+    /// Endpoints further than this many slots behind the most up-to-date one are only used when
+    /// no up-to-date endpoint is available
+    pub max_slot_lag: u64,
+
+    /// How many more attempts to make after a request fails with a transport error or an
+    /// unhealthy node
+    pub max_retries: usize,
+
+    /// Pause before retrying an endpoint already tried for the same request
+    pub retry_delay: Duration,
+
+    /// Consecutive failures after which an endpoint is skipped
+    pub circuit_breaker_threshold: u32,
+
+    /// How long a failing endpoint is skipped before being tried again
+    pub circuit_breaker_cooldown: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(5),
+            health_check_timeout: Duration::from_secs(2),
+            max_slot_lag: 20,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(250),
+            circuit_breaker_threshold: 3,
+            circuit_breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointState {
+    /// Last slot reported by `getSlot`
+    slot: Option<Slot>,
+    consecutive_failures: u32,
+    circuit_open_until: Option<Instant>,
+}
+
+impl EndpointState {
+    fn is_available(&self, now: Instant) -> bool {
+        self.circuit_open_until
+            .map_or(true, |open_until| now >= open_until)
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    endpoints: Vec<EndpointState>,
+    pinned: Option<usize>,
+    next_health_check: Instant,
+}
+
+impl State {
+    /// Highest slot reported by an available endpoint
+    fn best_slot(&self, now: Instant) -> Option<Slot> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_available(now))
+            .filter_map(|endpoint| endpoint.slot)
+            .max()
+    }
+}
+
+pub struct FailoverSender {
+    senders: Vec<Box<dyn RpcSender + Send + Sync + 'static>>,
+    config: FailoverConfig,
+    state: Mutex<State>,
+}
+
+impl FailoverSender {
+    /// Create a sender over HTTP endpoints, in order of preference when equally up-to-date.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `urls` is empty.
+    pub fn new<U: ToString>(urls: impl IntoIterator<Item = U>) -> Self {
+        Self::new_with_config(urls, FailoverConfig::default())
+    }
+
+    /// Create a sender over HTTP endpoints with the given policies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `urls` is empty.
+    pub fn new_with_config<U: ToString>(
+        urls: impl IntoIterator<Item = U>,
+        config: FailoverConfig,
+    ) -> Self {
+        Self::new_with_senders(
+            urls.into_iter()
+                .map(|url| {
+                    Box::new(HttpSender::new(url)) as Box<dyn RpcSender + Send + Sync + 'static>
+                })
+                .collect(),
+            config,
+        )
+    }
+
+    /// Create a sender over arbitrary senders, e.g. [`HttpSender`]s with custom clients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `senders` is empty.
+    pub fn new_with_senders(
+        senders: Vec<Box<dyn RpcSender + Send + Sync + 'static>>,
+        config: FailoverConfig,
+    ) -> Self {
+        assert!(!senders.is_empty(), "no RPC endpoints");
+        let state = State {
+            endpoints: senders.iter().map(|_| EndpointState::default()).collect(),
+            pinned: None,
+            next_health_check: Instant::now(),
+        };
+        Self {
+            senders,
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Poll every endpoint's slot, moving the pin if needed as each result arrives
+    async fn check_health(&self) {
+        join_all((0..self.senders.len()).map(|index| self.check_endpoint_health(index))).await;
+    }
+
+    async fn check_endpoint_health(&self, index: usize) {
+        let params = json!([{ "commitment": "processed" }]);
+        let slot = timeout(
+            self.config.health_check_timeout,
+            self.senders[index].send(RpcRequest::GetSlot, params),
+        )
+        .await;
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        match slot.map(|slot| slot.map(serde_json::from_value::<Slot>)) {
+            Ok(Ok(Ok(slot))) => {
+                self.record_success(&mut state, index);
+                state.endpoints[index].slot = Some(slot);
+            }
+            Ok(Ok(Err(err))) => {
+                debug!(
+                    "{} returned an invalid slot: {err}",
+                    self.senders[index].url()
+                );
+                self.record_failure(&mut state, index, now);
+            }
+            Ok(Err(err)) => {
+                debug!(
+                    "{} failed its health check: {err}",
+                    self.senders[index].url()
+                );
+                self.record_failure(&mut state, index, now);
+            }
+            Err(_) => {
+                debug!("{} health check timed out", self.senders[index].url());
+                self.record_failure(&mut state, index, now);
+            }
+        }
+        self.repin(&mut state, now);
+    }
+
+    /// Keep the pinned endpoint while it is available and up-to-date, otherwise pin the most
+    /// up-to-date available endpoint
+    fn repin(&self, state: &mut State, now: Instant) {
+        let best_slot = state.best_slot(now);
+        if let Some(pinned) = state.pinned {
+            let endpoint = &state.endpoints[pinned];
+            if endpoint.is_available(now) && self.is_up_to_date(endpoint, best_slot) {
+                return;
+            }
+        }
+        let pinned = state
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, endpoint)| endpoint.is_available(now))
+            .min_by_key(|(index, endpoint)| (Reverse(endpoint.slot), *index))
+            .map(|(index, _)| index);
+        if pinned != state.pinned {
+            if let Some(pinned) = pinned {
+                info!("Pinning RPC requests to {}", self.senders[pinned].url());
+            }
+            state.pinned = pinned;
+        }
+    }
+
+    /// Endpoints in the order to try them: the pinned one, then other up-to-date ones, then
+    /// lagging ones, then those with an open circuit
+    fn ordered_endpoints(&self, state: &State, now: Instant) -> Vec<usize> {
+        let best_slot = state.best_slot(now);
+        let mut order: Vec<usize> = (0..state.endpoints.len()).collect();
+        order.sort_by_key(|index| {
+            let endpoint = &state.endpoints[*index];
+            let tier = if !endpoint.is_available(now) {
+                3
+            } else if state.pinned == Some(*index) {
+                0
+            } else if self.is_up_to_date(endpoint, best_slot) {
+                1
+            } else {
+                2
+            };
+            (tier, Reverse(endpoint.slot), *index)
+        });
+        order
+    }
+
+    fn is_up_to_date(&self, endpoint: &EndpointState, best_slot: Option<Slot>) -> bool {
+        match (endpoint.slot, best_slot) {
+            (Some(slot), Some(best_slot)) => {
+                best_slot.saturating_sub(slot) <= self.config.max_slot_lag
+            }
+            (None, Some(_)) => false,
+            (_, None) => true,
+        }
+    }
+
+    fn record_success(&self, state: &mut State, index: usize) {
+        let endpoint = &mut state.endpoints[index];
+        endpoint.consecutive_failures = 0;
+        endpoint.circuit_open_until = None;
+    }
+
+    fn record_failure(&self, state: &mut State, index: usize, now: Instant) {
+        let endpoint = &mut state.endpoints[index];
+        endpoint.consecutive_failures = endpoint.consecutive_failures.saturating_add(1);
+        if endpoint.consecutive_failures >= self.config.circuit_breaker_threshold {
+            if endpoint
+                .circuit_open_until
+                .map_or(true, |open_until| now >= open_until)
+            {
+                warn!(
+                    "Skipping {} for {:?} after {} consecutive failures",
+                    self.senders[index].url(),
+                    self.config.circuit_breaker_cooldown,
+                    endpoint.consecutive_failures,
+                );
+            }
+            endpoint.circuit_open_until = Some(now + self.config.circuit_breaker_cooldown);
+            if state.pinned == Some(index) {
+                self.repin(state, now);
+            }
+        }
+    }
+
+    /// Make `send` to the best endpoint, retrying endpoint failures on the next ones. Timed out
+    /// requests are only retried if `idempotent`. Runs the health check alongside if it is due.
+    async fn send_with_failover<'a, T, F, Fut>(&'a self, idempotent: bool, send: F) -> Result<T>
+    where
+        F: Fn(&'a (dyn RpcSender + Send + Sync + 'static)) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let health_check_due = {
+            let now = Instant::now();
+            let mut state = self.state.lock().unwrap();
+            let due = now >= state.next_health_check;
+            if due {
+                state.next_health_check = now + self.config.health_check_interval;
+            }
+            due
+        };
+        let request = Box::pin(self.send_to_best_endpoint(idempotent, send));
+        if !health_check_due {
+            return request.await;
+        }
+        // Polled first, so polls that complete immediately are taken into account
+        match select(Box::pin(self.check_health()), request).await {
+            Either::Left(((), request)) => request.await,
+            Either::Right((result, _abandoned_health_check)) => result,
+        }
+    }
+
+    async fn send_to_best_endpoint<'a, T, F, Fut>(&'a self, idempotent: bool, send: F) -> Result<T>
+    where
+        F: Fn(&'a (dyn RpcSender + Send + Sync + 'static)) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let order = {
+            let state = self.state.lock().unwrap();
+            self.ordered_endpoints(&state, Instant::now())
+        };
+        let mut attempt = 0;
+        loop {
+            if attempt > 0 && attempt % order.len() == 0 {
+                sleep(self.config.retry_delay).await;
+            }
+            let index = order[attempt % order.len()];
+            let result = send(self.senders[index].as_ref()).await;
+            let mut state = self.state.lock().unwrap();
+            match result {
+                Err(err) if is_endpoint_failure(&err.kind) => {
+                    self.record_failure(&mut state, index, Instant::now());
+                    if attempt >= self.config.max_retries || (!idempotent && is_timeout(&err.kind))
+                    {
+                        return Err(err);
+                    }
+                    debug!(
+                        "Request failed on {}, retrying: {err}",
+                        self.senders[index].url()
+                    );
+                    attempt += 1;
+                }
+                result => {
+                    self.record_success(&mut state, index);
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+/// Requests that may take effect twice if retried after a timeout
+fn is_idempotent(request: RpcRequest) -> bool {
+    !matches!(request, RpcRequest::RequestAirdrop)
+}
+
+fn is_timeout(kind: &ErrorKind) -> bool {
+    matches!(kind, ErrorKind::Reqwest(err) if err.is_timeout())
+}
+
+/// Whether `err` says more about the endpoint than about the request, so that another endpoint
+/// may succeed
+fn is_endpoint_failure(kind: &ErrorKind) -> bool {
+    match kind {
+        ErrorKind::Io(_) | ErrorKind::Reqwest(_) => true,
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl RpcSender for FailoverSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.send_with_failover(is_idempotent(request), |sender| {
+            sender.send(request, params.clone())
+        })
+        .await
+    }
+
+    /// Sends the whole batch to one endpoint, so that every call sees the same state
+    async fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, serde_json::Value)>,
+    ) -> Result<Vec<Result<serde_json::Value>>> {
+        let idempotent = requests.iter().all(|(request, _)| is_idempotent(*request));
+        self.send_with_failover(idempotent, |sender| sender.send_batch(requests.clone()))
+            .await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.senders
+            .iter()
+            .fold(RpcTransportStats::default(), |mut total, sender| {
+                let stats = sender.get_transport_stats();
+                total.request_count += stats.request_count;
+                total.elapsed_time += stats.elapsed_time;
+                total.rate_limited_time += stats.rate_limited_time;
+                total
+            })
+    }
+
+    /// The URL of the pinned endpoint
+    fn url(&self) -> String {
+        let pinned = self.state.lock().unwrap().pinned.unwrap_or_default();
+        self.senders[pinned].url()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::Value,
+        solana_rpc_client_api::request::RpcResponseErrorData,
+        std::{
+            io,
+            sync::{
+                atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+                Arc,
+            },
+        },
+    };
+
+    #[derive(Default)]
+    struct TestEndpoint {
+        slot: AtomicU64,
+        down: AtomicBool,
+        /// Requests time out instead of failing when down
+        times_out: AtomicBool,
+        /// `getSlot` never returns
+        hangs: AtomicBool,
+        requests: AtomicUsize,
+    }
+
+    /// A genuine reqwest timeout, from a server that never responds
+    async fn reqwest_timeout() -> reqwest::Error {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        reqwest::Client::builder()
+            .timeout(Duration::from_millis(10))
+            .build()
+            .unwrap()
+            .get(format!("http://{}", listener.local_addr().unwrap()))
+            .send()
+            .await
+            .unwrap_err()
+    }
+
+    struct TestSender {
+        url: String,
+        endpoint: Arc<TestEndpoint>,
+    }
+
+    #[async_trait]
+    impl RpcSender for TestSender {
+        async fn send(&self, request: RpcRequest, _params: Value) -> Result<Value> {
+            if request != RpcRequest::GetSlot {
+                self.endpoint.requests.fetch_add(1, Ordering::Relaxed);
+            } else if self.endpoint.hangs.load(Ordering::Relaxed) {
+                futures::future::pending::<()>().await;
+            }
+            if self.endpoint.down.load(Ordering::Relaxed) {
+                if self.endpoint.times_out.load(Ordering::Relaxed) {
+                    return Err(reqwest_timeout().await.into());
+                }
+                return Err(io::Error::new(io::ErrorKind::ConnectionRefused, "down").into());
+            }
+            match request {
+                RpcRequest::GetSlot => Ok(json!(self.endpoint.slot.load(Ordering::Relaxed))),
+                RpcRequest::GetVersion => Ok(json!(self.url)),
+                _ => Err(RpcError::RpcResponseError {
+                    code: -32602,
+                    message: "Invalid params".to_string(),
+                    data: RpcResponseErrorData::Empty,
+                }
+                .into()),
+            }
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            self.url.clone()
+        }
+    }
+
+    fn failover_sender(slots: &[Slot]) -> (FailoverSender, Vec<Arc<TestEndpoint>>) {
+        let endpoints: Vec<_> = slots
+            .iter()
+            .map(|slot| {
+                Arc::new(TestEndpoint {
+                    slot: AtomicU64::new(*slot),
+                    ..TestEndpoint::default()
+                })
+            })
+            .collect();
+        let senders = endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                Box::new(TestSender {
+                    url: index.to_string(),
+                    endpoint: endpoint.clone(),
+                }) as Box<dyn RpcSender + Send + Sync + 'static>
+            })
+            .collect();
+        let config = FailoverConfig {
+            health_check_interval: Duration::ZERO,
+            health_check_timeout: Duration::from_secs(60),
+            max_slot_lag: 10,
+            max_retries: 2,
+            retry_delay: Duration::ZERO,
+            circuit_breaker_threshold: 2,
+            circuit_breaker_cooldown: Duration::from_secs(60),
+        };
+        (FailoverSender::new_with_senders(senders, config), endpoints)
+    }
+
+    async fn get_version(sender: &FailoverSender) -> Result<Value> {
+        sender.send(RpcRequest::GetVersion, Value::Null).await
+    }
+
+    #[tokio::test]
+    async fn test_pins_most_up_to_date_endpoint() {
+        let (sender, endpoints) = failover_sender(&[100, 105, 90]);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("1"));
+        assert_eq!(sender.url(), "1");
+
+        // Stays pinned while within the allowed lag
+        endpoints[0].slot.store(110, Ordering::Relaxed);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("1"));
+
+        endpoints[2].slot.store(120, Ordering::Relaxed);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("2"));
+    }
+
+    #[tokio::test]
+    async fn test_failover_and_circuit_breaker() {
+        let (sender, endpoints) = failover_sender(&[100, 100]);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("0"));
+
+        // The failed health check and request open the circuit, so the next request goes
+        // straight to the other endpoint
+        endpoints[0].down.store(true, Ordering::Relaxed);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("1"));
+        assert_eq!(endpoints[0].requests.load(Ordering::Relaxed), 2);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("1"));
+        assert_eq!(endpoints[0].requests.load(Ordering::Relaxed), 2);
+
+        // With every endpoint down, the request is retried then fails
+        endpoints[1].down.store(true, Ordering::Relaxed);
+        assert!(get_version(&sender).await.is_err());
+        assert_eq!(
+            endpoints[0].requests.load(Ordering::Relaxed)
+                + endpoints[1].requests.load(Ordering::Relaxed),
+            7
+        );
+
+        // Request errors are returned without a retry
+        endpoints[1].down.store(false, Ordering::Relaxed);
+        let requests = endpoints[1].requests.load(Ordering::Relaxed);
+        assert!(sender
+            .send(RpcRequest::GetBalance, Value::Null)
+            .await
+            .is_err());
+        assert_eq!(endpoints[1].requests.load(Ordering::Relaxed), requests + 1);
+    }
+
+    #[tokio::test]
+    async fn test_requests_do_not_wait_for_health_checks() {
+        let (sender, endpoints) = failover_sender(&[100, 105]);
+        endpoints[1].hangs.store(true, Ordering::Relaxed);
+        let version = tokio::time::timeout(Duration::from_secs(10), get_version(&sender)).await;
+        assert_eq!(version.unwrap().unwrap(), json!("0"));
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_airdrop_is_not_retried() {
+        let (sender, endpoints) = failover_sender(&[100, 100]);
+        assert_eq!(get_version(&sender).await.unwrap(), json!("0"));
+        endpoints[0].down.store(true, Ordering::Relaxed);
+        endpoints[0].times_out.store(true, Ordering::Relaxed);
+
+        let err = sender
+            .send(RpcRequest::RequestAirdrop, Value::Null)
+            .await
+            .unwrap_err();
+        assert!(is_timeout(&err.kind));
+        assert_eq!(endpoints[1].requests.load(Ordering::Relaxed), 0);
+
+        // Other requests move on to the next endpoint
+        assert_eq!(get_version(&sender).await.unwrap(), json!("1"));
+    }
+}
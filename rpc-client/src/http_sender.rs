@@ -113,22 +113,13 @@ impl<'a> Drop for StatsUpdater<'a> {
     }
 }
 
-#[async_trait]
-impl RpcSender for HttpSender {
-    fn get_transport_stats(&self) -> RpcTransportStats {
-        self.stats.read().unwrap().clone()
-    }
-
-    async fn send(
+impl HttpSender {
+    /// Post `request_json`, retrying while rate limited, and return the response body
+    async fn post(
         &self,
-        request: RpcRequest,
-        params: serde_json::Value,
+        stats_updater: &mut StatsUpdater<'_>,
+        request_json: String,
     ) -> Result<serde_json::Value> {
-        let mut stats_updater = StatsUpdater::new(&self.stats);
-
-        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
-        let request_json = request.build_request_json(request_id, params).to_string();
-
         let mut too_many_requests_retries = 5;
         loop {
             let response = {
@@ -170,48 +161,135 @@ impl RpcSender for HttpSender {
                 return Err(response.error_for_status().unwrap_err().into());
             }
 
-            let mut json = response.json::<serde_json::Value>().await?;
-            if json["error"].is_object() {
-                return match serde_json::from_value::<RpcErrorObject>(json["error"].clone()) {
-                    Ok(rpc_error_object) => {
-                        let data = match rpc_error_object.code {
-                                    custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
-                                        match serde_json::from_value::<RpcSimulateTransactionResult>(json["error"]["data"].clone()) {
-                                            Ok(data) => RpcResponseErrorData::SendTransactionPreflightFailure(data),
-                                            Err(err) => {
-                                                debug!("Failed to deserialize RpcSimulateTransactionResult: {:?}", err);
-                                                RpcResponseErrorData::Empty
-                                            }
-                                        }
-                                    },
-                                    custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
-                                        match serde_json::from_value::<custom_error::NodeUnhealthyErrorData>(json["error"]["data"].clone()) {
-                                            Ok(custom_error::NodeUnhealthyErrorData {num_slots_behind}) => RpcResponseErrorData::NodeUnhealthy {num_slots_behind},
-                                            Err(_err) => {
-                                                RpcResponseErrorData::Empty
-                                            }
-                                        }
-                                    },
-                                    _ => RpcResponseErrorData::Empty
-                                };
-
-                        Err(RpcError::RpcResponseError {
-                            code: rpc_error_object.code,
-                            message: rpc_error_object.message,
-                            data,
+            return Ok(response.json::<serde_json::Value>().await?);
+        }
+    }
+}
+
+/// Extract the result of a JSON-RPC response object
+fn parse_response(mut json: serde_json::Value) -> Result<serde_json::Value> {
+    if json["error"].is_object() {
+        return match serde_json::from_value::<RpcErrorObject>(json["error"].clone()) {
+            Ok(rpc_error_object) => {
+                let data = match rpc_error_object.code {
+                    custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
+                        match serde_json::from_value::<RpcSimulateTransactionResult>(
+                            json["error"]["data"].clone(),
+                        ) {
+                            Ok(data) => RpcResponseErrorData::SendTransactionPreflightFailure(data),
+                            Err(err) => {
+                                debug!(
+                                    "Failed to deserialize RpcSimulateTransactionResult: {:?}",
+                                    err
+                                );
+                                RpcResponseErrorData::Empty
+                            }
                         }
-                        .into())
                     }
-                    Err(err) => Err(RpcError::RpcRequestError(format!(
-                        "Failed to deserialize RPC error response: {} [{}]",
-                        serde_json::to_string(&json["error"]).unwrap(),
-                        err
-                    ))
-                    .into()),
+                    custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
+                        match serde_json::from_value::<custom_error::NodeUnhealthyErrorData>(
+                            json["error"]["data"].clone(),
+                        ) {
+                            Ok(custom_error::NodeUnhealthyErrorData { num_slots_behind }) => {
+                                RpcResponseErrorData::NodeUnhealthy { num_slots_behind }
+                            }
+                            Err(_err) => RpcResponseErrorData::Empty,
+                        }
+                    }
+                    _ => RpcResponseErrorData::Empty,
                 };
+
+                Err(RpcError::RpcResponseError {
+                    code: rpc_error_object.code,
+                    message: rpc_error_object.message,
+                    data,
+                }
+                .into())
+            }
+            Err(err) => Err(RpcError::RpcRequestError(format!(
+                "Failed to deserialize RPC error response: {} [{}]",
+                serde_json::to_string(&json["error"]).unwrap(),
+                err
+            ))
+            .into()),
+        };
+    }
+    Ok(json["result"].take())
+}
+
+#[async_trait]
+impl RpcSender for HttpSender {
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.stats.read().unwrap().clone()
+    }
+
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let mut stats_updater = StatsUpdater::new(&self.stats);
+
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let request_json = request.build_request_json(request_id, params).to_string();
+
+        let json = self.post(&mut stats_updater, request_json).await?;
+        parse_response(json)
+    }
+
+    async fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, serde_json::Value)>,
+    ) -> Result<Vec<Result<serde_json::Value>>> {
+        // Servers reject empty batches
+        if requests.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut stats_updater = StatsUpdater::new(&self.stats);
+
+        let len = requests.len();
+        let first_request_id = self.request_id.fetch_add(len as u64, Ordering::Relaxed);
+        let request_json = serde_json::Value::Array(
+            requests
+                .into_iter()
+                .zip(first_request_id..)
+                .map(|((request, params), request_id)| {
+                    request.build_request_json(request_id, params)
+                })
+                .collect(),
+        )
+        .to_string();
+
+        let json = self.post(&mut stats_updater, request_json).await?;
+        let serde_json::Value::Array(responses) = json else {
+            // An invalid batch is answered with a single error
+            return Err(parse_response(json).err().unwrap_or_else(|| {
+                RpcError::RpcRequestError("Expected an array of responses".to_string()).into()
+            }));
+        };
+        // Responses may come in any order
+        let mut results: Vec<Option<Result<serde_json::Value>>> = (0..len).map(|_| None).collect();
+        for response in responses {
+            let index = response["id"]
+                .as_u64()
+                .and_then(|request_id| request_id.checked_sub(first_request_id))
+                .and_then(|index| usize::try_from(index).ok())
+                .filter(|index| *index < len);
+            if let Some(index) = index {
+                results[index] = Some(parse_response(response));
             }
-            return Ok(json["result"].take());
         }
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| {
+                    Err(RpcError::RpcRequestError(
+                        "Missing response to batched request".to_string(),
+                    )
+                    .into())
+                })
+            })
+            .collect())
     }
 
     fn url(&self) -> String {
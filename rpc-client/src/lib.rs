@@ -1,8 +1,10 @@
 #![allow(clippy::arithmetic_side_effects)]
 
+pub mod failover_sender;
 pub mod http_sender;
 pub mod mock_sender;
 pub mod nonblocking;
+pub mod rpc_batch;
 pub mod rpc_client;
 pub mod rpc_sender;
 pub mod spinner;
@@ -351,9 +351,31 @@ impl RpcSender for MockSender {
                     units_consumed: None,
                     return_data: None,
                     inner_instructions: None,
-                    replacement_blockhash: None
+                    replacement_blockhash: None,
+                    account_diffs: None,
+                    instruction_units_consumed: None,
                 },
             })?,
+            "simulateBundle" => {
+                let num_transactions = params.as_array().unwrap()[0].as_array().unwrap().len();
+                serde_json::to_value(Response {
+                    context: RpcResponseContext { slot: 1, api_version: None },
+                    value: vec![
+                        RpcSimulateTransactionResult {
+                            err: None,
+                            logs: None,
+                            accounts: None,
+                            units_consumed: None,
+                            return_data: None,
+                            inner_instructions: None,
+                            replacement_blockhash: None,
+                            account_diffs: None,
+                            instruction_units_consumed: None,
+                        };
+                        num_transactions
+                    ],
+                })?
+            }
             "getMinimumBalanceForRentExemption" => json![20],
             "getVersion" => {
                 let version = Version::default();
@@ -18,6 +18,7 @@ use {
     crate::{
         http_sender::HttpSender,
         mock_sender::MockSender,
+        rpc_batch::{RpcBatch, RpcBatchResults},
         rpc_client::{
             GetConfirmedSignaturesForAddress2Config, RpcClientConfig, SerializableMessage,
             SerializableTransaction,
@@ -1412,6 +1413,100 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates sending a bundle of transactions, in order, against a single bank.
+    ///
+    /// Each transaction observes the account state left behind by the
+    /// transactions before it. Simulation stops at the first transaction that
+    /// fails, so the returned results may be fewer than the transactions
+    /// provided; the last result's [`err`] field is `Some` in that case.
+    ///
+    /// [`err`]: solana_rpc_client_api::response::RpcSimulateTransactionResult::err
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// #     signature::Signer,
+    /// #     signer::keypair::Keypair,
+    /// #     system_transaction,
+    /// # };
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// // Transfer lamports from Alice to Bob, then from Bob to Carol
+    /// #     let alice = Keypair::new();
+    /// #     let bob = Keypair::new();
+    /// #     let carol = Keypair::new();
+    /// #     let lamports = 50;
+    /// let latest_blockhash = rpc_client.get_latest_blockhash().await?;
+    /// let bundle = [
+    ///     system_transaction::transfer(&alice, &bob.pubkey(), lamports, latest_blockhash),
+    ///     system_transaction::transfer(&bob, &carol.pubkey(), lamports, latest_blockhash),
+    /// ];
+    /// let results = rpc_client.simulate_bundle(&bundle).await?;
+    /// assert!(results.value.iter().all(|result| result.err.is_none()));
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn simulate_bundle(
+        &self,
+        transactions: &[impl SerializableTransaction],
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        self.simulate_bundle_with_config(
+            transactions,
+            RpcSimulateBundleConfig {
+                commitment: Some(self.commitment()),
+                ..RpcSimulateBundleConfig::default()
+            },
+        )
+        .await
+    }
+
+    /// Simulates sending a bundle of transactions, in order, against a single bank.
+    ///
+    /// See [`simulate_bundle`] for details. Accounts listed in the
+    /// [`accounts`] field of the config are reported after each transaction.
+    ///
+    /// [`simulate_bundle`]: RpcClient::simulate_bundle
+    /// [`accounts`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::accounts
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    pub async fn simulate_bundle_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateBundleConfig,
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        let encoding = if let Some(encoding) = config.encoding {
+            encoding
+        } else {
+            self.default_cluster_transaction_encoding().await?
+        };
+        let commitment = config.commitment.unwrap_or_default();
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        let config = RpcSimulateBundleConfig {
+            encoding: Some(encoding),
+            commitment: Some(commitment),
+            ..config
+        };
+        let serialized_encoded = transactions
+            .iter()
+            .map(|transaction| serialize_and_encode(transaction, encoding))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.send(
+            RpcRequest::SimulateBundle,
+            json!([serialized_encoded, config]),
+        )
+        .await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3972,6 +4067,37 @@ impl RpcClient {
             })?
     }
 
+    /// Returns the account information for a [`Pubkey`] as of a past rooted slot.
+    ///
+    /// The node rebuilds the bank at `slot` from its snapshot archives and
+    /// ledger, so this can take a long time and is only served by nodes that
+    /// opted in with `--enable-rpc-historical-account-reads`.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getHistoricalAccountInfo` RPC
+    /// method.
+    pub async fn get_historical_account_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoConfig,
+    ) -> RpcResult<Option<Account>> {
+        let Response {
+            context,
+            value: rpc_account,
+        } = self
+            .send::<Response<Option<UiAccount>>>(
+                RpcRequest::GetHistoricalAccountInfo,
+                json!([pubkey.to_string(), slot, config]),
+            )
+            .await?;
+        Ok(Response {
+            context,
+            value: rpc_account.and_then(|rpc_account| rpc_account.decode()),
+        })
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -5371,6 +5497,26 @@ impl RpcClient {
             .map_err(|err| ClientError::new_with_request(err.into(), request))
     }
 
+    /// Send the calls queued in `batch` as a single request.
+    ///
+    /// Returns an error if the request as a whole fails. The result of each
+    /// call is taken from the returned [`RpcBatchResults`]; see
+    /// [`rpc_batch`](crate::rpc_batch).
+    pub async fn send_batch(&self, batch: RpcBatch) -> ClientResult<RpcBatchResults> {
+        let requests = batch.into_requests();
+        let request_types: Vec<_> = requests.iter().map(|(request, _)| *request).collect();
+        let results = self.sender.send_batch(requests).await?;
+        if results.len() != request_types.len() {
+            return Err(RpcError::RpcRequestError(format!(
+                "Expected {} batch results, got {}",
+                request_types.len(),
+                results.len()
+            ))
+            .into());
+        }
+        Ok(RpcBatchResults::new(&request_types, results))
+    }
+
     pub fn get_transport_stats(&self) -> RpcTransportStats {
         self.sender.get_transport_stats()
     }
@@ -0,0 +1,230 @@
+//! Typed JSON-RPC batch requests.
+//!
+//! An [`RpcBatch`] queues calls and is sent as a single request with
+//! [`RpcClient::send_batch`]. Queuing a call returns an [`RpcBatchCall`]
+//! handle, which takes that call's typed result or error from the
+//! [`RpcBatchResults`].
+//!
+//! Unlike the corresponding [`RpcClient`] methods, calls without a commitment
+//! use the server's default commitment, not the client's.
+//!
+//! [`RpcClient`]: crate::rpc_client::RpcClient
+//! [`RpcClient::send_batch`]: crate::rpc_client::RpcClient::send_batch
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use solana_account_decoder::UiAccountEncoding;
+//! # use solana_rpc_client::{rpc_batch::RpcBatch, rpc_client::RpcClient};
+//! # use solana_rpc_client_api::{client_error::Error, config::RpcAccountInfoConfig};
+//! # use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+//! # let client = RpcClient::new("http://localhost:8899".to_string());
+//! # let pubkeys = [Pubkey::new_unique(), Pubkey::new_unique()];
+//! let mut batch = RpcBatch::new();
+//! let config = RpcAccountInfoConfig {
+//!     encoding: Some(UiAccountEncoding::Base64Zstd),
+//!     ..RpcAccountInfoConfig::default()
+//! };
+//! let accounts: Vec<_> = pubkeys
+//!     .iter()
+//!     .map(|pubkey| batch.get_account_info_with_config(pubkey, config.clone()))
+//!     .collect();
+//! let slot = batch.get_slot_with_commitment(CommitmentConfig::confirmed());
+//!
+//! let mut results = client.send_batch(batch)?;
+//! println!("slot {}", results.take(slot)?);
+//! for account in accounts {
+//!     match results.take(account) {
+//!         Ok(account) => println!("{:?}", account.value),
+//!         Err(err) => println!("{err}"),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+use {
+    serde::de::DeserializeOwned,
+    serde_json::{json, Value},
+    solana_account_decoder::UiAccount,
+    solana_rpc_client_api::{
+        client_error::{Error as ClientError, Result as ClientResult},
+        config::RpcAccountInfoConfig,
+        request::{RpcError, RpcRequest},
+        response::{Response, RpcBlockhash},
+    },
+    solana_sdk::{
+        clock::Slot, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    },
+    solana_transaction_status::TransactionStatus,
+    std::marker::PhantomData,
+};
+
+/// Calls to send together in one request.
+#[derive(Debug, Default)]
+pub struct RpcBatch {
+    requests: Vec<(RpcRequest, Value)>,
+}
+
+/// A queued call, used to take its result from the [`RpcBatchResults`].
+#[derive(Debug)]
+#[must_use]
+pub struct RpcBatchCall<T> {
+    index: usize,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl RpcBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Queue an arbitrary call with positional `params`, whose result deserializes to `T`.
+    pub fn add<T: DeserializeOwned>(
+        &mut self,
+        request: RpcRequest,
+        params: Vec<Value>,
+    ) -> RpcBatchCall<T> {
+        self.requests.push((request, Value::Array(params)));
+        RpcBatchCall {
+            index: self.requests.len() - 1,
+            _result: PhantomData,
+        }
+    }
+
+    /// Queue a [`getAccountInfo`] call.
+    ///
+    /// [`getAccountInfo`]: https://solana.com/docs/rpc/http/getaccountinfo
+    pub fn get_account_info_with_config(
+        &mut self,
+        pubkey: &Pubkey,
+        config: RpcAccountInfoConfig,
+    ) -> RpcBatchCall<Response<Option<UiAccount>>> {
+        self.add(
+            RpcRequest::GetAccountInfo,
+            vec![json!(pubkey.to_string()), json!(config)],
+        )
+    }
+
+    /// Queue a [`getMultipleAccounts`] call.
+    ///
+    /// [`getMultipleAccounts`]: https://solana.com/docs/rpc/http/getmultipleaccounts
+    pub fn get_multiple_accounts_with_config(
+        &mut self,
+        pubkeys: &[Pubkey],
+        config: RpcAccountInfoConfig,
+    ) -> RpcBatchCall<Response<Vec<Option<UiAccount>>>> {
+        let pubkeys: Vec<_> = pubkeys.iter().map(|pubkey| pubkey.to_string()).collect();
+        self.add(
+            RpcRequest::GetMultipleAccounts,
+            vec![json!(pubkeys), json!(config)],
+        )
+    }
+
+    /// Queue a [`getBalance`] call.
+    ///
+    /// [`getBalance`]: https://solana.com/docs/rpc/http/getbalance
+    pub fn get_balance_with_commitment(
+        &mut self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> RpcBatchCall<Response<u64>> {
+        self.add(
+            RpcRequest::GetBalance,
+            vec![json!(pubkey.to_string()), json!(commitment_config)],
+        )
+    }
+
+    /// Queue a [`getSlot`] call.
+    ///
+    /// [`getSlot`]: https://solana.com/docs/rpc/http/getslot
+    pub fn get_slot_with_commitment(
+        &mut self,
+        commitment_config: CommitmentConfig,
+    ) -> RpcBatchCall<Slot> {
+        self.add(RpcRequest::GetSlot, vec![json!(commitment_config)])
+    }
+
+    /// Queue a [`getBlockHeight`] call.
+    ///
+    /// [`getBlockHeight`]: https://solana.com/docs/rpc/http/getblockheight
+    pub fn get_block_height_with_commitment(
+        &mut self,
+        commitment_config: CommitmentConfig,
+    ) -> RpcBatchCall<u64> {
+        self.add(RpcRequest::GetBlockHeight, vec![json!(commitment_config)])
+    }
+
+    /// Queue a [`getLatestBlockhash`] call.
+    ///
+    /// [`getLatestBlockhash`]: https://solana.com/docs/rpc/http/getlatestblockhash
+    pub fn get_latest_blockhash_with_commitment(
+        &mut self,
+        commitment_config: CommitmentConfig,
+    ) -> RpcBatchCall<Response<RpcBlockhash>> {
+        self.add(
+            RpcRequest::GetLatestBlockhash,
+            vec![json!(commitment_config)],
+        )
+    }
+
+    /// Queue a [`getSignatureStatuses`] call.
+    ///
+    /// [`getSignatureStatuses`]: https://solana.com/docs/rpc/http/getsignaturestatuses
+    pub fn get_signature_statuses(
+        &mut self,
+        signatures: &[Signature],
+    ) -> RpcBatchCall<Response<Vec<Option<TransactionStatus>>>> {
+        let signatures: Vec<_> = signatures
+            .iter()
+            .map(|signature| signature.to_string())
+            .collect();
+        self.add(RpcRequest::GetSignatureStatuses, vec![json!(signatures)])
+    }
+
+    pub(crate) fn into_requests(self) -> Vec<(RpcRequest, Value)> {
+        self.requests
+    }
+}
+
+/// The result of every call in a batch.
+#[derive(Debug)]
+pub struct RpcBatchResults {
+    results: Vec<(RpcRequest, Option<ClientResult<Value>>)>,
+}
+
+impl RpcBatchResults {
+    pub(crate) fn new(requests: &[RpcRequest], results: Vec<ClientResult<Value>>) -> Self {
+        Self {
+            results: requests
+                .iter()
+                .zip(results)
+                .map(|(request, result)| (*request, Some(result)))
+                .collect(),
+        }
+    }
+
+    /// Take the result of `call`, which must have been queued in the batch
+    /// these are the results of.
+    pub fn take<T: DeserializeOwned>(&mut self, call: RpcBatchCall<T>) -> ClientResult<T> {
+        let (request, result) = self
+            .results
+            .get_mut(call.index)
+            .and_then(|(request, result)| Some((*request, result.take()?)))
+            .ok_or_else(|| {
+                ClientError::from(RpcError::ForUser(
+                    "call is not part of this batch".to_string(),
+                ))
+            })?;
+        let value = result.map_err(|err| err.into_with_request(request))?;
+        serde_json::from_value(value)
+            .map_err(|err| ClientError::new_with_request(err.into(), request))
+    }
+}
@@ -19,6 +19,7 @@ use {
         http_sender::HttpSender,
         mock_sender::MockSender,
         nonblocking::{self, rpc_client::get_rpc_request_str},
+        rpc_batch::{RpcBatch, RpcBatchResults},
         rpc_sender::*,
     },
     serde::Serialize,
@@ -879,6 +880,15 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).send(request, params))
     }
 
+    /// Send the calls queued in `batch` as a single request.
+    ///
+    /// Returns an error if the request as a whole fails. The result of each
+    /// call is taken from the returned [`RpcBatchResults`]; see
+    /// [`rpc_batch`](crate::rpc_batch).
+    pub fn send_batch(&self, batch: RpcBatch) -> ClientResult<RpcBatchResults> {
+        self.invoke((self.rpc_client.as_ref()).send_batch(batch))
+    }
+
     /// Check the confirmation status of a transaction.
     ///
     /// Returns `true` if the given transaction succeeded and has been committed
@@ -1151,6 +1161,70 @@ impl RpcClient {
         )
     }
 
+    /// Simulates sending a bundle of transactions, in order, against a single bank.
+    ///
+    /// Each transaction observes the account state left behind by the
+    /// transactions before it. Simulation stops at the first transaction that
+    /// fails, so the returned results may be fewer than the transactions
+    /// provided; the last result's [`err`] field is `Some` in that case.
+    ///
+    /// [`err`]: solana_rpc_client_api::response::RpcSimulateTransactionResult::err
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// #     signature::Signer,
+    /// #     signer::keypair::Keypair,
+    /// #     system_transaction,
+    /// # };
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// // Transfer lamports from Alice to Bob, then from Bob to Carol
+    /// # let alice = Keypair::new();
+    /// # let bob = Keypair::new();
+    /// # let carol = Keypair::new();
+    /// # let lamports = 50;
+    /// let latest_blockhash = rpc_client.get_latest_blockhash()?;
+    /// let bundle = [
+    ///     system_transaction::transfer(&alice, &bob.pubkey(), lamports, latest_blockhash),
+    ///     system_transaction::transfer(&bob, &carol.pubkey(), lamports, latest_blockhash),
+    /// ];
+    /// let results = rpc_client.simulate_bundle(&bundle)?;
+    /// assert!(results.value.iter().all(|result| result.err.is_none()));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn simulate_bundle(
+        &self,
+        transactions: &[impl SerializableTransaction],
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundle(transactions))
+    }
+
+    /// Simulates sending a bundle of transactions, in order, against a single bank.
+    ///
+    /// See [`simulate_bundle`] for details. Accounts listed in the
+    /// [`accounts`] field of the config are reported after each transaction.
+    ///
+    /// [`simulate_bundle`]: RpcClient::simulate_bundle
+    /// [`accounts`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::accounts
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    pub fn simulate_bundle_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateBundleConfig,
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundle_with_config(transactions, config))
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3252,6 +3326,27 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_account_with_config(pubkey, config))
     }
 
+    /// Returns the account information for a [`Pubkey`] as of a past rooted slot.
+    ///
+    /// The node rebuilds the bank at `slot` from its snapshot archives and
+    /// ledger, so this can take a long time and is only served by nodes that
+    /// opted in with `--enable-rpc-historical-account-reads`.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getHistoricalAccountInfo` RPC
+    /// method.
+    pub fn get_historical_account_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoConfig,
+    ) -> RpcResult<Option<Account>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_historical_account_with_config(pubkey, slot, config),
+        )
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -4099,7 +4194,7 @@ mod tests {
         jsonrpc_core::{futures::prelude::*, Error, IoHandler, Params},
         jsonrpc_http_server::{AccessControlAllowOrigin, DomainsValidation, ServerBuilder},
         serde_json::{json, Number},
-        solana_rpc_client_api::client_error::ErrorKind,
+        solana_rpc_client_api::{client_error::ErrorKind, request::RpcError},
         solana_sdk::{
             instruction::InstructionError,
             signature::{Keypair, Signer},
@@ -4180,6 +4275,68 @@ mod tests {
         assert!(blockhash.is_err());
     }
 
+    #[test]
+    fn test_send_batch() {
+        let (sender, receiver) = unbounded();
+        thread::spawn(move || {
+            let rpc_addr = "0.0.0.0:0".parse().unwrap();
+            let mut io = IoHandler::default();
+            io.add_method("getBalance", |_params: Params| {
+                future::ok(Value::Number(Number::from(50)))
+            });
+            io.add_method("getSlot", |_params: Params| {
+                future::ok(Value::Number(Number::from(7)))
+            });
+            io.add_method("getBlockHeight", |_params: Params| {
+                future::err(Error::invalid_params("bad commitment"))
+            });
+
+            let server = ServerBuilder::new(io)
+                .threads(1)
+                .start_http(&rpc_addr)
+                .expect("Unable to start RPC server");
+            sender.send(*server.address()).unwrap();
+            server.wait();
+        });
+
+        let rpc_addr = receiver.recv().unwrap();
+        let rpc_client = RpcClient::new_socket(rpc_addr);
+
+        let mut batch = RpcBatch::new();
+        let balances: Vec<_> = (0..3)
+            .map(|_| {
+                batch.add::<u64>(
+                    RpcRequest::GetBalance,
+                    vec![json!(Pubkey::new_unique().to_string())],
+                )
+            })
+            .collect();
+        let slot = batch.get_slot_with_commitment(CommitmentConfig::confirmed());
+        let block_height = batch.get_block_height_with_commitment(CommitmentConfig::confirmed());
+        // The right call, with the wrong result type
+        let wrong_type = batch.add::<String>(RpcRequest::GetSlot, vec![]);
+        assert_eq!(batch.len(), 6);
+
+        let mut results = rpc_client.send_batch(batch).unwrap();
+        assert_eq!(rpc_client.get_transport_stats().request_count, 1);
+        for balance in balances {
+            assert_eq!(results.take(balance).unwrap(), 50);
+        }
+        assert_eq!(results.take(slot).unwrap(), 7);
+        assert_matches!(
+            results.take(block_height).unwrap_err().kind,
+            ErrorKind::RpcError(RpcError::RpcResponseError { .. })
+        );
+        assert_matches!(
+            results.take(wrong_type).unwrap_err().kind,
+            ErrorKind::SerdeJson(_)
+        );
+
+        // Empty batches aren't sent
+        assert!(rpc_client.send_batch(RpcBatch::new()).is_ok());
+        assert_eq!(rpc_client.get_transport_stats().request_count, 1);
+    }
+
     #[test]
     fn test_send_transaction() {
         let rpc_client = RpcClient::new_mock("succeeds".to_string());
@@ -31,6 +31,21 @@ pub trait RpcSender {
         request: RpcRequest,
         params: serde_json::Value,
     ) -> Result<serde_json::Value>;
+
+    /// Send several requests at once, returning the result of each in order.
+    ///
+    /// The default implementation sends them one at a time.
+    async fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, serde_json::Value)>,
+    ) -> Result<Vec<Result<serde_json::Value>>> {
+        let mut results = Vec::with_capacity(requests.len());
+        for (request, params) in requests {
+            results.push(self.send(request, params).await);
+        }
+        Ok(results)
+    }
+
     fn get_transport_stats(&self) -> RpcTransportStats;
     fn url(&self) -> String;
 }
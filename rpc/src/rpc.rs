@@ -9,6 +9,7 @@ use {
     crossbeam_channel::{unbounded, Receiver, Sender},
     jsonrpc_core::{futures::future, types::error, BoxFuture, Error, Metadata, Result},
     jsonrpc_derive::rpc,
+    rayon::{ThreadPool, ThreadPoolBuilder},
     solana_account_decoder::{
         parse_token::{is_known_spl_token_id, token_amount_to_ui_amount, UiTokenAmount},
         UiAccount, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
@@ -16,6 +17,7 @@ use {
     solana_accounts_db::{
         accounts::AccountAddressFilter,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
+        utils::create_all_accounts_run_and_snapshot_dirs,
     },
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::entry::Entry,
@@ -26,9 +28,12 @@ use {
         token_2022::{self, ACCOUNTTYPE_ACCOUNT},
     },
     solana_ledger::{
+        bank_forks_utils,
         blockstore::{Blockstore, SignatureInfosForAddress},
         blockstore_db::BlockstoreError,
         blockstore_meta::{PerfSample, PerfSampleV1, PerfSampleV2},
+        blockstore_options::{AccessType, BlockstoreOptions},
+        blockstore_processor::ProcessOptions,
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
     },
@@ -44,7 +49,8 @@ use {
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
             MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_BUNDLE_TRANSACTIONS,
+            NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
@@ -68,6 +74,7 @@ use {
         exit::Exit,
         feature_set,
         fee_calculator::FeeCalculator,
+        genesis_config::GenesisConfig,
         hash::Hash,
         message::SanitizedMessage,
         pubkey::{Pubkey, PUBKEY_BYTES},
@@ -86,7 +93,7 @@ use {
         tpu_info::NullTpuInfo,
     },
     solana_stake_program,
-    solana_storage_bigtable::Error as StorageError,
+    solana_storage_bigtable::{Error as StorageError, LongTermLedgerStorage},
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
         map_inner_instructions, BlockEncodingOptions, ConfirmedBlock,
@@ -105,7 +112,9 @@ use {
         cmp::{max, min, Reverse},
         collections::{BinaryHeap, HashMap, HashSet},
         convert::TryFrom,
+        fs,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -113,6 +122,7 @@ use {
         },
         time::Duration,
     },
+    tokio::sync::oneshot,
 };
 
 pub mod account_resolver;
@@ -146,6 +156,9 @@ pub struct JsonRpcConfig {
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
+    /// Keep the full ledger history in this directory, which is used as a fallback for blocks and
+    /// transactions cleaned up from the blockstore and receives new rooted blocks
+    pub rpc_filesystem_ledger_storage_path: Option<PathBuf>,
     pub max_multiple_accounts: Option<usize>,
     pub account_indexes: AccountSecondaryIndexes,
     pub rpc_threads: usize,
@@ -154,6 +167,8 @@ pub struct JsonRpcConfig {
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
     pub max_request_body_size: Option<usize>,
+    /// Serve `getHistoricalAccountInfo` by replaying the ledger from local snapshot archives
+    pub enable_historical_account_reads: bool,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
 }
@@ -204,7 +219,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    long_term_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
@@ -212,13 +227,15 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    historical_account_read_pool: Option<Arc<ThreadPool>>,
+    historical_account_read_in_progress: Arc<AtomicBool>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
 impl JsonRpcRequestProcessor {
     pub fn clone_without_bigtable(&self) -> JsonRpcRequestProcessor {
         Self {
-            bigtable_ledger_storage: None, // Disable BigTable
+            long_term_ledger_storage: None, // Disable BigTable and other long-term storage
             ..self.clone()
         }
     }
@@ -320,7 +337,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        long_term_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -330,6 +347,17 @@ impl JsonRpcRequestProcessor {
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
+        // Rebuilding a bank replays the ledger, which can take minutes, so it is kept off the RPC
+        // worker threads
+        let historical_account_read_pool = config.enable_historical_account_reads.then(|| {
+            Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(1)
+                    .thread_name(|i| format!("solRpcHistAcc{i:02}"))
+                    .build()
+                    .unwrap(),
+            )
+        });
         (
             Self {
                 config,
@@ -342,7 +370,7 @@ impl JsonRpcRequestProcessor {
                 cluster_info,
                 genesis_hash,
                 transaction_sender: Arc::new(Mutex::new(sender)),
-                bigtable_ledger_storage,
+                long_term_ledger_storage,
                 optimistically_confirmed_bank,
                 largest_accounts_cache,
                 max_slots,
@@ -350,6 +378,8 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                historical_account_read_pool,
+                historical_account_read_in_progress: Arc::default(),
             },
             receiver,
         )
@@ -416,7 +446,7 @@ impl JsonRpcRequestProcessor {
             cluster_info,
             genesis_hash,
             transaction_sender: Arc::new(Mutex::new(sender)),
-            bigtable_ledger_storage: None,
+            long_term_ledger_storage: None,
             optimistically_confirmed_bank,
             largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::new(30))),
             max_slots: Arc::new(MaxSlots::default()),
@@ -424,6 +454,8 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            historical_account_read_pool: None,
+            historical_account_read_in_progress: Arc::default(),
         }
     }
 
@@ -472,6 +504,145 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, accounts))
     }
 
+    pub async fn get_historical_account_info(
+        &self,
+        pubkey: Pubkey,
+        slot: Slot,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        let Some(historical_account_read_pool) = self.historical_account_read_pool.as_ref() else {
+            return Err(RpcCustomError::HistoricalAccountReadsNotAvailable.into());
+        };
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            ..
+        } = config.unwrap_or_default();
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+
+        let (root, live_bank) = {
+            let bank_forks = self.bank_forks.read().unwrap();
+            (bank_forks.root(), bank_forks.get(slot))
+        };
+        if slot > root {
+            return Err(Error::invalid_params(format!(
+                "Slot {slot} is not rooted yet; highest root is {root}"
+            )));
+        }
+        if let Some(bank) = live_bank {
+            let response = get_encoded_account(&bank, &pubkey, encoding, data_slice, None)?;
+            return Ok(new_response(&bank, response));
+        }
+        if !self.blockstore.is_root(slot) {
+            return Err(RpcCustomError::SlotSkipped { slot }.into());
+        }
+        let snapshot_config = self
+            .snapshot_config
+            .clone()
+            .ok_or(RpcCustomError::NoSnapshot)?;
+
+        // Only one rebuild at a time, each one replays the ledger into its own accounts db
+        if self
+            .historical_account_read_in_progress
+            .swap(true, Ordering::AcqRel)
+        {
+            return Err(RpcCustomError::HistoricalAccountReadInProgress.into());
+        }
+        let (sender, receiver) = oneshot::channel();
+        let processor = self.clone();
+        historical_account_read_pool.spawn(move || {
+            let response = processor.rebuild_historical_account(
+                &pubkey,
+                slot,
+                encoding,
+                data_slice,
+                &snapshot_config,
+            );
+            processor
+                .historical_account_read_in_progress
+                .store(false, Ordering::Release);
+            let _ = sender.send(response);
+        });
+        receiver.await.map_err(|_| Error::internal_error())?
+    }
+
+    fn rebuild_historical_account(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        encoding: UiAccountEncoding,
+        data_slice: Option<UiDataSliceConfig>,
+        snapshot_config: &SnapshotConfig,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        let ledger_path = self.blockstore.ledger_path();
+        let genesis_config = GenesisConfig::load(ledger_path).map_err(|err| {
+            warn!("get_historical_account_info: failed to load genesis config: {err}");
+            Error::internal_error()
+        })?;
+        // Replay records roots, dead slots and bank hashes in a primary blockstore, which must
+        // not happen to the validator's own
+        let blockstore = Blockstore::open_with_options(
+            ledger_path,
+            BlockstoreOptions {
+                access_type: AccessType::Secondary,
+                enforce_ulimit_nofile: false,
+                column_options: self.blockstore.column_options().clone(),
+                ..BlockstoreOptions::default()
+            },
+        )
+        .map_err(|err| {
+            warn!("get_historical_account_info: failed to open blockstore: {err}");
+            Error::internal_error()
+        })?;
+
+        // The scratch directory keeps the rebuilt accounts and bank snapshots away from the
+        // validator's own, which would otherwise be purged while loading
+        let scratch_dir = ledger_path.join("historical-account-reads");
+        let _ = fs::remove_dir_all(&scratch_dir);
+        let (account_paths, _) =
+            create_all_accounts_run_and_snapshot_dirs(&[scratch_dir.join("accounts")]).map_err(
+                |err| {
+                    warn!("get_historical_account_info: failed to create accounts dirs: {err}");
+                    Error::internal_error()
+                },
+            )?;
+        let snapshot_config = SnapshotConfig {
+            full_snapshot_archives_dir: snapshot_config.full_snapshot_archives_dir.clone(),
+            incremental_snapshot_archives_dir: snapshot_config
+                .incremental_snapshot_archives_dir
+                .clone(),
+            bank_snapshots_dir: scratch_dir.join("snapshots"),
+            ..SnapshotConfig::new_load_only()
+        };
+        let exit = Arc::new(AtomicBool::new(false));
+
+        let response = bank_forks_utils::load_bank_at_slot(
+            &genesis_config,
+            &blockstore,
+            account_paths,
+            Some(&snapshot_config),
+            slot,
+            ProcessOptions {
+                account_indexes: self.config.account_indexes.clone(),
+                ..ProcessOptions::default()
+            },
+            exit.clone(),
+        )
+        .map_err(|err| Error {
+            code: error::ErrorCode::InternalError,
+            message: format!("Unable to rebuild bank at slot {slot}: {err}"),
+            data: None,
+        })
+        .and_then(|bank| {
+            let account = get_encoded_account(&bank, pubkey, encoding, data_slice, None)?;
+            Ok(new_response(&bank, account))
+        });
+
+        exit.store(true, Ordering::Relaxed);
+        let _ = fs::remove_dir_all(&scratch_dir);
+        response
+    }
+
     pub fn get_minimum_balance_for_rent_exemption(
         &self,
         data_len: usize,
@@ -552,7 +723,7 @@ impl JsonRpcRequestProcessor {
         // Rewards for this epoch are found in the first confirmed block of the next epoch
         let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(epoch.saturating_add(1));
         if first_slot_in_epoch < first_available_block {
-            if self.bigtable_ledger_storage.is_some() {
+            if self.long_term_ledger_storage.is_some() {
                 return Err(RpcCustomError::LongTermStorageSlotSkipped {
                     slot: first_slot_in_epoch,
                 }
@@ -1127,9 +1298,9 @@ impl JsonRpcRequestProcessor {
                     Ok(encoded_block)
                 };
                 if result.is_err() {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
+                    if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
                         let bigtable_result =
-                            bigtable_ledger_storage.get_confirmed_block(slot).await;
+                            long_term_ledger_storage.get_confirmed_block(slot).await;
                         self.check_bigtable_result(&bigtable_result)?;
                         return bigtable_result.ok().map(encode_block).transpose();
                     }
@@ -1227,8 +1398,8 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // [start_slot..end_slot] can be fetched from BigTable. This range should not ever run
             // into unfinalized confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return bigtable_ledger_storage
+            if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                return long_term_ledger_storage
                     .get_confirmed_blocks(start_slot, (end_slot - start_slot) as usize + 1) // increment limit by 1 to ensure returned range is inclusive of both start_slot and end_slot
                     .await
                     .map(|mut bigtable_blocks| {
@@ -1297,8 +1468,8 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // range can be fetched from BigTable. This range should not ever run into unfinalized
             // confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return Ok(bigtable_ledger_storage
+            if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                return Ok(long_term_ledger_storage
                     .get_confirmed_blocks(start_slot, limit)
                     .await
                     .unwrap_or_default());
@@ -1365,8 +1536,8 @@ impl JsonRpcRequestProcessor {
             let result = self.blockstore.get_rooted_block_time(slot);
             self.check_blockstore_root(&result, slot)?;
             if result.is_err() {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let bigtable_result = bigtable_ledger_storage.get_confirmed_block(slot).await;
+                if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                    let bigtable_result = long_term_ledger_storage.get_confirmed_block(slot).await;
                     self.check_bigtable_result(&bigtable_result)?;
                     return Ok(bigtable_result
                         .ok()
@@ -1458,8 +1629,8 @@ impl JsonRpcRequestProcessor {
                     })
                 {
                     Some(status)
-                } else if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    bigtable_ledger_storage
+                } else if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                    long_term_ledger_storage
                         .get_signature_status(&signature)
                         .await
                         .map(Some)
@@ -1567,8 +1738,8 @@ impl JsonRpcRequestProcessor {
                     }
                 }
                 None => {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        return bigtable_ledger_storage
+                    if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                        return long_term_ledger_storage
                             .get_confirmed_transaction(&signature)
                             .await
                             .unwrap_or(None)
@@ -1590,7 +1761,7 @@ impl JsonRpcRequestProcessor {
         end_slot: Slot,
     ) -> Vec<Signature> {
         if self.config.enable_rpc_transaction_history {
-            // TODO: Add bigtable_ledger_storage support as a part of
+            // TODO: Add long_term_ledger_storage support as a part of
             // https://github.com/solana-labs/solana/pull/10928
             let end_slot = min(
                 end_slot,
@@ -1670,7 +1841,7 @@ impl JsonRpcRequestProcessor {
             };
 
             if results.len() < limit {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
+                if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
                     let mut bigtable_before = before;
                     if !results.is_empty() {
                         limit -= results.len();
@@ -1681,7 +1852,7 @@ impl JsonRpcRequestProcessor {
                     // uploaded to long-term storage, modify the storage query to return all latest
                     // signatures to prevent erroring on RowNotFound. This can race with upload.
                     if found_before && bigtable_before.is_some() {
-                        match bigtable_ledger_storage
+                        match long_term_ledger_storage
                             .get_signature_status(&bigtable_before.unwrap())
                             .await
                         {
@@ -1696,7 +1867,7 @@ impl JsonRpcRequestProcessor {
                         }
                     }
 
-                    let bigtable_results = bigtable_ledger_storage
+                    let bigtable_results = long_term_ledger_storage
                         .get_confirmed_signatures_for_address(
                             &address,
                             bigtable_before.as_ref(),
@@ -1738,8 +1909,8 @@ impl JsonRpcRequestProcessor {
             .get_first_available_block()
             .unwrap_or_default();
 
-        if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-            let bigtable_slot = bigtable_ledger_storage
+        if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+            let bigtable_slot = long_term_ledger_storage
                 .get_first_available_block()
                 .await
                 .unwrap_or(None)
@@ -3014,6 +3185,15 @@ pub mod rpc_accounts {
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<Option<UiAccount>>>>;
 
+        #[rpc(meta, name = "getHistoricalAccountInfo")]
+        fn get_historical_account_info(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoConfig>,
+        ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>>;
+
         #[rpc(meta, name = "getBlockCommitment")]
         fn get_block_commitment(
             &self,
@@ -3084,6 +3264,24 @@ pub mod rpc_accounts {
             meta.get_multiple_accounts(pubkeys, config)
         }
 
+        fn get_historical_account_info(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoConfig>,
+        ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>> {
+            debug!(
+                "get_historical_account_info rpc request received: {:?} at slot {}",
+                pubkey_str, slot
+            );
+            let pubkey = match verify_pubkey(&pubkey_str) {
+                Ok(pubkey) => pubkey,
+                Err(err) => return Box::pin(future::err(err)),
+            };
+            Box::pin(async move { meta.get_historical_account_info(pubkey, slot, config).await })
+        }
+
         fn get_block_commitment(
             &self,
             meta: Self::Metadata,
@@ -3352,6 +3550,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3708,6 +3914,7 @@ pub mod rpc_full {
                     units_consumed,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
+                    instruction_compute_units: _,
                 } = preflight_bank.simulate_transaction(&transaction, false)
                 {
                     match err {
@@ -3728,6 +3935,8 @@ pub mod rpc_full {
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
                             replacement_blockhash: None,
+                            account_diffs: None,
+                            instruction_units_consumed: None,
                         },
                     }
                     .into());
@@ -3759,6 +3968,8 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                account_diffs: enable_account_diffs,
+                instruction_units,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3799,6 +4010,17 @@ pub mod rpc_full {
                 verify_transaction(&transaction, &bank.feature_set)?;
             }
 
+            let pre_simulation_writable_accounts = enable_account_diffs.then(|| {
+                transaction
+                    .message()
+                    .account_keys()
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| transaction.message().is_writable(*index))
+                    .map(|(_, pubkey)| (*pubkey, bank.get_account(pubkey).unwrap_or_default()))
+                    .collect::<Vec<_>>()
+            });
+
             let TransactionSimulationResult {
                 result,
                 logs,
@@ -3806,11 +4028,30 @@ pub mod rpc_full {
                 units_consumed,
                 return_data,
                 inner_instructions,
+                instruction_compute_units,
             } = bank.simulate_transaction(&transaction, enable_cpi_recording);
 
             let account_keys = transaction.message().account_keys();
             let number_of_accounts = account_keys.len();
 
+            let account_diffs = if result.is_err() {
+                None
+            } else {
+                pre_simulation_writable_accounts.map(|pre_simulation_writable_accounts| {
+                    pre_simulation_writable_accounts
+                        .iter()
+                        .filter_map(|(pubkey, pre_account)| {
+                            post_simulation_accounts
+                                .iter()
+                                .find(|(post_pubkey, _)| post_pubkey == pubkey)
+                                .map(|(_, post_account)| {
+                                    RpcAccountDiff::new(pubkey, pre_account, post_account)
+                                })
+                        })
+                        .collect()
+                })
+            };
+
             let accounts = if let Some(config_accounts) = config_accounts {
                 let accounts_encoding = config_accounts
                     .encoding
@@ -3873,10 +4114,178 @@ pub mod rpc_full {
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
                     replacement_blockhash: blockhash,
+                    account_diffs,
+                    instruction_units_consumed: instruction_compute_units
+                        .filter(|_| instruction_units),
                 },
             ))
         }
 
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("No transactions provided"));
+            }
+            if data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+
+            let config_accounts = config_accounts
+                .map(|config_accounts| {
+                    let accounts_encoding = config_accounts
+                        .encoding
+                        .unwrap_or(UiAccountEncoding::Base64);
+                    if accounts_encoding == UiAccountEncoding::Binary
+                        || accounts_encoding == UiAccountEncoding::Base58
+                    {
+                        return Err(Error::invalid_params("base58 encoding not supported"));
+                    }
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+                    let addresses = config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address_str| verify_pubkey(address_str))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((addresses, accounts_encoding))
+                })
+                .transpose()?;
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let mut blockhash: Option<RpcBlockhash> = None;
+            if replace_recent_blockhash {
+                if sig_verify {
+                    return Err(Error::invalid_params(
+                        "sigVerify may not be used with replaceRecentBlockhash",
+                    ));
+                }
+                let recent_blockhash = bank.last_blockhash();
+                let last_valid_block_height = bank
+                    .get_blockhash_last_valid_block_height(&recent_blockhash)
+                    .expect("bank blockhash queue should contain blockhash");
+                blockhash.replace(RpcBlockhash {
+                    blockhash: recent_blockhash.to_string(),
+                    last_valid_block_height,
+                });
+            }
+
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
+                    }
+                    let transaction = sanitize_transaction(
+                        unsanitized_tx,
+                        bank,
+                        bank.get_reserved_account_keys(),
+                    )?;
+                    if sig_verify {
+                        verify_transaction(&transaction, &bank.feature_set)?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let simulation_results =
+                bank.simulate_transaction_bundle(&transactions, enable_cpi_recording);
+
+            // Accumulates the account state left behind by each successive transaction
+            let mut post_simulation_accounts_map = HashMap::new();
+            let results = transactions
+                .iter()
+                .zip(simulation_results)
+                .map(|(transaction, simulation_result)| {
+                    let TransactionSimulationResult {
+                        result,
+                        logs,
+                        post_simulation_accounts,
+                        units_consumed,
+                        return_data,
+                        inner_instructions,
+                        instruction_compute_units: _,
+                    } = simulation_result;
+
+                    let accounts = if let Some((addresses, accounts_encoding)) = &config_accounts {
+                        if result.is_err() {
+                            Some(vec![None; addresses.len()])
+                        } else {
+                            post_simulation_accounts_map.extend(post_simulation_accounts);
+                            Some(
+                                addresses
+                                    .iter()
+                                    .map(|pubkey| {
+                                        get_encoded_account(
+                                            bank,
+                                            pubkey,
+                                            *accounts_encoding,
+                                            None,
+                                            Some(&post_simulation_accounts_map),
+                                        )
+                                    })
+                                    .collect::<Result<Vec<_>>>()?,
+                            )
+                        }
+                    } else {
+                        None
+                    };
+
+                    let account_keys = transaction.message().account_keys();
+                    let inner_instructions = inner_instructions.map(|info| {
+                        map_inner_instructions(info)
+                            .map(|converted| UiInnerInstructions::parse(converted, &account_keys))
+                            .collect()
+                    });
+
+                    Ok(RpcSimulateTransactionResult {
+                        err: result.err(),
+                        logs: Some(logs),
+                        accounts,
+                        units_consumed: Some(units_consumed),
+                        return_data: return_data.map(|return_data| return_data.into()),
+                        inner_instructions,
+                        replacement_blockhash: blockhash.clone(),
+                        account_diffs: None,
+                        instruction_units_consumed: None,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(bank, results))
+        }
+
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
             debug!("minimum_ledger_slot rpc request received");
             meta.minimum_ledger_slot()
@@ -4755,11 +5164,13 @@ pub mod tests {
         solana_ledger::{
             blockstore_meta::PerfSampleV2,
             blockstore_processor::fill_blockstore_slot_with_ticks,
+            create_new_tmp_ledger_auto_delete,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
         solana_rpc_client_api::{
             custom_error::{
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READS_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -5698,6 +6109,126 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_rpc_get_historical_account_info() {
+        let address = Pubkey::new_unique().to_string();
+
+        let rpc = RpcHandler::start();
+        let request = create_test_request("getHistoricalAccountInfo", Some(json!([address, 0])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNT_READS_NOT_AVAILABLE,
+            String::from("Historical account reads are not available from this node"),
+        );
+        assert_eq!(response, expected);
+
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_historical_account_reads: true,
+            ..JsonRpcConfig::default()
+        });
+        let request = create_test_request(
+            "getHistoricalAccountInfo",
+            Some(json!([rpc.mint_keypair.pubkey().to_string(), 0])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        let expected = json!({
+            "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
+            "value":{
+                "owner": "11111111111111111111111111111111",
+                "lamports": TEST_MINT_LAMPORTS,
+                "data": "",
+                "executable": false,
+                "rentEpoch": 0,
+                "space": 0,
+            },
+        });
+        assert_eq!(result, expected);
+
+        let request = create_test_request("getHistoricalAccountInfo", Some(json!([address, 1])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Slot 1 is not rooted yet; highest root is 0"),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_get_historical_account_info_rebuild() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(TEST_MINT_LAMPORTS);
+        let (ledger_path, _) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let snapshot_archives_dir = ledger_path.path().join("snapshot-archives");
+        fs::create_dir_all(&snapshot_archives_dir).unwrap();
+        let snapshot_config = SnapshotConfig {
+            full_snapshot_archives_dir: snapshot_archives_dir.clone(),
+            incremental_snapshot_archives_dir: snapshot_archives_dir,
+            ..SnapshotConfig::new_load_only()
+        };
+
+        // Root slot 1, so that slot 0 is no longer in bank forks and has to be rebuilt
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let bank0 = bank_forks.read().unwrap().root_bank();
+        let bank1 = Bank::new_from_parent(bank0, &Pubkey::default(), 1);
+        bank_forks.write().unwrap().insert(bank1);
+        bank_forks
+            .write()
+            .unwrap()
+            .set_root(1, &AbsRequestSender::default(), None)
+            .unwrap();
+        assert!(bank_forks.read().unwrap().get(0).is_none());
+
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let bank = bank_forks.read().unwrap().root_bank();
+        let meta = JsonRpcRequestProcessor::new(
+            JsonRpcConfig {
+                enable_historical_account_reads: true,
+                ..JsonRpcConfig::default()
+            },
+            Some(snapshot_config),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::default())),
+            blockstore.clone(),
+            create_validator_exit(Arc::new(AtomicBool::new(false))),
+            RpcHealth::stub(optimistically_confirmed_bank.clone(), blockstore.clone()),
+            Arc::new(new_test_cluster_info()),
+            Hash::default(),
+            None,
+            optimistically_confirmed_bank,
+            Arc::new(RwLock::new(LargestAccountsCache::new(30))),
+            Arc::new(MaxSlots::default()),
+            Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            Arc::new(PrioritizationFeeCache::default()),
+        )
+        .0;
+        let mut io = MetaIoHandler::default();
+        io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
+
+        let request = create_test_request(
+            "getHistoricalAccountInfo",
+            Some(json!([mint_keypair.pubkey().to_string(), 0])),
+        );
+        let response = io
+            .handle_request_sync(&request.to_string(), meta.clone())
+            .unwrap();
+        let result: Value = parse_success_result(serde_json::from_str(&response).unwrap());
+        assert_eq!(result["context"]["slot"], 0);
+        assert_eq!(result["value"]["lamports"], TEST_MINT_LAMPORTS);
+
+        // The rebuild is cleaned up and another one may start
+        assert!(!meta
+            .historical_account_read_in_progress
+            .load(Ordering::Acquire));
+        assert!(!ledger_path.path().join("historical-account-reads").exists());
+    }
+
     #[test]
     fn test_rpc_get_multiple_accounts() {
         let rpc = RpcHandler::start();
@@ -6035,6 +6566,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData":null,
                     "unitsConsumed":150,
@@ -6121,6 +6654,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData":null,
                     "unitsConsumed":150,
@@ -6151,6 +6686,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData": null,
                     "unitsConsumed":150,
@@ -6202,6 +6739,8 @@ pub mod tests {
                     "accounts":null,
                     "innerInstructions":null,
                     "logs":[],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData": null,
                     "unitsConsumed":0,
@@ -6238,6 +6777,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": {
                         "blockhash": latest_blockhash.to_string(),
                         "lastValidBlockHeight": expiry_slot
@@ -6386,6 +6927,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData": null,
                     "unitsConsumed": 150,
@@ -6467,6 +7010,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success",
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData":null,
                     "unitsConsumed":1200,
@@ -6511,6 +7056,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success",
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData":null,
                     "unitsConsumed":1200,
@@ -6598,6 +7145,8 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success",
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
+                    "accountDiffs": null,
+                    "instructionUnitsConsumed": null,
                     "replacementBlockhash": null,
                     "returnData":null,
                     "unitsConsumed":1200,
@@ -6612,6 +7161,149 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction_with_account_diffs() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta,
+            ref io,
+            ref mint_keypair,
+            ..
+        } = rpc;
+
+        let bob_pubkey = Pubkey::new_unique();
+        let tx = system_transaction::transfer(
+            mint_keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+        let mint_balance = bank.get_balance(&mint_keypair.pubkey());
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}", {{"accountDiffs": true, "instructionUnits": true}}]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let result: RpcSimulateTransactionResult =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(result.err, None);
+        assert_eq!(result.instruction_units_consumed, Some(vec![150]));
+
+        let account_diffs = result.account_diffs.unwrap();
+        assert_eq!(account_diffs.len(), 2);
+        let mint_diff = &account_diffs[0];
+        assert_eq!(mint_diff.pubkey, mint_keypair.pubkey().to_string());
+        assert_eq!(mint_diff.pre_lamports, mint_balance);
+        assert!(mint_diff.post_lamports <= mint_balance - rent_exempt_amount);
+        assert!(mint_diff.data_changes.is_empty());
+        assert_eq!(
+            account_diffs[1],
+            RpcAccountDiff {
+                pubkey: bob_pubkey.to_string(),
+                pre_lamports: 0,
+                post_lamports: rent_exempt_amount,
+                pre_owner: system_program::id().to_string(),
+                post_owner: system_program::id().to_string(),
+                pre_data_len: 0,
+                post_data_len: 0,
+                data_changes: vec![],
+            }
+        );
+
+        // Neither diffs nor per-instruction units are returned unless requested
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}"]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let result: RpcSimulateTransactionResult =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(result.account_diffs, None);
+        assert_eq!(result.instruction_units_consumed, None);
+    }
+
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta,
+            ref io,
+            ref mint_keypair,
+            ..
+        } = rpc;
+
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let encode = |tx: &Transaction| BASE64_STANDARD.encode(serialize(tx).unwrap());
+        let fund_alice = encode(&system_transaction::transfer(
+            mint_keypair,
+            &alice.pubkey(),
+            10 * rent_exempt_amount,
+            recent_blockhash,
+        ));
+        let fund_bob = encode(&system_transaction::transfer(
+            &alice,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        ));
+        bank.freeze();
+
+        let simulate_bundle = |transactions: &[&String]| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "simulateBundle",
+                "params": [
+                    transactions,
+                    {
+                        "encoding": "base64",
+                        "accounts": {
+                            "encoding": "base64",
+                            "addresses": [bob_pubkey.to_string()],
+                        },
+                    },
+                ],
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            serde_json::from_str::<Value>(&res.unwrap()).unwrap()
+        };
+
+        // alice can only pay bob if she sees the funds transferred by the first transaction
+        let json = simulate_bundle(&[&fund_alice, &fund_bob]);
+        let results: Vec<RpcSimulateTransactionResult> =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].err, None);
+        assert_eq!(results[0].accounts, Some(vec![None]));
+        assert_eq!(results[1].err, None);
+        let bob_account = results[1].accounts.as_ref().unwrap()[0].as_ref().unwrap();
+        assert_eq!(bob_account.lamports, rent_exempt_amount);
+
+        // Without the funding transaction, simulation stops at the first failure
+        let json = simulate_bundle(&[&fund_bob, &fund_alice]);
+        let results: Vec<RpcSimulateTransactionResult> =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].err.is_some());
+        assert_eq!(results[0].accounts, Some(vec![None]));
+
+        // Nothing is committed to the bank
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        let json = simulate_bundle(&[]);
+        assert_eq!(json["error"]["message"], "No transactions provided");
+    }
+
     #[test]
     #[should_panic(expected = "simulation bank must be frozen")]
     fn test_rpc_simulate_transaction_panic_on_unfrozen_bank() {
@@ -6974,7 +7666,7 @@ pub mod tests {
         assert_eq!(
             res,
             Some(
-                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"logs":[],"replacementBlockhash":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
+                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accountDiffs":null,"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"instructionUnitsConsumed":null,"logs":[],"replacementBlockhash":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
             )
         );
 
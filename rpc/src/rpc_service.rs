@@ -41,7 +41,7 @@ use {
         native_token::lamports_to_sol,
     },
     solana_send_transaction_service::send_transaction_service::{self, SendTransactionService},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, FilesystemLedgerStorage, LongTermLedgerStorage},
     std::{
         net::SocketAddr,
         path::{Path, PathBuf},
@@ -122,6 +122,47 @@ impl RpcRequestMiddleware {
             .unwrap()
     }
 
+    /// List the file names of the chain of incremental snapshot archives that ends at the highest
+    /// incremental snapshot archive, oldest first and one per line
+    fn incremental_snapshot_archive_chain(
+        snapshot_config: &SnapshotConfig,
+    ) -> hyper::Response<hyper::Body> {
+        let Some(full_snapshot_archive_info) =
+            snapshot_utils::get_highest_full_snapshot_archive_info(
+                &snapshot_config.full_snapshot_archives_dir,
+            )
+        else {
+            return Self::not_found();
+        };
+        let Some(incremental_snapshot_archive_info) =
+            snapshot_utils::get_highest_incremental_snapshot_archive_info(
+                &snapshot_config.incremental_snapshot_archives_dir,
+                full_snapshot_archive_info.slot(),
+            )
+        else {
+            return Self::not_found();
+        };
+
+        let file_names = snapshot_utils::get_incremental_snapshot_archive_chain(
+            &snapshot_config.incremental_snapshot_archives_dir,
+            full_snapshot_archive_info.slot(),
+            &incremental_snapshot_archive_info,
+        )
+        .iter()
+        .filter_map(|snapshot_archive_info| {
+            snapshot_archive_info
+                .path()
+                .file_name()?
+                .to_str()
+                .map(|file_name| format!("{file_name}\n"))
+        })
+        .collect::<String>();
+        hyper::Response::builder()
+            .status(hyper::StatusCode::OK)
+            .body(hyper::Body::from(file_names))
+            .unwrap()
+    }
+
     fn strip_leading_slash(path: &str) -> Option<&str> {
         path.strip_prefix('/')
     }
@@ -249,6 +290,11 @@ impl RequestMiddleware for RpcRequestMiddleware {
         trace!("request uri: {}", request.uri());
 
         if let Some(ref snapshot_config) = self.snapshot_config {
+            if request.uri().path()
+                == snapshot_utils::INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_DOWNLOAD_PATH
+            {
+                return Self::incremental_snapshot_archive_chain(snapshot_config).into();
+            }
             if request.uri().path() == FULL_SNAPSHOT_REQUEST_PATH
                 || request.uri().path() == INCREMENTAL_SNAPSHOT_REQUEST_PATH
             {
@@ -401,7 +447,19 @@ impl JsonRpcService {
 
         let exit_bigtable_ledger_upload_service = Arc::new(AtomicBool::new(false));
 
-        let (bigtable_ledger_storage, _bigtable_ledger_upload_service) =
+        let start_ledger_upload_service = |long_term_ledger_storage| {
+            Arc::new(BigTableUploadService::new_with_config(
+                runtime.clone(),
+                long_term_ledger_storage,
+                blockstore.clone(),
+                block_commitment_cache.clone(),
+                max_complete_transaction_status_slot.clone(),
+                max_complete_rewards_slot.clone(),
+                ConfirmedBlockUploadConfig::default(),
+                exit_bigtable_ledger_upload_service.clone(),
+            ))
+        };
+        let (long_term_ledger_storage, _bigtable_ledger_upload_service) =
             if let Some(RpcBigtableConfig {
                 enable_bigtable_ledger_upload,
                 ref bigtable_instance_name,
@@ -424,21 +482,11 @@ impl JsonRpcService {
                     ))
                     .map(|bigtable_ledger_storage| {
                         info!("BigTable ledger storage initialized");
+                        let bigtable_ledger_storage: Arc<dyn LongTermLedgerStorage> =
+                            Arc::new(bigtable_ledger_storage);
 
-                        let bigtable_ledger_upload_service = if enable_bigtable_ledger_upload {
-                            Some(Arc::new(BigTableUploadService::new_with_config(
-                                runtime.clone(),
-                                bigtable_ledger_storage.clone(),
-                                blockstore.clone(),
-                                block_commitment_cache.clone(),
-                                max_complete_transaction_status_slot.clone(),
-                                max_complete_rewards_slot.clone(),
-                                ConfirmedBlockUploadConfig::default(),
-                                exit_bigtable_ledger_upload_service.clone(),
-                            )))
-                        } else {
-                            None
-                        };
+                        let bigtable_ledger_upload_service = enable_bigtable_ledger_upload
+                            .then(|| start_ledger_upload_service(bigtable_ledger_storage.clone()));
 
                         (
                             Some(bigtable_ledger_storage),
@@ -449,6 +497,19 @@ impl JsonRpcService {
                         error!("Failed to initialize BigTable ledger storage: {:?}", err);
                         (None, None)
                     })
+            } else if let Some(ref path) = config.rpc_filesystem_ledger_storage_path {
+                FilesystemLedgerStorage::open(path)
+                    .map(|filesystem_ledger_storage| {
+                        let filesystem_ledger_storage: Arc<dyn LongTermLedgerStorage> =
+                            Arc::new(filesystem_ledger_storage);
+                        let ledger_upload_service =
+                            start_ledger_upload_service(filesystem_ledger_storage.clone());
+                        (Some(filesystem_ledger_storage), Some(ledger_upload_service))
+                    })
+                    .unwrap_or_else(|err| {
+                        error!("Failed to open filesystem ledger storage: {:?}", err);
+                        (None, None)
+                    })
             } else {
                 (None, None)
             };
@@ -468,7 +529,7 @@ impl JsonRpcService {
             health.clone(),
             cluster_info.clone(),
             genesis_hash,
-            bigtable_ledger_storage,
+            long_term_ledger_storage,
             optimistically_confirmed_bank,
             largest_accounts_cache,
             max_slots,
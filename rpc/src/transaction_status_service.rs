@@ -377,6 +377,7 @@ pub(crate) mod tests {
             )),
             return_data: None,
             executed_units: 0,
+            instruction_compute_units: None,
             accounts_data_len_delta: 0,
         });
 
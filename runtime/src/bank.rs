@@ -45,6 +45,7 @@ use {
                 EpochRewardCalculateParamInfo, EpochRewardStatus, StakeRewards, VoteRewardsAccounts,
             },
         },
+        bank_event_notifier_interface::{BankEvent, BankEventNotifierArc},
         bank_forks::BankForks,
         epoch_stakes::{EpochStakes, NodeVoteAccounts},
         installed_scheduler_pool::{BankWithScheduler, InstalledSchedulerRwLock},
@@ -63,7 +64,7 @@ use {
     },
     byteorder::{ByteOrder, LittleEndian},
     dashmap::{DashMap, DashSet},
-    itertools::izip,
+    itertools::{izip, Itertools},
     log::*,
     rayon::{
         iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
@@ -231,6 +232,10 @@ pub const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
 
 pub const MAX_LEADER_SCHEDULE_STAKES: Epoch = 5;
 
+/// After simulation, transactions will need to be forwarded to the leader for processing. During
+/// forwarding, the transaction could expire if the delay is not accounted for.
+const MAX_SIMULATION_AGE: usize = MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY;
+
 #[derive(Default)]
 struct RentMetrics {
     hold_range_us: AtomicU64,
@@ -361,6 +366,7 @@ pub struct TransactionSimulationResult {
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    pub instruction_compute_units: Option<Vec<u64>>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -578,6 +584,7 @@ impl PartialEq for Bank {
             feature_set: _,
             reserved_account_keys: _,
             drop_callback: _,
+            bank_event_notifier: _,
             freeze_started: _,
             vote_only_bank: _,
             cost_tracker: _,
@@ -657,6 +664,16 @@ impl AbiExample for OptionalDropCallback {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct OptionalBankEventNotifier(Option<BankEventNotifierArc>);
+
+#[cfg(RUSTC_WITH_SPECIALIZATION)]
+impl AbiExample for OptionalBankEventNotifier {
+    fn example() -> Self {
+        Self(None)
+    }
+}
+
 /// Manager for the state of all accounts and programs after processing its entries.
 /// AbiExample is needed even without Serialize/Deserialize; actual (de-)serialization
 /// are implemented elsewhere for versioning
@@ -803,6 +820,10 @@ pub struct Bank {
     /// callback function only to be called when dropping and should only be called once
     pub drop_callback: RwLock<OptionalDropCallback>,
 
+    /// receives epoch boundary, partitioned rewards and feature activation events;
+    /// inherited by child banks
+    bank_event_notifier: RwLock<OptionalBankEventNotifier>,
+
     pub freeze_started: AtomicBool,
 
     vote_only_bank: bool,
@@ -939,6 +960,7 @@ impl Bank {
             feature_set: Arc::<FeatureSet>::default(),
             reserved_account_keys: Arc::<ReservedAccountKeys>::default(),
             drop_callback: RwLock::new(OptionalDropCallback(None)),
+            bank_event_notifier: RwLock::new(OptionalBankEventNotifier(None)),
             freeze_started: AtomicBool::default(),
             vote_only_bank: false,
             cost_tracker: RwLock::<CostTracker>::default(),
@@ -1198,6 +1220,9 @@ impl Bank {
                     .as_ref()
                     .map(|drop_callback| drop_callback.clone_box()),
             )),
+            bank_event_notifier: RwLock::new(OptionalBankEventNotifier(
+                parent.bank_event_notifier.read().unwrap().0.clone(),
+            )),
             freeze_started: AtomicBool::new(false),
             cost_tracker: RwLock::new(CostTracker::default()),
             accounts_data_size_initial,
@@ -1238,6 +1263,12 @@ impl Bank {
                     parent.block_height(),
                     reward_calc_tracer,
                 );
+                new.notify_bank_event(&BankEvent::EpochBoundary {
+                    slot: new.slot(),
+                    parent_slot: parent.slot(),
+                    parent_epoch: parent.epoch(),
+                    epoch: new.epoch(),
+                });
             } else {
                 // Save a snapshot of stakes for use in consensus and stake weighted networking
                 let leader_schedule_epoch = new.epoch_schedule().get_leader_schedule_epoch(slot);
@@ -1514,6 +1545,18 @@ impl Bank {
         *self.drop_callback.write().unwrap() = OptionalDropCallback(callback);
     }
 
+    /// Set the notifier for bank-level events. Banks created from this one
+    /// via `new_from_parent` inherit it.
+    pub fn set_bank_event_notifier(&self, notifier: Option<BankEventNotifierArc>) {
+        *self.bank_event_notifier.write().unwrap() = OptionalBankEventNotifier(notifier);
+    }
+
+    pub(crate) fn notify_bank_event(&self, event: &BankEvent) {
+        if let Some(notifier) = self.bank_event_notifier.read().unwrap().0.as_ref() {
+            notifier.notify_bank_event(event);
+        }
+    }
+
     pub fn vote_only_bank(&self) -> bool {
         self.vote_only_bank
     }
@@ -1653,6 +1696,7 @@ impl Bank {
             feature_set: Arc::<FeatureSet>::default(),
             reserved_account_keys: Arc::<ReservedAccountKeys>::default(),
             drop_callback: RwLock::new(OptionalDropCallback(None)),
+            bank_event_notifier: RwLock::new(OptionalBankEventNotifier(None)),
             freeze_started: AtomicBool::new(fields.hash != Hash::default()),
             vote_only_bank: false,
             cost_tracker: RwLock::new(CostTracker::default()),
@@ -3431,9 +3475,123 @@ impl Bank {
         transaction: &SanitizedTransaction,
         enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
-        let account_keys = transaction.message().account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let account_overrides =
+            self.get_account_overrides_for_simulation(&transaction.message().account_keys());
+        self.simulate_transaction_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            &account_overrides,
+            MAX_SIMULATION_AGE,
+        )
+        .0
+    }
+
+    /// Run a transaction that already landed against a bank without committing the results;
+    /// does not check if the bank is frozen. Unlike `simulate_transaction_unchecked`, the
+    /// transaction's blockhash may be as old as block replay accepts, since it will not be
+    /// forwarded afterwards.
+    pub fn simulate_landed_transaction_unchecked(
+        &self,
+        transaction: &SanitizedTransaction,
+        enable_cpi_recording: bool,
+    ) -> TransactionSimulationResult {
+        let account_overrides =
+            self.get_account_overrides_for_simulation(&transaction.message().account_keys());
+        self.simulate_transaction_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            &account_overrides,
+            MAX_PROCESSING_AGE,
+        )
+        .0
+    }
+
+    /// Run a bundle of transactions, in order, against a frozen bank without committing the
+    /// results. Each transaction observes the account writes of the transactions before it.
+    pub fn simulate_transaction_bundle(
+        &self,
+        transactions: &[SanitizedTransaction],
+        enable_cpi_recording: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.simulate_transaction_bundle_unchecked(transactions, enable_cpi_recording)
+    }
+
+    /// Run a bundle of transactions, in order, against a bank without committing the results;
+    /// does not check if the bank is frozen, enabling use in single-Bank test frameworks.
+    ///
+    /// Simulation stops at the first transaction that fails, so the returned results may be
+    /// shorter than `transactions`. Program deployments, upgrades and closures by an earlier
+    /// transaction are visible to later ones as they would be within one block, and a
+    /// transaction repeating an earlier signature fails with `AlreadyProcessed`.
+    pub fn simulate_transaction_bundle_unchecked(
+        &self,
+        transactions: &[SanitizedTransaction],
+        enable_cpi_recording: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        let bundle_account_keys = transactions
+            .iter()
+            .flat_map(|transaction| transaction.message().account_keys().iter().copied())
+            .unique()
+            .collect::<Vec<_>>();
+        let mut account_overrides = self
+            .get_account_overrides_for_simulation(&AccountKeys::new(&bundle_account_keys, None));
+
+        let mut signatures = HashSet::with_capacity(transactions.len());
+        let mut results = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            if !signatures.insert(transaction.signature()) {
+                results.push(TransactionSimulationResult {
+                    result: Err(TransactionError::AlreadyProcessed),
+                    logs: vec![],
+                    post_simulation_accounts: vec![],
+                    units_consumed: 0,
+                    return_data: None,
+                    inner_instructions: None,
+                    instruction_compute_units: None,
+                });
+                break;
+            }
+
+            let (result, programs_modified) = self.simulate_transaction_with_overrides(
+                transaction,
+                enable_cpi_recording,
+                &account_overrides,
+                MAX_SIMULATION_AGE,
+            );
+            let is_err = result.result.is_err();
+            if !is_err {
+                let message = transaction.message();
+                for (index, (pubkey, account)) in result.post_simulation_accounts.iter().enumerate()
+                {
+                    if message.is_writable(index) {
+                        account_overrides.set_account(pubkey, Some(account.clone()));
+                    }
+                }
+                if let Some(programs_modified) = programs_modified {
+                    account_overrides.merge_programs_modified(&programs_modified);
+                }
+            }
+            results.push(result);
+            if is_err {
+                break;
+            }
+        }
+        results
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &SanitizedTransaction,
+        enable_cpi_recording: bool,
+        account_overrides: &AccountOverrides,
+        max_age: usize,
+    ) -> (
+        TransactionSimulationResult,
+        Option<Box<ProgramCacheForTxBatch>>,
+    ) {
+        let number_of_accounts = transaction.message().account_keys().len();
         let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
@@ -3443,17 +3601,15 @@ impl Bank {
             ..
         } = self.load_and_execute_transactions(
             &batch,
-            // After simulation, transactions will need to be forwarded to the leader
-            // for processing. During forwarding, the transaction could expire if the
-            // delay is not accounted for.
-            MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY,
+            max_age,
             ExecutionRecordingConfig {
                 enable_cpi_recording,
                 enable_log_recording: true,
                 enable_return_data_recording: true,
+                enable_instruction_compute_units_recording: true,
             },
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
             true,
         );
@@ -3490,24 +3646,34 @@ impl Bank {
                     TransactionError::InvalidProgramForExecution,
                 ));
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data, inner_instructions) = match execution_result {
-            TransactionExecutionResult::Executed { details, .. } => (
-                details.log_messages,
-                details.return_data,
-                details.inner_instructions,
-            ),
-            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
-        };
+        let (logs, return_data, inner_instructions, instruction_compute_units, programs_modified) =
+            match execution_result {
+                TransactionExecutionResult::Executed {
+                    details,
+                    programs_modified_by_tx,
+                } => (
+                    details.log_messages,
+                    details.return_data,
+                    details.inner_instructions,
+                    details.instruction_compute_units,
+                    Some(programs_modified_by_tx),
+                ),
+                TransactionExecutionResult::NotExecuted(_) => (None, None, None, None, None),
+            };
         let logs = logs.unwrap_or_default();
 
-        TransactionSimulationResult {
-            result: flattened_result,
-            logs,
-            post_simulation_accounts,
-            units_consumed,
-            return_data,
-            inner_instructions,
-        }
+        (
+            TransactionSimulationResult {
+                result: flattened_result,
+                logs,
+                post_simulation_accounts,
+                units_consumed,
+                return_data,
+                inner_instructions,
+                instruction_compute_units,
+            },
+            programs_modified,
+        )
     }
 
     fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
@@ -4976,6 +5142,7 @@ impl Bank {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: true,
+                enable_instruction_compute_units_recording: false,
             },
             &mut ExecuteTimings::default(),
             Some(1000 * 1000),
@@ -6450,6 +6617,10 @@ impl Bank {
                         self.store_account(feature_id, &account);
                     }
                     info!("Feature {} activated at slot {}", feature_id, self.slot());
+                    self.notify_bank_event(&BankEvent::FeatureActivated {
+                        slot: self.slot(),
+                        feature_id,
+                    });
                 }
             }
         }
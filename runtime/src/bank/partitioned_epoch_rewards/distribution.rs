@@ -1,6 +1,9 @@
 use {
     super::{Bank, EpochRewardStatus},
-    crate::bank::metrics::{report_partitioned_reward_metrics, RewardsStoreMetrics},
+    crate::{
+        bank::metrics::{report_partitioned_reward_metrics, RewardsStoreMetrics},
+        bank_event_notifier_interface::BankEvent,
+    },
     solana_accounts_db::stake_rewards::StakeReward,
     solana_measure::measure_us,
     solana_sdk::account::ReadableAccount,
@@ -83,6 +86,15 @@ impl Bank {
         };
 
         report_partitioned_reward_metrics(self, metrics);
+
+        self.notify_bank_event(&BankEvent::PartitionedRewardsDistributed {
+            slot: self.slot(),
+            epoch: self.epoch(),
+            partition_index,
+            num_partitions: all_stake_rewards.len() as u64,
+            num_stake_accounts: this_partition_stake_rewards.len() as u64,
+            lamports: total_rewards_in_lamports,
+        });
     }
 
     /// insert non-zero stake rewards to self.rewards
@@ -9,6 +9,7 @@ use {
     crate::{
         accounts_background_service::{PrunedBanksRequestHandler, SendDroppedBankCallback},
         bank_client::BankClient,
+        bank_event_notifier_interface::BankEventNotifier,
         bank_forks::BankForks,
         genesis_utils::{
             self, activate_all_features, activate_feature, bootstrap_validator_stake_lamports,
@@ -240,6 +241,7 @@ fn new_execution_result(
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
             return_data: None,
             executed_units: 0,
+            instruction_compute_units: None,
             accounts_data_len_delta: 0,
         },
         programs_modified_by_tx: Box::<ProgramCacheForTxBatch>::default(),
@@ -9263,6 +9265,7 @@ fn test_tx_log_order() {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_instruction_compute_units_recording: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -9373,6 +9376,7 @@ fn test_tx_return_data() {
                     enable_cpi_recording: false,
                     enable_log_recording: false,
                     enable_return_data_recording: true,
+                    enable_instruction_compute_units_recording: false,
                 },
                 &mut ExecuteTimings::default(),
                 None,
@@ -12812,6 +12816,102 @@ fn test_failed_simulation_compute_units() {
     assert_eq!(expected_consumed_units, simulation.units_consumed);
 }
 
+#[test]
+fn test_simulate_transaction_bundle() {
+    let (genesis_config, mint_keypair) = create_genesis_config(10 * LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    let blockhash = bank.last_blockhash();
+
+    let transactions = [
+        system_transaction::transfer(&mint_keypair, &alice.pubkey(), LAMPORTS_PER_SOL, blockhash),
+        // Only succeeds if the transfer to alice above is visible
+        system_transaction::transfer(&alice, &bob, LAMPORTS_PER_SOL / 2, blockhash),
+        system_transaction::transfer(&alice, &bob, 10 * LAMPORTS_PER_SOL, blockhash),
+        // Never simulated, since the previous transaction fails
+        system_transaction::transfer(&alice, &bob, 1, blockhash),
+    ]
+    .into_iter()
+    .map(SanitizedTransaction::from_transaction_for_tests)
+    .collect::<Vec<_>>();
+
+    bank.freeze();
+    let results = bank.simulate_transaction_bundle(&transactions, false);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result, Ok(()));
+    assert_eq!(results[1].result, Ok(()));
+    assert_eq!(
+        results[2].result,
+        Err(TransactionError::InstructionError(
+            0,
+            SystemError::ResultWithNegativeLamports.into()
+        ))
+    );
+    let bob_account = results[1]
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == bob)
+        .map(|(_, account)| account.lamports());
+    assert_eq!(bob_account, Some(LAMPORTS_PER_SOL / 2));
+    assert_eq!(
+        results[0].instruction_compute_units.as_ref().unwrap().len(),
+        1
+    );
+
+    // Nothing was committed to the bank
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_eq!(bank.get_balance(&bob), 0);
+}
+
+#[test]
+fn test_simulate_landed_transaction_max_age() {
+    let (genesis_config, mint_keypair) = create_genesis_config(10 * LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let transaction =
+        SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+            &mint_keypair,
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+            bank.last_blockhash(),
+        ));
+    for _ in 0..=MAX_SIMULATION_AGE {
+        bank.register_unique_recent_blockhash_for_test();
+    }
+
+    // Too old to be forwarded, but not to be replayed
+    assert_eq!(
+        bank.simulate_transaction_unchecked(&transaction, false)
+            .result,
+        Err(TransactionError::BlockhashNotFound)
+    );
+    assert_eq!(
+        bank.simulate_landed_transaction_unchecked(&transaction, false)
+            .result,
+        Ok(())
+    );
+}
+
+#[test]
+fn test_simulate_transaction_bundle_duplicate_signature() {
+    let (genesis_config, mint_keypair) = create_genesis_config(10 * LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let bob = Pubkey::new_unique();
+    let transaction = SanitizedTransaction::from_transaction_for_tests(
+        system_transaction::transfer(&mint_keypair, &bob, LAMPORTS_PER_SOL, bank.last_blockhash()),
+    );
+
+    bank.freeze();
+    let results = bank.simulate_transaction_bundle(
+        &[transaction.clone(), transaction.clone(), transaction],
+        false,
+    );
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].result, Ok(()));
+    assert_eq!(results[1].result, Err(TransactionError::AlreadyProcessed));
+    assert!(results[1].post_simulation_accounts.is_empty());
+}
+
 #[test]
 fn test_filter_program_errors_and_collect_fee_details() {
     // TX  | EXECUTION RESULT            | is nonce | COLLECT            | ADDITIONAL          | COLLECT
@@ -13064,3 +13164,66 @@ fn test_deploy_last_epoch_slot() {
     let result_with_feature_enabled = bank.process_transaction(&transaction);
     assert_eq!(result_with_feature_enabled, Ok(()));
 }
+
+#[derive(Debug, Default)]
+struct BankEventRecorder {
+    epoch_boundaries: Mutex<Vec<(Slot, Epoch, Epoch)>>,
+    activated_features: Mutex<Vec<(Slot, Pubkey)>>,
+}
+
+impl BankEventNotifier for BankEventRecorder {
+    fn notify_bank_event(&self, event: &BankEvent) {
+        match *event {
+            BankEvent::EpochBoundary {
+                slot,
+                parent_epoch,
+                epoch,
+                ..
+            } => self
+                .epoch_boundaries
+                .lock()
+                .unwrap()
+                .push((slot, parent_epoch, epoch)),
+            BankEvent::FeatureActivated { slot, feature_id } => self
+                .activated_features
+                .lock()
+                .unwrap()
+                .push((slot, *feature_id)),
+            BankEvent::PartitionedRewardsDistributed { .. } => {}
+        }
+    }
+}
+
+#[test]
+fn test_bank_event_notifier() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+    let recorder = Arc::new(BankEventRecorder::default());
+    bank0.set_bank_event_notifier(Some(recorder.clone()));
+
+    // Queue a feature to be activated at the next epoch boundary
+    let feature_id = feature_set::update_hashes_per_tick::id();
+    let feature_account_balance =
+        std::cmp::max(genesis_config.rent.minimum_balance(Feature::size_of()), 1);
+    bank0.store_account(
+        &feature_id,
+        &feature::create_account(&Feature { activated_at: None }, feature_account_balance),
+    );
+
+    // No events within the same epoch
+    let bank1 = Arc::new(Bank::new_from_parent(bank0, &Pubkey::default(), 1));
+    assert!(recorder.epoch_boundaries.lock().unwrap().is_empty());
+    assert!(recorder.activated_features.lock().unwrap().is_empty());
+
+    // The notifier is inherited, so crossing into epoch 1 is reported
+    let slot = genesis_config.epoch_schedule.get_first_slot_in_epoch(1);
+    let _bank2 = Bank::new_from_parent(bank1, &Pubkey::default(), slot);
+    assert_eq!(
+        *recorder.epoch_boundaries.lock().unwrap(),
+        vec![(slot, 0, 1)]
+    );
+    assert_eq!(
+        *recorder.activated_features.lock().unwrap(),
+        vec![(slot, feature_id)]
+    );
+}
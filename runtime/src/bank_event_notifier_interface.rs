@@ -0,0 +1,40 @@
+use {
+    solana_sdk::{
+        clock::{Epoch, Slot},
+        pubkey::Pubkey,
+    },
+    std::{fmt::Debug, sync::Arc},
+};
+
+/// Bank-level state transitions which are not otherwise observable except
+/// through the sysvar and feature account writes they cause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BankEvent<'a> {
+    /// The first bank of a new epoch has been created and the epoch
+    /// transition (stake activation, rewards calculation) has been processed.
+    EpochBoundary {
+        slot: Slot,
+        parent_slot: Slot,
+        parent_epoch: Epoch,
+        epoch: Epoch,
+    },
+    /// One partition of the epoch's stake rewards has been credited.
+    PartitionedRewardsDistributed {
+        slot: Slot,
+        epoch: Epoch,
+        partition_index: u64,
+        num_partitions: u64,
+        num_stake_accounts: u64,
+        lamports: u64,
+    },
+    /// A feature has been activated in this bank.
+    FeatureActivated { slot: Slot, feature_id: &'a Pubkey },
+}
+
+pub trait BankEventNotifier: Debug {
+    /// Notified while the bank is being built, so the bank may still be on a
+    /// fork that is later abandoned.
+    fn notify_bank_event(&self, event: &BankEvent);
+}
+
+pub type BankEventNotifierArc = Arc<dyn BankEventNotifier + Sync + Send>;
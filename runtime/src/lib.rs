@@ -7,6 +7,7 @@ extern crate lazy_static;
 pub mod accounts_background_service;
 pub mod bank;
 pub mod bank_client;
+pub mod bank_event_notifier_interface;
 pub mod bank_forks;
 pub mod bank_utils;
 pub mod commitment;
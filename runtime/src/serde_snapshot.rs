@@ -24,9 +24,11 @@ use {
         accounts_file::AccountsFile,
         accounts_hash::AccountsHash,
         accounts_index::AccountSecondaryIndexes,
+        accounts_partition::RentPayingAccountsByPartition,
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         blockhash_queue::BlockhashQueue,
         epoch_accounts_hash::EpochAccountsHash,
+        lazy_accounts_source::LazyAccounts,
     },
     solana_measure::measure::Measure,
     solana_sdk::{
@@ -389,6 +391,76 @@ where
     )
 }
 
+/// Reconstruct the bank of a full snapshot without its account storage files. Its accounts are
+/// loaded from `lazy_accounts` instead, when they are first needed.
+pub(crate) fn bank_from_stream_with_lazy_accounts<R>(
+    serde_style: SerdeStyle,
+    snapshot_stream: &mut BufReader<R>,
+    account_paths: &[PathBuf],
+    lazy_accounts: LazyAccounts,
+    genesis_config: &GenesisConfig,
+    runtime_config: &RuntimeConfig,
+    accounts_db_config: Option<AccountsDbConfig>,
+    exit: Arc<AtomicBool>,
+) -> std::result::Result<Bank, Error>
+where
+    R: Read,
+{
+    let (bank_fields, accounts_db_fields) = fields_from_stream(serde_style, snapshot_stream)?;
+    let AccountsDbFields(_, snapshot_version, snapshot_slot, bank_hash_info, _, _) =
+        accounts_db_fields;
+
+    let mut accounts_db = AccountsDb::new_with_config(
+        account_paths.to_vec(),
+        &genesis_config.cluster_type,
+        AccountSecondaryIndexes::default(),
+        AccountShrinkThreshold::default(),
+        accounts_db_config,
+        None,
+        exit,
+    );
+    if let Some(epoch_accounts_hash) = bank_fields.epoch_accounts_hash {
+        accounts_db
+            .epoch_accounts_hash_manager
+            .set_valid(EpochAccountsHash::new(epoch_accounts_hash), 0);
+    }
+    accounts_db.set_accounts_hash_from_snapshot(
+        snapshot_slot,
+        bank_hash_info.accounts_hash,
+        bank_fields.capitalization,
+    );
+    accounts_db
+        .set_accounts_delta_hash_from_snapshot(snapshot_slot, bank_hash_info.accounts_delta_hash);
+    accounts_db.update_bank_hash_stats_from_snapshot(snapshot_slot, bank_hash_info.stats);
+    accounts_db
+        .write_version
+        .fetch_add(snapshot_version, Ordering::Release);
+    accounts_db.accounts_index.add_root(snapshot_slot);
+    // Nothing is known to be rent paying, the accounts are not scanned
+    accounts_db
+        .accounts_index
+        .rent_paying_accounts_by_partition
+        .set(RentPayingAccountsByPartition::new(
+            &genesis_config.epoch_schedule,
+        ))
+        .unwrap();
+    accounts_db.set_lazy_accounts_source(lazy_accounts, bank_fields.capitalization);
+
+    let bank_rc = BankRc::new(Accounts::new(Arc::new(accounts_db)), bank_fields.slot);
+    let bank = Bank::new_from_fields(
+        bank_rc,
+        genesis_config,
+        Arc::new(runtime_config.clone()),
+        bank_fields,
+        None,
+        None,
+        false,
+        // The size of the accounts data is unknown without reading every account
+        0,
+    );
+    Ok(bank)
+}
+
 pub(crate) fn bank_to_stream<W>(
     serde_style: SerdeStyle,
     stream: &mut BufWriter<W>,
@@ -3,8 +3,8 @@ use {
         bank::{builtins::BuiltinPrototype, Bank, BankFieldsToDeserialize, BankSlotDelta},
         runtime_config::RuntimeConfig,
         serde_snapshot::{
-            bank_from_streams, bank_to_stream, fields_from_streams,
-            BankIncrementalSnapshotPersistence, SerdeStyle,
+            bank_from_stream_with_lazy_accounts, bank_from_streams, bank_to_stream,
+            fields_from_streams, BankIncrementalSnapshotPersistence, SerdeStyle,
         },
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
@@ -18,9 +18,10 @@ use {
             get_snapshot_file_name, get_storages_to_serialize, hard_link_storages_to_snapshot,
             rebuild_storages_from_snapshot_dir, serialize_snapshot_data_file,
             verify_and_unarchive_snapshots, verify_unpacked_snapshots_dir_and_version,
-            AddBankSnapshotError, ArchiveFormat, BankSnapshotInfo, BankSnapshotKind, SnapshotError,
-            SnapshotRootPaths, SnapshotVersion, StorageAndNextAccountsFileId,
-            UnpackedSnapshotsDirAndVersion, VerifySlotDeltasError,
+            AddBankSnapshotError, ArchiveFormat, BankSnapshotInfo, BankSnapshotKind,
+            IndexedArchiveAccounts, IndexedSnapshotArchive, SnapshotError, SnapshotRootPaths,
+            SnapshotVersion, StorageAndNextAccountsFileId, UnpackedSnapshotsDirAndVersion,
+            VerifySlotDeltasError,
         },
         status_cache,
     },
@@ -48,7 +49,7 @@ use {
     std::{
         collections::HashSet,
         fs,
-        io::{BufWriter, Write},
+        io::{BufReader, BufWriter, Write},
         num::NonZeroUsize,
         path::{Path, PathBuf},
         sync::{atomic::AtomicBool, Arc},
@@ -267,6 +268,10 @@ pub fn bank_fields_from_snapshot_archives(
 
 /// Rebuild bank from snapshot archives.  Handles either just a full snapshot, or both a full
 /// snapshot and an incremental snapshot.
+///
+/// If the incremental snapshot archive is the last of a chain of incremental snapshot archives,
+/// the rest of the chain must be next to it.  The bank is verified against the accounts hash of
+/// the whole chain.
 #[allow(clippy::too_many_arguments)]
 pub fn bank_from_snapshot_archives(
     account_paths: &[PathBuf],
@@ -413,6 +418,78 @@ pub fn bank_from_snapshot_archives(
     Ok((bank, timings))
 }
 
+/// Rebuild the bank of a full snapshot archive in the `TarZstdIndexed` format without unpacking
+/// its account storage files
+///
+/// Accounts are read from the archive when they are first loaded, see `LazyAccountsSource`.
+/// The stake and vote accounts are read right away, to rebuild the bank's stakes cache. The
+/// accounts hash is not verified, since that would read every account of the archive.
+pub fn bank_from_indexed_snapshot_archive(
+    account_paths: &[PathBuf],
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    genesis_config: &GenesisConfig,
+    runtime_config: &RuntimeConfig,
+    accounts_db_config: Option<AccountsDbConfig>,
+    exit: Arc<AtomicBool>,
+) -> snapshot_utils::Result<Bank> {
+    let archive_path = full_snapshot_archive_info.path();
+    let slot = full_snapshot_archive_info.slot();
+    info!(
+        "Loading bank from indexed snapshot archive {} without unpacking it",
+        archive_path.display()
+    );
+    let archive = IndexedSnapshotArchive::open(archive_path)?;
+    let read_file = |path: PathBuf| {
+        archive.read_file(&path)?.ok_or_else(|| {
+            SnapshotError::InvalidIndexedArchive(
+                archive_path.clone(),
+                format!("missing {}", path.display()),
+            )
+        })
+    };
+
+    let snapshots_dir = Path::new("snapshots");
+    let version = read_file(PathBuf::from(snapshot_utils::SNAPSHOT_VERSION_FILENAME))?;
+    let version = String::from_utf8_lossy(&version);
+    match version.trim().parse::<SnapshotVersion>() {
+        Ok(SnapshotVersion::V1_2_0) => {}
+        Err(err) => {
+            return Err(SnapshotError::InvalidIndexedArchive(
+                archive_path.clone(),
+                format!("snapshot version {version}: {err}"),
+            ))
+        }
+    }
+    let bank_snapshot =
+        read_file(get_bank_snapshot_dir(snapshots_dir, slot).join(get_snapshot_file_name(slot)))?;
+    let slot_deltas: Vec<BankSlotDelta> = bincode::options()
+        .with_limit(snapshot_utils::MAX_SNAPSHOT_DATA_FILE_SIZE)
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize(&read_file(
+            snapshots_dir.join(snapshot_utils::SNAPSHOT_STATUS_CACHE_FILENAME),
+        )?)?;
+
+    let lazy_accounts = Arc::new(IndexedArchiveAccounts::new(archive, slot));
+    let bank = bank_from_stream_with_lazy_accounts(
+        SerdeStyle::Newer,
+        &mut BufReader::new(bank_snapshot.as_slice()),
+        account_paths,
+        lazy_accounts,
+        genesis_config,
+        runtime_config,
+        accounts_db_config,
+        exit,
+    )?;
+    verify_bank_against_expected_slot_hash(&bank, slot, *full_snapshot_archive_info.hash())?;
+    verify_slot_deltas(slot_deltas.as_slice(), &bank)?;
+    bank.status_cache.write().unwrap().append(&slot_deltas);
+    bank.set_initial_accounts_hash_verification_completed();
+
+    info!("Loaded bank for slot: {}", bank.slot());
+    Ok(bank)
+}
+
 /// Rebuild bank from snapshot archives
 ///
 /// This function searches `full_snapshot_archives_dir` and `incremental_snapshot_archives_dir` for
@@ -1027,11 +1104,49 @@ pub fn bank_to_incremental_snapshot_archive(
     archive_format: ArchiveFormat,
     maximum_full_snapshot_archives_to_retain: NonZeroUsize,
     maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
+) -> snapshot_utils::Result<IncrementalSnapshotArchiveInfo> {
+    bank_to_chained_incremental_snapshot_archive(
+        bank_snapshots_dir,
+        bank,
+        full_snapshot_slot,
+        full_snapshot_slot,
+        snapshot_version,
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+        archive_format,
+        maximum_full_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_archives_to_retain,
+    )
+}
+
+/// Convenience function to create an incremental snapshot archive out of any Bank, based on the
+/// incremental snapshot archive at `incremental_snapshot_base_slot`.  The new archive extends that
+/// archive's chain, which must lead back to the full snapshot at `full_snapshot_slot`.  The Bank
+/// will be frozen during the process.
+/// This is only called from ledger-tool or tests.
+///
+/// Requires:
+///     - `bank` is complete
+///     - `bank`'s slot is greater than `incremental_snapshot_base_slot`
+///     - `incremental_snapshot_base_slot` is not less than `full_snapshot_slot`
+#[allow(clippy::too_many_arguments)]
+pub fn bank_to_chained_incremental_snapshot_archive(
+    bank_snapshots_dir: impl AsRef<Path>,
+    bank: &Bank,
+    full_snapshot_slot: Slot,
+    incremental_snapshot_base_slot: Slot,
+    snapshot_version: Option<SnapshotVersion>,
+    full_snapshot_archives_dir: impl AsRef<Path>,
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    archive_format: ArchiveFormat,
+    maximum_full_snapshot_archives_to_retain: NonZeroUsize,
+    maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
 ) -> snapshot_utils::Result<IncrementalSnapshotArchiveInfo> {
     let snapshot_version = snapshot_version.unwrap_or_default();
 
     assert!(bank.is_complete());
-    assert!(bank.slot() > full_snapshot_slot);
+    assert!(bank.slot() > incremental_snapshot_base_slot);
+    assert!(incremental_snapshot_base_slot >= full_snapshot_slot);
     bank.squash(); // Bank may not be a root
     bank.force_flush_accounts_cache();
     bank.clean_accounts(Some(full_snapshot_slot));
@@ -1056,9 +1171,10 @@ pub fn bank_to_incremental_snapshot_archive(
         slot_deltas,
     )?;
 
-    package_and_archive_incremental_snapshot(
+    package_and_archive_chained_incremental_snapshot(
         bank,
         full_snapshot_slot,
+        incremental_snapshot_base_slot,
         &bank_snapshot_info,
         full_snapshot_archives_dir,
         incremental_snapshot_archives_dir,
@@ -1132,6 +1248,38 @@ pub fn package_and_archive_incremental_snapshot(
     snapshot_version: SnapshotVersion,
     maximum_full_snapshot_archives_to_retain: NonZeroUsize,
     maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
+) -> snapshot_utils::Result<IncrementalSnapshotArchiveInfo> {
+    package_and_archive_chained_incremental_snapshot(
+        bank,
+        incremental_snapshot_base_slot,
+        incremental_snapshot_base_slot,
+        bank_snapshot_info,
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+        snapshot_storages,
+        archive_format,
+        snapshot_version,
+        maximum_full_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_archives_to_retain,
+    )
+}
+
+/// Helper function to hold shared code to package, process, and archive incremental snapshots
+/// that are based on the incremental snapshot archive at `incremental_snapshot_chain_base_slot`.
+/// The accounts hash is still relative to the full snapshot, at `incremental_snapshot_base_slot`.
+#[allow(clippy::too_many_arguments)]
+fn package_and_archive_chained_incremental_snapshot(
+    bank: &Bank,
+    incremental_snapshot_base_slot: Slot,
+    incremental_snapshot_chain_base_slot: Slot,
+    bank_snapshot_info: &BankSnapshotInfo,
+    full_snapshot_archives_dir: impl AsRef<Path>,
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    snapshot_storages: Vec<Arc<AccountStorageEntry>>,
+    archive_format: ArchiveFormat,
+    snapshot_version: SnapshotVersion,
+    maximum_full_snapshot_archives_to_retain: NonZeroUsize,
+    maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
 ) -> snapshot_utils::Result<IncrementalSnapshotArchiveInfo> {
     let accounts_package = AccountsPackage::new_for_snapshot(
         AccountsPackageKind::Snapshot(SnapshotKind::IncrementalSnapshot(
@@ -1190,7 +1338,8 @@ pub fn package_and_archive_incremental_snapshot(
         bank_incremental_snapshot_persistence.as_ref(),
     );
 
-    let snapshot_package = SnapshotPackage::new(accounts_package, accounts_hash_kind);
+    let mut snapshot_package = SnapshotPackage::new(accounts_package, accounts_hash_kind);
+    snapshot_package.rebase_on_incremental_snapshot(incremental_snapshot_chain_base_slot);
     archive_snapshot_package(
         &snapshot_package,
         full_snapshot_archives_dir,
@@ -1200,7 +1349,7 @@ pub fn package_and_archive_incremental_snapshot(
     )?;
 
     Ok(IncrementalSnapshotArchiveInfo::new(
-        incremental_snapshot_base_slot,
+        incremental_snapshot_chain_base_slot,
         snapshot_package.snapshot_archive_info,
     ))
 }
@@ -1273,7 +1422,8 @@ mod tests {
                 purge_bank_snapshots_older_than_slot, purge_incomplete_bank_snapshots,
                 purge_old_bank_snapshots, purge_old_bank_snapshots_at_startup,
                 snapshot_storage_rebuilder::get_slot_and_append_vec_id,
-                write_full_snapshot_slot_file, ArchiveFormat, SNAPSHOT_FULL_SNAPSHOT_SLOT_FILENAME,
+                write_full_snapshot_slot_file, ArchiveFormat, IndexedSnapshotArchive,
+                SNAPSHOT_FULL_SNAPSHOT_SLOT_FILENAME,
             },
             status_cache::Status,
         },
@@ -1476,6 +1626,179 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test roundtrip of bank to an indexed full snapshot, then back again by unpacking its frames
+    /// from several threads, and reading single accounts and files from the archive through its
+    /// index
+    #[test]
+    fn test_roundtrip_bank_to_and_from_indexed_full_snapshot() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        while !bank0.is_complete() {
+            bank0.register_unique_tick();
+        }
+
+        let slot = 1;
+        let bank1 =
+            new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank0, &collector, slot);
+        bank1
+            .transfer(sol_to_lamports(2.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank1
+            .transfer(sol_to_lamports(3.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank1.is_complete() {
+            bank1.register_unique_tick();
+        }
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            ArchiveFormat::TarZstdIndexed,
+            snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        assert_eq!(
+            full_snapshot_archive_info.archive_format(),
+            ArchiveFormat::TarZstdIndexed
+        );
+
+        let archive = IndexedSnapshotArchive::open(full_snapshot_archive_info.path()).unwrap();
+        assert_eq!(
+            archive.num_accounts(),
+            bank1.get_all_accounts().unwrap().len() as u64
+        );
+        for pubkey in [key1.pubkey(), key2.pubkey(), mint_keypair.pubkey()] {
+            assert_eq!(
+                archive.get_account(&pubkey).unwrap(),
+                bank1.get_account(&pubkey)
+            );
+        }
+        assert_eq!(archive.get_account(&Pubkey::new_unique()).unwrap(), None);
+        assert_eq!(
+            archive
+                .read_file(snapshot_utils::SNAPSHOT_VERSION_FILENAME)
+                .unwrap()
+                .unwrap(),
+            SnapshotVersion::default().as_str().as_bytes()
+        );
+        let unpack_dir = tempfile::TempDir::new().unwrap();
+        assert!(archive
+            .unpack_storage(slot, unpack_dir.path())
+            .unwrap()
+            .unwrap()
+            .is_file());
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            None,
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+        roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
+        assert_eq!(*bank1, roundtrip_bank);
+    }
+
+    /// Test loading the bank of an indexed full snapshot with its accounts read from the archive
+    /// on demand, and building on top of it
+    #[test]
+    fn test_bank_from_indexed_snapshot_archive() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        while !bank0.is_complete() {
+            bank0.register_unique_tick();
+        }
+        let bank1 = new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank0, &collector, 1);
+        while !bank1.is_complete() {
+            bank1.register_unique_tick();
+        }
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            full_snapshot_archives_dir.path(),
+            ArchiveFormat::TarZstdIndexed,
+            snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+
+        let lazy_bank = bank_from_indexed_snapshot_archive(
+            &[accounts_dir],
+            &full_snapshot_archive_info,
+            &genesis_config,
+            &RuntimeConfig::default(),
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            Arc::default(),
+        )
+        .unwrap();
+        assert_eq!(lazy_bank.slot(), bank1.slot());
+        assert_eq!(lazy_bank.hash(), bank1.hash());
+        assert_eq!(lazy_bank.capitalization(), bank1.capitalization());
+        for pubkey in [key1.pubkey(), mint_keypair.pubkey()] {
+            assert_eq!(lazy_bank.get_account(&pubkey), bank1.get_account(&pubkey));
+        }
+        assert_eq!(lazy_bank.get_account(&Pubkey::new_unique()), None);
+
+        // Transactions load their accounts from the archive, and store them as usual
+        let bank2 = Bank::new_from_parent(Arc::new(lazy_bank), &collector, 2);
+        bank2
+            .transfer(sol_to_lamports(2.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        assert_eq!(bank2.get_balance(&key1.pubkey()), sol_to_lamports(3.));
+
+        // The accounts which were never loaded still count towards the capitalization when
+        // calculating the accounts hash, so warping does not find a mismatch
+        let bank3 = Bank::warp_from_parent(
+            Arc::new(bank2),
+            &collector,
+            10,
+            CalcAccountsHashDataSource::IndexForTests,
+        );
+        assert_eq!(bank3.get_balance(&key1.pubkey()), sol_to_lamports(3.));
+    }
+
     /// Test roundtrip of bank to snapshots, then back again, with incremental snapshots.  In this
     /// version, build up a few slots and take a full snapshot.  Continue on a few more slots and
     /// take an incremental snapshot.  Rebuild the bank from both the incremental snapshot and full
@@ -1612,6 +1935,144 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test roundtrip of bank to snapshots, then back again, with a chain of incremental
+    /// snapshots.  Take a full snapshot, then an incremental snapshot based on it, then two more
+    /// incremental snapshots that are each based on the previous one.  Rebuild the bank from the
+    /// full snapshot and the last incremental snapshot, which pulls in the rest of the chain.
+    #[test]
+    fn test_roundtrip_bank_to_and_from_incremental_snapshot_chain() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank0
+            .transfer(sol_to_lamports(2.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank0.is_complete() {
+            bank0.register_unique_tick();
+        }
+
+        let bank1 = new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank0, &collector, 1);
+        bank1
+            .transfer(sol_to_lamports(3.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank1.is_complete() {
+            bank1.register_unique_tick();
+        }
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::TarZstd;
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+            snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        let full_snapshot_slot = full_snapshot_archive_info.slot();
+
+        let mut bank = bank1;
+        let mut incremental_snapshot_base_slot = full_snapshot_slot;
+        let mut incremental_snapshot_archive_infos = vec![];
+        for slot in 2..5 {
+            bank =
+                new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank, &collector, slot);
+            bank.transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+                .unwrap();
+            while !bank.is_complete() {
+                bank.register_unique_tick();
+            }
+
+            let incremental_snapshot_archive_info = bank_to_chained_incremental_snapshot_archive(
+                bank_snapshots_dir.path(),
+                &bank,
+                full_snapshot_slot,
+                incremental_snapshot_base_slot,
+                None,
+                full_snapshot_archives_dir.path(),
+                incremental_snapshot_archives_dir.path(),
+                snapshot_archive_format,
+                snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+                snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            )
+            .unwrap();
+            assert_eq!(
+                incremental_snapshot_archive_info.base_slot(),
+                incremental_snapshot_base_slot
+            );
+            incremental_snapshot_base_slot = slot;
+            incremental_snapshot_archive_infos.push(incremental_snapshot_archive_info);
+        }
+
+        let highest_incremental_snapshot_archive_info =
+            snapshot_utils::get_highest_incremental_snapshot_archive_info(
+                incremental_snapshot_archives_dir.path(),
+                full_snapshot_slot,
+            )
+            .unwrap();
+        assert_eq!(
+            &highest_incremental_snapshot_archive_info,
+            incremental_snapshot_archive_infos.last().unwrap()
+        );
+        assert_eq!(
+            snapshot_utils::get_incremental_snapshot_archive_chain(
+                incremental_snapshot_archives_dir.path(),
+                full_snapshot_slot,
+                &highest_incremental_snapshot_archive_info,
+            ),
+            incremental_snapshot_archive_infos,
+        );
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            Some(&highest_incremental_snapshot_archive_info),
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+        roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
+        assert_eq!(*bank, roundtrip_bank);
+
+        // Without the middle of the chain, the last incremental snapshot cannot be loaded
+        fs::remove_file(incremental_snapshot_archive_infos[1].path()).unwrap();
+        assert!(
+            snapshot_utils::get_highest_incremental_snapshot_archive_info(
+                incremental_snapshot_archives_dir.path(),
+                full_snapshot_slot,
+            )
+            .is_some_and(|incremental_snapshot_archive_info| {
+                incremental_snapshot_archive_info == incremental_snapshot_archive_infos[0]
+            })
+        );
+    }
+
     /// Test rebuilding bank from the latest snapshot archives
     #[test]
     fn test_bank_from_latest_snapshot_archives() {
@@ -41,6 +41,11 @@ pub struct SnapshotConfig {
     /// NOTE: Incremental snapshots will only be kept for the latest full snapshot
     pub maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
 
+    /// Maximum number of incremental snapshot archives in a chain, where each is based on the
+    /// previous one instead of on the full snapshot.  Once a chain is this long, the next
+    /// incremental snapshot archive starts a new chain.  A length of 1 disables chaining.
+    pub maximum_incremental_snapshot_chain_length: NonZeroUsize,
+
     /// This is the `debug_verify` parameter to use when calling `update_accounts_hash()`
     pub accounts_hash_debug_verify: bool,
 
@@ -65,6 +70,8 @@ impl Default for SnapshotConfig {
                 snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
             maximum_incremental_snapshot_archives_to_retain:
                 snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            maximum_incremental_snapshot_chain_length:
+                snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_CHAIN_LENGTH,
             accounts_hash_debug_verify: false,
             packager_thread_niceness_adj: 0,
         }
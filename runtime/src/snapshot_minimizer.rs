@@ -10,16 +10,19 @@ use {
     },
     solana_accounts_db::{
         accounts_db::{AccountStorageEntry, AccountsDb, GetUniqueAccountsResult, PurgeStats},
+        accounts_index::{ScanConfig, ScanResult},
         accounts_partition,
     },
     solana_measure::measure,
     solana_sdk::{
-        account::ReadableAccount,
+        account::{AccountSharedData, ReadableAccount},
         account_utils::StateMut,
+        bpf_loader, bpf_loader_deprecated,
         bpf_loader_upgradeable::{self, UpgradeableLoaderState},
         clock::Slot,
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
         reserved_account_keys::ReservedAccountKeys,
+        sysvar,
     },
     std::{
         collections::HashSet,
@@ -30,12 +33,100 @@ use {
     },
 };
 
+/// Selects accounts for a minimized snapshot to keep, on top of the ones the runtime needs
+#[derive(Clone, Debug, Default)]
+pub struct AccountSelection {
+    /// Accounts to keep
+    pub accounts: HashSet<Pubkey>,
+    /// Programs whose accounts are all kept
+    pub owners: HashSet<Pubkey>,
+    /// Programs whose program derived accounts are kept, together with every account reachable
+    /// from them through the addresses stored in account data (e.g. the vaults that a PDA stores
+    /// the address of, and the mints of those vaults)
+    pub pda_owners: HashSet<Pubkey>,
+}
+
+impl AccountSelection {
+    /// Returns true if no account is selected
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.owners.is_empty() && self.pda_owners.is_empty()
+    }
+
+    /// Resolves this selection against `bank`, into the set of accounts to keep
+    pub fn resolve(&self, bank: &Bank) -> ScanResult<DashSet<Pubkey>> {
+        let account_set: DashSet<_> = self.accounts.iter().copied().collect();
+
+        for owner in &self.owners {
+            account_set.insert(*owner);
+            bank.get_program_accounts(owner, &ScanConfig::default())?
+                .into_iter()
+                .for_each(|(pubkey, _)| {
+                    account_set.insert(pubkey);
+                });
+        }
+
+        let mut accounts_to_follow = Vec::new();
+        for pda_owner in &self.pda_owners {
+            account_set.insert(*pda_owner);
+            accounts_to_follow.extend(
+                bank.get_program_accounts(pda_owner, &ScanConfig::default())?
+                    .into_iter()
+                    .filter(|(pubkey, _)| !pubkey.is_on_curve())
+                    .filter(|(pubkey, _)| account_set.insert(*pubkey))
+                    .map(|(_, account)| account),
+            );
+        }
+
+        // Follow the addresses stored in the data of the accounts found so far, until no new
+        // account is found
+        let accounts_index = &bank.rc.accounts.accounts_db.accounts_index;
+        while !accounts_to_follow.is_empty() {
+            accounts_to_follow = accounts_to_follow
+                .into_par_iter()
+                .flat_map_iter(|account| Self::stored_addresses(&account))
+                .filter(|candidate| {
+                    accounts_index.contains(candidate) && account_set.insert(*candidate)
+                })
+                .filter_map(|pubkey| bank.get_account(&pubkey))
+                .filter(Self::may_store_addresses)
+                .collect();
+        }
+
+        Ok(account_set)
+    }
+
+    /// Gets every address that the data of `account` may store.  Addresses are stored at
+    /// arbitrary offsets, so every one of them is tried, and only the candidates that are
+    /// existing accounts are to be kept.
+    fn stored_addresses(account: &AccountSharedData) -> HashSet<Pubkey> {
+        account
+            .data()
+            .windows(PUBKEY_BYTES)
+            .filter(|window| window.iter().any(|byte| *byte != 0))
+            .map(|window| Pubkey::try_from(window).unwrap())
+            .collect()
+    }
+
+    /// Returns false for accounts whose data is not followed: programs and sysvars are large, and
+    /// the addresses in them do not relate to the selected accounts
+    fn may_store_addresses(account: &AccountSharedData) -> bool {
+        let owner = account.owner();
+        !account.executable()
+            && !bpf_loader_upgradeable::check_id(owner)
+            && !bpf_loader::check_id(owner)
+            && !bpf_loader_deprecated::check_id(owner)
+            && !sysvar::check_id(owner)
+    }
+}
+
 /// Used to modify bank and accounts_db to create a minimized snapshot
 pub struct SnapshotMinimizer<'a> {
     bank: &'a Bank,
     starting_slot: Slot,
     ending_slot: Slot,
     minimized_account_set: DashSet<Pubkey>,
+    /// `minimize` leaves the storage of the starting slot as is, since replay starts from it
+    filter_starting_slot: bool,
 }
 
 impl<'a> SnapshotMinimizer<'a> {
@@ -51,13 +142,16 @@ impl<'a> SnapshotMinimizer<'a> {
         ending_slot: Slot,
         transaction_account_set: DashSet<Pubkey>,
     ) {
-        let minimizer = SnapshotMinimizer {
+        Self::minimize_account_set(SnapshotMinimizer {
             bank,
             starting_slot,
             ending_slot,
             minimized_account_set: transaction_account_set,
-        };
+            filter_starting_slot: false,
+        });
+    }
 
+    fn minimize_account_set(minimizer: SnapshotMinimizer<'a>) {
         minimizer.add_accounts(Self::get_active_bank_features, "active bank features");
         minimizer.add_accounts(Self::get_inactive_bank_features, "inactive bank features");
         minimizer.add_accounts(Self::get_static_runtime_accounts, "static runtime accounts");
@@ -79,6 +173,30 @@ impl<'a> SnapshotMinimizer<'a> {
         minimizer.bank.set_capitalization();
     }
 
+    /// Removes all accounts not selected by `account_selection` from `bank`, except the ones
+    /// needed to use the bank at all (features, sysvars, builtins, vote and stake accounts, and
+    /// the owners and program data of the kept accounts).
+    ///
+    /// Unlike `minimize`, no slot range has to be replayed from the result, so this is meant for
+    /// small, targeted snapshots that tests boot from, e.g. with `ProgramTest::from_snapshot`.
+    pub fn minimize_to_selection(
+        bank: &'a Bank,
+        account_selection: &AccountSelection,
+    ) -> ScanResult<()> {
+        let (account_set, measure) =
+            measure!(account_selection.resolve(bank), "resolve account selection");
+        let account_set = account_set?;
+        info!("Added {} selected accounts. {measure}", account_set.len());
+        Self::minimize_account_set(SnapshotMinimizer {
+            bank,
+            starting_slot: bank.slot(),
+            ending_slot: bank.slot(),
+            minimized_account_set: account_set,
+            filter_starting_slot: true,
+        });
+        Ok(())
+    }
+
     /// Helper function to measure time and number of accounts added
     fn add_accounts<F>(&self, add_accounts_fn: F, name: &'static str)
     where
@@ -278,7 +396,7 @@ impl<'a> SnapshotMinimizer<'a> {
 
         snapshot_storages.into_par_iter().for_each(|storage| {
             let slot = storage.slot();
-            if slot != self.starting_slot {
+            if slot != self.starting_slot || self.filter_starting_slot {
                 if minimized_slot_set.contains(&slot) {
                     self.filter_storage(&storage, &dead_storages);
                 } else {
@@ -381,8 +499,9 @@ impl<'a> SnapshotMinimizer<'a> {
 mod tests {
     use {
         crate::{
-            bank::Bank, genesis_utils::create_genesis_config_with_leader,
-            snapshot_minimizer::SnapshotMinimizer,
+            bank::Bank,
+            genesis_utils::create_genesis_config_with_leader,
+            snapshot_minimizer::{AccountSelection, SnapshotMinimizer},
         },
         dashmap::DashSet,
         solana_sdk::{
@@ -411,6 +530,7 @@ mod tests {
                 starting_slot: 100_000,
                 ending_slot: 110_000,
                 minimized_account_set: DashSet::new(),
+                filter_starting_slot: false,
             };
             minimizer.get_rent_collection_accounts();
             assert!(
@@ -432,6 +552,7 @@ mod tests {
                 starting_slot: 100_000,
                 ending_slot: 110_000,
                 minimized_account_set: DashSet::new(),
+                filter_starting_slot: false,
             };
             minimizer.get_rent_collection_accounts();
             assert_eq!(
@@ -451,6 +572,7 @@ mod tests {
                 starting_slot: 110_001,
                 ending_slot: 120_000,
                 minimized_account_set: DashSet::new(),
+                filter_starting_slot: false,
             };
             assert!(
                 minimizer.minimized_account_set.is_empty(),
@@ -479,6 +601,7 @@ mod tests {
             starting_slot: 0,
             ending_slot: 0,
             minimized_account_set: DashSet::new(),
+            filter_starting_slot: false,
         };
         minimizer.get_vote_accounts();
 
@@ -508,6 +631,7 @@ mod tests {
             starting_slot: 0,
             ending_slot: 0,
             minimized_account_set: DashSet::new(),
+            filter_starting_slot: false,
         };
         minimizer.get_stake_accounts();
 
@@ -548,6 +672,7 @@ mod tests {
             starting_slot: 0,
             ending_slot: 0,
             minimized_account_set: owner_accounts,
+            filter_starting_slot: false,
         };
 
         minimizer.get_owner_accounts();
@@ -586,6 +711,7 @@ mod tests {
             starting_slot: 0,
             ending_slot: 0,
             minimized_account_set: programdata_accounts,
+            filter_starting_slot: false,
         };
         minimizer.get_programdata_accounts();
         assert_eq!(minimizer.minimized_account_set.len(), 1);
@@ -602,6 +728,109 @@ mod tests {
             .contains(&programdata_address));
     }
 
+    #[test]
+    fn test_account_selection_resolve() {
+        solana_logger::setup();
+
+        let genesis_config = GenesisConfig::default();
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+
+        let account = Pubkey::new_unique();
+        bank.store_account(&account, &AccountSharedData::new(1, 0, &Pubkey::default()));
+
+        let owner = Pubkey::new_unique();
+        let owned_account = Pubkey::new_unique();
+        bank.store_account(&owned_account, &AccountSharedData::new(1, 0, &owner));
+
+        // The PDA refers to an existing account and to one that does not exist, and the account
+        // it refers to refers to another one
+        let pda_owner = Pubkey::new_unique();
+        let pda = Pubkey::find_program_address(&[b"pda"], &pda_owner).0;
+        let referenced_account = Pubkey::new_unique();
+        let transitively_referenced_account = Pubkey::new_unique();
+        let missing_account = Pubkey::new_unique();
+        let mut referenced_account_data = AccountSharedData::new(1, 40, &Pubkey::default());
+        referenced_account_data.data_as_mut_slice()[8..]
+            .copy_from_slice(transitively_referenced_account.as_ref());
+        bank.store_account(&referenced_account, &referenced_account_data);
+        bank.store_account(
+            &transitively_referenced_account,
+            &AccountSharedData::new(1, 0, &Pubkey::default()),
+        );
+        let mut pda_data = vec![7; 3];
+        pda_data.extend_from_slice(referenced_account.as_ref());
+        pda_data.extend_from_slice(missing_account.as_ref());
+        let mut pda_account = AccountSharedData::new(1, pda_data.len(), &pda_owner);
+        pda_account.set_data_from_slice(&pda_data);
+        bank.store_account(&pda, &pda_account);
+        // Accounts of the program that are not PDAs are not selected with `pda_owners`
+        let keypair_account = solana_sdk::signature::Keypair::new().pubkey();
+        bank.store_account(&keypair_account, &AccountSharedData::new(1, 0, &pda_owner));
+
+        let account_selection = AccountSelection {
+            accounts: [account].into(),
+            owners: [owner].into(),
+            pda_owners: [pda_owner].into(),
+        };
+        let account_set = account_selection.resolve(&bank).unwrap();
+        for pubkey in [
+            account,
+            owner,
+            owned_account,
+            pda_owner,
+            pda,
+            referenced_account,
+            transitively_referenced_account,
+        ] {
+            assert!(account_set.contains(&pubkey), "{pubkey} should be selected");
+        }
+        for pubkey in [missing_account, keypair_account] {
+            assert!(
+                !account_set.contains(&pubkey),
+                "{pubkey} should not be selected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimize_to_selection() {
+        solana_logger::setup();
+
+        let (genesis_config, _) = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+
+        let kept_account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let owned_account = Pubkey::new_unique();
+        let removed_account = Pubkey::new_unique();
+        bank.store_account(
+            &kept_account,
+            &AccountSharedData::new(10, 0, &Pubkey::default()),
+        );
+        bank.store_account(&owned_account, &AccountSharedData::new(20, 0, &owner));
+        bank.store_account(
+            &removed_account,
+            &AccountSharedData::new(30, 0, &Pubkey::default()),
+        );
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+        bank.squash();
+        bank.force_flush_accounts_cache();
+
+        let account_selection = AccountSelection {
+            accounts: [kept_account].into(),
+            owners: [owner].into(),
+            ..AccountSelection::default()
+        };
+        SnapshotMinimizer::minimize_to_selection(&bank, &account_selection).unwrap();
+
+        assert_eq!(bank.get_balance(&kept_account), 10);
+        assert_eq!(bank.get_balance(&owned_account), 20);
+        assert!(bank.get_account(&removed_account).is_none());
+        // The accounts needed to use the bank are kept
+        assert!(bank.get_account(&solana_sdk::sysvar::clock::id()).is_some());
+    }
+
     #[test]
     fn test_minimize_accounts_db() {
         solana_logger::setup();
@@ -644,6 +873,7 @@ mod tests {
             starting_slot: current_slot,
             ending_slot: current_slot,
             minimized_account_set,
+            filter_starting_slot: false,
         };
         minimizer.minimize_accounts_db();
 
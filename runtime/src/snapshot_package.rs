@@ -301,6 +301,40 @@ impl SnapshotPackage {
             enqueued: Instant::now(),
         }
     }
+
+    /// Base this incremental snapshot package on the incremental snapshot archive at `base_slot`,
+    /// instead of on the full snapshot, making it the next link in that archive's chain
+    ///
+    /// Only the storages newer than `base_slot` are archived, and the archive is named after
+    /// `base_slot`.  The bank snapshot is unchanged, and still relative to the full snapshot slot,
+    /// which is why `snapshot_kind` is unchanged too.
+    pub fn rebase_on_incremental_snapshot(&mut self, base_slot: Slot) {
+        let SnapshotKind::IncrementalSnapshot(full_snapshot_slot) = self.snapshot_kind else {
+            panic!("Only an incremental snapshot package can be based on an incremental snapshot!");
+        };
+        assert!(
+            full_snapshot_slot <= base_slot && base_slot < self.slot(),
+            "Incremental snapshot package's base slot must be within the full snapshot slot ({}) and its slot ({}), but was {}!",
+            full_snapshot_slot,
+            self.slot(),
+            base_slot,
+        );
+        self.snapshot_storages
+            .retain(|storage| storage.slot() > base_slot);
+        let incremental_snapshot_archives_dir = self
+            .snapshot_archive_info
+            .path
+            .parent()
+            .expect("snapshot archive path must have a parent directory")
+            .to_path_buf();
+        self.snapshot_archive_info.path = snapshot_utils::build_incremental_snapshot_archive_path(
+            incremental_snapshot_archives_dir,
+            base_slot,
+            self.snapshot_archive_info.slot,
+            &self.snapshot_archive_info.hash,
+            self.snapshot_archive_info.archive_format,
+        );
+    }
 }
 
 impl std::fmt::Debug for SnapshotPackage {
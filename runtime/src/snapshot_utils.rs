@@ -7,8 +7,9 @@ use {
         snapshot_config::SnapshotConfig,
         snapshot_hash::SnapshotHash,
         snapshot_package::SnapshotPackage,
-        snapshot_utils::snapshot_storage_rebuilder::{
-            RebuiltSnapshotStorage, SnapshotStorageRebuilder,
+        snapshot_utils::{
+            indexed_archive::IndexedArchiveWriter,
+            snapshot_storage_rebuilder::{RebuiltSnapshotStorage, SnapshotStorageRebuilder},
         },
     },
     bzip2::bufread::BzDecoder,
@@ -30,7 +31,7 @@ use {
     solana_sdk::{clock::Slot, hash::Hash},
     std::{
         cmp::Ordering,
-        collections::{HashMap, HashSet},
+        collections::HashSet,
         fmt, fs,
         io::{BufReader, BufWriter, Error as IoError, Read, Result as IoResult, Seek, Write},
         num::NonZeroUsize,
@@ -51,14 +52,23 @@ use {
 };
 
 mod archive_format;
+mod indexed_archive;
 pub mod snapshot_storage_rebuilder;
-pub use archive_format::*;
+pub use {
+    archive_format::*,
+    indexed_archive::{
+        ArchiveIndex, IndexedAccount, IndexedArchiveAccounts, IndexedFrame, IndexedSnapshotArchive,
+        IndexedStorage,
+    },
+};
 
 pub const SNAPSHOT_STATUS_CACHE_FILENAME: &str = "status_cache";
 pub const SNAPSHOT_VERSION_FILENAME: &str = "version";
 pub const SNAPSHOT_STATE_COMPLETE_FILENAME: &str = "state_complete";
 pub const SNAPSHOT_ACCOUNTS_HARDLINKS: &str = "accounts_hardlinks";
 pub const SNAPSHOT_ARCHIVE_DOWNLOAD_DIR: &str = "remote";
+/// RPC path that lists the file names of the highest chain of incremental snapshot archives
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_DOWNLOAD_PATH: &str = "/incremental-snapshot-chain";
 pub const SNAPSHOT_FULL_SNAPSHOT_SLOT_FILENAME: &str = "full_snapshot_slot";
 pub const MAX_SNAPSHOT_DATA_FILE_SIZE: u64 = 32 * 1024 * 1024 * 1024; // 32 GiB
 const MAX_SNAPSHOT_VERSION_FILE_SIZE: u64 = 8; // byte
@@ -73,8 +83,10 @@ pub const DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     unsafe { NonZeroUsize::new_unchecked(2) };
 pub const DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     unsafe { NonZeroUsize::new_unchecked(4) };
-pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.lz4)$";
-pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.lz4)$";
+pub const DEFAULT_MAX_INCREMENTAL_SNAPSHOT_CHAIN_LENGTH: NonZeroUsize =
+    unsafe { NonZeroUsize::new_unchecked(1) };
+pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.lz4|indexed\.tar\.zst)$";
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.lz4|indexed\.tar\.zst)$";
 
 #[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
 pub enum SnapshotVersion {
@@ -359,6 +371,9 @@ pub enum SnapshotError {
 
     #[error("failed to rebuild snapshot storages: {0}")]
     RebuildStorages(String),
+
+    #[error("invalid indexed snapshot archive '{0}': {1}")]
+    InvalidIndexedArchive(PathBuf, String),
 }
 
 #[derive(Error, Debug)]
@@ -779,6 +794,53 @@ pub fn archive_snapshot_package(
         E::SymlinkVersionFile(err, src_version_file, staging_version_file.clone())
     })?;
 
+    /// Append the files of the snapshot to `archive`, calling `begin_file` with the output of the
+    /// archive before each file, or the snapshots dir, is appended
+    fn append_snapshot_files<W: Write>(
+        archive: &mut tar::Builder<W>,
+        staging_version_file: &Path,
+        staging_snapshots_dir: &Path,
+        snapshot_storages: &[Arc<AccountStorageEntry>],
+        mut begin_file: impl FnMut(&mut W, &Path, Option<&AccountStorageEntry>) -> IoResult<()>,
+    ) -> std::result::Result<(), ArchiveSnapshotPackageError> {
+        use ArchiveSnapshotPackageError as E;
+        // Serialize the version and snapshots files before accounts so we can quickly determine the version
+        // and other bank fields. This is necessary if we want to interleave unpacking with reconstruction
+        begin_file(
+            archive.get_mut(),
+            Path::new(SNAPSHOT_VERSION_FILENAME),
+            None,
+        )
+        .map_err(E::ArchiveVersionFile)?;
+        archive
+            .append_path_with_name(staging_version_file, SNAPSHOT_VERSION_FILENAME)
+            .map_err(E::ArchiveVersionFile)?;
+        begin_file(archive.get_mut(), Path::new(SNAPSHOTS_DIR), None)
+            .map_err(E::ArchiveSnapshotsDir)?;
+        archive
+            .append_dir_all(SNAPSHOTS_DIR, staging_snapshots_dir)
+            .map_err(E::ArchiveSnapshotsDir)?;
+
+        for storage in snapshot_storages {
+            let path_in_archive = Path::new(ACCOUNTS_DIR).join(AccountsFile::file_name(
+                storage.slot(),
+                storage.append_vec_id(),
+            ));
+            begin_file(archive.get_mut(), &path_in_archive, Some(storage))
+                .map_err(|err| E::ArchiveAccountStorageFile(err, storage.path().to_path_buf()))?;
+            let mut header = tar::Header::new_gnu();
+            header
+                .set_path(path_in_archive)
+                .map_err(|err| E::ArchiveAccountStorageFile(err, storage.path().to_path_buf()))?;
+            header.set_size(storage.capacity());
+            header.set_cksum();
+            archive
+                .append(&header, storage.accounts.data_for_archive())
+                .map_err(|err| E::ArchiveAccountStorageFile(err, storage.path().to_path_buf()))?;
+        }
+        Ok(())
+    }
+
     // Tar the staging directory into the archive at `archive_path`
     let archive_path = tar_dir.join(format!(
         "{}{}.{}",
@@ -793,33 +855,13 @@ pub fn archive_snapshot_package(
 
         let do_archive_files = |encoder: &mut dyn Write| -> std::result::Result<(), E> {
             let mut archive = tar::Builder::new(encoder);
-            // Serialize the version and snapshots files before accounts so we can quickly determine the version
-            // and other bank fields. This is necessary if we want to interleave unpacking with reconstruction
-            archive
-                .append_path_with_name(&staging_version_file, SNAPSHOT_VERSION_FILENAME)
-                .map_err(E::ArchiveVersionFile)?;
-            archive
-                .append_dir_all(SNAPSHOTS_DIR, &staging_snapshots_dir)
-                .map_err(E::ArchiveSnapshotsDir)?;
-
-            for storage in &snapshot_package.snapshot_storages {
-                let path_in_archive = Path::new(ACCOUNTS_DIR).join(AccountsFile::file_name(
-                    storage.slot(),
-                    storage.append_vec_id(),
-                ));
-                let mut header = tar::Header::new_gnu();
-                header.set_path(path_in_archive).map_err(|err| {
-                    E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
-                })?;
-                header.set_size(storage.capacity());
-                header.set_cksum();
-                archive
-                    .append(&header, storage.accounts.data_for_archive())
-                    .map_err(|err| {
-                        E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
-                    })?;
-            }
-
+            append_snapshot_files(
+                &mut archive,
+                &staging_version_file,
+                &staging_snapshots_dir,
+                &snapshot_package.snapshot_storages,
+                |_, _, _| Ok(()),
+            )?;
             archive.into_inner().map_err(E::FinishArchive)?;
             Ok(())
         };
@@ -855,6 +897,21 @@ pub fn archive_snapshot_package(
             ArchiveFormat::Tar => {
                 do_archive_files(&mut archive_file)?;
             }
+            ArchiveFormat::TarZstdIndexed => {
+                let mut archive = tar::Builder::new(IndexedArchiveWriter::new(archive_file));
+                append_snapshot_files(
+                    &mut archive,
+                    &staging_version_file,
+                    &staging_snapshots_dir,
+                    &snapshot_package.snapshot_storages,
+                    |writer, path_in_archive, storage| match storage {
+                        Some(storage) => writer.begin_storage_file(path_in_archive, storage),
+                        None => writer.begin_file(path_in_archive),
+                    },
+                )?;
+                let writer = archive.into_inner().map_err(E::FinishArchive)?;
+                writer.finish().map_err(E::FinishEncoder)?;
+            }
         };
     }
 
@@ -1268,6 +1325,10 @@ pub(crate) fn get_storages_to_serialize(
 const PARALLEL_UNTAR_READERS_DEFAULT: usize = 4;
 
 /// Unarchives the given full and incremental snapshot archives, as long as they are compatible.
+///
+/// If the incremental snapshot archive is based on an earlier incremental snapshot archive, the
+/// rest of its chain is looked up next to it and unarchived too.  The storages of the whole chain
+/// are then returned with the unarchived incremental snapshot.
 pub fn verify_and_unarchive_snapshots(
     bank_snapshots_dir: impl AsRef<Path>,
     full_snapshot_archive_info: &FullSnapshotArchiveInfo,
@@ -1278,9 +1339,18 @@ pub fn verify_and_unarchive_snapshots(
     Option<UnarchivedSnapshot>,
     AtomicAccountsFileId,
 )> {
+    let incremental_snapshot_archive_chain = incremental_snapshot_archive_info
+        .map(|incremental_snapshot_archive_info| {
+            get_incremental_snapshot_archive_chain(
+                get_snapshot_archives_dir_of(incremental_snapshot_archive_info),
+                full_snapshot_archive_info.slot(),
+                incremental_snapshot_archive_info,
+            )
+        })
+        .unwrap_or_default();
     check_are_snapshots_compatible(
         full_snapshot_archive_info,
-        incremental_snapshot_archive_info,
+        incremental_snapshot_archive_chain.first(),
     )?;
 
     let parallel_divisions = (num_cpus::get() / 4).clamp(1, PARALLEL_UNTAR_READERS_DEFAULT);
@@ -1297,22 +1367,35 @@ pub fn verify_and_unarchive_snapshots(
         next_append_vec_id.clone(),
     )?;
 
-    let unarchived_incremental_snapshot =
-        if let Some(incremental_snapshot_archive_info) = incremental_snapshot_archive_info {
-            let unarchived_incremental_snapshot = unarchive_snapshot(
-                &bank_snapshots_dir,
-                TMP_SNAPSHOT_ARCHIVE_PREFIX,
-                incremental_snapshot_archive_info.path(),
-                "incremental snapshot untar",
-                account_paths,
-                incremental_snapshot_archive_info.archive_format(),
-                parallel_divisions,
-                next_append_vec_id.clone(),
-            )?;
-            Some(unarchived_incremental_snapshot)
-        } else {
-            None
-        };
+    let mut measure_untar_chain = Measure::start("incremental snapshot chain untar");
+    let mut unarchived_incremental_snapshot: Option<UnarchivedSnapshot> = None;
+    for incremental_snapshot_archive_info in &incremental_snapshot_archive_chain {
+        let mut unarchived_chain_link = unarchive_snapshot(
+            &bank_snapshots_dir,
+            TMP_SNAPSHOT_ARCHIVE_PREFIX,
+            incremental_snapshot_archive_info.path(),
+            "incremental snapshot untar",
+            account_paths,
+            incremental_snapshot_archive_info.archive_format(),
+            parallel_divisions,
+            next_append_vec_id.clone(),
+        )?;
+        // Each link in the chain only contains the storages newer than the previous link, so
+        // collect them all into the last link, whose bank snapshot is the one to load.
+        if let Some(previous_chain_link) = unarchived_incremental_snapshot.take() {
+            unarchived_chain_link
+                .storage
+                .extend(previous_chain_link.storage);
+        }
+        unarchived_incremental_snapshot = Some(unarchived_chain_link);
+    }
+    measure_untar_chain.stop();
+    if incremental_snapshot_archive_chain.len() > 1 {
+        info!("{measure_untar_chain}");
+        if let Some(unarchived_incremental_snapshot) = unarchived_incremental_snapshot.as_mut() {
+            unarchived_incremental_snapshot.measure_untar = measure_untar_chain;
+        }
+    }
 
     Ok((
         unarchived_full_snapshot,
@@ -1326,7 +1409,7 @@ fn spawn_unpack_snapshot_thread(
     file_sender: Sender<PathBuf>,
     account_paths: Arc<Vec<PathBuf>>,
     ledger_dir: Arc<PathBuf>,
-    mut archive: Archive<SharedBufferReader>,
+    mut archive: Archive<impl Read + Send + 'static>,
     parallel_selector: Option<ParallelSelector>,
     thread_index: usize,
 ) -> JoinHandle<()> {
@@ -1356,6 +1439,31 @@ fn streaming_unarchive_snapshot(
 ) -> Vec<JoinHandle<()>> {
     let account_paths = Arc::new(account_paths);
     let ledger_dir = Arc::new(ledger_dir);
+
+    if archive_format == ArchiveFormat::TarZstdIndexed {
+        // Each thread decompresses its share of the frames, seeking past the others
+        let indexed_archive = IndexedSnapshotArchive::open(&snapshot_archive_path)
+            .map_err(|err| {
+                IoError::other(format!(
+                    "failed to open snapshot archive '{}': {err}",
+                    snapshot_archive_path.display(),
+                ))
+            })
+            .unwrap();
+        return (0..num_threads)
+            .map(|thread_index| {
+                spawn_unpack_snapshot_thread(
+                    file_sender.clone(),
+                    account_paths.clone(),
+                    ledger_dir.clone(),
+                    Archive::new(indexed_archive.frames_reader(thread_index, num_threads)),
+                    None,
+                    thread_index,
+                )
+            })
+            .collect();
+    }
+
     let shared_buffer = untar_snapshot_create_shared_buffer(&snapshot_archive_path, archive_format);
 
     // All shared buffer readers need to be created before the threads are spawned
@@ -1817,6 +1925,93 @@ pub fn get_incremental_snapshot_archives(
     )
 }
 
+/// Get the chain of incremental snapshot archives that ends at `incremental_snapshot_archive_info`
+///
+/// An incremental snapshot archive is based either on a full snapshot, or on the incremental
+/// snapshot archive whose slot is its base slot.  Starting at `incremental_snapshot_archive_info`,
+/// follow the base slots back through the incremental snapshot archives in the directory, for as
+/// long as they are above `full_snapshot_slot`.  The chain is returned oldest first; it is only
+/// complete if the first archive's base slot is `full_snapshot_slot`.
+pub fn get_incremental_snapshot_archive_chain(
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    full_snapshot_slot: Slot,
+    incremental_snapshot_archive_info: &IncrementalSnapshotArchiveInfo,
+) -> Vec<IncrementalSnapshotArchiveInfo> {
+    if incremental_snapshot_archive_info.base_slot() <= full_snapshot_slot {
+        return vec![incremental_snapshot_archive_info.clone()];
+    }
+    build_incremental_snapshot_archive_chain(
+        &get_incremental_snapshot_archives(incremental_snapshot_archives_dir),
+        full_snapshot_slot,
+        incremental_snapshot_archive_info,
+    )
+}
+
+/// Build the chain of incremental snapshot archives that ends at `incremental_snapshot_archive_info`,
+/// out of `incremental_snapshot_archives`
+///
+/// See `get_incremental_snapshot_archive_chain()` for details.
+fn build_incremental_snapshot_archive_chain(
+    incremental_snapshot_archives: &[IncrementalSnapshotArchiveInfo],
+    full_snapshot_slot: Slot,
+    incremental_snapshot_archive_info: &IncrementalSnapshotArchiveInfo,
+) -> Vec<IncrementalSnapshotArchiveInfo> {
+    let mut chain = vec![incremental_snapshot_archive_info.clone()];
+    loop {
+        let base_slot = chain.last().unwrap().base_slot();
+        if base_slot <= full_snapshot_slot {
+            break;
+        }
+        // If there are several archives at the base slot, prefer the one with the shortest chain.
+        // Base slots strictly decrease along the chain, so this always terminates.
+        let Some(base_snapshot_archive_info) = incremental_snapshot_archives
+            .iter()
+            .filter(|incremental_snapshot_archive_info| {
+                incremental_snapshot_archive_info.slot() == base_slot
+                    && incremental_snapshot_archive_info.base_slot() >= full_snapshot_slot
+                    && incremental_snapshot_archive_info.base_slot() < base_slot
+            })
+            .min_by_key(|incremental_snapshot_archive_info| {
+                incremental_snapshot_archive_info.base_slot()
+            })
+        else {
+            break;
+        };
+        chain.push(base_snapshot_archive_info.clone());
+    }
+    chain.reverse();
+    chain
+}
+
+/// Is this chain of incremental snapshot archives complete, i.e. does it lead back to the full
+/// snapshot at `full_snapshot_slot`?
+fn is_incremental_snapshot_archive_chain_complete(
+    incremental_snapshot_archive_chain: &[IncrementalSnapshotArchiveInfo],
+    full_snapshot_slot: Slot,
+) -> bool {
+    incremental_snapshot_archive_chain
+        .first()
+        .is_some_and(|incremental_snapshot_archive_info| {
+            incremental_snapshot_archive_info.base_slot() == full_snapshot_slot
+        })
+}
+
+/// Get the snapshot archives directory that a snapshot archive is in
+///
+/// Downloaded snapshot archives are in the directory's remote subdirectory, which is searched
+/// together with the directory itself.
+fn get_snapshot_archives_dir_of(snapshot_archive_info: &impl SnapshotArchiveInfoGetter) -> &Path {
+    let dir = snapshot_archive_info
+        .path()
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    if dir.file_name() == Some(std::ffi::OsStr::new(SNAPSHOT_ARCHIVE_DOWNLOAD_DIR)) {
+        dir.parent().unwrap_or(dir)
+    } else {
+        dir
+    }
+}
+
 /// Get the highest slot of the full snapshot archives in a directory
 pub fn get_highest_full_snapshot_archive_slot(
     full_snapshot_archives_dir: impl AsRef<Path>,
@@ -1842,29 +2037,68 @@ pub fn get_highest_incremental_snapshot_archive_slot(
 pub fn get_highest_full_snapshot_archive_info(
     full_snapshot_archives_dir: impl AsRef<Path>,
 ) -> Option<FullSnapshotArchiveInfo> {
-    let mut full_snapshot_archives = get_full_snapshot_archives(full_snapshot_archives_dir);
-    full_snapshot_archives.sort_unstable();
-    full_snapshot_archives.into_iter().next_back()
+    get_highest_full_snapshot_archive_info_at_or_below(full_snapshot_archives_dir, Slot::MAX)
+}
+
+/// Get the path (and metadata) for the full snapshot archive with the highest slot that is not
+/// greater than `max_slot`, in a directory
+pub fn get_highest_full_snapshot_archive_info_at_or_below(
+    full_snapshot_archives_dir: impl AsRef<Path>,
+    max_slot: Slot,
+) -> Option<FullSnapshotArchiveInfo> {
+    get_full_snapshot_archives(full_snapshot_archives_dir)
+        .into_iter()
+        .filter(|full_snapshot_archive_info| full_snapshot_archive_info.slot() <= max_slot)
+        .max()
 }
 
 /// Get the path for the incremental snapshot archive with the highest slot, for a given full
 /// snapshot slot, in a directory
+///
+/// The incremental snapshot archive may be the last one of a chain of incremental snapshot
+/// archives; use `get_incremental_snapshot_archive_chain()` to get the rest of the chain.
 pub fn get_highest_incremental_snapshot_archive_info(
     incremental_snapshot_archives_dir: impl AsRef<Path>,
     full_snapshot_slot: Slot,
 ) -> Option<IncrementalSnapshotArchiveInfo> {
-    // Since we want to filter down to only the incremental snapshot archives that have the same
-    // full snapshot slot as the value passed in, perform the filtering before sorting to avoid
-    // doing unnecessary work.
-    let mut incremental_snapshot_archives =
-        get_incremental_snapshot_archives(incremental_snapshot_archives_dir)
-            .into_iter()
-            .filter(|incremental_snapshot_archive_info| {
-                incremental_snapshot_archive_info.base_slot() == full_snapshot_slot
-            })
-            .collect::<Vec<_>>();
-    incremental_snapshot_archives.sort_unstable();
-    incremental_snapshot_archives.into_iter().next_back()
+    get_highest_incremental_snapshot_archive_info_at_or_below(
+        incremental_snapshot_archives_dir,
+        full_snapshot_slot,
+        Slot::MAX,
+    )
+}
+
+/// Get the path for the incremental snapshot archive with the highest slot that is not greater
+/// than `max_slot`, for a given full snapshot slot, in a directory
+pub fn get_highest_incremental_snapshot_archive_info_at_or_below(
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    full_snapshot_slot: Slot,
+    max_slot: Slot,
+) -> Option<IncrementalSnapshotArchiveInfo> {
+    let incremental_snapshot_archives =
+        get_incremental_snapshot_archives(incremental_snapshot_archives_dir);
+    incremental_snapshot_archives
+        .iter()
+        .filter(|incremental_snapshot_archive_info| {
+            incremental_snapshot_archive_info.base_slot() >= full_snapshot_slot
+                && incremental_snapshot_archive_info.slot() <= max_slot
+                && is_incremental_snapshot_archive_chain_complete(
+                    &build_incremental_snapshot_archive_chain(
+                        &incremental_snapshot_archives,
+                        full_snapshot_slot,
+                        incremental_snapshot_archive_info,
+                    ),
+                    full_snapshot_slot,
+                )
+        })
+        // If there are several archives at the highest slot, prefer the one with the shortest
+        // chain, i.e. the lowest base slot
+        .max_by(|a, b| {
+            a.slot()
+                .cmp(&b.slot())
+                .then(b.base_slot().cmp(&a.base_slot()))
+        })
+        .cloned()
 }
 
 pub fn purge_old_snapshot_archives(
@@ -1924,41 +2158,62 @@ pub fn purge_old_snapshot_archives(
         incremental_snapshot_archives_dir.as_ref().display(),
         maximum_incremental_snapshot_archives_to_retain
     );
-    let mut incremental_snapshot_archives_by_base_slot = HashMap::<Slot, Vec<_>>::new();
-    for incremental_snapshot_archive in
-        get_incremental_snapshot_archives(&incremental_snapshot_archives_dir)
-    {
-        incremental_snapshot_archives_by_base_slot
-            .entry(incremental_snapshot_archive.base_slot())
-            .or_default()
-            .push(incremental_snapshot_archive)
-    }
+    let incremental_snapshot_archives =
+        get_incremental_snapshot_archives(&incremental_snapshot_archives_dir);
 
+    // Incremental snapshot archives may be based on earlier incremental snapshot archives, so
+    // retaining an incremental snapshot archive means retaining its whole chain too.
     let highest_full_snapshot_slot = retained_full_snapshot_slots.iter().max().copied();
-    for (base_slot, mut incremental_snapshot_archives) in incremental_snapshot_archives_by_base_slot
-    {
-        incremental_snapshot_archives.sort_unstable();
-        let num_to_retain = if Some(base_slot) == highest_full_snapshot_slot {
+    let mut incremental_snapshot_archive_paths_to_retain = HashSet::new();
+    for full_snapshot_slot in &retained_full_snapshot_slots {
+        let mut incremental_snapshot_archive_chains = incremental_snapshot_archives
+            .iter()
+            .filter(|incremental_snapshot_archive_info| {
+                incremental_snapshot_archive_info.base_slot() >= *full_snapshot_slot
+            })
+            .map(|incremental_snapshot_archive_info| {
+                build_incremental_snapshot_archive_chain(
+                    &incremental_snapshot_archives,
+                    *full_snapshot_slot,
+                    incremental_snapshot_archive_info,
+                )
+            })
+            .filter(|incremental_snapshot_archive_chain| {
+                is_incremental_snapshot_archive_chain_complete(
+                    incremental_snapshot_archive_chain,
+                    *full_snapshot_slot,
+                )
+            })
+            .collect::<Vec<_>>();
+        incremental_snapshot_archive_chains
+            .sort_unstable_by_key(|chain| chain.last().map(|ai| ai.slot()));
+
+        let num_to_retain = if Some(*full_snapshot_slot) == highest_full_snapshot_slot {
             maximum_incremental_snapshot_archives_to_retain.get()
         } else {
-            usize::from(retained_full_snapshot_slots.contains(&base_slot))
+            1
         };
         trace!(
-            "There are {} incremental snapshot archives for base slot {}, removing {} of them",
-            incremental_snapshot_archives.len(),
-            base_slot,
-            incremental_snapshot_archives
-                .len()
-                .saturating_sub(num_to_retain),
+            "There are {} incremental snapshot archives for base slot {}, retaining {} of them",
+            incremental_snapshot_archive_chains.len(),
+            full_snapshot_slot,
+            incremental_snapshot_archive_chains.len().min(num_to_retain),
         );
-
-        incremental_snapshot_archives.truncate(
-            incremental_snapshot_archives
-                .len()
-                .saturating_sub(num_to_retain),
+        incremental_snapshot_archive_paths_to_retain.extend(
+            incremental_snapshot_archive_chains
+                .iter()
+                .rev()
+                .take(num_to_retain)
+                .flatten()
+                .map(|ai| ai.path().to_path_buf()),
         );
-        remove_archives(&incremental_snapshot_archives);
     }
+
+    let incremental_snapshot_archives_to_remove = incremental_snapshot_archives
+        .into_iter()
+        .filter(|ai| !incremental_snapshot_archive_paths_to_retain.contains(ai.path()))
+        .collect::<Vec<_>>();
+    remove_archives(&incremental_snapshot_archives_to_remove);
 }
 
 #[cfg(feature = "dev-context-only-utils")]
@@ -2019,7 +2274,8 @@ fn untar_snapshot_create_shared_buffer(
     match archive_format {
         ArchiveFormat::TarBzip2 => SharedBuffer::new(BzDecoder::new(BufReader::new(open_file()))),
         ArchiveFormat::TarGzip => SharedBuffer::new(GzDecoder::new(BufReader::new(open_file()))),
-        ArchiveFormat::TarZstd => SharedBuffer::new(
+        // The index of an indexed archive is in skippable frames, which the decoder ignores
+        ArchiveFormat::TarZstd | ArchiveFormat::TarZstdIndexed => SharedBuffer::new(
             zstd::stream::read::Decoder::new(BufReader::new(open_file())).unwrap(),
         ),
         ArchiveFormat::TarLz4 => {
@@ -2931,6 +3187,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_highest_snapshot_archive_info_at_or_below() {
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        common_create_snapshot_archive_files(
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            10,
+            20,
+            30,
+            40,
+        );
+
+        let full_snapshot_slot = |max_slot| {
+            get_highest_full_snapshot_archive_info_at_or_below(
+                full_snapshot_archives_dir.path(),
+                max_slot,
+            )
+            .map(|info| info.slot())
+        };
+        assert_eq!(full_snapshot_slot(9), None);
+        assert_eq!(full_snapshot_slot(10), Some(10));
+        assert_eq!(full_snapshot_slot(15), Some(15));
+        assert_eq!(full_snapshot_slot(Slot::MAX), Some(19));
+
+        let incremental_snapshot_slot = |full_snapshot_slot, max_slot| {
+            get_highest_incremental_snapshot_archive_info_at_or_below(
+                incremental_snapshot_archives_dir.path(),
+                full_snapshot_slot,
+                max_slot,
+            )
+            .map(|info| info.slot())
+        };
+        assert_eq!(incremental_snapshot_slot(15, 29), None);
+        assert_eq!(incremental_snapshot_slot(15, 35), Some(35));
+        assert_eq!(incremental_snapshot_slot(15, Slot::MAX), Some(39));
+        assert_eq!(incremental_snapshot_slot(20, Slot::MAX), None);
+    }
+
     fn common_test_purge_old_snapshot_archives(
         snapshot_names: &[&String],
         maximum_full_snapshot_archives_to_retain: NonZeroUsize,
@@ -3219,6 +3514,81 @@ mod tests {
         assert!(remaining_incremental_snapshot_archives.is_empty());
     }
 
+    #[test]
+    fn test_purge_old_incremental_snapshot_archive_chains() {
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+
+        for full_snapshot_slot in [100, 200] {
+            let snapshot_filename =
+                format!("snapshot-{}-{}.tar", full_snapshot_slot, Hash::default());
+            let snapshot_path = full_snapshot_archives_dir.path().join(snapshot_filename);
+            fs::File::create(snapshot_path).unwrap();
+        }
+        for (base_slot, slot) in [
+            (50, 70),
+            (100, 120),
+            (120, 140),
+            (100, 160),
+            (200, 220),
+            (220, 240),
+            (240, 260),
+            (200, 280),
+            (280, 300),
+        ] {
+            let snapshot_filename = format!(
+                "incremental-snapshot-{}-{}-{}.tar",
+                base_slot,
+                slot,
+                Hash::default()
+            );
+            let snapshot_path = incremental_snapshot_archives_dir
+                .path()
+                .join(snapshot_filename);
+            fs::File::create(snapshot_path).unwrap();
+        }
+
+        let highest_incremental_snapshot_archive_info =
+            get_highest_incremental_snapshot_archive_info(
+                incremental_snapshot_archives_dir.path(),
+                200,
+            )
+            .unwrap();
+        assert_eq!(highest_incremental_snapshot_archive_info.slot(), 300);
+        let incremental_snapshot_archive_chain = get_incremental_snapshot_archive_chain(
+            incremental_snapshot_archives_dir.path(),
+            200,
+            &highest_incremental_snapshot_archive_info,
+        );
+        assert_eq!(
+            incremental_snapshot_archive_chain
+                .iter()
+                .map(|ai| (ai.base_slot(), ai.slot()))
+                .collect::<Vec<_>>(),
+            vec![(200, 280), (280, 300)],
+        );
+
+        purge_old_snapshot_archives(
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            NonZeroUsize::new(2).unwrap(),
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        // The two highest incremental snapshot archives for the latest full snapshot are kept,
+        // with their chains, as is the highest one for the older full snapshot.
+        let mut remaining_incremental_snapshot_archives =
+            get_incremental_snapshot_archives(incremental_snapshot_archives_dir.path())
+                .iter()
+                .map(|ai| (ai.base_slot(), ai.slot()))
+                .collect::<Vec<_>>();
+        remaining_incremental_snapshot_archives.sort_unstable();
+        assert_eq!(
+            remaining_incremental_snapshot_archives,
+            vec![(100, 160), (200, 280), (280, 300)],
+        );
+    }
+
     #[test]
     fn test_get_snapshot_accounts_hardlink_dir() {
         let slot: Slot = 1;
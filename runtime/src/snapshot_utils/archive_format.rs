@@ -4,12 +4,12 @@ use {
 };
 
 // SUPPORTED_ARCHIVE_COMPRESSION lists the compression types that can be
-// specified on the command line. "zstd" and "lz4" are valid whereas "gzip",
-// "bz2", "tar" and "none" have been deprecated. Thus, all newly created
-// snapshots will either use "zstd" or "lz4". By keeping the deprecated types
-// in the ArchiveFormat enum, pre-existing snapshot archives with the
-// deprecated compression types can still be read.
-pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4"];
+// specified on the command line. "zstd", "lz4" and "zstd-indexed" are valid
+// whereas "gzip", "bz2", "tar" and "none" have been deprecated. Thus, all newly
+// created snapshots will use "zstd", "lz4" or "zstd-indexed". By keeping the
+// deprecated types in the ArchiveFormat enum, pre-existing snapshot archives
+// with the deprecated compression types can still be read.
+pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4", "zstd-indexed"];
 pub const DEFAULT_ARCHIVE_COMPRESSION: &str = "zstd";
 
 pub const TAR_BZIP2_EXTENSION: &str = "tar.bz2";
@@ -17,6 +17,7 @@ pub const TAR_GZIP_EXTENSION: &str = "tar.gz";
 pub const TAR_ZSTD_EXTENSION: &str = "tar.zst";
 pub const TAR_LZ4_EXTENSION: &str = "tar.lz4";
 pub const TAR_EXTENSION: &str = "tar";
+pub const TAR_ZSTD_INDEXED_EXTENSION: &str = "indexed.tar.zst";
 
 /// The different archive formats used for snapshots
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Display)]
@@ -26,6 +27,9 @@ pub enum ArchiveFormat {
     TarZstd,
     TarLz4,
     Tar,
+    /// A zstd compressed tar archive with an index to read single files and accounts from it, see
+    /// `IndexedSnapshotArchive`
+    TarZstdIndexed,
 }
 
 impl ArchiveFormat {
@@ -37,6 +41,7 @@ impl ArchiveFormat {
             ArchiveFormat::TarZstd => TAR_ZSTD_EXTENSION,
             ArchiveFormat::TarLz4 => TAR_LZ4_EXTENSION,
             ArchiveFormat::Tar => TAR_EXTENSION,
+            ArchiveFormat::TarZstdIndexed => TAR_ZSTD_INDEXED_EXTENSION,
         }
     }
 
@@ -44,6 +49,7 @@ impl ArchiveFormat {
         match archive_format_str {
             "zstd" => Some(ArchiveFormat::TarZstd),
             "lz4" => Some(ArchiveFormat::TarLz4),
+            "zstd-indexed" => Some(ArchiveFormat::TarZstdIndexed),
             _ => None,
         }
     }
@@ -61,6 +67,7 @@ impl TryFrom<&str> for ArchiveFormat {
             TAR_ZSTD_EXTENSION => Ok(ArchiveFormat::TarZstd),
             TAR_LZ4_EXTENSION => Ok(ArchiveFormat::TarLz4),
             TAR_EXTENSION => Ok(ArchiveFormat::Tar),
+            TAR_ZSTD_INDEXED_EXTENSION => Ok(ArchiveFormat::TarZstdIndexed),
             _ => Err(ParseError::InvalidExtension(extension.to_string())),
         }
     }
@@ -101,6 +108,10 @@ mod tests {
         assert_eq!(ArchiveFormat::TarZstd.extension(), TAR_ZSTD_EXTENSION);
         assert_eq!(ArchiveFormat::TarLz4.extension(), TAR_LZ4_EXTENSION);
         assert_eq!(ArchiveFormat::Tar.extension(), TAR_EXTENSION);
+        assert_eq!(
+            ArchiveFormat::TarZstdIndexed.extension(),
+            TAR_ZSTD_INDEXED_EXTENSION
+        );
     }
 
     #[test]
@@ -125,6 +136,10 @@ mod tests {
             ArchiveFormat::try_from(TAR_EXTENSION),
             Ok(ArchiveFormat::Tar)
         );
+        assert_eq!(
+            ArchiveFormat::try_from(TAR_ZSTD_INDEXED_EXTENSION),
+            Ok(ArchiveFormat::TarZstdIndexed)
+        );
         assert_eq!(
             ArchiveFormat::try_from(INVALID_EXTENSION),
             Err(ParseError::InvalidExtension(INVALID_EXTENSION.to_string()))
@@ -153,6 +168,10 @@ mod tests {
             ArchiveFormat::from_str(TAR_EXTENSION),
             Ok(ArchiveFormat::Tar)
         );
+        assert_eq!(
+            ArchiveFormat::from_str(TAR_ZSTD_INDEXED_EXTENSION),
+            Ok(ArchiveFormat::TarZstdIndexed)
+        );
         assert_eq!(
             ArchiveFormat::from_str(INVALID_EXTENSION),
             Err(ParseError::InvalidExtension(INVALID_EXTENSION.to_string()))
@@ -161,7 +180,11 @@ mod tests {
 
     #[test]
     fn test_from_cli_arg() {
-        let golden = [Some(ArchiveFormat::TarZstd), Some(ArchiveFormat::TarLz4)];
+        let golden = [
+            Some(ArchiveFormat::TarZstd),
+            Some(ArchiveFormat::TarLz4),
+            Some(ArchiveFormat::TarZstdIndexed),
+        ];
 
         for (arg, expected) in zip(SUPPORTED_ARCHIVE_COMPRESSION.iter(), golden.into_iter()) {
             assert_eq!(ArchiveFormat::from_cli_arg(arg), expected);
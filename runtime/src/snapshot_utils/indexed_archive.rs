@@ -0,0 +1,578 @@
+//! Snapshot archives in the `TarZstdIndexed` format
+//!
+//! The archive is a regular zstd compressed tar archive, except that every file, or directory in
+//! the case of `snapshots/`, is compressed in its own zstd frame. It ends with an index of the
+//! frames and of the accounts in the account storage files, stored in zstd skippable frames so
+//! that decompressing the archive as a whole ignores them. The index allows reading a single file
+//! or account from the archive without unpacking the rest of it.
+//!
+//! The index of the frames and account storage files is serialized with bincode. The accounts are
+//! in a table of fixed size records sorted by pubkey, which is binary searched in place since it
+//! is too large to load for mainnet-beta. The last skippable frame is a footer of fixed size with
+//! the location of both.
+
+use {
+    super::{Result, SnapshotError},
+    serde_derive::{Deserialize, Serialize},
+    solana_accounts_db::{
+        accounts_db::{AccountStorageEntry, AccountsFileId},
+        accounts_file::AccountsFile,
+        lazy_accounts_source::LazyAccountsSource,
+    },
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        pubkey::Pubkey,
+    },
+    std::{
+        cmp::Ordering,
+        collections::HashMap,
+        fs::File,
+        io::{BufReader, Error as IoError, Read, Result as IoResult, Seek, SeekFrom, Take, Write},
+        path::{Path, PathBuf},
+        sync::RwLock,
+    },
+    tar::Archive,
+};
+
+const ZSTD_COMPRESSION_LEVEL: i32 = 0;
+// Skippable frames have a magic number in 0x184D2A50..=0x184D2A5F, followed by the length of
+// their content as a u32
+const SKIPPABLE_FRAME_MAGIC: u32 = 0x184D2A50;
+const SKIPPABLE_FRAME_HEADER_LEN: usize = 8;
+const MAX_SKIPPABLE_FRAME_CONTENT_LEN: usize = u32::MAX as usize;
+const INDEX_MAGIC: &[u8; 8] = b"SOLIDX02";
+// Offset and length of the index, offset of the accounts table, number of accounts and
+// `INDEX_MAGIC`
+const FOOTER_LEN: usize = 8 + 8 + 8 + 8 + INDEX_MAGIC.len();
+// Pubkey, storage and offset of an account in the accounts table
+const ACCOUNT_RECORD_LEN: usize = 32 + 4 + 8;
+const ACCOUNTS_PER_FRAME: u64 = 1 << 16;
+
+/// A zstd frame of the archive, holding the tar entries of `path`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFrame {
+    /// Path in the archive of the file or directory in the frame
+    pub path: String,
+    /// Offset of the frame in the archive
+    pub offset: u64,
+    /// Compressed length of the frame
+    pub len: u64,
+}
+
+/// An account storage file of the archive
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexedStorage {
+    pub slot: Slot,
+    pub id: AccountsFileId,
+    /// Length of the account data in the file, which may be padded up to its capacity
+    pub len: u64,
+    /// Index of the frame of the file in `ArchiveIndex::frames`
+    pub frame: u32,
+}
+
+/// Location of the latest version of an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedAccount {
+    pub pubkey: Pubkey,
+    /// Index of the account storage file in `ArchiveIndex::storages`
+    pub storage: u32,
+    /// Offset of the account in the account storage file
+    pub offset: u64,
+}
+
+impl IndexedAccount {
+    fn write_record(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(self.pubkey.as_ref());
+        output.extend_from_slice(&self.storage.to_le_bytes());
+        output.extend_from_slice(&self.offset.to_le_bytes());
+    }
+
+    fn from_record(record: &[u8; ACCOUNT_RECORD_LEN]) -> Self {
+        let (pubkey, rest) = record.split_at(32);
+        let (storage, offset) = rest.split_at(4);
+        Self {
+            pubkey: Pubkey::try_from(pubkey).unwrap(),
+            storage: u32::from_le_bytes(storage.try_into().unwrap()),
+            offset: u64::from_le_bytes(offset.try_into().unwrap()),
+        }
+    }
+}
+
+/// Length of the frames of the accounts table holding `num_accounts` accounts
+fn accounts_table_len(num_accounts: u64) -> u64 {
+    let full_frame_len =
+        SKIPPABLE_FRAME_HEADER_LEN as u64 + ACCOUNTS_PER_FRAME * ACCOUNT_RECORD_LEN as u64;
+    let remaining_accounts = num_accounts % ACCOUNTS_PER_FRAME;
+    (num_accounts / ACCOUNTS_PER_FRAME) * full_frame_len
+        + if remaining_accounts == 0 {
+            0
+        } else {
+            SKIPPABLE_FRAME_HEADER_LEN as u64 + remaining_accounts * ACCOUNT_RECORD_LEN as u64
+        }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveIndex {
+    /// The frames of the archive, in order
+    pub frames: Vec<IndexedFrame>,
+    pub storages: Vec<IndexedStorage>,
+}
+
+/// Counts the bytes written to the inner writer, to locate the frames
+struct CountingWriter<W> {
+    inner: W,
+    len: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let len = self.inner.write(buf)?;
+        self.len += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+
+enum WriterState<W: Write> {
+    BetweenFrames(CountingWriter<W>),
+    InFrame(zstd::stream::Encoder<'static, CountingWriter<W>>),
+}
+
+/// The output of a `tar::Builder` writing an indexed archive
+///
+/// `begin_file()` must be called before appending each file to the tar archive, so that it starts
+/// in a new frame.
+pub(crate) struct IndexedArchiveWriter<W: Write> {
+    // Only `None` while switching states
+    state: Option<WriterState<W>>,
+    index: ArchiveIndex,
+    accounts: Vec<IndexedAccount>,
+    // Slot of the storage of each account, to keep the latest version of each account
+    account_slots: Vec<Slot>,
+}
+
+impl<W: Write> IndexedArchiveWriter<W> {
+    pub(crate) fn new(output: W) -> Self {
+        Self {
+            state: Some(WriterState::BetweenFrames(CountingWriter {
+                inner: output,
+                len: 0,
+            })),
+            index: ArchiveIndex::default(),
+            accounts: Vec::default(),
+            account_slots: Vec::default(),
+        }
+    }
+
+    /// Start a new frame for the file or directory at `path` in the archive
+    pub(crate) fn begin_file(&mut self, path: &Path) -> IoResult<()> {
+        let output = self.end_frame()?;
+        self.index.frames.push(IndexedFrame {
+            path: path.to_string_lossy().into_owned(),
+            offset: output.len,
+            len: 0,
+        });
+        self.state = Some(WriterState::InFrame(zstd::stream::Encoder::new(
+            output,
+            ZSTD_COMPRESSION_LEVEL,
+        )?));
+        Ok(())
+    }
+
+    /// Start a new frame for the account storage file at `path` in the archive, and index its
+    /// accounts
+    pub(crate) fn begin_storage_file(
+        &mut self,
+        path: &Path,
+        storage: &AccountStorageEntry,
+    ) -> IoResult<()> {
+        self.begin_file(path)?;
+        let storage_index = self.index.storages.len() as u32;
+        self.index.storages.push(IndexedStorage {
+            slot: storage.slot(),
+            id: storage.append_vec_id(),
+            len: storage.accounts.len() as u64,
+            frame: self.index.frames.len() as u32 - 1,
+        });
+        for account in storage.accounts.account_iter() {
+            self.accounts.push(IndexedAccount {
+                pubkey: *account.pubkey(),
+                storage: storage_index,
+                offset: account.offset() as u64,
+            });
+            self.account_slots.push(storage.slot());
+        }
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> IoResult<CountingWriter<W>> {
+        match self.state.take().expect("writer state") {
+            WriterState::BetweenFrames(output) => Ok(output),
+            WriterState::InFrame(encoder) => {
+                let output = encoder.finish()?;
+                let frame = self.index.frames.last_mut().expect("frame was begun");
+                frame.len = output.len - frame.offset;
+                Ok(output)
+            }
+        }
+    }
+
+    /// End the last frame and write the index, after the tar archive is finished
+    pub(crate) fn finish(mut self) -> IoResult<W> {
+        let mut output = self.end_frame()?;
+
+        // Keep the latest version of each account: the one in the storage of the highest slot,
+        // at the highest offset within the storage
+        let mut accounts = std::mem::take(&mut self.accounts)
+            .into_iter()
+            .zip(self.account_slots)
+            .collect::<Vec<_>>();
+        accounts.sort_unstable_by(|(a, a_slot), (b, b_slot)| {
+            a.pubkey
+                .cmp(&b.pubkey)
+                .then(b_slot.cmp(a_slot))
+                .then(b.offset.cmp(&a.offset))
+        });
+        accounts.dedup_by(|(a, _), (b, _)| a.pubkey == b.pubkey);
+
+        let index = bincode::serialize(&self.index).map_err(IoError::other)?;
+        let index_offset = output.len;
+        for chunk in index.chunks(MAX_SKIPPABLE_FRAME_CONTENT_LEN) {
+            write_skippable_frame(&mut output, chunk)?;
+        }
+        let accounts_offset = output.len;
+        let mut records = Vec::with_capacity(ACCOUNTS_PER_FRAME as usize * ACCOUNT_RECORD_LEN);
+        for chunk in accounts.chunks(ACCOUNTS_PER_FRAME as usize) {
+            records.clear();
+            for (account, _slot) in chunk {
+                account.write_record(&mut records);
+            }
+            write_skippable_frame(&mut output, &records)?;
+        }
+        let mut footer = Vec::with_capacity(FOOTER_LEN);
+        footer.extend_from_slice(&index_offset.to_le_bytes());
+        footer.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        footer.extend_from_slice(&accounts_offset.to_le_bytes());
+        footer.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+        footer.extend_from_slice(INDEX_MAGIC);
+        write_skippable_frame(&mut output, &footer)?;
+        output.flush()?;
+        Ok(output.inner)
+    }
+}
+
+impl<W: Write> Write for IndexedArchiveWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        match self.state.as_mut().expect("writer state") {
+            WriterState::InFrame(encoder) => encoder.write(buf),
+            WriterState::BetweenFrames(_) => Err(IoError::other(
+                "begin_file() must be called before writing to the archive",
+            )),
+        }
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        match self.state.as_mut().expect("writer state") {
+            WriterState::InFrame(encoder) => encoder.flush(),
+            WriterState::BetweenFrames(output) => output.flush(),
+        }
+    }
+}
+
+fn write_skippable_frame(output: &mut impl Write, content: &[u8]) -> IoResult<()> {
+    output.write_all(&SKIPPABLE_FRAME_MAGIC.to_le_bytes())?;
+    output.write_all(&(content.len() as u32).to_le_bytes())?;
+    output.write_all(content)
+}
+
+/// Read the content of the skippable frame at the current position of `input`, if there is one
+fn read_skippable_frame(input: &mut impl Read) -> IoResult<Option<Vec<u8>>> {
+    let mut header = [0; SKIPPABLE_FRAME_HEADER_LEN];
+    input.read_exact(&mut header)?;
+    let (magic, len) = header.split_at(4);
+    if u32::from_le_bytes(magic.try_into().unwrap()) != SKIPPABLE_FRAME_MAGIC {
+        return Ok(None);
+    }
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as u64;
+    let mut content = Vec::new();
+    input.take(len).read_to_end(&mut content)?;
+    Ok((content.len() as u64 == len).then_some(content))
+}
+
+/// Decompresses a subset of the frames of an indexed archive, in order, as a single tar stream
+pub(crate) struct IndexedFramesReader {
+    path: PathBuf,
+    frames: std::vec::IntoIter<IndexedFrame>,
+    decoder: Option<zstd::stream::read::Decoder<'static, BufReader<Take<File>>>>,
+}
+
+impl Read for IndexedFramesReader {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        loop {
+            if let Some(decoder) = self.decoder.as_mut() {
+                let len = decoder.read(buf)?;
+                if len > 0 || buf.is_empty() {
+                    return Ok(len);
+                }
+            }
+            let Some(frame) = self.frames.next() else {
+                return Ok(0);
+            };
+            let mut file = File::open(&self.path)?;
+            file.seek(SeekFrom::Start(frame.offset))?;
+            self.decoder = Some(zstd::stream::read::Decoder::new(file.take(frame.len))?);
+        }
+    }
+}
+
+/// A snapshot archive in the `TarZstdIndexed` format
+#[derive(Debug)]
+pub struct IndexedSnapshotArchive {
+    path: PathBuf,
+    index: ArchiveIndex,
+    accounts_offset: u64,
+    num_accounts: u64,
+}
+
+impl IndexedSnapshotArchive {
+    /// Open the archive at `path` and read its index
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |reason: &str| {
+            SnapshotError::InvalidIndexedArchive(path.to_path_buf(), reason.to_string())
+        };
+
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let footer_frame_len = (SKIPPABLE_FRAME_HEADER_LEN + FOOTER_LEN) as u64;
+        if file_len < footer_frame_len {
+            return Err(invalid("too short to have an index"));
+        }
+        file.seek(SeekFrom::End(-(footer_frame_len as i64)))?;
+        let footer = read_skippable_frame(&mut file)?
+            .filter(|footer| footer.len() == FOOTER_LEN && footer.ends_with(INDEX_MAGIC))
+            .ok_or_else(|| invalid("missing index footer"))?;
+        let index_offset = u64::from_le_bytes(footer[..8].try_into().unwrap());
+        let index_len = u64::from_le_bytes(footer[8..16].try_into().unwrap());
+        let accounts_offset = u64::from_le_bytes(footer[16..24].try_into().unwrap());
+        let num_accounts = u64::from_le_bytes(footer[24..32].try_into().unwrap());
+        let accounts_end = num_accounts
+            .checked_mul(ACCOUNT_RECORD_LEN as u64)
+            .and_then(|_| accounts_offset.checked_add(accounts_table_len(num_accounts)));
+        if accounts_end.map_or(true, |end| end > file_len - footer_frame_len) {
+            return Err(invalid("truncated accounts table"));
+        }
+
+        file.seek(SeekFrom::Start(index_offset))?;
+        let mut index = Vec::new();
+        while (index.len() as u64) < index_len {
+            let content =
+                read_skippable_frame(&mut file)?.ok_or_else(|| invalid("truncated index"))?;
+            index.extend_from_slice(&content);
+        }
+        if index.len() as u64 != index_len {
+            return Err(invalid("index length mismatch"));
+        }
+        let index: ArchiveIndex = bincode::deserialize(&index)?;
+        if index
+            .storages
+            .iter()
+            .any(|storage| storage.frame as usize >= index.frames.len())
+        {
+            return Err(invalid("index references missing frames"));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            index,
+            accounts_offset,
+            num_accounts,
+        })
+    }
+
+    pub fn index(&self) -> &ArchiveIndex {
+        &self.index
+    }
+
+    /// Number of accounts in the accounts table of the archive
+    pub fn num_accounts(&self) -> u64 {
+        self.num_accounts
+    }
+
+    /// Reader of the tar entries in every `divisions`th frame of the archive, starting from
+    /// `division`, to unpack the archive from several threads
+    pub(crate) fn frames_reader(&self, division: usize, divisions: usize) -> IndexedFramesReader {
+        IndexedFramesReader {
+            path: self.path.clone(),
+            frames: self
+                .index
+                .frames
+                .iter()
+                .skip(division)
+                .step_by(divisions)
+                .cloned()
+                .collect::<Vec<_>>()
+                .into_iter(),
+            decoder: None,
+        }
+    }
+
+    /// Read the file at `path` in the archive, decompressing only the frame that holds it
+    pub fn read_file(&self, path: impl AsRef<Path>) -> Result<Option<Vec<u8>>> {
+        let path = path.as_ref();
+        let Some(frame) = self
+            .index
+            .frames
+            .iter()
+            .find(|frame| path.starts_with(&frame.path))
+        else {
+            return Ok(None);
+        };
+        self.read_file_in_frame(frame, path)
+    }
+
+    fn read_file_in_frame(&self, frame: &IndexedFrame, path: &Path) -> Result<Option<Vec<u8>>> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(frame.offset))?;
+        let mut archive = Archive::new(zstd::stream::read::Decoder::new(file.take(frame.len))?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.path()? == path {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                return Ok(Some(data));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read the account storage file of `slot` into `dir`, and return its path
+    pub fn unpack_storage(&self, slot: Slot, dir: impl AsRef<Path>) -> Result<Option<PathBuf>> {
+        let Some(storage) = self
+            .index
+            .storages
+            .iter()
+            .find(|storage| storage.slot == slot)
+        else {
+            return Ok(None);
+        };
+        self.unpack_indexed_storage(storage, dir.as_ref())
+    }
+
+    fn unpack_indexed_storage(
+        &self,
+        storage: &IndexedStorage,
+        dir: &Path,
+    ) -> Result<Option<PathBuf>> {
+        let frame = &self.index.frames[storage.frame as usize];
+        let Some(data) = self.read_file_in_frame(frame, Path::new(&frame.path))? else {
+            return Ok(None);
+        };
+        let path = dir.join(AccountsFile::file_name(storage.slot, storage.id));
+        std::fs::write(&path, data)?;
+        Ok(Some(path))
+    }
+
+    /// Get the latest version of the account at `pubkey` in the archive
+    ///
+    /// Only the account storage file holding the account is decompressed.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Result<Option<AccountSharedData>> {
+        let Some(account) = self.find_account(pubkey)? else {
+            return Ok(None);
+        };
+        let Some(storage) = self.index.storages.get(account.storage as usize) else {
+            return Err(SnapshotError::InvalidIndexedArchive(
+                self.path.clone(),
+                format!("account {pubkey} references a missing account storage file"),
+            ));
+        };
+
+        let dir = tempfile::TempDir::new()?;
+        let Some(path) = self.unpack_indexed_storage(storage, dir.path())? else {
+            return Err(SnapshotError::InvalidIndexedArchive(
+                self.path.clone(),
+                format!("missing account storage file for slot {}", storage.slot),
+            ));
+        };
+        let (accounts_file, _num_accounts) =
+            AccountsFile::new_from_file(path, storage.len as usize)?;
+        Ok(accounts_file
+            .get_stored_account_meta(account.offset as usize)
+            .map(|(stored_account, _next_offset)| stored_account.to_account_shared_data())
+            .filter(|account| account.lamports() != 0))
+    }
+
+    /// Binary search the accounts table for `pubkey`, reading only the records compared
+    fn find_account(&self, pubkey: &Pubkey) -> Result<Option<IndexedAccount>> {
+        let mut file = File::open(&self.path)?;
+        let (mut low, mut high) = (0, self.num_accounts);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let account = self.read_account_record(&mut file, middle)?;
+            match account.pubkey.cmp(pubkey) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Ok(Some(account)),
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_account_record(&self, file: &mut File, position: u64) -> Result<IndexedAccount> {
+        let offset = self.accounts_offset
+            + accounts_table_len(position - position % ACCOUNTS_PER_FRAME)
+            + SKIPPABLE_FRAME_HEADER_LEN as u64
+            + (position % ACCOUNTS_PER_FRAME) * ACCOUNT_RECORD_LEN as u64;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut record = [0; ACCOUNT_RECORD_LEN];
+        file.read_exact(&mut record)?;
+        Ok(IndexedAccount::from_record(&record))
+    }
+}
+
+/// The accounts of an indexed archive, read from it when they are first loaded
+#[derive(Debug)]
+pub struct IndexedArchiveAccounts {
+    archive: IndexedSnapshotArchive,
+    slot: Slot,
+    /// The accounts read so far, `None` for those missing from the archive
+    loaded: RwLock<HashMap<Pubkey, Option<AccountSharedData>>>,
+}
+
+impl IndexedArchiveAccounts {
+    /// The accounts of `archive`, which is a snapshot of `slot`
+    pub fn new(archive: IndexedSnapshotArchive, slot: Slot) -> Self {
+        Self {
+            archive,
+            slot,
+            loaded: RwLock::default(),
+        }
+    }
+}
+
+impl LazyAccountsSource for IndexedArchiveAccounts {
+    fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        if let Some(account) = self.loaded.read().unwrap().get(pubkey) {
+            return account.clone();
+        }
+        let account = self.archive.get_account(pubkey).unwrap_or_else(|err| {
+            panic!(
+                "Failed to read account {pubkey} from snapshot archive {}: {err}",
+                self.archive.path.display()
+            )
+        });
+        self.loaded
+            .write()
+            .unwrap()
+            .insert(*pubkey, account.clone());
+        account
+    }
+}
@@ -0,0 +1,525 @@
+use {
+    crate::{
+        append_signatures_for_address,
+        compression::{compress, decompress, CompressionMethod},
+        key_to_slot, slot_to_blocks_key, slot_to_entries_key, slot_to_tx_by_addr_key,
+        transaction_from_block, transaction_rows, Error, LongTermLedgerStorage, Result,
+        TransactionInfo,
+    },
+    async_trait::async_trait,
+    log::*,
+    prost::Message,
+    serde::{de::DeserializeOwned, Serialize},
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    solana_storage_proto::convert::{entries, generated, tx_by_addr},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, TransactionByAddrInfo, TransactionStatus,
+        VersionedConfirmedBlockWithEntries,
+    },
+    std::{
+        convert::TryInto,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+// Rows keyed by slot are grouped in directories of 2^16 consecutive keys
+const SLOT_KEY_GROUP_LEN: usize = 12;
+// Rows of the `tx` table are grouped in directories by the first characters of the signature
+const SIGNATURE_GROUP_LEN: usize = 2;
+
+/// Long-term ledger storage in a local directory, for RPC operators that want to keep the full
+/// transaction history without a BigTable instance.
+///
+/// It holds the same tables, row keys and row encodings as `LedgerStorage`, with a file per row.
+/// Files are written to a temporary path and renamed into place, and the `blocks` row of a slot
+/// is written last, so that partially uploaded blocks are never visible.
+#[derive(Clone)]
+pub struct FilesystemLedgerStorage {
+    path: Arc<PathBuf>,
+}
+
+impl FilesystemLedgerStorage {
+    /// Open the storage at `path`, creating the directory if it does not exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        fs::create_dir_all(path)?;
+        info!("Opened filesystem ledger storage at {}", path.display());
+        Ok(Self {
+            path: Arc::new(path.to_path_buf()),
+        })
+    }
+
+    fn blocks_dir(&self) -> PathBuf {
+        self.path.join("blocks")
+    }
+
+    fn entries_dir(&self) -> PathBuf {
+        self.path.join("entries")
+    }
+
+    fn tx_by_addr_dir(&self, address: &str) -> PathBuf {
+        self.path.join("tx-by-addr").join(address)
+    }
+
+    fn tx_path(&self, signature: &str) -> PathBuf {
+        self.path
+            .join("tx")
+            .join(&signature[..SIGNATURE_GROUP_LEN])
+            .join(signature)
+    }
+
+    fn read_transaction_info(&self, signature: &Signature) -> Result<TransactionInfo> {
+        let signature = signature.to_string();
+        read_bincode_row(&self.tx_path(&signature))?.ok_or(Error::SignatureNotFound)
+    }
+
+    fn read_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        let key = slot_to_blocks_key(slot);
+        let block = read_protobuf_row::<generated::ConfirmedBlock>(&slot_key_path(
+            &self.blocks_dir(),
+            &key,
+        ))?
+        .ok_or(Error::BlockNotFound(slot))?;
+        block
+            .try_into()
+            .map_err(|err| Error::ObjectCorrupt(format!("blocks/{key}: {err}")))
+    }
+
+    fn read_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        let mut slots = vec![];
+        for_each_slot_key(
+            &self.blocks_dir(),
+            &slot_to_blocks_key(start_slot),
+            &slot_to_blocks_key(Slot::MAX),
+            |key| {
+                slots.extend(key_to_slot(key));
+                Ok(slots.len() < limit)
+            },
+        )?;
+        Ok(slots)
+    }
+
+    fn read_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        // Figure out where to start listing from based on `before_signature`
+        let before = match before_signature {
+            None => (Slot::MAX, 0),
+            Some(before_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.read_transaction_info(before_signature)?;
+                (slot, index)
+            }
+        };
+
+        // Figure out where to end listing from based on `until_signature`
+        let until = match until_signature {
+            None => (0, u32::MAX),
+            Some(until_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.read_transaction_info(until_signature)?;
+                (slot, index)
+            }
+        };
+
+        let address = address.to_string();
+        let dir = self.tx_by_addr_dir(&address);
+        let mut infos = vec![];
+        for_each_slot_key(
+            &dir,
+            &slot_to_tx_by_addr_key(before.0),
+            &slot_to_tx_by_addr_key(until.0),
+            |key| {
+                let slot = !key_to_slot(key).ok_or_else(|| {
+                    Error::ObjectCorrupt(format!(
+                        "Failed to convert key to slot: tx-by-addr/{address}/{key}"
+                    ))
+                })?;
+                let Some(tx_by_addr) =
+                    read_protobuf_row::<tx_by_addr::TransactionByAddr>(&slot_key_path(&dir, key))?
+                else {
+                    return Ok(true);
+                };
+                let tx_by_addr_infos: Vec<TransactionByAddrInfo> =
+                    tx_by_addr.try_into().map_err(|err| {
+                        Error::ObjectCorrupt(format!(
+                            "Failed to deserialize: {err}: tx-by-addr/{address}/{key}"
+                        ))
+                    })?;
+                Ok(!append_signatures_for_address(
+                    &mut infos,
+                    slot,
+                    tx_by_addr_infos,
+                    before,
+                    until,
+                    limit,
+                ))
+            },
+        )?;
+        Ok(infos)
+    }
+
+    fn write_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        let VersionedConfirmedBlockWithEntries {
+            block: confirmed_block,
+            entries,
+        } = confirmed_block;
+        let (tx_cells, tx_by_addr_cells) = transaction_rows(slot, &confirmed_block);
+
+        for (signature, transaction_info) in tx_cells {
+            write_bincode_row(&self.tx_path(&signature), &transaction_info)?;
+        }
+        for (row_key, tx_by_addr) in tx_by_addr_cells {
+            let (address, key) = row_key
+                .split_once('/')
+                .expect("tx-by-addr keys are <address>/<slot key>");
+            write_protobuf_row(
+                &slot_key_path(&self.tx_by_addr_dir(address), key),
+                &tx_by_addr,
+            )?;
+        }
+        if !entries.is_empty() {
+            let entries = entries::Entries {
+                entries: entries.into_iter().enumerate().map(Into::into).collect(),
+            };
+            write_protobuf_row(
+                &slot_key_path(&self.entries_dir(), &slot_to_entries_key(slot)),
+                &entries,
+            )?;
+        }
+
+        // Store the block itself last, after all other metadata about the block has been
+        // successfully stored.  This avoids partial uploaded blocks from becoming visible to
+        // `get_confirmed_block()` and `get_confirmed_blocks()`
+        let block: generated::ConfirmedBlock = confirmed_block.into();
+        write_protobuf_row(
+            &slot_key_path(&self.blocks_dir(), &slot_to_blocks_key(slot)),
+            &block,
+        )
+    }
+
+    // Run the blocking filesystem accesses of `f` off of the async runtime's worker threads
+    async fn spawn_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Self) -> Result<T> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || f(storage))
+            .await
+            .map_err(Error::TokioJoinError)?
+    }
+}
+
+#[async_trait]
+impl LongTermLedgerStorage for FilesystemLedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        self.spawn_blocking(|storage| Ok(storage.read_confirmed_blocks(0, 1)?.first().copied()))
+            .await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        self.spawn_blocking(move |storage| storage.read_confirmed_blocks(start_slot, limit))
+            .await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        self.spawn_blocking(move |storage| storage.read_confirmed_block(slot))
+            .await
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        let signature = *signature;
+        self.spawn_blocking(move |storage| Ok(storage.read_transaction_info(&signature)?.into()))
+            .await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        let signature = *signature;
+        self.spawn_blocking(move |storage| {
+            // Figure out which block the transaction is located in
+            let TransactionInfo { slot, index, .. } = storage.read_transaction_info(&signature)?;
+
+            // Load the block and return the transaction
+            let block = storage.read_confirmed_block(slot)?;
+            Ok(transaction_from_block(&signature, slot, index, block))
+        })
+        .await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let address = *address;
+        let before_signature = before_signature.copied();
+        let until_signature = until_signature.copied();
+        self.spawn_blocking(move |storage| {
+            storage.read_confirmed_signatures_for_address(
+                &address,
+                before_signature.as_ref(),
+                until_signature.as_ref(),
+                limit,
+            )
+        })
+        .await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        self.spawn_blocking(move |storage| storage.write_confirmed_block(slot, confirmed_block))
+            .await
+    }
+}
+
+fn slot_key_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(&key[..SLOT_KEY_GROUP_LEN]).join(key)
+}
+
+// Names of the entries of `dir` in lexical order, none if `dir` does not exist
+fn sorted_file_names(dir: &Path) -> Result<Vec<String>> {
+    let mut names = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect::<Vec<_>>(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err.into()),
+    };
+    names.sort_unstable();
+    Ok(names)
+}
+
+// Call `f` on the keys of the rows in `dir` from `start_key` to `end_key` inclusive, in
+// ascending order, for as long as it returns true
+fn for_each_slot_key(
+    dir: &Path,
+    start_key: &str,
+    end_key: &str,
+    mut f: impl FnMut(&str) -> Result<bool>,
+) -> Result<()> {
+    for group in sorted_file_names(dir)? {
+        if group.as_str() < &start_key[..SLOT_KEY_GROUP_LEN] {
+            continue;
+        }
+        if group.as_str() > &end_key[..SLOT_KEY_GROUP_LEN] {
+            break;
+        }
+        for key in sorted_file_names(&dir.join(&group))? {
+            // Skip rows that are still being written
+            if key.len() != start_key.len() || key.as_str() < start_key {
+                continue;
+            }
+            if key.as_str() > end_key || !f(&key)? {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_row(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(data) => Ok(Some(decompress(&data)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn read_bincode_row<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    read_row(path)?
+        .map(|data| {
+            bincode::deserialize(&data)
+                .map_err(|err| Error::ObjectCorrupt(format!("{}: {err}", path.display())))
+        })
+        .transpose()
+}
+
+fn read_protobuf_row<T: Message + Default>(path: &Path) -> Result<Option<T>> {
+    read_row(path)?
+        .map(|data| {
+            T::decode(&data[..])
+                .map_err(|err| Error::ObjectCorrupt(format!("{}: {err}", path.display())))
+        })
+        .transpose()
+}
+
+fn write_row(path: &Path, data: &[u8]) -> Result<()> {
+    fs::create_dir_all(path.parent().expect("rows are in a table directory"))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, compress(CompressionMethod::Zstd, data)?)?;
+    fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+fn write_bincode_row<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_row(path, &bincode::serialize(value).unwrap())
+}
+
+fn write_protobuf_row<T: Message>(path: &Path, value: &T) -> Result<()> {
+    write_row(path, &value.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, system_transaction, transaction::VersionedTransaction,
+        },
+        solana_transaction_status::{
+            TransactionStatusMeta, VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
+        },
+    };
+
+    fn block_with_transfers(
+        parent_slot: Slot,
+        payer: &Keypair,
+        recipients: &[Pubkey],
+    ) -> VersionedConfirmedBlock {
+        let transactions = recipients
+            .iter()
+            .map(|recipient| VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(system_transaction::transfer(
+                    payer,
+                    recipient,
+                    1,
+                    Hash::new_unique(),
+                )),
+                meta: TransactionStatusMeta::default(),
+            })
+            .collect();
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions,
+            rewards: vec![],
+            block_time: Some(1_700_000_000),
+            block_height: Some(parent_slot + 1),
+        }
+    }
+
+    fn signature(block: &VersionedConfirmedBlock, index: usize) -> Signature {
+        block.transactions[index].transaction.signatures[0]
+    }
+
+    async fn upload(storage: &FilesystemLedgerStorage, slot: Slot, block: VersionedConfirmedBlock) {
+        storage
+            .upload_confirmed_block_with_entries(
+                slot,
+                VersionedConfirmedBlockWithEntries {
+                    block,
+                    entries: vec![],
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_ledger_storage() {
+        let ledger_storage_dir = tempfile::TempDir::new().unwrap();
+        let storage = FilesystemLedgerStorage::open(ledger_storage_dir.path()).unwrap();
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+        assert!(matches!(
+            storage.get_confirmed_block(5).await,
+            Err(Error::BlockNotFound(5))
+        ));
+
+        // Slots 5 and 70_000 are in different directories of the `blocks` table
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let block_5 = block_with_transfers(4, &payer, &[recipient, Pubkey::new_unique()]);
+        let block_70_000 = block_with_transfers(69_999, &payer, &[recipient]);
+        upload(&storage, 70_000, block_70_000.clone()).await;
+        upload(&storage, 5, block_5.clone()).await;
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(5));
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![5, 70_000]
+        );
+        assert_eq!(
+            storage.get_confirmed_blocks(6, 10).await.unwrap(),
+            vec![70_000]
+        );
+        assert_eq!(storage.get_confirmed_blocks(0, 1).await.unwrap(), vec![5]);
+        assert_eq!(
+            storage.get_confirmed_block(5).await.unwrap(),
+            ConfirmedBlock::from(block_5.clone())
+        );
+
+        let transaction = storage
+            .get_confirmed_transaction(&signature(&block_5, 1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 5);
+        assert_eq!(
+            transaction.tx_with_meta.transaction_signature(),
+            &signature(&block_5, 1)
+        );
+        assert_eq!(
+            storage
+                .get_signature_status(&signature(&block_70_000, 0))
+                .await
+                .unwrap()
+                .slot,
+            70_000
+        );
+        assert!(matches!(
+            storage.get_signature_status(&Signature::default()).await,
+            Err(Error::SignatureNotFound)
+        ));
+
+        let signatures_for_address = |before: Option<Signature>, until: Option<Signature>| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .get_confirmed_signatures_for_address(
+                        &recipient,
+                        before.as_ref(),
+                        until.as_ref(),
+                        10,
+                    )
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|(info, _index)| info.signature)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(
+            signatures_for_address(None, None).await,
+            vec![signature(&block_70_000, 0), signature(&block_5, 0)]
+        );
+        assert_eq!(
+            signatures_for_address(Some(signature(&block_70_000, 0)), None).await,
+            vec![signature(&block_5, 0)]
+        );
+        assert_eq!(
+            signatures_for_address(None, Some(signature(&block_5, 0))).await,
+            vec![signature(&block_70_000, 0)]
+        );
+    }
+}
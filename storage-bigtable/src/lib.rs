@@ -45,8 +45,12 @@ extern crate serde_derive;
 mod access_token;
 mod bigtable;
 mod compression;
+mod filesystem_storage;
+mod long_term_storage;
 mod root_ca_certificate;
 
+pub use {filesystem_storage::FilesystemLedgerStorage, long_term_storage::LongTermLedgerStorage};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -64,6 +68,9 @@ pub enum Error {
     #[error("Signature not found")]
     SignatureNotFound,
 
+    #[error("Object is corrupt: {0}")]
+    ObjectCorrupt(String),
+
     #[error("tokio error")]
     TokioJoinError(JoinError),
 }
@@ -379,6 +386,143 @@ impl From<LegacyTransactionByAddrInfo> for TransactionByAddrInfo {
     }
 }
 
+// Rows of the `tx` and `tx-by-addr` tables for the transactions of the block at `slot`
+fn transaction_rows(
+    slot: Slot,
+    confirmed_block: &VersionedConfirmedBlock,
+) -> (
+    Vec<(String, TransactionInfo)>,
+    Vec<(String, tx_by_addr::TransactionByAddr)>,
+) {
+    let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+
+    let reserved_account_keys = ReservedAccountKeys::new_all_activated();
+    let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
+    for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+        let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
+        let err = meta.status.clone().err();
+        let index = index as u32;
+        let signature = transaction.signatures[0];
+        let memo = extract_and_fmt_memos(transaction_with_meta);
+
+        for address in transaction_with_meta.account_keys().iter() {
+            // Historical note that previously only a set of sysvar ids were
+            // skipped from being uploaded. Now we skip uploaded for the set
+            // of all reserved account keys which will continue to grow in
+            // the future.
+            if !reserved_account_keys.is_reserved(address) {
+                by_addr
+                    .entry(address)
+                    .or_default()
+                    .push(TransactionByAddrInfo {
+                        signature,
+                        err: err.clone(),
+                        index,
+                        memo: memo.clone(),
+                        block_time: confirmed_block.block_time,
+                    });
+            }
+        }
+
+        tx_cells.push((
+            signature.to_string(),
+            TransactionInfo {
+                slot,
+                index,
+                err,
+                memo,
+            },
+        ));
+    }
+
+    let tx_by_addr_cells: Vec<_> = by_addr
+        .into_iter()
+        .map(|(address, transaction_info_by_addr)| {
+            (
+                format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
+                tx_by_addr::TransactionByAddr {
+                    tx_by_addrs: transaction_info_by_addr
+                        .into_iter()
+                        .map(|by_addr| by_addr.into())
+                        .collect(),
+                },
+            )
+        })
+        .collect();
+
+    (tx_cells, tx_by_addr_cells)
+}
+
+// Extract the transaction at `index` of `block`, checking that it is the one with `signature`
+fn transaction_from_block(
+    signature: &Signature,
+    slot: Slot,
+    index: u32,
+    block: ConfirmedBlock,
+) -> Option<ConfirmedTransactionWithStatusMeta> {
+    match block.transactions.into_iter().nth(index as usize) {
+        None => {
+            // report this somewhere actionable?
+            warn!("Transaction info for {} is corrupt", signature);
+            None
+        }
+        Some(tx_with_meta) => {
+            if tx_with_meta.transaction_signature() != signature {
+                warn!(
+                    "Transaction info or confirmed block for {} is corrupt",
+                    signature
+                );
+                None
+            } else {
+                Some(ConfirmedTransactionWithStatusMeta {
+                    slot,
+                    tx_with_meta,
+                    block_time: block.block_time,
+                })
+            }
+        }
+    }
+}
+
+// Append the signatures of the `tx-by-addr` row of `slot` to `infos`, most recent first, skipping
+// those at or after the `before` (slot, index) position and at or before the `until` position.
+// Returns true once `limit` signatures have been collected
+fn append_signatures_for_address(
+    infos: &mut Vec<(ConfirmedTransactionStatusWithSignature, u32)>,
+    slot: Slot,
+    mut tx_by_addr_infos: Vec<TransactionByAddrInfo>,
+    (first_slot, before_transaction_index): (Slot, u32),
+    (last_slot, until_transaction_index): (Slot, u32),
+    limit: usize,
+) -> bool {
+    tx_by_addr_infos.reverse();
+    for tx_by_addr_info in tx_by_addr_infos.into_iter() {
+        // Filter out records before `before_transaction_index`
+        if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
+            continue;
+        }
+        // Filter out records after `until_transaction_index`
+        if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
+            continue;
+        }
+        infos.push((
+            ConfirmedTransactionStatusWithSignature {
+                signature: tx_by_addr_info.signature,
+                slot,
+                err: tx_by_addr_info.err,
+                memo: tx_by_addr_info.memo,
+                block_time: tx_by_addr_info.block_time,
+            },
+            tx_by_addr_info.index,
+        ));
+        // Respect limit
+        if infos.len() >= limit {
+            return true;
+        }
+    }
+    false
+}
+
 pub const DEFAULT_INSTANCE_NAME: &str = "solana-ledger";
 pub const DEFAULT_APP_PROFILE_ID: &str = "default";
 pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; // 64MB
@@ -738,28 +882,7 @@ impl LedgerStorage {
 
         // Load the block and return the transaction
         let block = self.get_confirmed_block(slot).await?;
-        match block.transactions.into_iter().nth(index as usize) {
-            None => {
-                // report this somewhere actionable?
-                warn!("Transaction info for {} is corrupt", signature);
-                Ok(None)
-            }
-            Some(tx_with_meta) => {
-                if tx_with_meta.transaction_signature() != signature {
-                    warn!(
-                        "Transaction info or confirmed block for {} is corrupt",
-                        signature
-                    );
-                    Ok(None)
-                } else {
-                    Ok(Some(ConfirmedTransactionWithStatusMeta {
-                        slot,
-                        tx_with_meta,
-                        block_time: block.block_time,
-                    }))
-                }
-            }
-        }
+        Ok(transaction_from_block(signature, slot, index, block))
     }
 
     /// Get confirmed signatures for the provided address, in descending ledger order
@@ -847,7 +970,7 @@ impl LedgerStorage {
             )
             .await?;
 
-        'outer: for (row_key, data) in tx_by_addr_data {
+        for (row_key, data) in tx_by_addr_data {
             let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
                 bigtable::Error::ObjectCorrupt(format!(
                     "Failed to convert key to slot: tx-by-addr/{row_key}"
@@ -859,7 +982,7 @@ impl LedgerStorage {
                 tx_by_addr::TransactionByAddr,
             >(&data, "tx-by-addr", row_key.clone())?;
 
-            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
+            let cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
                 bigtable::CellData::Bincode(tx_by_addr) => {
                     tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
                 }
@@ -874,30 +997,15 @@ impl LedgerStorage {
                 }
             };
 
-            cell_data.reverse();
-            for tx_by_addr_info in cell_data.into_iter() {
-                // Filter out records before `before_transaction_index`
-                if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
-                    continue;
-                }
-                // Filter out records after `until_transaction_index`
-                if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
-                    continue;
-                }
-                infos.push((
-                    ConfirmedTransactionStatusWithSignature {
-                        signature: tx_by_addr_info.signature,
-                        slot,
-                        err: tx_by_addr_info.err,
-                        memo: tx_by_addr_info.memo,
-                        block_time: tx_by_addr_info.block_time,
-                    },
-                    tx_by_addr_info.index,
-                ));
-                // Respect limit
-                if infos.len() >= limit {
-                    break 'outer;
-                }
+            if append_signatures_for_address(
+                &mut infos,
+                slot,
+                cell_data,
+                (first_slot, before_transaction_index),
+                (last_slot, until_transaction_index),
+                limit,
+            ) {
+                break;
             }
         }
         Ok(infos)
@@ -932,65 +1040,11 @@ impl LedgerStorage {
             "LedgerStorage::upload_confirmed_block_with_entries request received: {:?}",
             slot
         );
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
         let VersionedConfirmedBlockWithEntries {
             block: confirmed_block,
             entries,
         } = confirmed_block;
-
-        let reserved_account_keys = ReservedAccountKeys::new_all_activated();
-        let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
-        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
-            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
-            let err = meta.status.clone().err();
-            let index = index as u32;
-            let signature = transaction.signatures[0];
-            let memo = extract_and_fmt_memos(transaction_with_meta);
-
-            for address in transaction_with_meta.account_keys().iter() {
-                // Historical note that previously only a set of sysvar ids were
-                // skipped from being uploaded. Now we skip uploaded for the set
-                // of all reserved account keys which will continue to grow in
-                // the future.
-                if !reserved_account_keys.is_reserved(address) {
-                    by_addr
-                        .entry(address)
-                        .or_default()
-                        .push(TransactionByAddrInfo {
-                            signature,
-                            err: err.clone(),
-                            index,
-                            memo: memo.clone(),
-                            block_time: confirmed_block.block_time,
-                        });
-                }
-            }
-
-            tx_cells.push((
-                signature.to_string(),
-                TransactionInfo {
-                    slot,
-                    index,
-                    err,
-                    memo,
-                },
-            ));
-        }
-
-        let tx_by_addr_cells: Vec<_> = by_addr
-            .into_iter()
-            .map(|(address, transaction_info_by_addr)| {
-                (
-                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
-                    tx_by_addr::TransactionByAddr {
-                        tx_by_addrs: transaction_info_by_addr
-                            .into_iter()
-                            .map(|by_addr| by_addr.into())
-                            .collect(),
-                    },
-                )
-            })
-            .collect();
+        let (tx_cells, tx_by_addr_cells) = transaction_rows(slot, &confirmed_block);
 
         let num_entries = entries.len();
         let entry_cell = (
@@ -33,7 +33,9 @@ use {
         signature::{Keypair, Signature},
         timing,
     },
-    solana_transaction_metrics_tracker::signature_if_should_track_packet,
+    solana_transaction_metrics_tracker::{
+        get_signature_from_packet, lifecycle_tracing, signature_if_should_track_packet,
+    },
     std::{
         iter::repeat_with,
         net::{IpAddr, SocketAddr, UdpSocket},
@@ -42,7 +44,7 @@ use {
             atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, RwLock,
         },
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime},
     },
     tokio::{
         // CAUTION: It's kind of sketch that we're mixing async and sync locks (see the RwLock above).
@@ -748,6 +750,19 @@ async fn packet_batch_sender(
 
                 total_bytes += packet_batch[i].meta().size;
 
+                if lifecycle_tracing::is_enabled() {
+                    if let Ok(signature) = get_signature_from_packet(&packet_batch[i]) {
+                        // Traced as received once sigverify verified the signature
+                        let now = SystemTime::now();
+                        let arrival_time = now
+                            .checked_sub(packet_accumulator.start_time.elapsed())
+                            .unwrap_or(now);
+                        lifecycle_tracing::record_arrival(
+                            &Signature::from(*signature),
+                            arrival_time,
+                        );
+                    }
+                }
                 if let Some(signature) = signature_if_should_track_packet(&packet_batch[i])
                     .ok()
                     .flatten()
@@ -1,4 +1,5 @@
 use {
+    solana_program_runtime::loaded_programs::ProgramCacheForTxBatch,
     solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
     std::collections::HashMap,
 };
@@ -7,6 +8,8 @@ use {
 #[derive(Default)]
 pub struct AccountOverrides {
     accounts: HashMap<Pubkey, AccountSharedData>,
+    /// Programs deployed, upgraded or closed by earlier transactions, e.g. in a simulated bundle
+    programs_modified: ProgramCacheForTxBatch,
 }
 
 impl AccountOverrides {
@@ -29,6 +32,17 @@ impl AccountOverrides {
     pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Merge in the programs modified by a transaction, so that transactions loaded with these
+    /// overrides observe them as if they had run earlier in the same batch
+    pub fn merge_programs_modified(&mut self, programs_modified: &ProgramCacheForTxBatch) {
+        self.programs_modified.merge(programs_modified);
+    }
+
+    /// Gets the programs modified by earlier transactions
+    pub fn programs_modified(&self) -> &ProgramCacheForTxBatch {
+        &self.programs_modified
+    }
 }
 
 #[cfg(test)]
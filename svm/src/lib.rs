@@ -6,6 +6,7 @@ pub mod account_overrides;
 pub mod account_rent_state;
 pub mod message_processor;
 pub mod runtime_config;
+pub mod standalone;
 pub mod transaction_account_state_info;
 pub mod transaction_error_metrics;
 pub mod transaction_processor;
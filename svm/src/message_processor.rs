@@ -34,12 +34,15 @@ impl MessageProcessor {
     /// For each instruction it calls the program entrypoint method and verifies that the result of
     /// the call does not violate the bank's accounting rules.
     /// The accounts are committed back to the bank only if every instruction succeeds.
+    /// If `instruction_compute_units` is provided, the compute units consumed by each
+    /// top-level instruction are appended to it in instruction order.
     pub fn process_message(
         message: &SanitizedMessage,
         program_indices: &[Vec<IndexOfAccount>],
         invoke_context: &mut InvokeContext,
         timings: &mut ExecuteTimings,
         accumulated_consumed_units: &mut u64,
+        mut instruction_compute_units: Option<&mut Vec<u64>>,
     ) -> Result<(), TransactionError> {
         debug_assert_eq!(program_indices.len(), message.instructions().len());
         for (instruction_index, ((program_id, instruction), program_indices)) in message
@@ -89,6 +92,7 @@ impl MessageProcessor {
                 });
             }
 
+            let mut compute_units_consumed = 0;
             let result = if is_precompile {
                 invoke_context
                     .transaction_context
@@ -106,7 +110,6 @@ impl MessageProcessor {
                     })
             } else {
                 let time = Measure::start("execute_instruction");
-                let mut compute_units_consumed = 0;
                 let result = invoke_context.process_instruction(
                     &instruction.data,
                     &instruction_accounts,
@@ -134,6 +137,10 @@ impl MessageProcessor {
                 result
             };
 
+            if let Some(instruction_compute_units) = instruction_compute_units.as_mut() {
+                instruction_compute_units.push(compute_units_consumed);
+            }
+
             result
                 .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
         }
@@ -289,6 +296,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -340,6 +348,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -381,6 +390,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -513,6 +523,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -549,6 +560,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
 
@@ -582,6 +594,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -676,6 +689,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
 
         assert_eq!(
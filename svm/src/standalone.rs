@@ -0,0 +1,781 @@
+//! A standalone SVM execution engine backed by an in-memory account store.
+//!
+//! `InMemoryAccounts` implements `TransactionProcessingCallback` over a map of accounts, and
+//! `StandaloneSvm` pairs it with a `TransactionBatchProcessor` set up by `StandaloneSvmBuilder`
+//! with sysvars, a feature set, builtins and a program cache. Processing a batch executes it and
+//! commits the account changes to the store, without depending on the runtime or accounts-db.
+//!
+//! Durable nonce transactions are not supported: a transaction is only accepted if its recent
+//! blockhash is the current blockhash of the store.
+
+use {
+    crate::{
+        account_loader::{LoadedTransaction, TransactionCheckResult},
+        runtime_config::RuntimeConfig,
+        transaction_error_metrics::TransactionErrorMetrics,
+        transaction_processor::{
+            ExecutionRecordingConfig, LoadAndExecuteSanitizedTransactionsOutput,
+            TransactionBatchProcessor, TransactionProcessingCallback,
+        },
+        transaction_results::TransactionExecutionResult,
+    },
+    solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1,
+    solana_loader_v4_program::create_program_runtime_environment_v2,
+    solana_program_runtime::{
+        compute_budget_processor::process_compute_budget_instructions,
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{
+            BlockRelation, ForkGraph, ProgramCache, ProgramCacheEntry, ProgramRuntimeEnvironments,
+        },
+        timings::ExecuteTimings,
+    },
+    solana_sdk::{
+        account::{
+            create_account_shared_data_with_fields, from_account, AccountSharedData,
+            ReadableAccount, WritableAccount,
+        },
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+        clock::{Clock, Epoch, Slot, UnixTimestamp},
+        compute_budget,
+        epoch_rewards::EpochRewards,
+        epoch_schedule::EpochSchedule,
+        feature_set::{
+            enable_program_runtime_v2_and_loader_v4,
+            include_loaded_accounts_data_size_in_fee_calculation,
+            remove_rounding_in_fee_calculation, FeatureSet,
+        },
+        fee::FeeStructure,
+        hash::Hash,
+        loader_v4,
+        message::SanitizedMessage,
+        native_loader,
+        precompiles::get_precompiles,
+        pubkey::Pubkey,
+        rent::Rent,
+        rent_collector::RentCollector,
+        signature::Signature,
+        slot_hashes::SlotHashes,
+        stake_history::StakeHistory,
+        sysvar::{last_restart_slot::LastRestartSlot, Sysvar, SysvarId},
+        transaction::{self, SanitizedTransaction, TransactionError, MAX_TX_ACCOUNT_LOCKS},
+        transaction_context::TransactionAccount,
+    },
+    std::{
+        cmp::Ordering,
+        collections::{HashMap, HashSet},
+        sync::{Arc, RwLock},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Fork graph of a single chain, on which every slot is an ancestor of all later slots
+#[derive(Debug, Default)]
+pub struct LinearForkGraph {
+    epoch_schedule: EpochSchedule,
+}
+
+impl ForkGraph for LinearForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+
+    fn slot_epoch(&self, slot: Slot) -> Option<Epoch> {
+        Some(self.epoch_schedule.get_epoch(slot))
+    }
+}
+
+/// An account store held in memory. Accounts without lamports do not exist.
+#[derive(Debug)]
+pub struct InMemoryAccounts {
+    accounts: RwLock<HashMap<Pubkey, AccountSharedData>>,
+    rent_collector: RentCollector,
+    feature_set: Arc<FeatureSet>,
+    blockhash: Hash,
+    lamports_per_signature: u64,
+}
+
+impl InMemoryAccounts {
+    pub fn new(
+        rent_collector: RentCollector,
+        feature_set: Arc<FeatureSet>,
+        blockhash: Hash,
+        lamports_per_signature: u64,
+    ) -> Self {
+        Self {
+            accounts: RwLock::default(),
+            rent_collector,
+            feature_set,
+            blockhash,
+            lamports_per_signature,
+        }
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.read().unwrap().get(pubkey).cloned()
+    }
+
+    /// Store `account`, removing it if it has no lamports
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        let mut accounts = self.accounts.write().unwrap();
+        if account.lamports() == 0 {
+            accounts.remove(&pubkey);
+        } else {
+            accounts.insert(pubkey, account);
+        }
+    }
+
+    pub fn blockhash(&self) -> Hash {
+        self.blockhash
+    }
+
+    pub fn lamports_per_signature(&self) -> u64 {
+        self.lamports_per_signature
+    }
+
+    fn set_sysvar<S: Sysvar>(&self, sysvar: &S) {
+        let lamports = self
+            .rent_collector
+            .rent
+            .minimum_balance(S::size_of())
+            .max(1);
+        self.set_account(
+            S::id(),
+            create_account_shared_data_with_fields(sysvar, (lamports, 0)),
+        );
+    }
+}
+
+impl TransactionProcessingCallback for InMemoryAccounts {
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        self.accounts
+            .read()
+            .unwrap()
+            .get(account)
+            .and_then(|account| owners.iter().position(|owner| account.owner() == owner))
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.get_account(pubkey)
+    }
+
+    fn get_last_blockhash_and_lamports_per_signature(&self) -> (Hash, u64) {
+        (self.blockhash, self.lamports_per_signature)
+    }
+
+    fn get_rent_collector(&self) -> &RentCollector {
+        &self.rent_collector
+    }
+
+    fn get_feature_set(&self) -> Arc<FeatureSet> {
+        self.feature_set.clone()
+    }
+
+    fn add_builtin_account(&self, name: &str, program_id: &Pubkey) {
+        self.set_account(
+            *program_id,
+            native_loader::create_loadable_account_with_fields(name, (1, 0)),
+        );
+    }
+}
+
+/// The outcome of `StandaloneSvm::process_transactions`, in transaction order
+#[derive(Debug)]
+pub struct ProcessedBatch {
+    pub execution_results: Vec<TransactionExecutionResult>,
+    /// Accounts committed by each transaction. Successful transactions commit all their writable
+    /// accounts, failed transactions only commit the fee payer, and transactions that were not
+    /// executed commit nothing.
+    pub account_changes: Vec<Vec<TransactionAccount>>,
+}
+
+pub struct StandaloneSvm {
+    accounts: InMemoryAccounts,
+    processor: TransactionBatchProcessor<LinearForkGraph>,
+    epoch_schedule: EpochSchedule,
+    fee_structure: FeeStructure,
+    runtime_config: Arc<RuntimeConfig>,
+    recording_config: ExecutionRecordingConfig,
+    processed_signatures: HashSet<Signature>,
+}
+
+impl StandaloneSvm {
+    pub fn builder() -> StandaloneSvmBuilder {
+        StandaloneSvmBuilder::default()
+    }
+
+    pub fn accounts(&self) -> &InMemoryAccounts {
+        &self.accounts
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get_account(pubkey)
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.set_account(pubkey, account)
+    }
+
+    pub fn processor(&self) -> &TransactionBatchProcessor<LinearForkGraph> {
+        &self.processor
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.clock().slot
+    }
+
+    pub fn blockhash(&self) -> Hash {
+        self.accounts.blockhash
+    }
+
+    /// Transactions must use `blockhash` as their recent blockhash from now on
+    pub fn set_blockhash(&mut self, blockhash: Hash) {
+        self.accounts.blockhash = blockhash;
+    }
+
+    fn clock(&self) -> Clock {
+        self.accounts
+            .get_account(&Clock::id())
+            .and_then(|account| from_account(&account))
+            .unwrap_or_default()
+    }
+
+    /// Advance to `slot`, which makes programs deployed at earlier slots visible
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        let epoch = self.epoch_schedule.get_epoch(slot);
+        let clock = Clock {
+            slot,
+            epoch,
+            leader_schedule_epoch: self.epoch_schedule.get_leader_schedule_epoch(slot),
+            ..self.clock()
+        };
+        self.accounts.set_sysvar(&clock);
+        self.accounts.rent_collector.epoch = epoch;
+
+        let builtin_program_ids = self.processor.builtin_program_ids.read().unwrap().clone();
+        self.processor = TransactionBatchProcessor::new(
+            slot,
+            epoch,
+            self.epoch_schedule.clone(),
+            self.fee_structure.clone(),
+            self.runtime_config.clone(),
+            self.processor.program_cache.clone(),
+            builtin_program_ids,
+        );
+        self.processor
+            .fill_missing_sysvar_cache_entries(&self.accounts);
+    }
+
+    /// Execute `transactions` and commit their account changes. Transactions are executed in
+    /// order: whenever one locks an account locked by an earlier transaction of the batch, the
+    /// earlier transactions are committed before it is loaded.
+    pub fn process_transactions(
+        &mut self,
+        transactions: &[SanitizedTransaction],
+    ) -> ProcessedBatch {
+        let mut processed_batch = ProcessedBatch {
+            execution_results: Vec::with_capacity(transactions.len()),
+            account_changes: Vec::with_capacity(transactions.len()),
+        };
+        let mut writable = HashSet::new();
+        let mut readonly = HashSet::new();
+        let mut start = 0;
+        for (index, transaction) in transactions.iter().enumerate() {
+            // Transactions with invalid locks are rejected without loading any account
+            let Ok(locks) = transaction.get_account_locks(self.account_lock_limit()) else {
+                continue;
+            };
+            let conflicts = locks
+                .writable
+                .iter()
+                .any(|key| writable.contains(*key) || readonly.contains(*key))
+                || locks.readonly.iter().any(|key| writable.contains(*key));
+            if conflicts {
+                self.process_non_conflicting(&transactions[start..index], &mut processed_batch);
+                start = index;
+                writable.clear();
+                readonly.clear();
+            }
+            writable.extend(locks.writable.into_iter().copied());
+            readonly.extend(locks.readonly.into_iter().copied());
+        }
+        self.process_non_conflicting(&transactions[start..], &mut processed_batch);
+        processed_batch
+    }
+
+    fn account_lock_limit(&self) -> usize {
+        self.runtime_config
+            .transaction_account_lock_limit
+            .unwrap_or(MAX_TX_ACCOUNT_LOCKS)
+    }
+
+    fn check_transaction(&self, transaction: &SanitizedTransaction) -> TransactionCheckResult {
+        let result = if let Err(err) = transaction.get_account_locks(self.account_lock_limit()) {
+            Err(err)
+        } else if self.processed_signatures.contains(transaction.signature()) {
+            Err(TransactionError::AlreadyProcessed)
+        } else if transaction.message().recent_blockhash() != &self.accounts.blockhash {
+            Err(TransactionError::BlockhashNotFound)
+        } else {
+            Ok(())
+        };
+        (result, None, Some(self.accounts.lamports_per_signature))
+    }
+
+    fn process_non_conflicting(
+        &mut self,
+        transactions: &[SanitizedTransaction],
+        processed_batch: &mut ProcessedBatch,
+    ) {
+        if transactions.is_empty() {
+            return;
+        }
+        let mut check_results: Vec<_> = transactions
+            .iter()
+            .map(|transaction| self.check_transaction(transaction))
+            .collect();
+        let LoadAndExecuteSanitizedTransactionsOutput {
+            loaded_transactions,
+            execution_results,
+        } = self.processor.load_and_execute_sanitized_transactions(
+            &self.accounts,
+            transactions,
+            &mut check_results,
+            &mut TransactionErrorMetrics::default(),
+            self.recording_config,
+            &mut ExecuteTimings::default(),
+            None,
+            self.runtime_config.log_messages_bytes_limit,
+            false,
+        );
+
+        for ((transaction, (loaded_transaction, _nonce)), execution_result) in transactions
+            .iter()
+            .zip(&loaded_transactions)
+            .zip(&execution_results)
+        {
+            let account_changes =
+                self.commit_transaction(transaction, loaded_transaction, execution_result);
+            processed_batch.account_changes.push(account_changes);
+        }
+        processed_batch.execution_results.extend(execution_results);
+    }
+
+    fn commit_transaction(
+        &mut self,
+        transaction: &SanitizedTransaction,
+        loaded_transaction: &transaction::Result<LoadedTransaction>,
+        execution_result: &TransactionExecutionResult,
+    ) -> Vec<TransactionAccount> {
+        let (
+            Ok(loaded_transaction),
+            TransactionExecutionResult::Executed {
+                details,
+                programs_modified_by_tx,
+            },
+        ) = (loaded_transaction, execution_result)
+        else {
+            return vec![];
+        };
+        self.processed_signatures.insert(*transaction.signature());
+
+        let message = transaction.message();
+        let account_changes: Vec<_> = if details.status.is_ok() {
+            self.processor
+                .program_cache
+                .write()
+                .unwrap()
+                .merge(programs_modified_by_tx);
+            loaded_transaction
+                .accounts
+                .iter()
+                .take(message.account_keys().len())
+                .enumerate()
+                .filter(|(index, _)| {
+                    message.is_writable(*index) && message.is_non_loader_key(*index)
+                })
+                .map(|(_, account)| account.clone())
+                .collect()
+        } else {
+            // The execution changes are dropped but the fee is still charged
+            let fee_payer = *message.fee_payer();
+            let mut account = self.accounts.get_account(&fee_payer).unwrap_or_default();
+            account.set_lamports(
+                account
+                    .lamports()
+                    .saturating_sub(self.calculate_fee(message)),
+            );
+            vec![(fee_payer, account)]
+        };
+
+        for (pubkey, account) in &account_changes {
+            self.accounts.set_account(*pubkey, account.clone());
+        }
+        account_changes
+    }
+
+    fn calculate_fee(&self, message: &SanitizedMessage) -> u64 {
+        let feature_set = &self.accounts.feature_set;
+        self.fee_structure.calculate_fee(
+            message,
+            self.accounts.lamports_per_signature,
+            &process_compute_budget_instructions(message.program_instructions_iter())
+                .unwrap_or_default()
+                .into(),
+            feature_set.is_active(&include_loaded_accounts_data_size_in_fee_calculation::id()),
+            feature_set.is_active(&remove_rounding_in_fee_calculation::id()),
+        )
+    }
+}
+
+/// Builds a `StandaloneSvm`. By default all features are enabled, and the system program, the
+/// compute budget program and the BPF loaders are registered as builtins.
+pub struct StandaloneSvmBuilder {
+    slot: Slot,
+    epoch_schedule: EpochSchedule,
+    rent: Rent,
+    feature_set: FeatureSet,
+    fee_structure: FeeStructure,
+    blockhash: Hash,
+    unix_timestamp: Option<UnixTimestamp>,
+    runtime_config: RuntimeConfig,
+    recording_config: ExecutionRecordingConfig,
+    builtins: Vec<(Pubkey, String, BuiltinFunctionWithContext)>,
+    accounts: Vec<TransactionAccount>,
+}
+
+impl Default for StandaloneSvmBuilder {
+    fn default() -> Self {
+        Self {
+            slot: Slot::default(),
+            epoch_schedule: EpochSchedule::default(),
+            rent: Rent::default(),
+            feature_set: FeatureSet::all_enabled(),
+            fee_structure: FeeStructure::default(),
+            blockhash: Hash::new_unique(),
+            unix_timestamp: None,
+            runtime_config: RuntimeConfig::default(),
+            recording_config: ExecutionRecordingConfig::new_single_setting(true),
+            builtins: vec![],
+            accounts: vec![],
+        }
+    }
+}
+
+impl StandaloneSvmBuilder {
+    pub fn slot(mut self, slot: Slot) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    pub fn epoch_schedule(mut self, epoch_schedule: EpochSchedule) -> Self {
+        self.epoch_schedule = epoch_schedule;
+        self
+    }
+
+    pub fn rent(mut self, rent: Rent) -> Self {
+        self.rent = rent;
+        self
+    }
+
+    pub fn feature_set(mut self, feature_set: FeatureSet) -> Self {
+        self.feature_set = feature_set;
+        self
+    }
+
+    pub fn fee_structure(mut self, fee_structure: FeeStructure) -> Self {
+        self.fee_structure = fee_structure;
+        self
+    }
+
+    pub fn blockhash(mut self, blockhash: Hash) -> Self {
+        self.blockhash = blockhash;
+        self
+    }
+
+    /// Timestamp of the clock sysvar [default: the current time]
+    pub fn unix_timestamp(mut self, unix_timestamp: UnixTimestamp) -> Self {
+        self.unix_timestamp = Some(unix_timestamp);
+        self
+    }
+
+    pub fn runtime_config(mut self, runtime_config: RuntimeConfig) -> Self {
+        self.runtime_config = runtime_config;
+        self
+    }
+
+    pub fn recording_config(mut self, recording_config: ExecutionRecordingConfig) -> Self {
+        self.recording_config = recording_config;
+        self
+    }
+
+    /// Register a builtin program in addition to the default ones
+    pub fn builtin(
+        mut self,
+        program_id: Pubkey,
+        name: &str,
+        entrypoint: BuiltinFunctionWithContext,
+    ) -> Self {
+        self.builtins
+            .push((program_id, name.to_string(), entrypoint));
+        self
+    }
+
+    pub fn account(mut self, pubkey: Pubkey, account: AccountSharedData) -> Self {
+        self.accounts.push((pubkey, account));
+        self
+    }
+
+    pub fn build(self) -> StandaloneSvm {
+        let epoch = self.epoch_schedule.get_epoch(self.slot);
+        let rent_collector = RentCollector {
+            epoch,
+            epoch_schedule: self.epoch_schedule.clone(),
+            rent: self.rent.clone(),
+            ..RentCollector::default()
+        };
+        let lamports_per_signature = self.fee_structure.lamports_per_signature;
+        let feature_set = Arc::new(self.feature_set);
+        let accounts = InMemoryAccounts::new(
+            rent_collector,
+            feature_set.clone(),
+            self.blockhash,
+            lamports_per_signature,
+        );
+        for (pubkey, account) in self.accounts {
+            accounts.set_account(pubkey, account);
+        }
+
+        let unix_timestamp = self.unix_timestamp.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as UnixTimestamp
+        });
+        accounts.set_sysvar(&Clock {
+            slot: self.slot,
+            epoch_start_timestamp: unix_timestamp,
+            epoch,
+            leader_schedule_epoch: self.epoch_schedule.get_leader_schedule_epoch(self.slot),
+            unix_timestamp,
+        });
+        accounts.set_sysvar(&self.rent);
+        accounts.set_sysvar(&self.epoch_schedule);
+        accounts.set_sysvar(&EpochRewards::default());
+        accounts.set_sysvar(&SlotHashes::default());
+        accounts.set_sysvar(&StakeHistory::default());
+        accounts.set_sysvar(&LastRestartSlot::default());
+        for precompile in get_precompiles() {
+            if precompile
+                .feature
+                .map_or(true, |feature| feature_set.is_active(&feature))
+            {
+                let mut account = AccountSharedData::new(1, 0, &native_loader::id());
+                account.set_executable(true);
+                accounts.set_account(precompile.program_id, account);
+            }
+        }
+
+        let compute_budget = self.runtime_config.compute_budget.unwrap_or_default();
+        let mut program_cache = ProgramCache::new(self.slot, epoch);
+        program_cache.environments = ProgramRuntimeEnvironments {
+            program_runtime_v1: Arc::new(
+                create_program_runtime_environment_v1(
+                    &feature_set,
+                    &compute_budget,
+                    false, /* deployment */
+                    false, /* debugging_features */
+                )
+                .unwrap(),
+            ),
+            program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
+                &compute_budget,
+                false, /* debugging_features */
+            )),
+        };
+        program_cache.fork_graph = Some(Arc::new(RwLock::new(LinearForkGraph {
+            epoch_schedule: self.epoch_schedule.clone(),
+        })));
+
+        let runtime_config = Arc::new(self.runtime_config);
+        let processor = TransactionBatchProcessor::new(
+            self.slot,
+            epoch,
+            self.epoch_schedule.clone(),
+            self.fee_structure.clone(),
+            runtime_config.clone(),
+            Arc::new(RwLock::new(program_cache)),
+            HashSet::new(),
+        );
+
+        let mut builtins: Vec<(Pubkey, String, BuiltinFunctionWithContext)> = vec![
+            (
+                solana_system_program::id(),
+                "system_program".to_string(),
+                solana_system_program::system_processor::Entrypoint::vm,
+            ),
+            (
+                compute_budget::id(),
+                "compute_budget_program".to_string(),
+                solana_compute_budget_program::Entrypoint::vm,
+            ),
+            (
+                bpf_loader_deprecated::id(),
+                "solana_bpf_loader_deprecated_program".to_string(),
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+            (
+                bpf_loader::id(),
+                "solana_bpf_loader_program".to_string(),
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+            (
+                bpf_loader_upgradeable::id(),
+                "solana_bpf_loader_upgradeable_program".to_string(),
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+        ];
+        if feature_set.is_active(&enable_program_runtime_v2_and_loader_v4::id()) {
+            builtins.push((
+                loader_v4::id(),
+                "loader_v4".to_string(),
+                solana_loader_v4_program::Entrypoint::vm,
+            ));
+        }
+        builtins.extend(self.builtins);
+        for (program_id, name, entrypoint) in builtins {
+            processor.add_builtin(
+                &accounts,
+                program_id,
+                &name,
+                ProgramCacheEntry::new_builtin(0, name.len(), entrypoint),
+            );
+        }
+        processor.fill_missing_sysvar_cache_entries(&accounts);
+
+        StandaloneSvm {
+            accounts,
+            processor,
+            epoch_schedule: self.epoch_schedule,
+            fee_structure: self.fee_structure,
+            runtime_config,
+            recording_config: self.recording_config,
+            processed_signatures: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            native_token::LAMPORTS_PER_SOL,
+            signature::{Keypair, Signer},
+            system_program, system_transaction,
+        },
+    };
+
+    fn transfer(
+        svm: &StandaloneSvm,
+        from: &Keypair,
+        to: &Pubkey,
+        lamports: u64,
+    ) -> SanitizedTransaction {
+        SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+            from,
+            to,
+            lamports,
+            svm.blockhash(),
+        ))
+    }
+
+    #[test]
+    fn test_process_transactions() {
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let fee = FeeStructure::default().lamports_per_signature;
+        let mut svm = StandaloneSvm::builder()
+            .account(
+                payer.pubkey(),
+                AccountSharedData::new(10 * LAMPORTS_PER_SOL, 0, &system_program::id()),
+            )
+            .build();
+
+        // The second transfer conflicts with the first one, so it observes its changes
+        let batch = svm.process_transactions(&[
+            transfer(&svm, &payer, &recipient, LAMPORTS_PER_SOL),
+            transfer(&svm, &payer, &recipient, 2 * LAMPORTS_PER_SOL),
+        ]);
+        assert!(batch
+            .execution_results
+            .iter()
+            .all(TransactionExecutionResult::was_executed_successfully));
+        let payer_lamports = 7 * LAMPORTS_PER_SOL - 2 * fee;
+        assert_eq!(
+            batch.account_changes[1]
+                .iter()
+                .map(|(pubkey, account)| (*pubkey, account.lamports()))
+                .collect::<Vec<_>>(),
+            vec![
+                (payer.pubkey(), payer_lamports),
+                (recipient, 3 * LAMPORTS_PER_SOL)
+            ]
+        );
+        assert_eq!(
+            svm.get_account(&payer.pubkey()).unwrap().lamports(),
+            payer_lamports
+        );
+        assert_eq!(
+            svm.get_account(&recipient).unwrap().lamports(),
+            3 * LAMPORTS_PER_SOL
+        );
+
+        // A failed transaction only pays the fee
+        let overdraft = transfer(&svm, &payer, &recipient, 100 * LAMPORTS_PER_SOL);
+        let batch = svm.process_transactions(&[overdraft.clone()]);
+        assert!(batch.execution_results[0].was_executed());
+        assert!(!batch.execution_results[0].was_executed_successfully());
+        assert_eq!(batch.account_changes[0].len(), 1);
+        assert_eq!(
+            svm.get_account(&payer.pubkey()).unwrap().lamports(),
+            payer_lamports - fee
+        );
+
+        // Replays and stale blockhashes are rejected
+        let stale = transfer(&svm, &payer, &recipient, LAMPORTS_PER_SOL);
+        svm.set_blockhash(Hash::new_unique());
+        let batch = svm.process_transactions(&[overdraft, stale]);
+        assert!(matches!(
+            batch.execution_results[0],
+            TransactionExecutionResult::NotExecuted(TransactionError::AlreadyProcessed)
+        ));
+        assert!(matches!(
+            batch.execution_results[1],
+            TransactionExecutionResult::NotExecuted(TransactionError::BlockhashNotFound)
+        ));
+        assert!(batch.account_changes.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_builder_sysvars() {
+        let mut svm = StandaloneSvm::builder()
+            .slot(10)
+            .unix_timestamp(1_700_000_000)
+            .build();
+        let sysvar_cache = svm.processor().get_sysvar_cache_for_tests();
+        let clock = sysvar_cache.get_clock().unwrap();
+        assert_eq!(clock.slot, 10);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+        assert_eq!(*sysvar_cache.get_rent().unwrap(), Rent::default());
+        assert!(svm.get_account(&solana_system_program::id()).is_some());
+
+        svm.warp_to_slot(20);
+        assert_eq!(svm.slot(), 20);
+        let sysvar_cache = svm.processor().get_sysvar_cache_for_tests();
+        assert_eq!(sysvar_cache.get_clock().unwrap().slot, 20);
+    }
+}
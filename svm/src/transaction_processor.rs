@@ -72,6 +72,7 @@ pub struct ExecutionRecordingConfig {
     pub enable_cpi_recording: bool,
     pub enable_log_recording: bool,
     pub enable_return_data_recording: bool,
+    pub enable_instruction_compute_units_recording: bool,
 }
 
 impl ExecutionRecordingConfig {
@@ -80,6 +81,7 @@ impl ExecutionRecordingConfig {
             enable_return_data_recording: option,
             enable_log_recording: option,
             enable_cpi_recording: option,
+            enable_instruction_compute_units_recording: option,
         }
     }
 }
@@ -245,6 +247,11 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 execution_results,
             };
         }
+        if let Some(account_overrides) = account_overrides {
+            programs_loaded_for_tx_batch
+                .borrow_mut()
+                .merge(account_overrides.programs_modified());
+        }
         program_cache_time.stop();
 
         let mut load_time = Measure::start("accounts_load");
@@ -684,6 +691,10 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             lamports_per_signature,
         );
 
+        let mut instruction_compute_units = recording_config
+            .enable_instruction_compute_units_recording
+            .then(|| Vec::with_capacity(tx.message().instructions().len()));
+
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
             tx.message(),
@@ -691,6 +702,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             &mut invoke_context,
             timings,
             &mut executed_units,
+            instruction_compute_units.as_mut(),
         );
         process_message_time.stop();
 
@@ -784,6 +796,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 durable_nonce_fee,
                 return_data,
                 executed_units,
+                instruction_compute_units,
                 accounts_data_len_delta,
             },
             programs_modified_by_tx: Box::new(programs_modified_by_tx),
@@ -1774,6 +1787,7 @@ mod tests {
             enable_cpi_recording: false,
             enable_log_recording: true,
             enable_return_data_recording: false,
+            enable_instruction_compute_units_recording: false,
         };
 
         let result = batch_processor.execute_loaded_transaction(
@@ -1856,6 +1870,37 @@ mod tests {
         };
         assert!(log_messages.is_none());
         assert!(inner_instructions.is_some());
+
+        record_config.enable_cpi_recording = false;
+        record_config.enable_instruction_compute_units_recording = true;
+
+        let result = batch_processor.execute_loaded_transaction(
+            &mock_bank,
+            &sanitized_transaction,
+            &mut loaded_transaction,
+            ComputeBudget::default(),
+            None,
+            record_config,
+            &mut ExecuteTimings::default(),
+            &mut TransactionErrorMetrics::default(),
+            None,
+            &loaded_programs,
+        );
+
+        let TransactionExecutionResult::Executed {
+            details:
+                TransactionExecutionDetails {
+                    inner_instructions,
+                    instruction_compute_units,
+                    ..
+                },
+            ..
+        } = result
+        else {
+            panic!("Unexpected result")
+        };
+        assert!(inner_instructions.is_none());
+        assert_eq!(instruction_compute_units.map(|units| units.len()), Some(1));
     }
 
     #[test]
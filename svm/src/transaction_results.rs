@@ -77,6 +77,9 @@ pub struct TransactionExecutionDetails {
     pub durable_nonce_fee: Option<DurableNonceFee>,
     pub return_data: Option<TransactionReturnData>,
     pub executed_units: u64,
+    /// Compute units consumed by each top-level instruction, in instruction order.
+    /// Only recorded if `enable_instruction_compute_units_recording` is set.
+    pub instruction_compute_units: Option<Vec<u64>>,
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
@@ -454,6 +454,7 @@ fn svm_integration() {
         enable_log_recording: true,
         enable_return_data_recording: true,
         enable_cpi_recording: false,
+        enable_instruction_compute_units_recording: false,
     };
     let mut timings = ExecuteTimings::default();
 
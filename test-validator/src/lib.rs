@@ -37,21 +37,24 @@ use {
         runtime_config::RuntimeConfig, snapshot_config::SnapshotConfig,
     },
     solana_sdk::{
-        account::{Account, AccountSharedData, WritableAccount},
+        account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
         bpf_loader_upgradeable::UpgradeableLoaderState,
         clock::{Slot, DEFAULT_MS_PER_SLOT},
         commitment_config::CommitmentConfig,
         epoch_schedule::EpochSchedule,
         exit::Exit,
+        feature,
         feature_set::FEATURE_NAMES,
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         instruction::{AccountMeta, Instruction},
         message::Message,
+        native_loader,
         native_token::sol_to_lamports,
         pubkey::Pubkey,
         rent::Rent,
         signature::{read_keypair_file, write_keypair_file, Keypair, Signer},
+        stake, sysvar,
     },
     solana_streamer::socket::SocketAddrSpace,
     solana_tpu_client::tpu_client::{
@@ -503,6 +506,34 @@ impl TestValidatorGenesis {
         Ok(self)
     }
 
+    /// Add the accounts of the full snapshot archives in `snapshot_archives`, such as ones
+    /// minimized with `SnapshotMinimizer::minimize_to_selection`, to the test environment
+    ///
+    /// The genesis config of each archive's cluster must be in the directory of the archive.
+    /// Sysvars, features, builtins, and vote and stake accounts are left to the test environment.
+    pub fn add_accounts_from_snapshot_archives<T, P>(
+        &mut self,
+        snapshot_archives: T,
+    ) -> Result<&mut Self, String>
+    where
+        T: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        for snapshot_archive in snapshot_archives {
+            let accounts =
+                solana_program_test::accounts_from_snapshot_archive(snapshot_archive.as_ref())?;
+            self.add_accounts(accounts.into_iter().filter(|(_, account)| {
+                let owner = account.owner();
+                !sysvar::check_id(owner)
+                    && !feature::check_id(owner)
+                    && !native_loader::check_id(owner)
+                    && !solana_sdk::vote::program::check_id(owner)
+                    && !stake::program::check_id(owner)
+            }));
+        }
+        Ok(self)
+    }
+
     /// Add an account to the test environment with the account data in the provided `filename`
     pub fn add_account_with_file_data(
         &mut self,
@@ -6,6 +6,8 @@ use {
     solana_sdk::{packet::Packet, short_vec::decode_shortu16_len, signature::SIGNATURE_BYTES},
 };
 
+pub mod lifecycle_tracing;
+
 // The mask is 12 bits long (1<<12 = 4096), it means the probability of matching
 // the transaction is 1/4096 assuming the portion being matched is random.
 lazy_static! {
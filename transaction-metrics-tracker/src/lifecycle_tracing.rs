@@ -0,0 +1,713 @@
+//! Span-based tracing of a sampled transaction's lifecycle through the validator pipeline.
+//!
+//! Each pipeline stage records an event when a sampled transaction reaches it. Events are only
+//! recorded once the transaction's signature was verified, so traces can't be started with forged
+//! signatures. The streamer remembers when a transaction arrived over QUIC with `record_arrival`,
+//! and sigverify records it as `Received` once the signature was verified. Once the transaction is committed or dropped, or no event arrived for
+//! `trace_timeout`, its events are turned into one trace: a root span covering the whole lifecycle
+//! with a child span per stage, each ending at that stage's event. Traces are keyed by the
+//! transaction signature and exported to an OpenTelemetry collector over OTLP/HTTP with the JSON
+//! encoding.
+//!
+//! Sampling hashes the signature with a key chosen randomly when the process starts, so every
+//! stage traces the same transactions without coordination while senders can't pick signatures
+//! that get traced. Recording threads append events to their own buffer, which the export thread
+//! drains.
+
+use {
+    lazy_static::lazy_static,
+    log::*,
+    serde_json::{json, Value},
+    solana_sdk::signature::Signature,
+    std::{
+        collections::{hash_map::RandomState, HashMap, VecDeque},
+        fmt::Write,
+        hash::{BuildHasher, Hasher},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    },
+};
+
+pub const DEFAULT_OTLP_TRACES_ENDPOINT: &str = "http://127.0.0.1:4318/v1/traces";
+pub const DEFAULT_SAMPLE_RATE: f64 = 0.0001;
+const DEFAULT_TRACE_TIMEOUT: Duration = Duration::from_secs(30);
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+// Bounds memory if many sampled transactions never reach a terminal stage. The oldest trace is
+// exported incomplete to make room for a new one.
+const MAX_PENDING_TRACES: usize = 100_000;
+// Bounds the events a thread buffers between exports
+const MAX_BUFFERED_EVENTS: usize = 10_000;
+// Bounds the arrival times kept for transactions whose signature was not verified yet
+const MAX_PENDING_ARRIVALS: usize = 10_000;
+// Arrival times are dropped after this long to make room, their transaction was discarded or
+// failed verification
+const ARRIVAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+// OTLP span kinds and status codes
+const SPAN_KIND_INTERNAL: u64 = 1;
+const STATUS_CODE_OK: u64 = 1;
+const STATUS_CODE_ERROR: u64 = 2;
+
+#[derive(Clone, Debug)]
+pub struct TransactionTracingConfig {
+    /// OTLP/HTTP traces endpoint of the collector
+    pub otlp_endpoint: String,
+    /// Fraction of transactions to trace, between 0 and 1
+    pub sample_rate: f64,
+    /// Reported as the `service.name` resource attribute
+    pub service_name: String,
+    /// Reported as the `service.instance.id` resource attribute, e.g. the validator identity
+    pub service_instance_id: String,
+    /// Export a trace that has not been committed or dropped after this long without new events
+    pub trace_timeout: Duration,
+}
+
+impl Default for TransactionTracingConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: DEFAULT_OTLP_TRACES_ENDPOINT.to_string(),
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            service_name: "agave-validator".to_string(),
+            service_instance_id: String::new(),
+            trace_timeout: DEFAULT_TRACE_TIMEOUT,
+        }
+    }
+}
+
+/// The points in the pipeline a traced transaction passes through, in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionStage {
+    /// The streamer received the transaction over QUIC, or sigverify received it from another
+    /// source
+    Received,
+    /// Signature verification passed
+    SigVerified,
+    /// The banking stage scheduler buffered the transaction
+    BankingBuffered,
+    /// A consume worker started processing the transaction
+    Consuming,
+    /// The transaction was executed
+    Executed,
+    /// The transaction was recorded into PoH
+    PohRecorded,
+    /// The transaction was committed to the bank
+    Committed,
+}
+
+impl TransactionStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionStage::Received => "received",
+            TransactionStage::SigVerified => "sigverified",
+            TransactionStage::BankingBuffered => "banking_buffered",
+            TransactionStage::Consuming => "consuming",
+            TransactionStage::Executed => "executed",
+            TransactionStage::PohRecorded => "poh_recorded",
+            TransactionStage::Committed => "committed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum EventKind {
+    Stage(TransactionStage),
+    /// The transaction will be retried, e.g. because of an account lock conflict
+    Retry(String),
+    /// The transaction was dropped and will not be processed further
+    Dropped(String),
+}
+
+#[derive(Clone, Debug)]
+struct Event {
+    name: &'static str,
+    kind: EventKind,
+    time: SystemTime,
+}
+
+struct PendingTrace {
+    events: Vec<Event>,
+    last_update: Instant,
+}
+
+#[derive(Debug)]
+struct FinishedTrace {
+    signature: Signature,
+    events: Vec<Event>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+// The sampling threshold, compared against the keyed hash of a signature
+static SAMPLE_THRESHOLD: AtomicU64 = AtomicU64::new(0);
+
+type EventBuffer = Arc<Mutex<Vec<(Signature, Event)>>>;
+
+lazy_static! {
+    static ref SAMPLING_KEY: RandomState = RandomState::new();
+    // The event buffer of every thread that recorded an event
+    static ref BUFFERS: Mutex<Vec<EventBuffer>> = Mutex::new(Vec::new());
+    static ref ARRIVALS: Mutex<PendingArrivals> =
+        Mutex::new(PendingArrivals::new(MAX_PENDING_ARRIVALS, ARRIVAL_TIMEOUT));
+}
+
+thread_local! {
+    static BUFFER: EventBuffer = {
+        let buffer = EventBuffer::default();
+        BUFFERS.lock().unwrap().push(buffer.clone());
+        buffer
+    };
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Change the fraction of transactions traced
+pub fn set_sample_rate(sample_rate: f64) {
+    let threshold = (sample_rate.clamp(0.0, 1.0) * u64::MAX as f64) as u64;
+    SAMPLE_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+fn is_sampled(signature: &[u8]) -> bool {
+    let threshold = SAMPLE_THRESHOLD.load(Ordering::Relaxed);
+    let mut hasher = SAMPLING_KEY.build_hasher();
+    hasher.write(signature);
+    threshold == u64::MAX || hasher.finish() < threshold
+}
+
+/// Whether events for this transaction should be recorded
+pub fn should_trace(signature: &Signature) -> bool {
+    is_enabled() && is_sampled(signature.as_ref())
+}
+
+/// Record that a sampled transaction reached `stage` now
+pub fn record(signature: &Signature, stage: TransactionStage) {
+    record_at(signature, stage, SystemTime::now())
+}
+
+/// Record that a sampled transaction reached `stage` at `time`
+pub fn record_at(signature: &Signature, stage: TransactionStage, time: SystemTime) {
+    if should_trace(signature) {
+        record_event(
+            signature,
+            Event {
+                name: stage.as_str(),
+                kind: EventKind::Stage(stage),
+                time,
+            },
+        );
+    }
+}
+
+/// Remember that a sampled transaction arrived at `time`, before its signature is verified. The
+/// stage verifying it takes the time with `take_arrival` to record `TransactionStage::Received`.
+pub fn record_arrival(signature: &Signature, time: SystemTime) {
+    if should_trace(signature) {
+        ARRIVALS
+            .lock()
+            .unwrap()
+            .insert(*signature, time, SystemTime::now());
+    }
+}
+
+/// Take the time a sampled transaction arrived, if `record_arrival` remembered it
+pub fn take_arrival(signature: &Signature) -> Option<SystemTime> {
+    if should_trace(signature) {
+        ARRIVALS.lock().unwrap().take(signature)
+    } else {
+        None
+    }
+}
+
+/// Record that `stage` could not process a sampled transaction now but will retry it
+pub fn record_retry(signature: &Signature, stage: &'static str, reason: impl ToString) {
+    if should_trace(signature) {
+        record_event(
+            signature,
+            Event {
+                name: stage,
+                kind: EventKind::Retry(reason.to_string()),
+                time: SystemTime::now(),
+            },
+        );
+    }
+}
+
+/// Record that `stage` dropped a sampled transaction
+pub fn record_dropped(signature: &Signature, stage: &'static str, reason: impl ToString) {
+    if should_trace(signature) {
+        record_event(
+            signature,
+            Event {
+                name: stage,
+                kind: EventKind::Dropped(reason.to_string()),
+                time: SystemTime::now(),
+            },
+        );
+    }
+}
+
+fn is_terminal(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Stage(TransactionStage::Committed) | EventKind::Dropped(_)
+    )
+}
+
+fn record_event(signature: &Signature, event: Event) {
+    BUFFER.with(|buffer| {
+        let mut buffer = buffer.lock().unwrap();
+        if buffer.len() < MAX_BUFFERED_EVENTS {
+            buffer.push((*signature, event));
+        }
+    });
+}
+
+/// Move the events buffered by every thread into `traces`
+fn drain_buffers(traces: &mut PendingTraces) {
+    BUFFERS.lock().unwrap().retain(|buffer| {
+        // Checked before draining, so an exited thread's last events are not lost
+        let exited = Arc::strong_count(buffer) == 1;
+        for (signature, event) in std::mem::take(&mut *buffer.lock().unwrap()) {
+            traces.insert(signature, event);
+        }
+        !exited
+    });
+}
+
+/// Arrival times of sampled transactions, until their signature is verified
+struct PendingArrivals {
+    max_pending: usize,
+    timeout: Duration,
+    arrivals: HashMap<Signature, SystemTime>,
+}
+
+impl PendingArrivals {
+    fn new(max_pending: usize, timeout: Duration) -> Self {
+        Self {
+            max_pending,
+            timeout,
+            arrivals: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, signature: Signature, time: SystemTime, now: SystemTime) {
+        if self.arrivals.len() >= self.max_pending {
+            let timeout = self.timeout;
+            self.arrivals.retain(|_, arrival| {
+                now.duration_since(*arrival)
+                    .map_or(true, |age| age < timeout)
+            });
+        }
+        // Arrivals still waiting for verification are kept over new ones
+        if self.arrivals.len() < self.max_pending {
+            self.arrivals.insert(signature, time);
+        }
+    }
+
+    fn take(&mut self, signature: &Signature) -> Option<SystemTime> {
+        self.arrivals.remove(signature)
+    }
+}
+
+/// Assembles recorded events into traces. Only used by the export thread.
+struct PendingTraces {
+    max_pending: usize,
+    pending: HashMap<Signature, PendingTrace>,
+    // Signatures in the order their traces started, to find the oldest when full
+    order: VecDeque<Signature>,
+    finished: Vec<FinishedTrace>,
+}
+
+impl PendingTraces {
+    fn new(max_pending: usize) -> Self {
+        Self {
+            max_pending,
+            pending: HashMap::new(),
+            order: VecDeque::new(),
+            finished: vec![],
+        }
+    }
+
+    fn insert(&mut self, signature: Signature, event: Event) {
+        let terminal = is_terminal(&event);
+        if !self.pending.contains_key(&signature) {
+            if self.pending.len() >= self.max_pending {
+                self.finish_oldest();
+            }
+            self.order.push_back(signature);
+        }
+        let trace = self
+            .pending
+            .entry(signature)
+            .or_insert_with(|| PendingTrace {
+                events: vec![],
+                last_update: Instant::now(),
+            });
+        trace.events.push(event);
+        trace.last_update = Instant::now();
+        if terminal {
+            self.finish(signature);
+        }
+    }
+
+    fn finish(&mut self, signature: Signature) {
+        if let Some(trace) = self.pending.remove(&signature) {
+            self.finished.push(FinishedTrace {
+                signature,
+                events: trace.events,
+            });
+        }
+    }
+
+    fn finish_oldest(&mut self) {
+        while let Some(signature) = self.order.pop_front() {
+            if self.pending.contains_key(&signature) {
+                self.finish(signature);
+                return;
+            }
+        }
+    }
+
+    /// Take finished traces, and traces that timed out waiting for a terminal event
+    fn take_finished(&mut self, trace_timeout: Duration) -> Vec<FinishedTrace> {
+        let timed_out: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, trace)| trace.last_update.elapsed() >= trace_timeout)
+            .map(|(signature, _)| *signature)
+            .collect();
+        for signature in timed_out {
+            self.finish(signature);
+        }
+        let pending = &self.pending;
+        self.order
+            .retain(|signature| pending.contains_key(signature));
+        std::mem::take(&mut self.finished)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+/// Build the OTLP JSON spans of one trace
+fn trace_spans(trace: &FinishedTrace) -> Vec<Value> {
+    let mut events = trace.events.clone();
+    // Stages on different threads may record out of order
+    events.sort_by_key(|event| event.time);
+    let (Some(first), Some(last)) = (events.first(), events.last()) else {
+        return vec![];
+    };
+
+    let signature = trace.signature.as_ref();
+    let trace_id = to_hex(&signature[..16]);
+    let root_span_id = u64::from_le_bytes(signature[16..24].try_into().unwrap()).max(1);
+    let span_id = |index: u64| to_hex(&root_span_id.wrapping_add(index).max(1).to_be_bytes());
+
+    let (outcome, status) = match &last.kind {
+        EventKind::Stage(TransactionStage::Committed) => {
+            ("committed", json!({"code": STATUS_CODE_OK}))
+        }
+        EventKind::Dropped(reason) => (
+            "dropped",
+            json!({"code": STATUS_CODE_ERROR, "message": format!("{}: {reason}", last.name)}),
+        ),
+        _ => ("incomplete", json!({})),
+    };
+
+    let mut spans = vec![json!({
+        "traceId": trace_id,
+        "spanId": span_id(0),
+        "name": "transaction",
+        "kind": SPAN_KIND_INTERNAL,
+        "startTimeUnixNano": unix_nanos(first.time),
+        "endTimeUnixNano": unix_nanos(last.time),
+        "attributes": [
+            string_attribute("solana.signature", &trace.signature.to_string()),
+            string_attribute("solana.outcome", outcome),
+            string_attribute("solana.last_stage", last.name),
+        ],
+        "status": status,
+    })];
+
+    for (index, window) in events.windows(2).enumerate() {
+        let (previous, event) = (&window[0], &window[1]);
+        let (status, attributes) = match &event.kind {
+            EventKind::Stage(_) => (json!({"code": STATUS_CODE_OK}), vec![]),
+            EventKind::Retry(reason) => (
+                json!({}),
+                vec![string_attribute("solana.retry_reason", reason)],
+            ),
+            EventKind::Dropped(reason) => (
+                json!({"code": STATUS_CODE_ERROR, "message": reason}),
+                vec![string_attribute("solana.drop_reason", reason)],
+            ),
+        };
+        spans.push(json!({
+            "traceId": trace_id,
+            "spanId": span_id(index as u64 + 1),
+            "parentSpanId": span_id(0),
+            "name": event.name,
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": unix_nanos(previous.time),
+            "endTimeUnixNano": unix_nanos(event.time),
+            "attributes": attributes,
+            "status": status,
+        }));
+    }
+    spans
+}
+
+fn export_request(config: &TransactionTracingConfig, traces: &[FinishedTrace]) -> Value {
+    let mut resource_attributes = vec![string_attribute("service.name", &config.service_name)];
+    if !config.service_instance_id.is_empty() {
+        resource_attributes.push(string_attribute(
+            "service.instance.id",
+            &config.service_instance_id,
+        ));
+    }
+    json!({
+        "resourceSpans": [{
+            "resource": {"attributes": resource_attributes},
+            "scopeSpans": [{
+                "scope": {"name": "solana-transaction-lifecycle", "version": env!("CARGO_PKG_VERSION")},
+                "spans": traces.iter().flat_map(trace_spans).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+/// Enables tracing and exports finished traces until `exit` is set
+pub struct TransactionTracingService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl TransactionTracingService {
+    pub fn new(config: TransactionTracingConfig, exit: Arc<AtomicBool>) -> Self {
+        info!(
+            "Tracing {} of transactions to {}",
+            config.sample_rate, config.otlp_endpoint
+        );
+        set_sample_rate(config.sample_rate);
+        ENABLED.store(true, Ordering::Relaxed);
+
+        let thread_hdl = Builder::new()
+            .name("solTxTracing".to_string())
+            .spawn(move || {
+                let client = reqwest::blocking::Client::builder()
+                    .timeout(EXPORT_TIMEOUT)
+                    .build()
+                    .expect("transaction tracing http client");
+                let mut traces = PendingTraces::new(MAX_PENDING_TRACES);
+                while !exit.load(Ordering::Relaxed) {
+                    sleep(EXPORT_INTERVAL);
+                    drain_buffers(&mut traces);
+                    Self::export(&client, &config, traces.take_finished(config.trace_timeout));
+                }
+                ENABLED.store(false, Ordering::Relaxed);
+                // Export everything still pending on the way out
+                drain_buffers(&mut traces);
+                Self::export(&client, &config, traces.take_finished(Duration::ZERO));
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn export(
+        client: &reqwest::blocking::Client,
+        config: &TransactionTracingConfig,
+        traces: Vec<FinishedTrace>,
+    ) {
+        if traces.is_empty() {
+            return;
+        }
+        let response = client
+            .post(&config.otlp_endpoint)
+            .json(&export_request(config, &traces))
+            .send();
+        match response {
+            Ok(response) if !response.status().is_success() => warn!(
+                "failed to export {} transaction traces: {}",
+                traces.len(),
+                response.status()
+            ),
+            Ok(_) => debug!("exported {} transaction traces", traces.len()),
+            Err(err) => warn!(
+                "failed to export {} transaction traces: {}",
+                traces.len(),
+                err
+            ),
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, name: &'static str, millis: u64) -> Event {
+        Event {
+            name,
+            kind,
+            time: UNIX_EPOCH + Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_is_sampled() {
+        let signature = [0xff; 64];
+        set_sample_rate(0.0);
+        assert!(!is_sampled(&signature));
+        set_sample_rate(1.0);
+        assert!(is_sampled(&signature));
+
+        // Signatures sharing a prefix are sampled independently
+        set_sample_rate(0.5);
+        let num_sampled = (0..1000u32)
+            .filter(|i| {
+                let mut signature = [0u8; 64];
+                signature[60..].copy_from_slice(&i.to_le_bytes());
+                is_sampled(&signature)
+            })
+            .count();
+        assert!((350..650).contains(&num_sampled), "{num_sampled}");
+    }
+
+    #[test]
+    fn test_pending_arrivals() {
+        let mut arrivals = PendingArrivals::new(2, Duration::from_secs(10));
+        let signatures: Vec<_> = (0..4u8).map(|i| Signature::from([i; 64])).collect();
+        let time = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+        arrivals.insert(signatures[0], time(1), time(1));
+        arrivals.insert(signatures[1], time(5), time(5));
+        // Full, and no arrival timed out
+        arrivals.insert(signatures[2], time(6), time(6));
+        assert_eq!(arrivals.take(&signatures[2]), None);
+
+        // Full, the arrival that timed out makes room
+        arrivals.insert(signatures[3], time(12), time(12));
+        assert_eq!(arrivals.take(&signatures[0]), None);
+        assert_eq!(arrivals.take(&signatures[1]), Some(time(5)));
+        assert_eq!(arrivals.take(&signatures[3]), Some(time(12)));
+        assert_eq!(arrivals.take(&signatures[3]), None);
+    }
+
+    #[test]
+    fn test_pending_traces() {
+        let mut traces = PendingTraces::new(2);
+        let signatures: Vec<_> = (0..3u8).map(|i| Signature::from([i; 64])).collect();
+        let received = || event(EventKind::Stage(TransactionStage::Received), "received", 1);
+
+        traces.insert(signatures[0], received());
+        traces.insert(signatures[1], received());
+        traces.insert(
+            signatures[1],
+            event(
+                EventKind::Stage(TransactionStage::Committed),
+                "committed",
+                2,
+            ),
+        );
+        traces.insert(signatures[2], received());
+        traces.insert(signatures[0], received());
+        // Full, so the oldest trace is finished incomplete to make room
+        traces.insert(Signature::from([3u8; 64]), received());
+
+        let finished = traces.take_finished(Duration::from_secs(60));
+        let finished: Vec<_> = finished
+            .iter()
+            .map(|trace| (trace.signature, trace.events.len()))
+            .collect();
+        assert_eq!(finished, [(signatures[1], 2), (signatures[0], 2)]);
+        assert_eq!(traces.pending.len(), 2);
+        assert!(traces.pending.contains_key(&signatures[2]));
+
+        let finished = traces.take_finished(Duration::ZERO);
+        assert_eq!(finished.len(), 2);
+        assert!(traces.pending.is_empty() && traces.order.is_empty());
+    }
+
+    #[test]
+    fn test_trace_spans() {
+        let signature = Signature::from([7u8; 64]);
+        let trace = FinishedTrace {
+            signature,
+            events: vec![
+                event(
+                    EventKind::Stage(TransactionStage::SigVerified),
+                    "sigverified",
+                    3,
+                ),
+                event(EventKind::Stage(TransactionStage::Received), "received", 1),
+                event(EventKind::Retry("AccountInUse".to_string()), "executed", 5),
+                event(
+                    EventKind::Dropped("BlockhashNotFound".to_string()),
+                    "executed",
+                    9,
+                ),
+            ],
+        };
+
+        let spans = trace_spans(&trace);
+        assert_eq!(spans.len(), 4);
+
+        let root = &spans[0];
+        assert_eq!(root["traceId"], to_hex(&[7u8; 16]));
+        assert_eq!(root["name"], "transaction");
+        assert_eq!(root["startTimeUnixNano"], "1000000");
+        assert_eq!(root["endTimeUnixNano"], "9000000");
+        assert_eq!(root["status"]["code"], STATUS_CODE_ERROR);
+        assert_eq!(root["status"]["message"], "executed: BlockhashNotFound");
+        assert_eq!(root["attributes"][1]["value"]["stringValue"], "dropped");
+
+        let names: Vec<_> = spans[1..]
+            .iter()
+            .map(|span| span["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["sigverified", "executed", "executed"]);
+        for span in &spans[1..] {
+            assert_eq!(span["parentSpanId"], root["spanId"]);
+            assert_eq!(span["traceId"], root["traceId"]);
+            assert_ne!(span["spanId"], root["spanId"]);
+        }
+        assert_eq!(spans[1]["startTimeUnixNano"], "1000000");
+        assert_eq!(spans[1]["endTimeUnixNano"], "3000000");
+        assert_eq!(
+            spans[2]["attributes"][0]["value"]["stringValue"],
+            "AccountInUse"
+        );
+
+        let request = export_request(&TransactionTracingConfig::default(), &[trace]);
+        assert_eq!(
+            request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+}
@@ -42,6 +42,7 @@ pub mod parse_accounts;
 pub mod parse_address_lookup_table;
 pub mod parse_associated_token;
 pub mod parse_bpf_loader;
+pub mod parse_idl;
 pub mod parse_instruction;
 pub mod parse_stake;
 pub mod parse_system;
@@ -0,0 +1,190 @@
+use {
+    crate::parse_instruction::{InstructionParser, ParseInstructionError, ParsedInstructionEnum},
+    inflector::Inflector,
+    serde_json::{Map, Value},
+    solana_account_decoder::idl::{sighash, Idl, IdlError, IdlInstructionAccount},
+    solana_sdk::{instruction::CompiledInstruction, message::AccountKeys, pubkey::Pubkey},
+    std::path::Path,
+};
+
+/// Parses the instructions of a program described by an Anchor-style IDL. Instructions are
+/// matched by discriminator, and parsed into their accounts, by name, followed by their arguments.
+pub struct IdlInstructionParser {
+    program_id: Pubkey,
+    program_name: String,
+    idl: Idl,
+    discriminators: Vec<Vec<u8>>,
+}
+
+impl IdlInstructionParser {
+    pub fn new(idl: Idl) -> Result<Self, IdlError> {
+        let program_id = idl.program_id()?;
+        let program_name = idl.program_name().unwrap_or_else(|| program_id.to_string());
+        let discriminators = idl
+            .instructions
+            .iter()
+            .map(|instruction| {
+                instruction.discriminator.clone().unwrap_or_else(|| {
+                    sighash("global", &instruction.name.to_snake_case()).to_vec()
+                })
+            })
+            .collect();
+        Ok(Self {
+            program_id,
+            program_name,
+            idl,
+            discriminators,
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, IdlError> {
+        Self::new(Idl::from_file(path)?)
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    fn not_parsable(&self) -> ParseInstructionError {
+        ParseInstructionError::RegisteredInstructionNotParsable(self.program_name.clone())
+    }
+}
+
+fn flatten_account_names(accounts: &[IdlInstructionAccount], names: &mut Vec<String>) {
+    for account in accounts {
+        if account.accounts.is_empty() {
+            names.push(account.name.to_camel_case());
+        } else {
+            flatten_account_names(&account.accounts, names);
+        }
+    }
+}
+
+impl InstructionParser for IdlInstructionParser {
+    fn program_name(&self) -> &str {
+        &self.program_name
+    }
+
+    fn parse(
+        &self,
+        instruction: &CompiledInstruction,
+        account_keys: &AccountKeys,
+    ) -> Result<Value, ParseInstructionError> {
+        let (idl_instruction, discriminator) = self
+            .idl
+            .instructions
+            .iter()
+            .zip(&self.discriminators)
+            .find(|(_, discriminator)| instruction.data.starts_with(discriminator))
+            .ok_or_else(|| self.not_parsable())?;
+
+        let mut account_names = vec![];
+        flatten_account_names(&idl_instruction.accounts, &mut account_names);
+        let mut accounts = instruction.accounts.iter().map(|index| {
+            account_keys
+                .get(*index as usize)
+                .map(|pubkey| Value::String(pubkey.to_string()))
+                .ok_or_else(|| self.not_parsable())
+        });
+        let mut info = Map::new();
+        for name in account_names {
+            // Trailing optional accounts may be omitted
+            let Some(account) = accounts.next() else {
+                break;
+            };
+            info.insert(name, account?);
+        }
+        let remaining_accounts = accounts.collect::<Result<Vec<_>, _>>()?;
+        if !remaining_accounts.is_empty() {
+            info.insert(
+                "remainingAccounts".to_string(),
+                Value::Array(remaining_accounts),
+            );
+        }
+
+        let mut data = &instruction.data[discriminator.len()..];
+        for arg in &idl_instruction.args {
+            let value = self
+                .idl
+                .decode(&arg.ty, &mut data)
+                .map_err(|_| self.not_parsable())?;
+            info.insert(arg.name.to_camel_case(), value);
+        }
+
+        Ok(serde_json::to_value(ParsedInstructionEnum {
+            instruction_type: idl_instruction.name.to_camel_case(),
+            info: Value::Object(info),
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn test_parse_idl_instruction() {
+        let program_id = Pubkey::new_unique();
+        let idl: Idl = serde_json::from_value(json!({
+            "address": program_id.to_string(),
+            "metadata": { "name": "counter" },
+            "instructions": [
+                {
+                    "name": "initialize",
+                    "accounts": [{ "name": "counter" }, { "name": "payer" }],
+                    "args": []
+                },
+                {
+                    "name": "add",
+                    "discriminator": [7],
+                    "accounts": [
+                        { "name": "counter" },
+                        { "name": "auth", "accounts": [{ "name": "owner_key" }] }
+                    ],
+                    "args": [{ "name": "amount_to_add", "type": "u32" }]
+                }
+            ]
+        }))
+        .unwrap();
+        let parser = IdlInstructionParser::new(idl).unwrap();
+        assert_eq!(parser.program_id(), &program_id);
+        assert_eq!(parser.program_name(), "counter");
+
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), program_id];
+        let account_keys = AccountKeys::new(&keys, None);
+
+        let mut data = vec![7];
+        data.extend(5u32.to_le_bytes());
+        let instruction = CompiledInstruction::new_from_raw_parts(2, data, vec![0, 1, 0]);
+        assert_eq!(
+            parser.parse(&instruction, &account_keys).unwrap(),
+            json!({
+                "type": "add",
+                "info": {
+                    "counter": keys[0].to_string(),
+                    "ownerKey": keys[1].to_string(),
+                    "remainingAccounts": [keys[0].to_string()],
+                    "amountToAdd": 5,
+                }
+            })
+        );
+
+        let instruction = CompiledInstruction::new_from_raw_parts(
+            2,
+            sighash("global", "initialize").to_vec(),
+            vec![0],
+        );
+        assert_eq!(
+            parser.parse(&instruction, &account_keys).unwrap(),
+            json!({ "type": "initialize", "info": { "counter": keys[0].to_string() } })
+        );
+
+        // Truncated arguments, unknown discriminator and unknown account index
+        let instruction = CompiledInstruction::new_from_raw_parts(2, vec![7, 5], vec![0]);
+        assert!(parser.parse(&instruction, &account_keys).is_err());
+        let instruction = CompiledInstruction::new_from_raw_parts(2, vec![8], vec![]);
+        assert!(parser.parse(&instruction, &account_keys).is_err());
+        let instruction = CompiledInstruction::new_from_raw_parts(2, vec![7, 5, 0, 0, 0], vec![9]);
+        assert!(parser.parse(&instruction, &account_keys).is_err());
+    }
+}
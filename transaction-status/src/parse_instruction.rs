@@ -19,6 +19,7 @@ use {
     std::{
         collections::HashMap,
         str::{from_utf8, Utf8Error},
+        sync::{Arc, RwLock},
     },
     thiserror::Error,
 };
@@ -58,6 +59,48 @@ lazy_static! {
         m.insert(*VOTE_PROGRAM_ID, ParsableProgram::Vote);
         m
     };
+    static ref REGISTERED_PARSERS: RwLock<HashMap<Pubkey, Arc<dyn InstructionParser>>> =
+        RwLock::default();
+}
+
+/// Parses the instructions of a program without a built-in parser
+pub trait InstructionParser: Send + Sync {
+    /// Reported as the `program` of parsed instructions
+    fn program_name(&self) -> &str;
+
+    fn parse(
+        &self,
+        instruction: &CompiledInstruction,
+        account_keys: &AccountKeys,
+    ) -> Result<Value, ParseInstructionError>;
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegisterInstructionParserError {
+    #[error("{0} has a built-in instruction parser")]
+    BuiltinParser(Pubkey),
+
+    #[error("{0} already has a registered instruction parser")]
+    AlreadyRegistered(Pubkey),
+}
+
+/// Register `parser` for the instructions of `program_id`, typically at startup so that
+/// `jsonParsed` transactions include them
+pub fn register_instruction_parser(
+    program_id: Pubkey,
+    parser: Arc<dyn InstructionParser>,
+) -> Result<(), RegisterInstructionParserError> {
+    if PARSABLE_PROGRAM_IDS.contains_key(&program_id) {
+        return Err(RegisterInstructionParserError::BuiltinParser(program_id));
+    }
+    let mut registered_parsers = REGISTERED_PARSERS.write().unwrap();
+    if registered_parsers.contains_key(&program_id) {
+        return Err(RegisterInstructionParserError::AlreadyRegistered(
+            program_id,
+        ));
+    }
+    registered_parsers.insert(program_id, parser);
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -71,6 +114,9 @@ pub enum ParseInstructionError {
     #[error("Program not parsable")]
     ProgramNotParsable,
 
+    #[error("{0} instruction not parsable")]
+    RegisteredInstructionNotParsable(String),
+
     #[error("Internal error, please report")]
     SerdeJsonError(#[from] serde_json::error::Error),
 }
@@ -113,9 +159,20 @@ pub fn parse(
     account_keys: &AccountKeys,
     stack_height: Option<u32>,
 ) -> Result<ParsedInstruction, ParseInstructionError> {
-    let program_name = PARSABLE_PROGRAM_IDS
-        .get(program_id)
-        .ok_or(ParseInstructionError::ProgramNotParsable)?;
+    let Some(program_name) = PARSABLE_PROGRAM_IDS.get(program_id) else {
+        let parser = REGISTERED_PARSERS
+            .read()
+            .unwrap()
+            .get(program_id)
+            .cloned()
+            .ok_or(ParseInstructionError::ProgramNotParsable)?;
+        return Ok(ParsedInstruction {
+            program: parser.program_name().to_string(),
+            program_id: program_id.to_string(),
+            parsed: parser.parse(instruction, account_keys)?,
+            stack_height,
+        });
+    };
     let parsed_json = match program_name {
         ParsableProgram::AddressLookupTable => {
             serde_json::to_value(parse_address_lookup_table(instruction, account_keys)?)?
@@ -202,6 +259,65 @@ mod test {
         assert!(parse(&non_parsable_program_id, &memo_instruction, &no_keys, None).is_err());
     }
 
+    struct LengthParser;
+
+    impl InstructionParser for LengthParser {
+        fn program_name(&self) -> &str {
+            "length"
+        }
+
+        fn parse(
+            &self,
+            instruction: &CompiledInstruction,
+            _account_keys: &AccountKeys,
+        ) -> Result<Value, ParseInstructionError> {
+            if instruction.data.is_empty() {
+                return Err(ParseInstructionError::RegisteredInstructionNotParsable(
+                    self.program_name().to_string(),
+                ));
+            }
+            Ok(json!({ "len": instruction.data.len() }))
+        }
+    }
+
+    #[test]
+    fn test_register_instruction_parser() {
+        let no_keys = AccountKeys::new(&[], None);
+        let program_id = Pubkey::new_unique();
+        let mut instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: vec![1, 2, 3],
+        };
+        assert!(parse(&program_id, &instruction, &no_keys, None).is_err());
+
+        register_instruction_parser(program_id, Arc::new(LengthParser)).unwrap();
+        assert_eq!(
+            register_instruction_parser(program_id, Arc::new(LengthParser)),
+            Err(RegisterInstructionParserError::AlreadyRegistered(
+                program_id
+            ))
+        );
+        assert_eq!(
+            register_instruction_parser(*MEMO_V1_PROGRAM_ID, Arc::new(LengthParser)),
+            Err(RegisterInstructionParserError::BuiltinParser(
+                *MEMO_V1_PROGRAM_ID
+            ))
+        );
+
+        assert_eq!(
+            parse(&program_id, &instruction, &no_keys, Some(2)).unwrap(),
+            ParsedInstruction {
+                program: "length".to_string(),
+                program_id: program_id.to_string(),
+                parsed: json!({ "len": 3 }),
+                stack_height: Some(2),
+            }
+        );
+        instruction.data.clear();
+        assert!(parse(&program_id, &instruction, &no_keys, None).is_err());
+    }
+
     #[test]
     fn test_parse_memo() {
         let good_memo = "good memo".to_string();
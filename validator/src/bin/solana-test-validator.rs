@@ -463,6 +463,13 @@ fn main() {
             println!("Error: add_accounts_from_directories failed: {e}");
             exit(1);
         })
+        .add_accounts_from_snapshot_archives(
+            matches.values_of("account_snapshot").unwrap_or_default(),
+        )
+        .unwrap_or_else(|e| {
+            println!("Error: add_accounts_from_snapshot_archives failed: {e}");
+            exit(1);
+        })
         .deactivate_features(&features_to_deactivate);
 
     genesis.rpc_config(JsonRpcConfig {
@@ -1195,7 +1195,15 @@ fn download_snapshots(
                 .any(|snapshot_archive| {
                     snapshot_archive.slot() == incremental_snapshot_hash.0
                         && snapshot_archive.hash().0 == incremental_snapshot_hash.1
-                        && snapshot_archive.base_slot() == full_snapshot_hash.0
+                        && snapshot_utils::get_incremental_snapshot_archive_chain(
+                            incremental_snapshot_archives_dir,
+                            full_snapshot_hash.0,
+                            &snapshot_archive,
+                        )
+                        .first()
+                        .is_some_and(|first_snapshot_archive| {
+                            first_snapshot_archive.base_slot() == full_snapshot_hash.0
+                        })
                 })
             {
                 info!(
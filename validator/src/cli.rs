@@ -13,8 +13,8 @@ use {
         hidden_unless_forced,
         input_validators::{
             is_keypair, is_keypair_or_ask_keyword, is_parsable, is_pow2, is_pubkey,
-            is_pubkey_or_keypair, is_slot, is_url_or_moniker, is_valid_percentage, is_within_range,
-            validate_maximum_full_snapshot_archives_to_retain,
+            is_pubkey_or_keypair, is_slot, is_url, is_url_or_moniker, is_valid_percentage,
+            is_within_range, validate_maximum_full_snapshot_archives_to_retain,
             validate_maximum_incremental_snapshot_archives_to_retain,
         },
         keypair::SKIP_SEED_PHRASE_VALIDATION_ARG,
@@ -37,7 +37,8 @@ use {
         snapshot_utils::{
             SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION,
             DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
-            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN, SUPPORTED_ARCHIVE_COMPRESSION,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_CHAIN_LENGTH, SUPPORTED_ARCHIVE_COMPRESSION,
         },
     },
     solana_sdk::{
@@ -48,8 +49,9 @@ use {
         self, MAX_BATCH_SEND_RATE_MS, MAX_TRANSACTION_BATCH_SIZE,
     },
     solana_tpu_client::tpu_client::DEFAULT_TPU_CONNECTION_POOL_SIZE,
+    solana_transaction_metrics_tracker::lifecycle_tracing,
     solana_unified_scheduler_pool::DefaultSchedulerPool,
-    std::{path::PathBuf, str::FromStr},
+    std::{num::NonZeroUsize, path::PathBuf, str::FromStr},
 };
 
 pub mod thread_args;
@@ -253,6 +255,22 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Upload new confirmed blocks into a BigTable instance"),
         )
+        .arg(
+            Arg::with_name("rpc_filesystem_ledger_storage")
+                .long("rpc-filesystem-ledger-storage")
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("enable_rpc_transaction_history")
+                .conflicts_with_all(&[
+                    "enable_rpc_bigtable_ledger_storage",
+                    "enable_bigtable_ledger_upload",
+                ])
+                .help(
+                    "Keep the full ledger history in this directory. New confirmed blocks are \
+                     stored in it, and historical transaction info is fetched from it as a \
+                     fallback to local ledger data",
+                ),
+        )
         .arg(
             Arg::with_name("enable_extended_tx_metadata_storage")
                 .long("enable-extended-tx-metadata-storage")
@@ -263,6 +281,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      transaction info stored",
                 ),
         )
+        .arg(
+            Arg::with_name("enable_rpc_historical_account_reads")
+                .long("enable-rpc-historical-account-reads")
+                .requires("full_rpc_api")
+                .takes_value(false)
+                .help(
+                    "Enable the 'getHistoricalAccountInfo' API, which rebuilds account state at a \
+                     past slot from local snapshot archives and the ledger. Each request replays \
+                     the ledger in a scratch directory and is very CPU, memory and disk intensive",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_max_multiple_accounts")
                 .long("rpc-max-multiple-accounts")
@@ -539,6 +568,19 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      purging older snapshots.",
                 ),
         )
+        .arg(
+            Arg::with_name("maximum_incremental_snapshot_chain_length")
+                .long("maximum-incremental-snapshot-chain-length")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .default_value(&default_args.maximum_incremental_snapshot_chain_length)
+                .validator(is_parsable::<NonZeroUsize>)
+                .help(
+                    "The maximum number of incremental snapshot archives in a chain, where each \
+                     one is based on the previous one rather than on the full snapshot. Chaining \
+                     keeps incremental snapshot archives small. A value of 1 disables chaining.",
+                ),
+        )
         .arg(
             Arg::with_name("snapshot_packager_niceness_adj")
                 .long("snapshot-packager-niceness-adjustment")
@@ -745,6 +787,75 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      SIGUSR1 signal to the validator process will cause it to re-open the log file",
                 ),
         )
+        .arg(
+            Arg::with_name("prometheus_bind_address")
+                .long("prometheus-bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help(
+                    "Serve metrics in the Prometheus text format at /metrics on this address. \
+                     Metrics are still sent to InfluxDB when SOLANA_METRICS_CONFIG is set",
+                ),
+        )
+        .arg(
+            Arg::with_name("prometheus_namespace")
+                .long("prometheus-namespace")
+                .value_name("NAMESPACE")
+                .takes_value(true)
+                .default_value(&default_args.prometheus_namespace)
+                .help("Prefix of every metric name served by --prometheus-bind-address"),
+        )
+        .arg(
+            Arg::with_name("prometheus_rename_metric")
+                .long("prometheus-rename-metric")
+                .value_name("NAME=NEW_NAME")
+                .takes_value(true)
+                .multiple(true)
+                .requires("prometheus_bind_address")
+                .validator(is_rename)
+                .help(
+                    "Serve the datapoint or counter NAME as NEW_NAME at /metrics. An empty \
+                     NEW_NAME stops it from being served",
+                ),
+        )
+        .arg(
+            Arg::with_name("prometheus_rename_tag")
+                .long("prometheus-rename-tag")
+                .value_name("TAG=NEW_LABEL")
+                .takes_value(true)
+                .multiple(true)
+                .requires("prometheus_bind_address")
+                .validator(is_rename)
+                .help(
+                    "Serve the datapoint tag TAG as the label NEW_LABEL at /metrics. An empty \
+                     NEW_LABEL drops the tag",
+                ),
+        )
+        .arg(
+            Arg::with_name("transaction_tracing_otlp_endpoint")
+                .long("transaction-tracing-otlp-endpoint")
+                .value_name("URL")
+                .takes_value(true)
+                .validator(is_url)
+                .help(
+                    "Trace sampled transactions from signature verification through the \
+                     banking stage to commit, and export the traces to this OTLP/HTTP endpoint \
+                     of an OpenTelemetry collector, e.g. http://127.0.0.1:4318/v1/traces",
+                ),
+        )
+        .arg(
+            Arg::with_name("transaction_tracing_sample_rate")
+                .long("transaction-tracing-sample-rate")
+                .value_name("FRACTION")
+                .takes_value(true)
+                .default_value(&default_args.transaction_tracing_sample_rate)
+                .validator(is_sample_rate)
+                .help(
+                    "Fraction of transactions traced by --transaction-tracing-otlp-endpoint, \
+                     between 0 and 1",
+                ),
+        )
         .arg(
             Arg::with_name("wait_for_supermajority")
                 .long("wait-for-supermajority")
@@ -1207,6 +1318,45 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .multiple(true)
                 .help("Specify the configuration file for the Geyser plugin."),
         )
+        .arg(
+            Arg::with_name("geyser_plugin_replay_buffer_size")
+                .long("geyser-plugin-replay-buffer-size")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .requires("geyser_plugin_config")
+                .help(
+                    "Keep this many of the most recent account, transaction and slot \
+                     notifications, so Geyser plugins that are loaded or reloaded at runtime \
+                     can ask to replay them from a given slot. Only account and transaction \
+                     notifications wanted by a plugin loaded at the time are kept, so a plugin \
+                     reloaded with wider filters, or one that was not loaded, cannot replay \
+                     notifications nobody subscribed to [default: disabled]",
+                ),
+        )
+        .arg(
+            Arg::with_name("program_idl")
+                .long("program-idl")
+                .alias("instruction-parser-idl")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Parse the instructions and accounts of the program described by this \
+                     Anchor IDL JSON file in jsonParsed RPC responses",
+                ),
+        )
+        .arg(
+            Arg::with_name("instruction_parser_plugin")
+                .long("instruction-parser-plugin")
+                .value_name("LIBRARY")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Load instruction parsers for jsonParsed RPC responses from this dynamic \
+                     library, which must export _create_instruction_parser_plugin",
+                ),
+        )
         .arg(
             Arg::with_name("snapshot_archive_format")
                 .long("snapshot-archive-format")
@@ -1528,6 +1678,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .possible_values(BlockProductionMethod::cli_names())
                 .help(BlockProductionMethod::cli_message()),
         )
+        .arg(
+            Arg::with_name("transaction_scheduler_plugin")
+                .long("transaction-scheduler-plugin")
+                .value_name("LIBRARY")
+                .takes_value(true)
+                .help(
+                    "Schedule transactions with the scheduler exported by this dynamic library, \
+                     instead of the built-in prio-graph scheduler. Only used with \
+                     `--block-production-method central-scheduler`",
+                ),
+        )
         .arg(
             Arg::with_name("unified_scheduler_handler_threads")
                 .long("unified-scheduler-handler-threads")
@@ -2184,6 +2345,7 @@ pub struct DefaultArgs {
     pub maximum_local_snapshot_age: String,
     pub maximum_full_snapshot_archives_to_retain: String,
     pub maximum_incremental_snapshot_archives_to_retain: String,
+    pub maximum_incremental_snapshot_chain_length: String,
     pub snapshot_packager_niceness_adjustment: String,
     pub full_snapshot_archive_interval_slots: String,
     pub incremental_snapshot_archive_interval_slots: String,
@@ -2192,6 +2354,9 @@ pub struct DefaultArgs {
 
     pub contact_debug_interval: String,
 
+    pub prometheus_namespace: String,
+    pub transaction_tracing_sample_rate: String,
+
     pub snapshot_version: SnapshotVersion,
     pub snapshot_archive_format: String,
 
@@ -2274,6 +2439,8 @@ impl DefaultArgs {
                 .to_string(),
             maximum_incremental_snapshot_archives_to_retain:
                 DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN.to_string(),
+            maximum_incremental_snapshot_chain_length:
+                DEFAULT_MAX_INCREMENTAL_SNAPSHOT_CHAIN_LENGTH.to_string(),
             snapshot_packager_niceness_adjustment: "0".to_string(),
             full_snapshot_archive_interval_slots: DEFAULT_FULL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS
                 .to_string(),
@@ -2283,6 +2450,8 @@ impl DefaultArgs {
             max_snapshot_download_abort: MAX_SNAPSHOT_DOWNLOAD_ABORT.to_string(),
             snapshot_archive_format: DEFAULT_ARCHIVE_COMPRESSION.to_string(),
             contact_debug_interval: "120000".to_string(),
+            prometheus_namespace: "solana".to_string(),
+            transaction_tracing_sample_rate: lifecycle_tracing::DEFAULT_SAMPLE_RATE.to_string(),
             snapshot_version: SnapshotVersion::default(),
             rocksdb_shred_compaction: "level".to_string(),
             rocksdb_ledger_compression: "none".to_string(),
@@ -2333,6 +2502,21 @@ pub fn port_range_validator(port_range: String) -> Result<(), String> {
     }
 }
 
+fn is_rename(rename: String) -> Result<(), String> {
+    match rename.split_once('=') {
+        Some((name, _)) if !name.is_empty() => Ok(()),
+        _ => Err(format!("expected NAME=NEW_NAME, got '{rename}'")),
+    }
+}
+
+fn is_sample_rate(rate: String) -> Result<(), String> {
+    match rate.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(()),
+        Ok(rate) => Err(format!("sample rate must be between 0 and 1, got {rate}")),
+        Err(err) => Err(format!("invalid sample rate '{rate}': {err}")),
+    }
+}
+
 fn hash_validator(hash: String) -> Result<(), String> {
     Hash::from_str(&hash)
         .map(|_| ())
@@ -2553,6 +2737,20 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                      parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("account_snapshot")
+                .long("account-snapshot")
+                .value_name("SNAPSHOT_ARCHIVE")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Load the accounts of a full snapshot archive, such as one minimized with \
+                     `solana-ledger-tool create-snapshot --minimized`. The genesis config of the \
+                     snapshot's cluster must be in the directory of the archive. Sysvars, \
+                     features, builtins, and vote and stake accounts are not loaded. If the \
+                     ledger already exists then this parameter is silently ignored",
+                ),
+        )
         .arg(
             Arg::with_name("ticks_per_slot")
                 .long("ticks-per-slot")
@@ -0,0 +1,68 @@
+//! Instruction parsers loaded from a dynamic library at startup.
+//!
+//! The dynamic library must export a "C" function `_create_instruction_parser_plugin` which
+//! creates the implementation of `InstructionParserPlugin` and returns it to the caller. It must
+//! be built with the same Rust compiler and `solana-transaction-status` version as the validator.
+
+use {
+    libloading::{Library, Symbol},
+    log::*,
+    solana_sdk::pubkey::Pubkey,
+    solana_transaction_status::parse_instruction::{
+        register_instruction_parser, InstructionParser, RegisterInstructionParserError,
+    },
+    std::{path::Path, sync::Arc},
+    thiserror::Error,
+};
+
+pub trait InstructionParserPlugin {
+    fn name(&self) -> &'static str;
+
+    /// The parsers to register, by program id
+    fn instruction_parsers(&self) -> Vec<(Pubkey, Arc<dyn InstructionParser>)>;
+}
+
+#[derive(Error, Debug)]
+pub enum InstructionParserPluginError {
+    #[error("Failed to load instruction parser plugin: {0}")]
+    PluginLoadError(#[from] libloading::Error),
+
+    #[error(transparent)]
+    RegisterError(#[from] RegisterInstructionParserError),
+}
+
+/// Load the instruction parser plugin at `path` and register its parsers, returning the ids of
+/// the programs they parse
+///
+/// # Safety
+///
+/// This runs the library's initialization routines and `_create_instruction_parser_plugin`,
+/// which must return a valid, heap-allocated `InstructionParserPlugin`.
+pub unsafe fn load_instruction_parser_plugin(
+    path: &Path,
+) -> Result<Vec<Pubkey>, InstructionParserPluginError> {
+    type PluginConstructor = unsafe fn() -> *mut dyn InstructionParserPlugin;
+
+    let lib = Library::new(path)?;
+    let plugin = {
+        let constructor: Symbol<PluginConstructor> =
+            lib.get(b"_create_instruction_parser_plugin")?;
+        Box::from_raw(constructor())
+    };
+    // Registered parsers run code from the library for the rest of the process, so it is never
+    // unloaded
+    std::mem::forget(lib);
+
+    let parsers = plugin.instruction_parsers();
+    let mut program_ids = Vec::with_capacity(parsers.len());
+    for (program_id, parser) in parsers {
+        register_instruction_parser(program_id, parser)?;
+        program_ids.push(program_id);
+    }
+    info!(
+        "Loaded instruction parser plugin {} for programs {:?}",
+        plugin.name(),
+        program_ids
+    );
+    Ok(program_ids)
+}
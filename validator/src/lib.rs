@@ -20,6 +20,8 @@ pub mod admin_rpc_service;
 pub mod bootstrap;
 pub mod cli;
 pub mod dashboard;
+pub mod instruction_parser_plugin;
+pub mod transaction_scheduler_plugin;
 
 #[cfg(unix)]
 fn redirect_stderr(filename: &str) {
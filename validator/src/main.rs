@@ -8,14 +8,19 @@ use {
         bootstrap,
         cli::{self, app, warn_for_deprecated_arguments, DefaultArgs},
         dashboard::Dashboard,
+        instruction_parser_plugin::load_instruction_parser_plugin,
         ledger_lockfile, lock_ledger, new_spinner_progress_bar, println_name_value,
         redirect_stderr_to_file,
+        transaction_scheduler_plugin::load_scheduler_factory,
     },
     clap::{crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit, ArgMatches},
     console::style,
     crossbeam_channel::unbounded,
     log::*,
     rand::{seq::SliceRandom, thread_rng},
+    solana_account_decoder::{
+        idl::Idl, parse_account_data::register_account_parser, parse_idl::IdlAccountParser,
+    },
     solana_accounts_db::{
         accounts_db::{AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage},
         accounts_index::{
@@ -48,6 +53,7 @@ use {
         },
         use_snapshot_archives_at_startup::{self, UseSnapshotArchivesAtStartup},
     },
+    solana_metrics::prometheus::{PrometheusConfig, PrometheusExporter},
     solana_perf::recycler::enable_recycler_warming,
     solana_poh::poh_service,
     solana_rpc::{
@@ -72,8 +78,14 @@ use {
     solana_send_transaction_service::send_transaction_service,
     solana_streamer::socket::SocketAddrSpace,
     solana_tpu_client::tpu_client::DEFAULT_TPU_ENABLE_UDP,
+    solana_transaction_metrics_tracker::lifecycle_tracing::{
+        TransactionTracingConfig, TransactionTracingService,
+    },
+    solana_transaction_status::{
+        parse_idl::IdlInstructionParser, parse_instruction::register_instruction_parser,
+    },
     std::{
-        collections::{HashSet, VecDeque},
+        collections::{HashMap, HashSet, VecDeque},
         env,
         fs::{self, File},
         net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -81,7 +93,10 @@ use {
         path::{Path, PathBuf},
         process::exit,
         str::FromStr,
-        sync::{Arc, RwLock},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
         time::{Duration, SystemTime},
     },
 };
@@ -1267,6 +1282,52 @@ pub fn main() {
     };
     let starting_with_geyser_plugins: bool = on_start_geyser_plugin_config_files.is_some();
 
+    for idl_path in values_t!(matches, "program_idl", PathBuf).unwrap_or_default() {
+        let (instruction_parser, account_parser) = Idl::from_file(&idl_path)
+            .and_then(|idl| {
+                let parse_accounts = !idl.accounts.is_empty();
+                Ok((
+                    IdlInstructionParser::new(idl.clone())?,
+                    parse_accounts
+                        .then(|| IdlAccountParser::new(idl))
+                        .transpose()?,
+                ))
+            })
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to load the IDL {}: {err}", idl_path.display());
+                exit(1);
+            });
+        let program_id = *instruction_parser.program_id();
+        let registered = register_instruction_parser(program_id, Arc::new(instruction_parser))
+            .map_err(|err| err.to_string())
+            .and_then(|()| match account_parser {
+                Some(account_parser) => {
+                    register_account_parser(program_id, Arc::new(account_parser))
+                        .map_err(|err| err.to_string())
+                }
+                None => Ok(()),
+            });
+        if let Err(err) = registered {
+            eprintln!("Failed to register the IDL {}: {err}", idl_path.display());
+            exit(1);
+        }
+        info!(
+            "Parsing instructions and accounts of {program_id} with the IDL {}",
+            idl_path.display()
+        );
+    }
+    for plugin_path in values_t!(matches, "instruction_parser_plugin", PathBuf).unwrap_or_default()
+    {
+        // Safety: the operator vouches for the library by passing it on the command line
+        if let Err(err) = unsafe { load_instruction_parser_plugin(&plugin_path) } {
+            eprintln!(
+                "Failed to load the instruction parser plugin {}: {err}",
+                plugin_path.display()
+            );
+            exit(1);
+        }
+    }
+
     let rpc_bigtable_config = if matches.is_present("enable_rpc_bigtable_ledger_storage")
         || matches.is_present("enable_bigtable_ledger_upload")
     {
@@ -1358,11 +1419,16 @@ pub fn main() {
             enable_extended_tx_metadata_storage: matches.is_present("enable_cpi_and_log_storage")
                 || matches.is_present("enable_extended_tx_metadata_storage"),
             rpc_bigtable_config,
+            rpc_filesystem_ledger_storage_path: matches
+                .value_of("rpc_filesystem_ledger_storage")
+                .map(PathBuf::from),
             faucet_addr: matches.value_of("rpc_faucet_addr").map(|address| {
                 solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
             }),
             full_api,
             obsolete_v1_7_api: matches.is_present("obsolete_v1_7_rpc_api"),
+            enable_historical_account_reads: matches
+                .is_present("enable_rpc_historical_account_reads"),
             max_multiple_accounts: Some(value_t_or_exit!(
                 matches,
                 "rpc_max_multiple_accounts",
@@ -1385,6 +1451,12 @@ pub fn main() {
             )),
         },
         on_start_geyser_plugin_config_files,
+        geyser_plugin_replay_buffer_size: value_t!(
+            matches,
+            "geyser_plugin_replay_buffer_size",
+            usize
+        )
+        .unwrap_or(0),
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
             (
                 SocketAddr::new(rpc_bind_address, rpc_port),
@@ -1542,6 +1614,11 @@ pub fn main() {
         "maximum_incremental_snapshots_to_retain",
         NonZeroUsize
     );
+    let maximum_incremental_snapshot_chain_length = value_t_or_exit!(
+        matches,
+        "maximum_incremental_snapshot_chain_length",
+        NonZeroUsize
+    );
     let snapshot_packager_niceness_adj =
         value_t_or_exit!(matches, "snapshot_packager_niceness_adj", i8);
     let minimal_snapshot_download_speed =
@@ -1636,6 +1713,7 @@ pub fn main() {
         snapshot_version,
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_chain_length,
         accounts_hash_debug_verify: validator_config.accounts_db_test_hash_calculation,
         packager_thread_niceness_adj: snapshot_packager_niceness_adj,
     };
@@ -1701,6 +1779,16 @@ pub fn main() {
         BlockProductionMethod
     )
     .unwrap_or_default();
+    if let Some(library) = matches.value_of("transaction_scheduler_plugin") {
+        validator_config.transaction_scheduler_factory =
+            match unsafe { load_scheduler_factory(Path::new(library)) } {
+                Ok(factory) => factory,
+                Err(err) => {
+                    eprintln!("Failed to load transaction scheduler plugin {library}: {err}");
+                    exit(1);
+                }
+            };
+    }
     validator_config.unified_scheduler_handler_threads =
         value_t!(matches, "unified_scheduler_handler_threads", usize).ok();
 
@@ -1904,6 +1992,63 @@ pub fn main() {
 
     solana_metrics::set_host_id(identity_keypair.pubkey().to_string());
     solana_metrics::set_panic_hook("validator", Some(String::from(solana_version)));
+    let prometheus_exporter = matches.value_of("prometheus_bind_address").map(|address| {
+        let bind_address = solana_net_utils::parse_host_port(address).unwrap_or_else(|err| {
+            eprintln!("Failed to parse --prometheus-bind-address: {err}");
+            exit(1);
+        });
+        let renames_of = |name: &str| -> HashMap<String, String> {
+            values_t!(matches, name, String)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|rename| {
+                    rename
+                        .split_once('=')
+                        .map(|(from, to)| (from.to_string(), to.to_string()))
+                })
+                .collect()
+        };
+        let config = PrometheusConfig {
+            namespace: value_t_or_exit!(matches, "prometheus_namespace", String),
+            name_map: renames_of("prometheus_rename_metric"),
+            tag_map: renames_of("prometheus_rename_tag"),
+        };
+        let prometheus_exit = Arc::new(AtomicBool::new(false));
+        let exporter = PrometheusExporter::new(bind_address, config, prometheus_exit.clone())
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to start the Prometheus exporter on {bind_address}: {err}");
+                exit(1);
+            });
+        validator_config
+            .validator_exit
+            .write()
+            .unwrap()
+            .register_exit(Box::new(move || {
+                prometheus_exit.store(true, Ordering::Relaxed)
+            }));
+        exporter
+    });
+    let transaction_tracing_service =
+        matches
+            .value_of("transaction_tracing_otlp_endpoint")
+            .map(|otlp_endpoint| {
+                let config = TransactionTracingConfig {
+                    otlp_endpoint: otlp_endpoint.to_string(),
+                    sample_rate: value_t_or_exit!(matches, "transaction_tracing_sample_rate", f64),
+                    service_instance_id: identity_keypair.pubkey().to_string(),
+                    ..TransactionTracingConfig::default()
+                };
+                let tracing_exit = Arc::new(AtomicBool::new(false));
+                let service = TransactionTracingService::new(config, tracing_exit.clone());
+                validator_config
+                    .validator_exit
+                    .write()
+                    .unwrap()
+                    .register_exit(Box::new(move || {
+                        tracing_exit.store(true, Ordering::Relaxed)
+                    }));
+                service
+            });
     solana_entry::entry::init_poh();
     snapshot_utils::remove_tmp_snapshot_archives(&full_snapshot_archives_dir);
     snapshot_utils::remove_tmp_snapshot_archives(&incremental_snapshot_archives_dir);
@@ -1970,6 +2115,16 @@ pub fn main() {
     }
     info!("Validator initialized");
     validator.join();
+    if let Some(prometheus_exporter) = prometheus_exporter {
+        prometheus_exporter
+            .join()
+            .expect("prometheus_exporter thread join");
+    }
+    if let Some(transaction_tracing_service) = transaction_tracing_service {
+        transaction_tracing_service
+            .join()
+            .expect("transaction_tracing_service thread join");
+    }
     info!("Validator exiting..");
 }
 
@@ -0,0 +1,35 @@
+//! Central transaction schedulers loaded from a dynamic library at startup.
+//!
+//! The dynamic library must export a "C" function `_create_scheduler_factory` which creates the
+//! implementation of `SchedulerFactory` and returns it to the caller. It must be built with the
+//! same Rust compiler and `solana-core` version as the validator.
+
+use {
+    libloading::{Library, Symbol},
+    log::*,
+    solana_core::banking_stage::transaction_scheduler::scheduler::SchedulerFactory,
+    std::{path::Path, sync::Arc},
+};
+
+/// Load the scheduler factory exported by the library at `path`
+///
+/// # Safety
+///
+/// This runs the library's initialization routines and `_create_scheduler_factory`, which must
+/// return a valid, heap-allocated `SchedulerFactory`.
+pub unsafe fn load_scheduler_factory(
+    path: &Path,
+) -> Result<Arc<dyn SchedulerFactory>, libloading::Error> {
+    type FactoryConstructor = unsafe fn() -> *mut dyn SchedulerFactory;
+
+    let lib = Library::new(path)?;
+    let factory = {
+        let constructor: Symbol<FactoryConstructor> = lib.get(b"_create_scheduler_factory")?;
+        Box::from_raw(constructor())
+    };
+    // The scheduler runs code from the library for the rest of the process, so it is never
+    // unloaded
+    std::mem::forget(lib);
+    info!("Loaded transaction scheduler plugin {}", path.display());
+    Ok(Arc::from(factory))
+}
@@ -2,6 +2,7 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
+    crate::slo::{is_threshold, SloConfig, SloMonitor, Threshold},
     clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg},
     log::*,
     solana_clap_utils::{
@@ -11,12 +12,12 @@ use {
     },
     solana_cli_output::display::format_labeled_address,
     solana_metrics::{datapoint_error, datapoint_info},
-    solana_notifier::{NotificationType, Notifier},
+    solana_notifier::{Alert, NotificationType, Notifier, Severity},
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::{client_error, response::RpcVoteAccountStatus},
     solana_sdk::{
         hash::Hash,
-        native_token::{sol_to_lamports, Sol},
+        native_token::{lamports_to_sol, sol_to_lamports, Sol},
         pubkey::Pubkey,
     },
     std::{
@@ -27,6 +28,36 @@ use {
     },
 };
 
+mod slo;
+
+struct Failure {
+    test_name: &'static str,
+    message: String,
+    validator_identity: Option<Pubkey>,
+    metrics: Vec<(&'static str, f64)>,
+}
+
+impl Failure {
+    fn new(test_name: &'static str, message: String) -> Self {
+        Self {
+            test_name,
+            message,
+            validator_identity: None,
+            metrics: vec![],
+        }
+    }
+
+    fn with_validator_identity(mut self, validator_identity: Pubkey) -> Self {
+        self.validator_identity = Some(validator_identity);
+        self
+    }
+
+    fn with_metric(mut self, name: &'static str, value: f64) -> Self {
+        self.metrics.push((name, value));
+        self
+    }
+}
+
 struct Config {
     address_labels: HashMap<String, String>,
     ignore_http_bad_gateway: bool,
@@ -39,6 +70,7 @@ struct Config {
     unhealthy_threshold: usize,
     validator_identity_pubkeys: Vec<Pubkey>,
     name_suffix: String,
+    slo_config: SloConfig,
 }
 
 fn get_config() -> Config {
@@ -65,7 +97,14 @@ fn get_config() -> Config {
         and a sending number owned by that account,
         define environment variable before running `agave-watchtower`:
 
-        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'")
+        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'
+
+        To POST structured JSON alerts (severity, check, validator identity, metric values and
+        dedup key) to any HTTP endpoint, define the webhook URL and optionally a body template
+        whose {{field}} placeholders are replaced by JSON-encoded alert fields:
+
+        export WEBHOOK_URL=...
+        export WEBHOOK_TEMPLATE='{\"text\": {{message}}, \"severity\": {{severity}}}'")
         .arg({
             let arg = Arg::with_name("config_file")
                 .short("C")
@@ -151,6 +190,45 @@ fn get_config() -> Config {
                 .default_value("80")
                 .help("Alert when the current stake for the cluster drops below this value"),
         )
+        .arg(
+            Arg::with_name("max_leader_skip_rate")
+                .long("max-leader-skip-rate")
+                .value_name("PERCENTAGE[,CLEAR_PERCENTAGE]")
+                .takes_value(true)
+                .validator(is_threshold)
+                .help("Alert when the leader skip rate of a monitored validator in the current \
+                    epoch exceeds this percentage, until it drops back to CLEAR_PERCENTAGE")
+        )
+        .arg(
+            Arg::with_name("min_leader_slots")
+                .long("min-leader-slots")
+                .value_name("COUNT")
+                .takes_value(true)
+                .default_value("10")
+                .validator(is_parsable::<usize>)
+                .help("Leader slots a validator must have had in the current epoch before \
+                    --max-leader-skip-rate is evaluated")
+        )
+        .arg(
+            Arg::with_name("max_vote_credits_deficit")
+                .long("max-vote-credits-deficit")
+                .value_name("PERCENTAGE[,CLEAR_PERCENTAGE]")
+                .takes_value(true)
+                .validator(is_threshold)
+                .help("Alert when the vote credits a monitored validator earned in the current \
+                    epoch are more than this percentage below the cluster median, until the \
+                    shortfall drops back to CLEAR_PERCENTAGE")
+        )
+        .arg(
+            Arg::with_name("max_root_slot_lag")
+                .long("max-root-slot-lag")
+                .value_name("SLOTS[,CLEAR_SLOTS]")
+                .takes_value(true)
+                .validator(is_threshold)
+                .help("Alert when the root slot of a monitored validator is more than this many \
+                    slots behind the cluster's finalized slot, until the lag drops back to \
+                    CLEAR_SLOTS")
+        )
         .arg(
             Arg::with_name("ignore_http_bad_gateway")
                 .long("ignore-http-bad-gateway")
@@ -199,6 +277,18 @@ fn get_config() -> Config {
 
     let name_suffix = value_t_or_exit!(matches, "name_suffix", String);
 
+    let threshold_of = |name| {
+        matches
+            .value_of(name)
+            .map(|value| Threshold::parse(value).unwrap())
+    };
+    let slo_config = SloConfig {
+        leader_skip_rate: threshold_of("max_leader_skip_rate"),
+        min_leader_slots: value_t_or_exit!(matches, "min_leader_slots", usize),
+        vote_credits_deficit: threshold_of("max_vote_credits_deficit"),
+        root_slot_lag: threshold_of("max_root_slot_lag"),
+    };
+
     let config = Config {
         address_labels: config.address_labels,
         ignore_http_bad_gateway,
@@ -211,6 +301,7 @@ fn get_config() -> Config {
         unhealthy_threshold,
         validator_identity_pubkeys,
         name_suffix,
+        slo_config,
     };
 
     info!("RPC URL: {}", config.json_rpc_url);
@@ -253,6 +344,8 @@ fn main() -> Result<(), Box<dyn error::Error>> {
 
     let rpc_client = RpcClient::new_with_timeout(config.json_rpc_url.clone(), config.rpc_timeout);
     let notifier = Notifier::default();
+    let source = format!("agave-watchtower{}", config.name_suffix);
+    let mut slo_monitor = SloMonitor::new(config.slo_config.clone());
     let mut last_transaction_count = 0;
     let mut last_recent_blockhash = Hash::default();
     let mut last_notification_msg = "".into();
@@ -297,18 +390,22 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 if transaction_count > last_transaction_count {
                     last_transaction_count = transaction_count;
                 } else {
-                    failures.push((
-                        "transaction-count",
-                        format!(
-                            "Transaction count is not advancing: {transaction_count} <= {last_transaction_count}"
-                        ),
-                    ));
+                    failures.push(
+                        Failure::new(
+                            "transaction-count",
+                            format!(
+                                "Transaction count is not advancing: {transaction_count} <= {last_transaction_count}"
+                            ),
+                        )
+                        .with_metric("transaction_count", transaction_count as f64)
+                        .with_metric("last_transaction_count", last_transaction_count as f64),
+                    );
                 }
 
                 if recent_blockhash != last_recent_blockhash {
                     last_recent_blockhash = recent_blockhash;
                 } else {
-                    failures.push((
+                    failures.push(Failure::new(
                         "recent-blockhash",
                         format!("Unable to get new blockhash: {recent_blockhash}"),
                     ));
@@ -317,13 +414,21 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 if config.monitor_active_stake
                     && current_stake_percent < config.active_stake_alert_threshold as f64
                 {
-                    failures.push((
-                        "current-stake",
-                        format!("Current stake is {current_stake_percent:.2}%"),
-                    ));
+                    failures.push(
+                        Failure::new(
+                            "current-stake",
+                            format!("Current stake is {current_stake_percent:.2}%"),
+                        )
+                        .with_metric("current_stake_percent", current_stake_percent)
+                        .with_metric(
+                            "active_stake_alert_threshold",
+                            config.active_stake_alert_threshold as f64,
+                        ),
+                    );
                 }
 
                 let mut validator_errors = vec![];
+                let mut failed_validator_identities = vec![];
                 for validator_identity in config.validator_identity_pubkeys.iter() {
                     let formatted_validator_identity = format_labeled_address(
                         &validator_identity.to_string(),
@@ -335,35 +440,69 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                         .any(|vai| vai.node_pubkey == *validator_identity.to_string())
                     {
                         validator_errors.push(format!("{formatted_validator_identity} delinquent"));
+                        failed_validator_identities.push(*validator_identity);
                     } else if !vote_accounts
                         .current
                         .iter()
                         .any(|vai| vai.node_pubkey == *validator_identity.to_string())
                     {
                         validator_errors.push(format!("{formatted_validator_identity} missing"));
+                        failed_validator_identities.push(*validator_identity);
                     }
 
                     if let Some(balance) = validator_balances.get(validator_identity) {
                         if *balance < config.minimum_validator_identity_balance {
-                            failures.push((
-                                "balance",
-                                format!("{} has {}", formatted_validator_identity, Sol(*balance)),
-                            ));
+                            failures.push(
+                                Failure::new(
+                                    "balance",
+                                    format!(
+                                        "{} has {}",
+                                        formatted_validator_identity,
+                                        Sol(*balance)
+                                    ),
+                                )
+                                .with_validator_identity(*validator_identity)
+                                .with_metric("balance_sol", lamports_to_sol(*balance))
+                                .with_metric(
+                                    "minimum_balance_sol",
+                                    lamports_to_sol(config.minimum_validator_identity_balance),
+                                ),
+                            );
                         }
                     }
                 }
 
+                if !config.slo_config.is_empty() {
+                    match slo_monitor.check(
+                        &rpc_client,
+                        &vote_accounts,
+                        &config.validator_identity_pubkeys,
+                        &config.address_labels,
+                    ) {
+                        Ok(slo_failures) => failures.extend(slo_failures),
+                        Err(err) => failures.push(Failure::new("rpc-error", err.to_string())),
+                    }
+                }
+
                 if !validator_errors.is_empty() {
-                    failures.push(("delinquent", validator_errors.join(",")));
+                    let mut failure = Failure::new("delinquent", validator_errors.join(","))
+                        .with_metric(
+                            "delinquent_validator_count",
+                            failed_validator_identities.len() as f64,
+                        );
+                    if let [validator_identity] = failed_validator_identities[..] {
+                        failure = failure.with_validator_identity(validator_identity);
+                    }
+                    failures.push(failure);
                 }
 
                 for failure in failures.iter() {
-                    error!("{} sanity failure: {}", failure.0, failure.1);
+                    error!("{} sanity failure: {}", failure.test_name, failure.message);
                 }
                 failures.into_iter().next() // Only report the first failure if any
             }
             Err(err) => {
-                let mut failure = Some(("rpc-error", err.to_string()));
+                let mut failure = Some(Failure::new("rpc-error", err.to_string()));
 
                 if let client_error::ErrorKind::Reqwest(reqwest_err) = err.kind() {
                     if let Some(client_error::reqwest::StatusCode::BAD_GATEWAY) =
@@ -379,21 +518,34 @@ fn main() -> Result<(), Box<dyn error::Error>> {
             }
         };
 
-        if let Some((failure_test_name, failure_error_message)) = &failure {
+        if let Some(failure) = &failure {
             let notification_msg = format!(
                 "agave-watchtower{}: Error: {}: {}",
-                config.name_suffix, failure_test_name, failure_error_message
+                config.name_suffix, failure.test_name, failure.message
             );
             num_consecutive_failures += 1;
             if num_consecutive_failures > config.unhealthy_threshold {
                 datapoint_info!("watchtower-sanity", ("ok", false, bool));
                 if last_notification_msg != notification_msg {
-                    notifier.send(&notification_msg, &NotificationType::Trigger { incident });
+                    let mut alert = Alert::new(
+                        &source,
+                        notification_msg.clone(),
+                        NotificationType::Trigger { incident },
+                    )
+                    .with_check(failure.test_name)
+                    .with_metric("consecutive_failures", num_consecutive_failures as f64);
+                    if let Some(validator_identity) = failure.validator_identity {
+                        alert = alert.with_validator_identity(validator_identity);
+                    }
+                    for (name, value) in &failure.metrics {
+                        alert = alert.with_metric(*name, *value);
+                    }
+                    notifier.send_alert(&alert);
                 }
                 datapoint_error!(
                     "watchtower-sanity-failure",
-                    ("test", failure_test_name, String),
-                    ("err", failure_error_message, String)
+                    ("test", failure.test_name, String),
+                    ("err", failure.message, String)
                 );
                 last_notification_msg = notification_msg;
             } else {
@@ -414,9 +566,14 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                     humantime::format_duration(alarm_duration)
                 );
                 info!("{}", all_clear_msg);
-                notifier.send(
-                    &format!("agave-watchtower{}: {}", config.name_suffix, all_clear_msg),
-                    &NotificationType::Resolve { incident },
+                notifier.send_alert(
+                    &Alert::new(
+                        &source,
+                        format!("{source}: {all_clear_msg}"),
+                        NotificationType::Resolve { incident },
+                    )
+                    .with_severity(Severity::Info)
+                    .with_metric("alarm_duration_secs", alarm_duration.as_secs() as f64),
                 );
             }
             last_notification_msg = "".into();
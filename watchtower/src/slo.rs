@@ -0,0 +1,346 @@
+//! Per-validator service level objectives
+//!
+//! Each rule measures a value for every monitored validator identity where a higher value is
+//! worse. A rule fires once its value exceeds the trigger threshold and stays firing until the
+//! value drops to the clear threshold or below, so a value hovering around the trigger threshold
+//! does not flap.
+
+use {
+    crate::Failure,
+    solana_cli_output::display::format_labeled_address,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{client_error, response::RpcVoteAccountStatus},
+    solana_sdk::{clock::Slot, commitment_config::CommitmentConfig, pubkey::Pubkey},
+    std::collections::{HashMap, HashSet},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    pub trigger: f64,
+    pub clear: f64,
+}
+
+impl Threshold {
+    /// Parse `TRIGGER[,CLEAR]`. The clear threshold defaults to the trigger threshold.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|err| format!("invalid threshold '{value}': {err}"))
+        };
+        let (trigger, clear) = match value.split_once(',') {
+            Some((trigger, clear)) => (parse(trigger)?, parse(clear)?),
+            None => {
+                let trigger = parse(value)?;
+                (trigger, trigger)
+            }
+        };
+        if clear > trigger {
+            return Err(format!(
+                "clear threshold {clear} must not exceed trigger threshold {trigger}"
+            ));
+        }
+        Ok(Self { trigger, clear })
+    }
+}
+
+pub fn is_threshold(value: String) -> Result<(), String> {
+    Threshold::parse(&value).map(|_| ())
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SloConfig {
+    /// Percentage of leader slots skipped in the current epoch
+    pub leader_skip_rate: Option<Threshold>,
+    /// Leader slots required in the current epoch before the skip rate is evaluated
+    pub min_leader_slots: usize,
+    /// Percentage by which the vote credits earned in the current epoch fall short of the cluster
+    /// median
+    pub vote_credits_deficit: Option<Threshold>,
+    /// Slots by which the vote account root trails the cluster's finalized slot
+    pub root_slot_lag: Option<Threshold>,
+}
+
+impl SloConfig {
+    pub fn is_empty(&self) -> bool {
+        self.leader_skip_rate.is_none()
+            && self.vote_credits_deficit.is_none()
+            && self.root_slot_lag.is_none()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Rule {
+    LeaderSkipRate,
+    VoteCreditsDeficit,
+    RootSlotLag,
+}
+
+impl Rule {
+    fn test_name(&self) -> &'static str {
+        match self {
+            Rule::LeaderSkipRate => "leader-skip-rate",
+            Rule::VoteCreditsDeficit => "vote-credits",
+            Rule::RootSlotLag => "root-slot-lag",
+        }
+    }
+
+    fn metric_name(&self) -> &'static str {
+        match self {
+            Rule::LeaderSkipRate => "leader_skip_rate_percent",
+            Rule::VoteCreditsDeficit => "vote_credits_deficit_percent",
+            Rule::RootSlotLag => "root_slot_lag",
+        }
+    }
+
+    fn describe(&self, threshold: &Threshold) -> String {
+        match self {
+            Rule::LeaderSkipRate => format!("leader skip rate above {}%", threshold.trigger),
+            Rule::VoteCreditsDeficit => format!(
+                "vote credits more than {}% below the cluster median",
+                threshold.trigger
+            ),
+            Rule::RootSlotLag => format!("root slot more than {} slots behind", threshold.trigger),
+        }
+    }
+}
+
+pub struct SloMonitor {
+    config: SloConfig,
+    firing: HashSet<(Rule, Pubkey)>,
+}
+
+impl SloMonitor {
+    pub fn new(config: SloConfig) -> Self {
+        Self {
+            config,
+            firing: HashSet::new(),
+        }
+    }
+
+    /// Evaluate every rule for `validator_identities`, returning a failure for each rule that is
+    /// firing
+    pub fn check(
+        &mut self,
+        rpc_client: &RpcClient,
+        vote_accounts: &RpcVoteAccountStatus,
+        validator_identities: &[Pubkey],
+        address_labels: &HashMap<String, String>,
+    ) -> client_error::Result<Vec<Failure>> {
+        let mut measurements = vec![];
+
+        if let Some(threshold) = self.config.leader_skip_rate {
+            let block_production = rpc_client.get_block_production()?.value;
+            for validator_identity in validator_identities {
+                let skip_rate = block_production
+                    .by_identity
+                    .get(&validator_identity.to_string())
+                    .and_then(|(leader_slots, blocks_produced)| {
+                        leader_skip_rate(
+                            *leader_slots,
+                            *blocks_produced,
+                            self.config.min_leader_slots,
+                        )
+                    });
+                if let Some(skip_rate) = skip_rate {
+                    measurements.push((
+                        Rule::LeaderSkipRate,
+                        threshold,
+                        *validator_identity,
+                        skip_rate,
+                    ));
+                }
+            }
+        }
+
+        let vote_account_infos = || {
+            vote_accounts
+                .current
+                .iter()
+                .chain(vote_accounts.delinquent.iter())
+        };
+        let find_vote_account = |validator_identity: &Pubkey| {
+            let validator_identity = validator_identity.to_string();
+            vote_account_infos().find(|info| info.node_pubkey == validator_identity)
+        };
+
+        if let Some(threshold) = self.config.vote_credits_deficit {
+            if let Some(epoch) = vote_account_infos()
+                .filter_map(|info| info.epoch_credits.last().map(|(epoch, ..)| *epoch))
+                .max()
+            {
+                let median = median_epoch_credits(
+                    vote_account_infos().map(|info| epoch_credits(&info.epoch_credits, epoch)),
+                );
+                for validator_identity in validator_identities {
+                    if let (Some(info), Some(median)) =
+                        (find_vote_account(validator_identity), median)
+                    {
+                        let credits = epoch_credits(&info.epoch_credits, epoch);
+                        measurements.push((
+                            Rule::VoteCreditsDeficit,
+                            threshold,
+                            *validator_identity,
+                            vote_credits_deficit(credits, median),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(threshold) = self.config.root_slot_lag {
+            let finalized_slot =
+                rpc_client.get_slot_with_commitment(CommitmentConfig::finalized())?;
+            for validator_identity in validator_identities {
+                if let Some(info) = find_vote_account(validator_identity) {
+                    measurements.push((
+                        Rule::RootSlotLag,
+                        threshold,
+                        *validator_identity,
+                        root_slot_lag(finalized_slot, info.root_slot) as f64,
+                    ));
+                }
+            }
+        }
+
+        let mut failures = vec![];
+        for (rule, threshold, validator_identity, value) in measurements {
+            if self.update(rule, &threshold, validator_identity, value) {
+                let formatted_validator_identity =
+                    format_labeled_address(&validator_identity.to_string(), address_labels);
+                failures.push(
+                    Failure::new(
+                        rule.test_name(),
+                        format!(
+                            "{} {}",
+                            formatted_validator_identity,
+                            rule.describe(&threshold)
+                        ),
+                    )
+                    .with_validator_identity(validator_identity)
+                    .with_metric(rule.metric_name(), value)
+                    .with_metric("trigger_threshold", threshold.trigger)
+                    .with_metric("clear_threshold", threshold.clear),
+                );
+            }
+        }
+        Ok(failures)
+    }
+
+    /// Record a new value for `rule`, returning whether the rule is firing
+    fn update(
+        &mut self,
+        rule: Rule,
+        threshold: &Threshold,
+        validator_identity: Pubkey,
+        value: f64,
+    ) -> bool {
+        let key = (rule, validator_identity);
+        if self.firing.contains(&key) {
+            if value <= threshold.clear {
+                self.firing.remove(&key);
+            }
+        } else if value > threshold.trigger {
+            self.firing.insert(key);
+        }
+        self.firing.contains(&key)
+    }
+}
+
+fn leader_skip_rate(
+    leader_slots: usize,
+    blocks_produced: usize,
+    min_leader_slots: usize,
+) -> Option<f64> {
+    (leader_slots > 0 && leader_slots >= min_leader_slots)
+        .then(|| leader_slots.saturating_sub(blocks_produced) as f64 * 100. / leader_slots as f64)
+}
+
+fn epoch_credits(epoch_credits: &[(u64, u64, u64)], epoch: u64) -> u64 {
+    epoch_credits
+        .iter()
+        .find(|(credits_epoch, ..)| *credits_epoch == epoch)
+        .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+        .unwrap_or_default()
+}
+
+fn median_epoch_credits(credits: impl Iterator<Item = u64>) -> Option<u64> {
+    let mut credits: Vec<_> = credits.collect();
+    credits.sort_unstable();
+    credits.get(credits.len() / 2).copied()
+}
+
+fn vote_credits_deficit(credits: u64, median: u64) -> f64 {
+    if median == 0 {
+        0.
+    } else {
+        median.saturating_sub(credits) as f64 * 100. / median as f64
+    }
+}
+
+fn root_slot_lag(finalized_slot: Slot, root_slot: Slot) -> Slot {
+    finalized_slot.saturating_sub(root_slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_parse() {
+        assert_eq!(
+            Threshold::parse("25").unwrap(),
+            Threshold {
+                trigger: 25.,
+                clear: 25.
+            }
+        );
+        assert_eq!(
+            Threshold::parse("25,15").unwrap(),
+            Threshold {
+                trigger: 25.,
+                clear: 15.
+            }
+        );
+        assert!(Threshold::parse("15,25").is_err());
+        assert!(Threshold::parse("high").is_err());
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut monitor = SloMonitor::new(SloConfig::default());
+        let threshold = Threshold::parse("25,15").unwrap();
+        let validator_identity = Pubkey::new_unique();
+        let mut update =
+            |value| monitor.update(Rule::LeaderSkipRate, &threshold, validator_identity, value);
+
+        assert!(!update(25.));
+        assert!(update(26.));
+        assert!(update(20.));
+        assert!(update(25.));
+        assert!(!update(15.));
+        assert!(!update(20.));
+    }
+
+    #[test]
+    fn test_measurements() {
+        assert_eq!(leader_skip_rate(0, 0, 0), None);
+        assert_eq!(leader_skip_rate(8, 6, 10), None);
+        assert_eq!(leader_skip_rate(20, 15, 10), Some(25.));
+
+        let history = [(4, 100, 50), (5, 300, 100)];
+        assert_eq!(epoch_credits(&history, 5), 200);
+        assert_eq!(epoch_credits(&history, 4), 50);
+        assert_eq!(epoch_credits(&history, 6), 0);
+
+        assert_eq!(median_epoch_credits([].into_iter()), None);
+        assert_eq!(median_epoch_credits([30, 10, 20].into_iter()), Some(20));
+        assert_eq!(vote_credits_deficit(150, 200), 25.);
+        assert_eq!(vote_credits_deficit(250, 200), 0.);
+        assert_eq!(vote_credits_deficit(0, 0), 0.);
+
+        assert_eq!(root_slot_lag(100, 68), 32);
+        assert_eq!(root_slot_lag(100, 120), 0);
+    }
+}